@@ -0,0 +1,67 @@
+//! Build Script
+//!
+//! Records the commit, build time and compiler version for `GET /version`.
+//! `GIT_SHA` overrides the commit for builds without a `.git` directory,
+//! such as Docker images, and `SOURCE_DATE_EPOCH` the build time for
+//! reproducible builds.
+
+use std::env;
+use std::path::Path;
+use std::process::Command;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+fn main() {
+    println!("cargo:rerun-if-changed=build.rs");
+    println!("cargo:rerun-if-env-changed=GIT_SHA");
+    println!("cargo:rerun-if-env-changed=SOURCE_DATE_EPOCH");
+
+    if let Some(sha) = git_sha() {
+        println!("cargo:rustc-env=MCP_BUILD_GIT_SHA={}", sha);
+    }
+
+    let timestamp = env::var("SOURCE_DATE_EPOCH")
+        .ok()
+        .and_then(|epoch| epoch.trim().parse::<u64>().ok())
+        .or_else(|| {
+            SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .ok()
+                .map(|elapsed| elapsed.as_secs())
+        });
+    if let Some(timestamp) = timestamp {
+        println!("cargo:rustc-env=MCP_BUILD_TIMESTAMP={}", timestamp);
+    }
+
+    let rustc = env::var("RUSTC").unwrap_or_else(|_| "rustc".to_string());
+    if let Some(version) = command_output(Command::new(rustc).arg("--version")) {
+        println!("cargo:rustc-env=MCP_BUILD_RUSTC_VERSION={}", version);
+    }
+}
+
+/// Returns the commit being built, from `GIT_SHA` or `git rev-parse`
+fn git_sha() -> Option<String> {
+    if let Some(sha) = env::var("GIT_SHA").ok().filter(|sha| !sha.is_empty()) {
+        return Some(sha);
+    }
+
+    // Rebuild when HEAD moves to another branch or commit
+    let git_dir = command_output(Command::new("git").args(["rev-parse", "--git-dir"]))?;
+    let git_dir = Path::new(&git_dir);
+    println!("cargo:rerun-if-changed={}", git_dir.join("HEAD").display());
+    let head_ref = command_output(Command::new("git").args(["symbolic-ref", "-q", "HEAD"]))
+        .map(|head_ref| git_dir.join(head_ref));
+    // Packed refs have no file of their own, and a missing file would rerun every build
+    if let Some(head_ref) = head_ref.filter(|head_ref| head_ref.exists()) {
+        println!("cargo:rerun-if-changed={}", head_ref.display());
+    }
+
+    command_output(Command::new("git").args(["rev-parse", "HEAD"]))
+}
+
+/// Runs a command, returning its trimmed standard output if it succeeded
+fn command_output(command: &mut Command) -> Option<String> {
+    let output = command.output().ok()?;
+    let stdout = String::from_utf8(output.stdout).ok()?;
+    let stdout = stdout.trim();
+    (output.status.success() && !stdout.is_empty()).then(|| stdout.to_string())
+}
@@ -105,9 +105,9 @@ impl MetricsCollector {
 
         Ok(SystemSnapshot {
             timestamp: now,
-            cpu_usage: cpu_usage.max(0.0).min(100.0),
-            memory_usage: memory_usage.max(0.0).min(100.0),
-            disk_usage: disk_usage.max(0.0).min(100.0),
+            cpu_usage: cpu_usage.clamp(0.0, 100.0),
+            memory_usage: memory_usage.clamp(0.0, 100.0),
+            disk_usage: disk_usage.clamp(0.0, 100.0),
             network_rx,
             network_tx,
             process_count,
@@ -341,7 +341,7 @@ impl MetricsCollector {
                 GREEN.mix(0.8).stroke_width(3),
             ))?
             .label("Received")
-            .legend(|(x, y)| PathElement::new(vec![(x, y), (x + 20, y)], &GREEN));
+            .legend(|(x, y)| PathElement::new(vec![(x, y), (x + 20, y)], GREEN));
 
         // Transmitted traffic
         chart
@@ -350,12 +350,12 @@ impl MetricsCollector {
                 RGBColor(255, 165, 0).mix(0.8).stroke_width(3),
             ))?
             .label("Transmitted")
-            .legend(|(x, y)| PathElement::new(vec![(x, y), (x + 20, y)], &RGBColor(255, 165, 0)));
+            .legend(|(x, y)| PathElement::new(vec![(x, y), (x + 20, y)], RGBColor(255, 165, 0)));
 
         chart
             .configure_series_labels()
-            .background_style(&WHITE.mix(0.8))
-            .border_style(&BLACK)
+            .background_style(WHITE.mix(0.8))
+            .border_style(BLACK)
             .draw()?;
 
         root.present()?;
@@ -0,0 +1,303 @@
+//! Threshold Alerts
+//!
+//! Rules that compare a sampled metric against a threshold, and the evaluator
+//! the background sampler feeds each snapshot into. A rule fires once its
+//! condition has held for `duration_secs`.
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+/// Metric an alert rule watches
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum AlertMetric {
+    /// CPU usage percentage
+    Cpu,
+    /// Memory usage percentage
+    Memory,
+    /// 1-minute load average
+    Load,
+    /// Usage percentage of the fullest mounted filesystem
+    Disk,
+    /// Highest inode usage percentage of any mounted filesystem
+    Inodes,
+    /// Share of the last 10 seconds some task waited for CPU (PSI)
+    #[serde(rename = "cpu_pressure")]
+    CpuPressure,
+    /// Share of the last 10 seconds some task waited for memory (PSI)
+    #[serde(rename = "memory_pressure")]
+    MemoryPressure,
+    /// Share of the last 10 seconds some task waited for I/O (PSI)
+    #[serde(rename = "io_pressure")]
+    IoPressure,
+    /// Share of the system-wide file handle limit in use
+    Fd,
+    /// Bits of entropy available in the kernel pool
+    Entropy,
+}
+
+impl fmt::Display for AlertMetric {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            AlertMetric::Cpu => "cpu",
+            AlertMetric::Memory => "memory",
+            AlertMetric::Load => "load",
+            AlertMetric::Disk => "disk",
+            AlertMetric::Inodes => "inodes",
+            AlertMetric::CpuPressure => "cpu_pressure",
+            AlertMetric::MemoryPressure => "memory_pressure",
+            AlertMetric::IoPressure => "io_pressure",
+            AlertMetric::Fd => "fd",
+            AlertMetric::Entropy => "entropy",
+        })
+    }
+}
+
+/// How a metric is compared against the threshold
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Comparator {
+    /// Fires above the threshold
+    #[serde(rename = ">")]
+    Above,
+    /// Fires at or above the threshold
+    #[serde(rename = ">=")]
+    AtLeast,
+    /// Fires below the threshold
+    #[serde(rename = "<")]
+    Below,
+    /// Fires at or below the threshold
+    #[serde(rename = "<=")]
+    AtMost,
+}
+
+impl Comparator {
+    /// Returns whether `value` meets the condition for `threshold`
+    pub fn holds(self, value: f64, threshold: f64) -> bool {
+        match self {
+            Comparator::Above => value > threshold,
+            Comparator::AtLeast => value >= threshold,
+            Comparator::Below => value < threshold,
+            Comparator::AtMost => value <= threshold,
+        }
+    }
+}
+
+impl fmt::Display for Comparator {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Comparator::Above => ">",
+            Comparator::AtLeast => ">=",
+            Comparator::Below => "<",
+            Comparator::AtMost => "<=",
+        })
+    }
+}
+
+/// A threshold condition on one metric
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct AlertRule {
+    /// Unique rule name, reported with the alert
+    pub name: String,
+    /// Metric to watch
+    pub metric: AlertMetric,
+    /// Comparison against the threshold
+    pub comparator: Comparator,
+    /// Threshold value, in the metric's unit
+    pub threshold: f64,
+    /// How long the condition must hold before the alert fires
+    #[serde(default)]
+    pub duration_secs: u64,
+    /// Endpoint notified when this rule fires or resolves, instead of the
+    /// global `[webhook]` URL
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub webhook_url: Option<String>,
+}
+
+/// Rules used when the configuration does not define any
+pub fn default_rules() -> Vec<AlertRule> {
+    vec![
+        AlertRule {
+            name: "disk_almost_full".to_string(),
+            metric: AlertMetric::Disk,
+            comparator: Comparator::Above,
+            threshold: 90.0,
+            duration_secs: 0,
+            webhook_url: None,
+        },
+        AlertRule {
+            name: "memory_exhausted".to_string(),
+            metric: AlertMetric::Memory,
+            comparator: Comparator::Above,
+            threshold: 95.0,
+            duration_secs: 60,
+            webhook_url: None,
+        },
+    ]
+}
+
+/// A rule whose condition currently holds long enough to fire
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Alert {
+    /// Name of the rule
+    pub rule: String,
+    /// Metric the rule watches
+    pub metric: AlertMetric,
+    /// Comparison against the threshold
+    pub comparator: Comparator,
+    /// Threshold of the rule
+    pub threshold: f64,
+    /// Metric value in the latest snapshot
+    pub value: f64,
+    /// When the condition started to hold
+    pub since: DateTime<Utc>,
+}
+
+/// Whether an alert started or stopped firing
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum AlertState {
+    /// The condition has held for the rule's duration
+    Firing,
+    /// A firing condition no longer holds
+    Resolved,
+}
+
+impl fmt::Display for AlertState {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            AlertState::Firing => "firing",
+            AlertState::Resolved => "resolved",
+        })
+    }
+}
+
+/// A rule that started or stopped firing in one evaluation
+#[derive(Debug, Clone, PartialEq)]
+pub struct AlertTransition {
+    /// The rule that changed state
+    pub rule: AlertRule,
+    /// The state it changed to
+    pub state: AlertState,
+    /// Latest metric value, or the last known one if the metric went missing
+    pub value: f64,
+    /// Time of the snapshot that caused the change
+    pub timestamp: DateTime<Utc>,
+}
+
+/// A rule's condition that currently holds
+#[derive(Debug, Clone, Copy)]
+struct Holding {
+    /// When the condition started to hold
+    since: DateTime<Utc>,
+    /// Metric value in the latest snapshot
+    value: f64,
+    /// Whether it has held for the rule's duration
+    fired: bool,
+}
+
+/// Tracks how long each rule's condition has held across snapshots
+#[derive(Debug, Clone)]
+pub struct AlertEvaluator {
+    /// Rules in configuration order
+    rules: Vec<AlertRule>,
+    /// Each rule's currently holding condition
+    holding: Vec<Option<Holding>>,
+}
+
+impl AlertEvaluator {
+    /// Creates an evaluator with no condition holding yet
+    pub fn new(rules: Vec<AlertRule>) -> Self {
+        let holding = vec![None; rules.len()];
+        Self { rules, holding }
+    }
+
+    /// Returns the configured rules
+    pub fn rules(&self) -> &[AlertRule] {
+        &self.rules
+    }
+
+    /// Returns whether any rule watches `metric`
+    pub fn watches(&self, metric: AlertMetric) -> bool {
+        self.rules.iter().any(|rule| rule.metric == metric)
+    }
+
+    /// Evaluates every rule against one snapshot
+    ///
+    /// A metric that `value_of` cannot provide clears the rule's condition,
+    /// like a value that does not meet it.
+    ///
+    /// # Arguments
+    ///
+    /// * `now` - Time of the snapshot
+    /// * `value_of` - Value of each metric in the snapshot
+    ///
+    /// # Returns
+    ///
+    /// Returns the rules that started or stopped firing with this snapshot
+    pub fn evaluate(
+        &mut self,
+        now: DateTime<Utc>,
+        value_of: impl Fn(AlertMetric) -> Option<f64>,
+    ) -> Vec<AlertTransition> {
+        let mut transitions = Vec::new();
+
+        for (rule, holding) in self.rules.iter().zip(self.holding.iter_mut()) {
+            let value = value_of(rule.metric);
+            let next = match value {
+                Some(value) if rule.comparator.holds(value, rule.threshold) => {
+                    let since = holding.map_or(now, |held| held.since);
+                    let held = (now - since).num_seconds().max(0) as u64;
+                    Some(Holding {
+                        since,
+                        value,
+                        fired: held >= rule.duration_secs,
+                    })
+                }
+                _ => None,
+            };
+
+            let transition = match (*holding, next) {
+                (Some(prev), next) if prev.fired && !next.is_some_and(|held| held.fired) => {
+                    Some((AlertState::Resolved, value.unwrap_or(prev.value)))
+                }
+                (prev, Some(next)) if next.fired && !prev.is_some_and(|held| held.fired) => {
+                    Some((AlertState::Firing, next.value))
+                }
+                _ => None,
+            };
+            if let Some((state, value)) = transition {
+                transitions.push(AlertTransition {
+                    rule: rule.clone(),
+                    state,
+                    value,
+                    timestamp: now,
+                });
+            }
+
+            *holding = next;
+        }
+
+        transitions
+    }
+
+    /// Returns the alerts firing as of the latest snapshot
+    pub fn firing(&self) -> Vec<Alert> {
+        self.rules
+            .iter()
+            .zip(&self.holding)
+            .filter_map(|(rule, holding)| {
+                let held = holding.filter(|held| held.fired)?;
+                Some(Alert {
+                    rule: rule.name.clone(),
+                    metric: rule.metric,
+                    comparator: rule.comparator,
+                    threshold: rule.threshold,
+                    value: held.value,
+                    since: held.since,
+                })
+            })
+            .collect()
+    }
+}
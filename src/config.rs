@@ -0,0 +1,529 @@
+//! Server Configuration
+//!
+//! Settings loaded from an optional TOML file and overridden by environment
+//! variables and command-line flags, in that order of precedence.
+
+use anyhow::{anyhow, bail, Context, Result};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashSet};
+use std::fmt;
+use std::net::{IpAddr, Ipv4Addr};
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+use tracing::info;
+
+use crate::alerts::{default_rules, AlertRule};
+use crate::system_monitor::core::{
+    DEFAULT_COLLECTION_TIMEOUT, DEFAULT_COMMAND_TIMEOUT, DEFAULT_HISTORY_CAPACITY,
+    DEFAULT_MIN_MONITORING_INTERVAL, DEFAULT_MONITORING_INTERVAL,
+};
+use crate::types::ProcessSortKey;
+
+/// Default HTTP port when no configuration sets one
+pub const DEFAULT_PORT: u16 = 57996;
+
+/// Default requests per second each client may make to the HTTP API
+pub const DEFAULT_RATE_LIMIT_PER_SEC: u32 = 10;
+
+/// Default number of requests a client may make at once before being limited
+pub const DEFAULT_RATE_LIMIT_BURST: u32 = 20;
+
+/// Collectors that can be switched off to save work on busy hosts
+///
+/// A disabled collector reports an empty list instead of being sampled.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct CollectorConfig {
+    /// Mounted filesystems and disk I/O counters
+    pub disks: bool,
+    /// Network interfaces and throughput rates
+    pub networks: bool,
+    /// Process listings and process trees
+    pub processes: bool,
+    /// NVIDIA GPUs in system metrics snapshots (off by default, since every
+    /// snapshot would run `nvidia-smi`; direct GPU queries always work)
+    pub gpus: bool,
+    /// Open file descriptor counts in process listings (off by default, since
+    /// it scans `/proc/<pid>/fd` of every process; single-process lookups
+    /// always count them)
+    pub process_fds: bool,
+    /// Most processes in a system metrics snapshot (None for all); process
+    /// listings page with their own `limit` instead
+    pub max_processes: Option<usize>,
+    /// Key that decides which processes a snapshot keeps when it has more
+    /// than `max_processes`, highest first
+    pub process_sort_by: ProcessSortKey,
+}
+
+impl Default for CollectorConfig {
+    fn default() -> Self {
+        Self {
+            disks: true,
+            networks: true,
+            processes: true,
+            gpus: false,
+            process_fds: false,
+            max_processes: None,
+            process_sort_by: ProcessSortKey::Cpu,
+        }
+    }
+}
+
+/// Payload shape of alert webhooks
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum WebhookFormat {
+    /// The alert fields as a JSON object
+    Json,
+    /// A Slack incoming-webhook message, `{"text": ...}`
+    Slack,
+}
+
+/// Where and how alert transitions are posted
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct WebhookConfig {
+    /// Endpoint for rules without their own `webhook_url`
+    pub url: Option<String>,
+    /// Payload shape
+    pub format: WebhookFormat,
+    /// JSON body to send instead of `format`, with `{rule}`, `{metric}`,
+    /// `{value}`, `{threshold}`, `{state}`, `{hostname}` and `{timestamp}`
+    /// replaced in its strings
+    pub template: Option<String>,
+    /// Minimum time between firing notifications for the same rule, in seconds
+    pub cooldown_secs: u64,
+    /// Delivery attempts after the first one fails
+    pub retries: u32,
+}
+
+impl Default for WebhookConfig {
+    fn default() -> Self {
+        Self {
+            url: None,
+            format: WebhookFormat::Json,
+            template: None,
+            cooldown_secs: 300,
+            retries: 2,
+        }
+    }
+}
+
+/// Format of the log lines written to stderr
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum LogFormat {
+    /// Human-readable lines
+    #[default]
+    Text,
+    /// One JSON object per line, with event fields as keys
+    Json,
+}
+
+impl FromStr for LogFormat {
+    type Err = String;
+
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        match s.trim().to_lowercase().as_str() {
+            "text" => Ok(LogFormat::Text),
+            "json" => Ok(LogFormat::Json),
+            other => Err(format!("Unknown log format: {}", other)),
+        }
+    }
+}
+
+impl fmt::Display for LogFormat {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            LogFormat::Text => "text",
+            LogFormat::Json => "json",
+        })
+    }
+}
+
+/// Server settings
+///
+/// Every field is optional in the file; `Config::default()` matches the
+/// behavior of running without a configuration file.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct Config {
+    /// Address the HTTP server binds to
+    pub bind_addr: IpAddr,
+    /// Port the HTTP server listens on
+    pub port: u16,
+    /// Bearer token required on every route except /health
+    pub auth_token: Option<String>,
+    /// PEM certificate chain for HTTPS
+    pub tls_cert: Option<PathBuf>,
+    /// PEM private key for HTTPS
+    pub tls_key: Option<PathBuf>,
+    /// Unix domain socket to serve on instead of the TCP port
+    pub socket_path: Option<PathBuf>,
+    /// Requests per second each client may make, on every route except
+    /// /health (0 disables the limit)
+    pub rate_limit_per_sec: u32,
+    /// Requests a client may make at once before the rate limit applies
+    pub rate_limit_burst: u32,
+    /// Interval between continuous monitoring samples in milliseconds
+    pub monitoring_interval_ms: u64,
+    /// Floor that requested monitoring intervals are clamped to in milliseconds
+    pub min_interval_ms: u64,
+    /// Time a single collection may take before the request fails, in milliseconds
+    pub collection_timeout_ms: u64,
+    /// Time an external command such as `ps` or `df` may run before it is
+    /// killed, in milliseconds
+    pub command_timeout_ms: u64,
+    /// Number of samples kept in the metrics history
+    pub history_size: usize,
+    /// Format of the log lines written to stderr
+    pub log_format: LogFormat,
+    /// Threshold alert rules evaluated while monitoring is active
+    pub alerts: Vec<AlertRule>,
+    /// Notifications sent when an alert fires or resolves
+    pub webhook: WebhookConfig,
+    /// Which metric collectors are enabled
+    pub collectors: CollectorConfig,
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Self {
+            bind_addr: IpAddr::V4(Ipv4Addr::UNSPECIFIED),
+            port: DEFAULT_PORT,
+            auth_token: None,
+            tls_cert: None,
+            tls_key: None,
+            socket_path: None,
+            rate_limit_per_sec: DEFAULT_RATE_LIMIT_PER_SEC,
+            rate_limit_burst: DEFAULT_RATE_LIMIT_BURST,
+            monitoring_interval_ms: DEFAULT_MONITORING_INTERVAL.as_millis() as u64,
+            min_interval_ms: DEFAULT_MIN_MONITORING_INTERVAL.as_millis() as u64,
+            collection_timeout_ms: DEFAULT_COLLECTION_TIMEOUT.as_millis() as u64,
+            command_timeout_ms: DEFAULT_COMMAND_TIMEOUT.as_millis() as u64,
+            history_size: DEFAULT_HISTORY_CAPACITY,
+            log_format: LogFormat::Text,
+            alerts: default_rules(),
+            webhook: WebhookConfig::default(),
+            collectors: CollectorConfig::default(),
+        }
+    }
+}
+
+/// Where the effective value of a setting came from
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ConfigSource {
+    /// Built-in default
+    Default,
+    /// The TOML configuration file
+    File,
+    /// An environment variable
+    Env,
+    /// A command-line flag
+    Cli,
+}
+
+impl fmt::Display for ConfigSource {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            ConfigSource::Default => "default",
+            ConfigSource::File => "config file",
+            ConfigSource::Env => "environment",
+            ConfigSource::Cli => "command line",
+        })
+    }
+}
+
+/// Settings that can be overridden, with their CLI flag and environment variable
+const OVERRIDES: &[(&str, Option<&str>, Option<&str>)] = &[
+    ("bind_addr", Some("--bind"), Some("MCP_BIND_ADDR")),
+    ("port", Some("--port"), Some("MCP_PORT")),
+    ("auth_token", None, Some("MCP_AUTH_TOKEN")),
+    ("tls_cert", Some("--tls-cert"), Some("MCP_TLS_CERT")),
+    ("tls_key", Some("--tls-key"), Some("MCP_TLS_KEY")),
+    ("socket_path", Some("--socket"), Some("MCP_SOCKET_PATH")),
+    (
+        "rate_limit_per_sec",
+        Some("--rate-limit"),
+        Some("MCP_RATE_LIMIT_PER_SEC"),
+    ),
+    (
+        "rate_limit_burst",
+        Some("--rate-limit-burst"),
+        Some("MCP_RATE_LIMIT_BURST"),
+    ),
+    ("monitoring_interval_ms", None, None),
+    ("min_interval_ms", None, Some("MCP_MIN_INTERVAL_MS")),
+    (
+        "collection_timeout_ms",
+        None,
+        Some("MCP_COLLECTION_TIMEOUT_MS"),
+    ),
+    ("command_timeout_ms", None, Some("MCP_COMMAND_TIMEOUT_MS")),
+    ("history_size", None, None),
+    ("log_format", Some("--log-format"), Some("MCP_LOG_FORMAT")),
+    ("alerts", None, None),
+    ("webhook", None, None),
+    ("collectors", None, None),
+];
+
+impl Config {
+    /// Loads and validates a TOML configuration file
+    ///
+    /// # Arguments
+    ///
+    /// * `path` - Path to the TOML file
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the file cannot be read, is not valid TOML,
+    /// contains unknown settings, or fails validation.
+    pub fn load(path: &Path) -> Result<Self> {
+        let (config, _) = Self::parse_file(path)?;
+        config.validate()?;
+        Ok(config)
+    }
+
+    /// Resolves the effective configuration from all sources
+    ///
+    /// The file named by `--config` is read first, then environment variables
+    /// and command-line flags override it.
+    ///
+    /// # Arguments
+    ///
+    /// * `args` - Command-line arguments
+    /// * `env` - Environment variable lookup, e.g. `|name| std::env::var(name).ok()`
+    ///
+    /// # Returns
+    ///
+    /// Returns the configuration and the source of each setting
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the file cannot be loaded, an override cannot be
+    /// parsed, or the resulting configuration fails validation.
+    pub fn resolve(
+        args: &[String],
+        env: impl Fn(&str) -> Option<String>,
+    ) -> Result<(Self, BTreeMap<&'static str, ConfigSource>)> {
+        let (mut config, file_keys) = match arg_value(args, "--config") {
+            Some(path) => Self::parse_file(Path::new(&path))?,
+            None => (Self::default(), toml::Table::new()),
+        };
+
+        let mut sources = BTreeMap::new();
+        for &(name, flag, var) in OVERRIDES {
+            let cli = flag.and_then(|flag| arg_value(args, flag));
+            let from_env = var.and_then(&env).filter(|value| !value.is_empty());
+
+            let (value, source) = match (cli, from_env) {
+                (Some(value), _) => (value, ConfigSource::Cli),
+                (None, Some(value)) => (value, ConfigSource::Env),
+                (None, None) if file_keys.contains_key(name) => {
+                    sources.insert(name, ConfigSource::File);
+                    continue;
+                }
+                (None, None) => {
+                    sources.insert(name, ConfigSource::Default);
+                    continue;
+                }
+            };
+
+            config.apply_override(name, &value)?;
+            sources.insert(name, source);
+        }
+
+        // A raised floor lifts the default interval, as it did before config files
+        if sources.get("monitoring_interval_ms") == Some(&ConfigSource::Default) {
+            config.monitoring_interval_ms =
+                config.monitoring_interval_ms.max(config.min_interval_ms);
+        }
+
+        config.validate()?;
+        Ok((config, sources))
+    }
+
+    /// Checks that the settings are consistent with each other
+    ///
+    /// # Errors
+    ///
+    /// Returns an error describing the first invalid setting
+    pub fn validate(&self) -> Result<()> {
+        if self.tls_cert.is_some() != self.tls_key.is_some() {
+            bail!("tls_cert and tls_key must be set together");
+        }
+        if self.socket_path.is_some() && self.tls_cert.is_some() {
+            bail!("socket_path cannot be combined with tls_cert and tls_key");
+        }
+        if self.auth_token.as_deref() == Some("") {
+            bail!("auth_token must not be empty");
+        }
+        if self.rate_limit_per_sec > 0 && self.rate_limit_burst == 0 {
+            bail!("rate_limit_burst must be greater than 0 while rate_limit_per_sec is set");
+        }
+        if self.monitoring_interval_ms == 0 {
+            bail!("monitoring_interval_ms must be greater than 0");
+        }
+        if self.min_interval_ms == 0 {
+            bail!("min_interval_ms must be greater than 0");
+        }
+        if self.monitoring_interval_ms < self.min_interval_ms {
+            bail!(
+                "monitoring_interval_ms ({}) is below min_interval_ms ({})",
+                self.monitoring_interval_ms,
+                self.min_interval_ms
+            );
+        }
+        if self.collection_timeout_ms == 0 {
+            bail!("collection_timeout_ms must be greater than 0");
+        }
+        if self.command_timeout_ms == 0 {
+            bail!("command_timeout_ms must be greater than 0");
+        }
+        if self.history_size == 0 {
+            bail!("history_size must be greater than 0");
+        }
+        if self.collectors.max_processes == Some(0) {
+            bail!("collectors.max_processes must be greater than 0");
+        }
+        let mut names = HashSet::new();
+        for rule in &self.alerts {
+            if !names.insert(rule.name.as_str()) {
+                bail!("Duplicate alert rule name: {}", rule.name);
+            }
+            if !rule.threshold.is_finite() {
+                bail!("Alert rule {} has a non-finite threshold", rule.name);
+            }
+            if let Some(url) = &rule.webhook_url {
+                validate_webhook_url(url)
+                    .with_context(|| format!("Invalid webhook_url for alert rule {}", rule.name))?;
+            }
+        }
+        if let Some(url) = &self.webhook.url {
+            validate_webhook_url(url).context("Invalid webhook url")?;
+        }
+        if let Some(template) = &self.webhook.template {
+            serde_json::from_str::<serde_json::Value>(template)
+                .context("Invalid webhook template")?;
+        }
+        Ok(())
+    }
+
+    /// Logs each setting with its effective value and source at INFO level
+    pub fn log_sources(&self, sources: &BTreeMap<&'static str, ConfigSource>) {
+        for (name, source) in sources {
+            info!("{} = {} ({})", name, self.display_setting(name), source);
+        }
+    }
+
+    /// Reads a TOML file, returning the parsed config and the keys it set
+    fn parse_file(path: &Path) -> Result<(Self, toml::Table)> {
+        let contents = std::fs::read_to_string(path)
+            .with_context(|| format!("Failed to read config file {}", path.display()))?;
+        let table: toml::Table = contents
+            .parse()
+            .with_context(|| format!("Invalid TOML in config file {}", path.display()))?;
+        let config = toml::Value::Table(table.clone())
+            .try_into()
+            .with_context(|| format!("Invalid setting in config file {}", path.display()))?;
+        Ok((config, table))
+    }
+
+    /// Sets one overridable setting from its string form
+    fn apply_override(&mut self, name: &str, value: &str) -> Result<()> {
+        match name {
+            "bind_addr" => self.bind_addr = parse_setting(name, value)?,
+            "port" => self.port = parse_setting(name, value)?,
+            "auth_token" => self.auth_token = Some(value.to_string()),
+            "tls_cert" => self.tls_cert = Some(PathBuf::from(value)),
+            "tls_key" => self.tls_key = Some(PathBuf::from(value)),
+            "socket_path" => self.socket_path = Some(PathBuf::from(value)),
+            "rate_limit_per_sec" => self.rate_limit_per_sec = parse_setting(name, value)?,
+            "rate_limit_burst" => self.rate_limit_burst = parse_setting(name, value)?,
+            "min_interval_ms" => self.min_interval_ms = parse_setting(name, value)?,
+            "collection_timeout_ms" => self.collection_timeout_ms = parse_setting(name, value)?,
+            "command_timeout_ms" => self.command_timeout_ms = parse_setting(name, value)?,
+            "log_format" => self.log_format = parse_setting(name, value)?,
+            _ => bail!("{} cannot be overridden", name),
+        }
+        Ok(())
+    }
+
+    /// Formats a setting for the startup log, redacting secrets
+    fn display_setting(&self, name: &str) -> String {
+        let path = |path: &Option<PathBuf>| {
+            path.as_ref()
+                .map_or_else(|| "unset".to_string(), |path| path.display().to_string())
+        };
+
+        match name {
+            "bind_addr" => self.bind_addr.to_string(),
+            "port" => self.port.to_string(),
+            "auth_token" if self.auth_token.is_some() => "<redacted>".to_string(),
+            "auth_token" => "unset".to_string(),
+            "tls_cert" => path(&self.tls_cert),
+            "tls_key" => path(&self.tls_key),
+            "socket_path" => path(&self.socket_path),
+            "rate_limit_per_sec" if self.rate_limit_per_sec == 0 => "disabled".to_string(),
+            "rate_limit_per_sec" => self.rate_limit_per_sec.to_string(),
+            "rate_limit_burst" => self.rate_limit_burst.to_string(),
+            "monitoring_interval_ms" => self.monitoring_interval_ms.to_string(),
+            "min_interval_ms" => self.min_interval_ms.to_string(),
+            "collection_timeout_ms" => self.collection_timeout_ms.to_string(),
+            "command_timeout_ms" => self.command_timeout_ms.to_string(),
+            "history_size" => self.history_size.to_string(),
+            "log_format" => self.log_format.to_string(),
+            "alerts" => {
+                let names: Vec<&str> = self.alerts.iter().map(|rule| rule.name.as_str()).collect();
+                format!("[{}]", names.join(", "))
+            }
+            "webhook" => format!(
+                "url={}, format={:?}, template={}, cooldown_secs={}, retries={}",
+                // Webhook URLs often embed a secret token
+                if self.webhook.url.is_some() {
+                    "<redacted>"
+                } else {
+                    "unset"
+                },
+                self.webhook.format,
+                if self.webhook.template.is_some() {
+                    "set"
+                } else {
+                    "unset"
+                },
+                self.webhook.cooldown_secs,
+                self.webhook.retries
+            ),
+            "collectors" => format!("{:?}", self.collectors),
+            _ => String::new(),
+        }
+    }
+}
+
+/// Returns the value of a `--name value` or `--name=value` argument
+fn arg_value(args: &[String], name: &str) -> Option<String> {
+    let prefix = format!("{}=", name);
+    args.iter().enumerate().find_map(|(i, arg)| {
+        if arg == name {
+            args.get(i + 1).cloned()
+        } else {
+            arg.strip_prefix(&prefix).map(str::to_string)
+        }
+    })
+}
+
+/// Checks that a webhook URL is an absolute HTTP or HTTPS URL
+fn validate_webhook_url(url: &str) -> Result<()> {
+    let parsed = reqwest::Url::parse(url)?;
+    if !matches!(parsed.scheme(), "http" | "https") {
+        bail!("{} is not an http or https URL", url);
+    }
+    Ok(())
+}
+
+/// Parses an override value, naming the setting on failure
+fn parse_setting<T: FromStr>(name: &str, value: &str) -> Result<T> {
+    value
+        .parse()
+        .map_err(|_| anyhow!("Invalid {} value: {}", name, value))
+}
@@ -0,0 +1,108 @@
+//! Monitor Errors
+//!
+//! Failure kinds reported by the collectors, so callers can tell a missing
+//! command from a permission problem or unparseable output.
+
+use std::io;
+use std::time::Duration;
+use thiserror::Error;
+
+/// Result type returned by the collectors and `SystemMonitor`
+pub type Result<T> = std::result::Result<T, MonitorError>;
+
+/// Error returned when system information cannot be collected
+#[derive(Debug, Error)]
+pub enum MonitorError {
+    /// A system command could not be started
+    #[error("Failed to execute {command}: {error}")]
+    CommandFailed {
+        /// Name of the command
+        command: String,
+        /// Why it could not be run
+        error: io::Error,
+    },
+    /// The caller lacks the privileges for the operation
+    #[error("Permission denied: {0}")]
+    PermissionDenied(String),
+    /// Command output or a system file had an unexpected format
+    #[error("{0}")]
+    ParseError(String),
+    /// The requested process does not exist
+    #[error("Not found: {0}")]
+    NotFound(String),
+    /// An argument was out of range, e.g. an unknown signal number
+    #[error("Invalid argument: {0}")]
+    InvalidArgument(String),
+    /// The platform does not report the requested data or support the operation
+    #[error("{0}")]
+    Unsupported(String),
+    /// Reading a system file failed
+    #[error("{context}: {error}")]
+    Io {
+        /// What was being read
+        context: String,
+        /// The underlying I/O error
+        error: io::Error,
+    },
+    /// The collection did not finish within its timeout
+    #[error("Collection timed out after {0:?}")]
+    Timeout(Duration),
+    /// The collection task panicked
+    #[error("Collection task panicked: {0}")]
+    Panicked(String),
+    /// One collector of a combined snapshot failed
+    #[error("{name} collector failed: {error}")]
+    Collector {
+        /// Name of the failing collector
+        name: &'static str,
+        /// Why it failed
+        error: Box<MonitorError>,
+    },
+}
+
+impl MonitorError {
+    /// Wraps a failure to start `command`
+    pub fn command(command: &str, error: io::Error) -> Self {
+        MonitorError::CommandFailed {
+            command: command.to_string(),
+            error,
+        }
+    }
+
+    /// Wraps an I/O error, classifying permission failures as `PermissionDenied`
+    pub fn io(context: impl Into<String>, error: io::Error) -> Self {
+        let context = context.into();
+        if error.kind() == io::ErrorKind::PermissionDenied {
+            MonitorError::PermissionDenied(context)
+        } else {
+            MonitorError::Io { context, error }
+        }
+    }
+
+    /// Returns the error a combined snapshot failed with, unwrapping `Collector`
+    pub fn root(&self) -> &MonitorError {
+        match self {
+            MonitorError::Collector { error, .. } => error.root(),
+            error => error,
+        }
+    }
+}
+
+#[cfg(unix)]
+impl From<nix::errno::Errno> for MonitorError {
+    fn from(errno: nix::errno::Errno) -> Self {
+        use nix::errno::Errno;
+
+        match errno {
+            Errno::ESRCH => MonitorError::NotFound("No such process".to_string()),
+            Errno::EPERM | Errno::EACCES => {
+                MonitorError::PermissionDenied(errno.desc().to_string())
+            }
+            Errno::EINVAL => MonitorError::InvalidArgument(errno.desc().to_string()),
+            errno => MonitorError::Io {
+                context: "System call failed".to_string(),
+                error: io::Error::from(errno),
+            },
+        }
+    }
+}
@@ -3,50 +3,268 @@
 //! REST API server using Axum for system monitoring data access.
 
 use axum::{
-    extract::State,
-    http::StatusCode,
-    response::{sse::Event, Sse, IntoResponse},
+    extract::{
+        ws::{Message, WebSocket, WebSocketUpgrade},
+        ConnectInfo, Query, State,
+    },
+    http::{header, StatusCode},
+    response::{
+        sse::{Event, KeepAlive},
+        IntoResponse, Response, Sse,
+    },
     routing::{get, post},
     Json, Router,
 };
-use futures::stream;
+use chrono::{DateTime, Utc};
 use futures::stream::StreamExt;
-use tokio::time::interval;
-use tokio_stream::wrappers::IntervalStream;
+use serde::Deserialize;
 use serde_json::{json, Value};
+use axum_server::tls_rustls::RustlsConfig;
+use std::collections::BTreeSet;
+use std::fs::File;
+use std::io::BufReader;
+use std::net::{IpAddr, Ipv4Addr, SocketAddr, TcpListener};
+use std::path::Path;
 use std::sync::Arc;
+use std::time::{Duration, Instant};
 use tokio::sync::RwLock;
-use tracing::{error, info};
+use tokio::time::{interval, MissedTickBehavior};
+use tracing::{error, info, warn};
 
-use crate::server::MCPServer;
+use crate::error::MonitorError;
+use crate::patch::PatchTracker;
+use crate::rate_limit::RateLimiter;
+use crate::server::{LogLevelHandle, MCPServer};
+use crate::system_monitor::core::MIN_INTERVAL_FLOOR;
+use crate::system_monitor::helpers::add_human_sizes;
 use crate::system_monitor::SystemMonitor;
 use crate::types::*;
 use axum::extract::Request;
 use axum::middleware::Next;
+use subtle::ConstantTimeEq;
 
+/// Logs each request and its response status and duration
+///
+/// Method, path, status and duration are event fields rather than part of the
+/// message, so they become keys of their own in JSON log output. Requests
+/// slower than `SLOW_REQUEST_THRESHOLD` are logged at WARN level. No other
+/// headers are logged, so the bearer token never reaches the logs.
 async fn log_requests(req: Request, next: Next) -> impl IntoResponse {
-    let timestamp = chrono::Utc::now().format("%H:%M:%S%.3f");
     let method = req.method().to_string();
     let path = req.uri().path().to_string();
     let headers = req.headers();
     let accept = headers.get("accept").map(|h| h.to_str().unwrap_or("?")).unwrap_or("none");
     let content_type = headers.get("content-type").map(|h| h.to_str().unwrap_or("?")).unwrap_or("none");
     
-    info!("[{}] {} {} (Accept: {}, Content-Type: {})", timestamp, method, path, accept, content_type);
-    
-    if path == "/" && accept.contains("text/event-stream") {
-        info!("🔍 SSE CONNECTION DETECTED - Full headers:");
-        for (name, value) in headers.iter() {
-            let value_str = value.to_str().unwrap_or("<binary>");
-            info!("  {}: {}", name, value_str);
-        }
-    }
+    info!(%method, %path, accept, content_type, "Request");
     
+    let start = Instant::now();
     let res = next.run(req).await;
-    info!("  → {} {}", res.status().as_u16(), res.status().canonical_reason().unwrap_or("?"));
+    let elapsed = start.elapsed();
+    let duration_ms = elapsed.as_millis() as u64;
+    let status = res.status().as_u16();
+    let reason = res.status().canonical_reason().unwrap_or("?");
+    if elapsed > SLOW_REQUEST_THRESHOLD {
+        warn!(%method, %path, status, duration_ms, "→ {} ({} ms, slow)", reason, duration_ms);
+    } else {
+        info!(%method, %path, status, duration_ms, "→ {} ({} ms)", reason, duration_ms);
+    }
     res
 }
 
+/// Rejects requests that lack the configured bearer token
+///
+/// The token is compared in constant time so response timing does not reveal
+/// how many leading bytes of a guess were correct.
+async fn require_bearer_token(
+    State(token): State<Arc<AuthToken>>,
+    req: Request,
+    next: Next,
+) -> Response {
+    let provided = req
+        .headers()
+        .get(header::AUTHORIZATION)
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| value.strip_prefix("Bearer "));
+
+    let authorized = provided
+        .map(|provided| bool::from(provided.as_bytes().ct_eq(token.0.as_bytes())))
+        .unwrap_or(false);
+
+    if authorized {
+        next.run(req).await
+    } else {
+        (
+            StatusCode::UNAUTHORIZED,
+            [(header::WWW_AUTHENTICATE, "Bearer")],
+            Json(json!({"error": "Unauthorized"})),
+        )
+            .into_response()
+    }
+}
+
+/// Rejects requests from clients that exceed the configured rate
+///
+/// Clients are told when to retry with `Retry-After`, in whole seconds.
+async fn limit_rate(State(limiter): State<Arc<RateLimiter>>, req: Request, next: Next) -> Response {
+    let client = req
+        .extensions()
+        .get::<ConnectInfo<SocketAddr>>()
+        .map(|ConnectInfo(addr)| addr.ip());
+
+    match limiter.check(client) {
+        Ok(()) => next.run(req).await,
+        Err(wait) => {
+            let retry_after = wait.as_secs_f64().ceil().max(1.0) as u64;
+            (
+                StatusCode::TOO_MANY_REQUESTS,
+                [(header::RETRY_AFTER, retry_after.to_string())],
+                Json(json!({"error": "Too Many Requests"})),
+            )
+                .into_response()
+        }
+    }
+}
+
+/// Bearer token required by the HTTP API, redacted from debug output
+#[derive(Clone)]
+struct AuthToken(String);
+
+impl std::fmt::Debug for AuthToken {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str("AuthToken(<redacted>)")
+    }
+}
+
+/// Error answer of a REST endpoint: the MCP error with a matching HTTP status
+#[derive(Debug)]
+struct ApiError {
+    /// HTTP status derived from the MCP error code
+    status: StatusCode,
+    /// The MCP error, sent as the response body
+    error: MCPError,
+}
+
+impl ApiError {
+    /// Wraps the error of a failed MCP response
+    ///
+    /// Permission errors map to 403, missing processes to 404, invalid params
+    /// to 400, metrics the platform lacks to 501 and everything else to 500.
+    fn from_mcp(error: Option<MCPError>) -> Self {
+        let error = error.unwrap_or_else(|| MCPError {
+            code: ERROR_INTERNAL_ERROR,
+            message: "Response has neither a result nor an error".to_string(),
+            data: None,
+        });
+        let status = match error.code {
+            ERROR_PERMISSION_DENIED => StatusCode::FORBIDDEN,
+            ERROR_PROCESS_NOT_FOUND => StatusCode::NOT_FOUND,
+            ERROR_INVALID_PARAMS => StatusCode::BAD_REQUEST,
+            ERROR_UNSUPPORTED => StatusCode::NOT_IMPLEMENTED,
+            _ => StatusCode::INTERNAL_SERVER_ERROR,
+        };
+        Self { status, error }
+    }
+}
+
+impl IntoResponse for ApiError {
+    fn into_response(self) -> Response {
+        (self.status, Json(json!({ "error": self.error }))).into_response()
+    }
+}
+
+/// Time after which a request is logged as slow
+const SLOW_REQUEST_THRESHOLD: Duration = Duration::from_secs(1);
+
+/// Default seconds between SSE metrics samples
+const SSE_DEFAULT_INTERVAL_SECS: u64 = 5;
+
+/// Idle time after which an SSE heartbeat comment is sent
+const SSE_HEARTBEAT_INTERVAL: Duration = Duration::from_secs(10);
+
+/// Send a full SSE sample after this many merge patches
+const SSE_PATCH_FULL_EVERY: u32 = 12;
+
+/// End an SSE stream after this many consecutive collection failures
+const SSE_MAX_CONSECUTIVE_FAILURES: u32 = 3;
+
+/// Default seconds between WebSocket metrics frames
+const WS_DEFAULT_INTERVAL_SECS: u64 = 5;
+
+/// Send a full WebSocket frame after this many merge patches
+const WS_PATCH_FULL_EVERY: u32 = 12;
+
+/// Content type of the Prometheus text exposition format
+const PROMETHEUS_CONTENT_TYPE: &str = "text/plain; version=0.0.4; charset=utf-8";
+
+/// Content type of the OpenMetrics 1.0 text format
+const OPENMETRICS_CONTENT_TYPE: &str = "application/openmetrics-text; version=1.0.0; charset=utf-8";
+
+/// Content type of InfluxDB line protocol
+const INFLUX_CONTENT_TYPE: &str = "text/plain; charset=utf-8";
+
+/// Units declared in OpenMetrics `# UNIT` lines, when a family name ends in one
+const OPENMETRICS_UNITS: &[&str] = &["bytes", "celsius", "mhz", "percent"];
+
+/// Byte count fields of `MemoryInfo`, formatted on `?human=true`
+const MEMORY_BYTE_FIELDS: &[&str] = &[
+    "total",
+    "used",
+    "free",
+    "available",
+    "swap_total",
+    "swap_used",
+    "swap_free",
+    "buffers",
+    "cached",
+    "slab",
+    "shmem",
+    "dirty",
+    "limit_bytes",
+];
+
+/// Byte count fields of `SystemSummary`
+const SUMMARY_BYTE_FIELDS: &[&str] = &["memory_used", "memory_total"];
+
+/// Byte count fields of `SwapDevice`
+const SWAP_BYTE_FIELDS: &[&str] = &["size_bytes", "used_bytes"];
+
+/// Byte count fields of `DiskInfo`
+const DISK_BYTE_FIELDS: &[&str] = &["total_space", "used_space", "free_space"];
+
+/// Byte count fields of `NetworkInfo`
+const NETWORK_BYTE_FIELDS: &[&str] = &["bytes_received", "bytes_transmitted"];
+
+/// Byte count fields of `DiskIOStats`
+const DISK_IO_BYTE_FIELDS: &[&str] = &["read_bytes", "write_bytes"];
+
+/// Byte count fields of `GpuInfo`
+const GPU_BYTE_FIELDS: &[&str] = &["memory_used", "memory_total"];
+
+/// Default permissions of the Unix socket: only the owner may connect
+pub const DEFAULT_SOCKET_MODE: u32 = 0o600;
+
+/// Pause after a failed accept on the Unix socket, as `axum::serve` does
+#[cfg(unix)]
+const UDS_ACCEPT_BACKOFF: Duration = Duration::from_secs(1);
+
+/// Byte count fields of `ProcessInfo`
+const PROCESS_BYTE_FIELDS: &[&str] = &["memory_usage", "read_bytes", "write_bytes"];
+
+/// Byte count fields of `ProcessMemory`
+const PROCESS_MEMORY_BYTE_FIELDS: &[&str] = &[
+    "vm_size",
+    "vm_rss",
+    "vm_data",
+    "vm_stk",
+    "vm_exe",
+    "vm_lib",
+    "vm_swap",
+    "rss_anon",
+    "rss_file",
+    "rss_shmem",
+];
+
 /// HTTP Server for REST API access to system monitoring
 ///
 /// Implements REST API server using Axum that provides HTTP endpoints
@@ -55,6 +273,15 @@ async fn log_requests(req: Request, next: Next) -> impl IntoResponse {
 pub struct HTTPServer {
     /// Internal MCP server for system monitoring requests
     mcp_server: MCPServer,
+    /// Bearer token required on every route except /health (None leaves the API open)
+    auth_token: Option<AuthToken>,
+    /// Address the listener binds to (defaults to all IPv4 interfaces)
+    bind_addr: IpAddr,
+    /// Per-client request limit on every route except /health (None leaves it unlimited)
+    rate_limiter: Option<Arc<RateLimiter>>,
+    /// Permissions of the socket file created by `run_uds`
+    #[cfg(unix)]
+    socket_mode: u32,
 }
 
 impl HTTPServer {
@@ -62,269 +289,930 @@ impl HTTPServer {
     pub fn new(system_monitor: Arc<RwLock<SystemMonitor>>) -> Self {
         Self {
             mcp_server: MCPServer::new(system_monitor),
+            auth_token: None,
+            bind_addr: IpAddr::V4(Ipv4Addr::UNSPECIFIED),
+            rate_limiter: None,
+            #[cfg(unix)]
+            socket_mode: DEFAULT_SOCKET_MODE,
         }
     }
 
+    /// Requires `Authorization: Bearer <token>` on every route except /health
+    pub fn with_auth_token(mut self, token: impl Into<String>) -> Self {
+        self.auth_token = Some(AuthToken(token.into()));
+        self
+    }
+
+    /// Binds to `addr` instead of all interfaces, e.g. 127.0.0.1 for loopback only
+    pub fn with_bind_addr(mut self, addr: IpAddr) -> Self {
+        self.bind_addr = addr;
+        self
+    }
+
+    /// Limits each client address to `per_second` requests per second, with
+    /// bursts of up to `burst`, on every route except /health
+    ///
+    /// Clients over the limit get 429 Too Many Requests. All clients of a
+    /// Unix socket share one limit.
+    pub fn with_rate_limit(mut self, per_second: u32, burst: u32) -> Self {
+        self.rate_limiter = Some(Arc::new(RateLimiter::new(per_second, burst)));
+        self
+    }
+
+    /// Fails collections that take longer than `timeout` instead of the default
+    pub fn with_collection_timeout(mut self, timeout: Duration) -> Self {
+        self.mcp_server = self.mcp_server.with_collection_timeout(timeout);
+        self
+    }
+
+    /// Lets clients change the log level with `logging/setLevel`
+    pub fn with_log_level_handle(mut self, handle: LogLevelHandle) -> Self {
+        self.mcp_server = self.mcp_server.with_log_level_handle(handle);
+        self
+    }
+
+    /// Creates the `run_uds` socket with `mode` instead of 0600, e.g. 0660
+    /// to let a group connect
+    #[cfg(unix)]
+    pub fn with_socket_mode(mut self, mode: u32) -> Self {
+        self.socket_mode = mode;
+        self
+    }
+
     /// Runs HTTP server on specified port
     pub async fn run(self, port: u16) -> Result<(), Box<dyn std::error::Error>> {
+        let listener = self.bind_listener(port)?;
+        info!("Starting HTTP server on http://{}", listener.local_addr()?);
+
+        // Client addresses key the rate limiter
+        let app = self
+            .router()
+            .into_make_service_with_connect_info::<SocketAddr>();
+        axum::serve(tokio::net::TcpListener::from_std(listener)?, app).await?;
+
+        Ok(())
+    }
+
+    /// Runs HTTPS server on specified port using a PEM certificate chain and private key
+    ///
+    /// The certificate and key are loaded before binding, so a missing or
+    /// malformed file fails startup instead of the first handshake.
+    pub async fn run_tls(
+        self,
+        port: u16,
+        cert_path: impl AsRef<Path>,
+        key_path: impl AsRef<Path>,
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        let tls_config = load_tls_config(cert_path.as_ref(), key_path.as_ref())?;
+        let listener = self.bind_listener(port)?;
+        info!(
+            "Starting HTTPS server on https://{}",
+            listener.local_addr()?
+        );
+
+        let app = self.router();
+        axum_server::from_tcp_rustls(listener, RustlsConfig::from_config(Arc::new(tls_config)))
+            .serve(app.into_make_service_with_connect_info::<SocketAddr>())
+            .await?;
+
+        Ok(())
+    }
+
+    /// Runs HTTP server on a Unix domain socket at `path`
+    ///
+    /// Serves the same routes as `run`, until Ctrl-C or SIGTERM, then removes
+    /// the socket file.
+    #[cfg(unix)]
+    pub async fn run_uds(self, path: impl AsRef<Path>) -> Result<(), Box<dyn std::error::Error>> {
+        self.run_uds_with_shutdown(path, shutdown_signal()).await
+    }
+
+    /// Runs HTTP server on a Unix domain socket at `path` until `shutdown` completes
+    ///
+    /// A socket file left behind by a server that did not shut down cleanly is
+    /// replaced, but one another server still accepts connections on is not.
+    /// The socket is created with mode 0600 unless `with_socket_mode` sets
+    /// another, and removed again when the server stops.
+    #[cfg(unix)]
+    pub async fn run_uds_with_shutdown(
+        self,
+        path: impl AsRef<Path>,
+        shutdown: impl std::future::Future<Output = ()>,
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        use hyper_util::rt::{TokioExecutor, TokioIo};
+        use hyper_util::server::conn::auto;
+        use hyper_util::service::TowerToHyperService;
+
+        let path = path.as_ref();
+        let (listener, socket_file) = bind_unix_socket(path, self.socket_mode)?;
+        info!("Starting HTTP server on unix:{}", path.display());
+
+        let app = self.router();
+        tokio::pin!(shutdown);
+        loop {
+            let stream = tokio::select! {
+                accepted = listener.accept() => match accepted {
+                    Ok((stream, _)) => stream,
+                    Err(e) => {
+                        error!("Failed to accept connection on {}: {}", path.display(), e);
+                        // Errors like EMFILE persist for a while, so retrying
+                        // at once would spin and flood the log
+                        tokio::select! {
+                            () = tokio::time::sleep(UDS_ACCEPT_BACKOFF) => continue,
+                            () = &mut shutdown => break,
+                        }
+                    }
+                },
+                () = &mut shutdown => break,
+            };
+
+            let service = TowerToHyperService::new(app.clone());
+            tokio::spawn(async move {
+                // Upgrades are needed for /ws
+                if let Err(e) = auto::Builder::new(TokioExecutor::new())
+                    .serve_connection_with_upgrades(TokioIo::new(stream), service)
+                    .await
+                {
+                    error!("Unix socket connection failed: {}", e);
+                }
+            });
+        }
+
+        info!("Shutting down, removing {}", path.display());
+        drop(socket_file);
+        Ok(())
+    }
+
+    /// Binds the listening socket up front so an unusable address fails startup
+    fn bind_listener(&self, port: u16) -> anyhow::Result<TcpListener> {
+        let addr = SocketAddr::new(self.bind_addr, port);
+        let listener = TcpListener::bind(addr).map_err(|e| match e.kind() {
+            std::io::ErrorKind::AddrInUse => anyhow::anyhow!(
+                "Port {} is already in use on {}; stop the other process or choose another port with --port or MCP_PORT",
+                port,
+                self.bind_addr
+            ),
+            std::io::ErrorKind::AddrNotAvailable => anyhow::anyhow!(
+                "Cannot bind to {}: the address is not assigned to this host",
+                addr
+            ),
+            _ => anyhow::anyhow!("Failed to bind {}: {}", addr, e),
+        })?;
+        listener.set_nonblocking(true)?;
+        Ok(listener)
+    }
+
+    /// Builds the router with all routes, without binding a listener
+    pub fn router(self) -> Router {
         let mcp_server = Arc::new(self.mcp_server);
 
-        let app = Router::new()
+        let mut routes = Router::new()
             .route("/", post(Self::handle_mcp_request).get(Self::handle_sse))
-            .route("/health", get(Self::health_check))
-            .layer(axum::middleware::from_fn(log_requests))
+            .route("/metrics", get(Self::prometheus_metrics))
+            .route("/metrics/influx", get(Self::influx_metrics))
+            .route("/ws", get(Self::handle_ws))
             .route("/api/system/info", get(Self::get_system_info))
+            .route("/api/system/uptime", get(Self::get_uptime))
+            .route("/api/system/summary", get(Self::get_summary))
             .route("/api/system/cpu", get(Self::get_cpu_info))
             .route("/api/system/memory", get(Self::get_memory_info))
+            .route("/api/system/loadavg", get(Self::get_load_average))
+            .route("/api/system/kernel-stats", get(Self::get_kernel_stats))
+            .route("/api/system/fd-usage", get(Self::get_fd_usage))
+            .route("/api/system/entropy", get(Self::get_entropy))
+            .route("/api/system/pressure", get(Self::get_pressure))
+            .route("/api/system/virtualization", get(Self::get_virtualization))
+            .route("/api/system/swaps", get(Self::get_swaps))
+            .route("/api/system/numa", get(Self::get_numa_topology))
+            .route("/api/system/block-devices", get(Self::get_block_devices))
             .route("/api/system/disks", get(Self::get_disk_info))
+            .route("/api/system/dir-usage", get(Self::get_directory_usage))
             .route("/api/system/networks", get(Self::get_network_info))
+            .route("/api/system/network-rates", get(Self::get_network_rates))
+            .route(
+                "/api/system/connections",
+                get(Self::get_network_connections),
+            )
+            .route("/api/system/socket-summary", get(Self::get_socket_summary))
+            .route("/api/system/disk-io", get(Self::get_disk_io))
+            .route("/api/system/temperatures", get(Self::get_temperatures))
+            .route("/api/system/battery", get(Self::get_battery_info))
+            .route("/api/system/gpu", get(Self::get_gpu_info))
+            .route("/api/system/smart", get(Self::get_disk_smart))
+            .route("/api/system/services", get(Self::get_services))
+            .route("/api/system/logs", get(Self::get_recent_logs))
+            .route("/api/system/history", get(Self::get_history))
+            .route("/api/system/stats", get(Self::get_history_stats))
             .route("/api/system/processes", get(Self::get_processes))
+            .route("/api/system/top", get(Self::get_top_processes))
+            .route("/api/self", get(Self::get_self))
             .route("/api/system/processes/:pid", get(Self::get_process_by_pid))
+            .route(
+                "/api/system/processes/:pid/tree",
+                get(Self::get_process_tree),
+            )
+            .route(
+                "/api/system/processes/:pid/memory",
+                get(Self::get_process_memory),
+            )
+            .route(
+                "/api/system/processes/:pid/limits",
+                get(Self::get_process_limits),
+            )
+            .route(
+                "/api/system/processes/:pid/signal",
+                post(Self::signal_process),
+            )
+            .route(
+                "/api/system/processes/:pid/nice",
+                post(Self::renice_process),
+            )
             .route("/api/system/metrics", get(Self::get_system_metrics))
+            .route("/api/system/diff", post(Self::diff_metrics))
             .route("/api/monitoring/start", post(Self::start_monitoring))
             .route("/api/monitoring/stop", post(Self::stop_monitoring))
+            .route(
+                "/api/monitoring/interval",
+                post(Self::set_monitoring_interval),
+            )
             .route("/api/monitoring/status", get(Self::get_monitoring_status))
-            .with_state(mcp_server);
+            .route("/api/alerts", get(Self::get_alerts));
 
-        let addr = format!("0.0.0.0:{}", port);
-        info!("Starting HTTP server on {}", addr);
+        if let Some(token) = self.auth_token {
+            routes = routes.route_layer(axum::middleware::from_fn_with_state(
+                Arc::new(token),
+                require_bearer_token,
+            ));
+        }
 
-        let listener = tokio::net::TcpListener::bind(&addr).await?;
-        axum::serve(listener, app).await?;
+        // Limit before authenticating, so floods of bad tokens are throttled too
+        if let Some(limiter) = self.rate_limiter {
+            routes = routes.route_layer(axum::middleware::from_fn_with_state(limiter, limit_rate));
+        }
 
-        Ok(())
+        // /health stays open so load balancers and orchestrators can probe it,
+        // and /version so deployments can be identified without credentials
+        routes
+            .route("/health", get(Self::health_check))
+            .route("/version", get(Self::version))
+            .layer(axum::middleware::from_fn(log_requests))
+            .with_state(mcp_server)
+    }
+
+    /// GET /version - Name, version and provenance of the running build
+    async fn version() -> Json<BuildInfo> {
+        Json(BuildInfo::current())
     }
 
     /// Health check endpoint
-    async fn health_check() -> Json<Value> {
+    ///
+    /// Probes the core data source instead of collecting full metrics, and
+    /// answers 503 when it is unavailable so load balancers stop routing here.
+    async fn health_check(State(server): State<Arc<MCPServer>>) -> Response {
         info!("🏥 Health check requested");
-        Json(json!({
-            "status": "healthy",
-            "service": "MCP System Monitor",
-            "timestamp": chrono::Utc::now()
-        }))
+        match server.probe().await {
+            Ok(()) => Json(json!({
+                "status": "healthy",
+                "service": "MCP System Monitor",
+                "timestamp": chrono::Utc::now()
+            }))
+            .into_response(),
+            Err(e) => {
+                error!("Health probe failed: {}", e);
+                (
+                    StatusCode::SERVICE_UNAVAILABLE,
+                    Json(json!({
+                        "status": "degraded",
+                        "reason": e.to_string(),
+                        "service": "MCP System Monitor",
+                        "timestamp": chrono::Utc::now()
+                    })),
+                )
+                    .into_response()
+            }
+        }
     }
 
-    /// GET /api/system/info - Get system information
-    async fn get_system_info(
+    /// GET /metrics - Current metrics in Prometheus or OpenMetrics text format
+    async fn prometheus_metrics(
         State(server): State<Arc<MCPServer>>,
-    ) -> Result<Json<Value>, StatusCode> {
-        let request = MCPRequest {
-            jsonrpc: "2.0".to_string(),
-            id: Some(uuid::Uuid::new_v4().to_string()),
-            method: METHOD_GET_SYSTEM_INFO.to_string(),
-            params: json!({}),
-        };
+        Query(query): Query<ExpositionQuery>,
+    ) -> Response {
+        let families = SystemMonitor::run_blocking(
+            &server.system_monitor(),
+            server.collection_timeout(),
+            metric_families,
+        )
+        .await
+        .map(|mut families| {
+            families.extend(request_families(&server));
+            families
+        });
 
-        let response = server.handle_request(request).await;
-        match response.result {
-            Some(result) => Ok(Json(result)),
-            None => {
-                error!("Failed to get system info: {:?}", response.error);
-                Err(StatusCode::INTERNAL_SERVER_ERROR)
+        match families {
+            Ok(families) => match query.format {
+                ExpositionFormat::Prometheus => (
+                    [(header::CONTENT_TYPE, PROMETHEUS_CONTENT_TYPE)],
+                    render_prometheus(&families),
+                )
+                    .into_response(),
+                ExpositionFormat::OpenMetrics => (
+                    [(header::CONTENT_TYPE, OPENMETRICS_CONTENT_TYPE)],
+                    render_openmetrics(&families),
+                )
+                    .into_response(),
+            },
+            Err(e) => {
+                error!("Failed to collect Prometheus metrics: {}", e);
+                (
+                    StatusCode::INTERNAL_SERVER_ERROR,
+                    format!("Failed to collect metrics: {}", e),
+                )
+                    .into_response()
             }
         }
     }
 
-    /// GET /api/system/cpu - Get CPU information
-    async fn get_cpu_info(State(server): State<Arc<MCPServer>>) -> Result<Json<Value>, StatusCode> {
-        let request = MCPRequest {
-            jsonrpc: "2.0".to_string(),
-            id: Some(uuid::Uuid::new_v4().to_string()),
-            method: METHOD_GET_CPU_INFO.to_string(),
-            params: json!({}),
-        };
+    /// GET /metrics/influx - Current metrics in InfluxDB line protocol
+    async fn influx_metrics(State(server): State<Arc<MCPServer>>) -> Response {
+        let collected = SystemMonitor::run_blocking(
+            &server.system_monitor(),
+            server.collection_timeout(),
+            |monitor| {
+                let families = metric_families(monitor)?;
+                let host = monitor.static_info().hostname.clone();
+                Ok((families, host, monitor.last_update()))
+            },
+        )
+        .await;
 
-        let response = server.handle_request(request).await;
-        match response.result {
-            Some(result) => Ok(Json(result)),
-            None => {
-                error!("Failed to get CPU info: {:?}", response.error);
-                Err(StatusCode::INTERNAL_SERVER_ERROR)
+        match collected {
+            Ok((mut families, host, timestamp)) => {
+                families.extend(request_families(&server));
+                (
+                    [(header::CONTENT_TYPE, INFLUX_CONTENT_TYPE)],
+                    render_influx(&families, &host, timestamp),
+                )
+                    .into_response()
+            }
+            Err(e) => {
+                error!("Failed to collect InfluxDB metrics: {}", e);
+                (
+                    StatusCode::INTERNAL_SERVER_ERROR,
+                    format!("Failed to collect metrics: {}", e),
+                )
+                    .into_response()
             }
         }
     }
 
+    /// GET /api/system/info - Get system information
+    async fn get_system_info(
+        State(server): State<Arc<MCPServer>>,
+    ) -> Result<Json<Value>, ApiError> {
+        Self::call_method(&server, METHOD_GET_SYSTEM_INFO, json!({}), "system info").await
+    }
+
+    /// GET /api/system/uptime - Get the uptime and boot time
+    async fn get_uptime(State(server): State<Arc<MCPServer>>) -> Result<Json<Value>, ApiError> {
+        Self::call_method(&server, METHOD_GET_UPTIME, json!({}), "uptime").await
+    }
+
+    /// GET /api/system/summary - Get headline numbers for status pages
+    async fn get_summary(
+        State(server): State<Arc<MCPServer>>,
+        Query(human): Query<HumanQuery>,
+    ) -> Result<Json<Value>, ApiError> {
+        let result = Self::call_method(&server, METHOD_GET_SUMMARY, json!({}), "summary").await;
+        Self::human_sizes(result, human, SUMMARY_BYTE_FIELDS)
+    }
+
+    /// GET /api/system/cpu - Get CPU information
+    async fn get_cpu_info(State(server): State<Arc<MCPServer>>) -> Result<Json<Value>, ApiError> {
+        Self::call_method(&server, METHOD_GET_CPU_INFO, json!({}), "CPU info").await
+    }
+
     /// GET /api/system/memory - Get memory information
     async fn get_memory_info(
         State(server): State<Arc<MCPServer>>,
-    ) -> Result<Json<Value>, StatusCode> {
-        let request = MCPRequest {
-            jsonrpc: "2.0".to_string(),
-            id: Some(uuid::Uuid::new_v4().to_string()),
-            method: METHOD_GET_MEMORY_INFO.to_string(),
-            params: json!({}),
-        };
+        Query(human): Query<HumanQuery>,
+    ) -> Result<Json<Value>, ApiError> {
+        let result =
+            Self::call_method(&server, METHOD_GET_MEMORY_INFO, json!({}), "memory info").await;
+        Self::human_sizes(result, human, MEMORY_BYTE_FIELDS)
+    }
 
-        let response = server.handle_request(request).await;
-        match response.result {
-            Some(result) => Ok(Json(result)),
-            None => {
-                error!("Failed to get memory info: {:?}", response.error);
-                Err(StatusCode::INTERNAL_SERVER_ERROR)
-            }
-        }
+    /// GET /api/system/loadavg - Get load average
+    async fn get_load_average(
+        State(server): State<Arc<MCPServer>>,
+    ) -> Result<Json<Value>, ApiError> {
+        Self::call_method(&server, METHOD_GET_LOAD_AVERAGE, json!({}), "load average").await
+    }
+
+    /// GET /api/system/kernel-stats - Get scheduler and process counters
+    async fn get_kernel_stats(
+        State(server): State<Arc<MCPServer>>,
+    ) -> Result<Json<Value>, ApiError> {
+        Self::call_method(&server, METHOD_GET_KERNEL_STATS, json!({}), "kernel stats").await
+    }
+
+    /// GET /api/system/fd-usage - Get system-wide file handle usage
+    async fn get_fd_usage(State(server): State<Arc<MCPServer>>) -> Result<Json<Value>, ApiError> {
+        Self::call_method(&server, METHOD_GET_FD_USAGE, json!({}), "file handle usage").await
+    }
+
+    /// GET /api/system/entropy - Get the kernel entropy pool
+    async fn get_entropy(State(server): State<Arc<MCPServer>>) -> Result<Json<Value>, ApiError> {
+        Self::call_method(&server, METHOD_GET_ENTROPY, json!({}), "entropy").await
+    }
+
+    /// GET /api/system/pressure - Get pressure stall information
+    async fn get_pressure(State(server): State<Arc<MCPServer>>) -> Result<Json<Value>, ApiError> {
+        Self::call_method(
+            &server,
+            METHOD_GET_PRESSURE,
+            json!({}),
+            "pressure stall information",
+        )
+        .await
+    }
+
+    /// GET /api/system/virtualization - Get the hypervisor or container environment
+    async fn get_virtualization(
+        State(server): State<Arc<MCPServer>>,
+    ) -> Result<Json<Value>, ApiError> {
+        Self::call_method(
+            &server,
+            METHOD_GET_VIRTUALIZATION,
+            json!({}),
+            "virtualization info",
+        )
+        .await
+    }
+
+    /// GET /api/system/swaps - Get per-device swap usage
+    async fn get_swaps(
+        State(server): State<Arc<MCPServer>>,
+        Query(human): Query<HumanQuery>,
+    ) -> Result<Json<Value>, ApiError> {
+        let result = Self::call_method(&server, METHOD_GET_SWAPS, json!({}), "swap devices").await;
+        Self::human_sizes(result, human, SWAP_BYTE_FIELDS)
+    }
+
+    /// GET /api/system/numa - Get NUMA nodes with their CPUs and memory
+    async fn get_numa_topology(
+        State(server): State<Arc<MCPServer>>,
+    ) -> Result<Json<Value>, ApiError> {
+        Self::call_method(
+            &server,
+            METHOD_GET_NUMA_TOPOLOGY,
+            json!({}),
+            "NUMA topology",
+        )
+        .await
+    }
+
+    /// GET /api/system/block-devices - Get disks and their partitions
+    async fn get_block_devices(
+        State(server): State<Arc<MCPServer>>,
+    ) -> Result<Json<Value>, ApiError> {
+        Self::call_method(
+            &server,
+            METHOD_GET_BLOCK_DEVICES,
+            json!({}),
+            "block devices",
+        )
+        .await
     }
 
     /// GET /api/system/disks - Get disk information
     async fn get_disk_info(
         State(server): State<Arc<MCPServer>>,
-    ) -> Result<Json<Value>, StatusCode> {
-        let request = MCPRequest {
-            jsonrpc: "2.0".to_string(),
-            id: Some(uuid::Uuid::new_v4().to_string()),
-            method: METHOD_GET_DISK_INFO.to_string(),
-            params: json!({}),
-        };
+        Query(human): Query<HumanQuery>,
+    ) -> Result<Json<Value>, ApiError> {
+        let result = Self::call_method(&server, METHOD_GET_DISK_INFO, json!({}), "disk info").await;
+        Self::human_sizes(result, human, DISK_BYTE_FIELDS)
+    }
 
-        let response = server.handle_request(request).await;
-        match response.result {
-            Some(result) => Ok(Json(result)),
-            None => {
-                error!("Failed to get disk info: {:?}", response.error);
-                Err(StatusCode::INTERNAL_SERVER_ERROR)
-            }
-        }
+    /// GET /api/system/dir-usage - Get the size of a directory and its largest subdirectories
+    async fn get_directory_usage(
+        State(server): State<Arc<MCPServer>>,
+        Query(query): Query<DirUsageQuery>,
+    ) -> Result<Json<Value>, ApiError> {
+        Self::call_method(
+            &server,
+            METHOD_GET_DIRECTORY_USAGE,
+            json!(query),
+            "directory usage",
+        )
+        .await
     }
 
     /// GET /api/system/networks - Get network information
     async fn get_network_info(
         State(server): State<Arc<MCPServer>>,
-    ) -> Result<Json<Value>, StatusCode> {
-        let request = MCPRequest {
-            jsonrpc: "2.0".to_string(),
-            id: Some(uuid::Uuid::new_v4().to_string()),
-            method: METHOD_GET_NETWORK_INFO.to_string(),
-            params: json!({}),
-        };
+        Query(query): Query<NetworkQuery>,
+        Query(human): Query<HumanQuery>,
+    ) -> Result<Json<Value>, ApiError> {
+        let result = Self::call_method(
+            &server,
+            METHOD_GET_NETWORK_INFO,
+            json!(query),
+            "network info",
+        )
+        .await;
+        Self::human_sizes(result, human, NETWORK_BYTE_FIELDS)
+    }
 
-        let response = server.handle_request(request).await;
-        match response.result {
-            Some(result) => Ok(Json(result)),
-            None => {
-                error!("Failed to get network info: {:?}", response.error);
-                Err(StatusCode::INTERNAL_SERVER_ERROR)
-            }
-        }
+    /// GET /api/system/network-rates - Get network throughput since the previous call
+    async fn get_network_rates(
+        State(server): State<Arc<MCPServer>>,
+    ) -> Result<Json<Value>, ApiError> {
+        Self::call_method(
+            &server,
+            METHOD_GET_NETWORK_RATES,
+            json!({}),
+            "network rates",
+        )
+        .await
+    }
+
+    /// GET /api/system/connections - Get TCP and UDP sockets, optionally one state only
+    async fn get_network_connections(
+        State(server): State<Arc<MCPServer>>,
+        Query(query): Query<ConnectionQuery>,
+    ) -> Result<Json<Value>, ApiError> {
+        Self::call_method(
+            &server,
+            METHOD_GET_NETWORK_CONNECTIONS,
+            json!(query),
+            "network connections",
+        )
+        .await
+    }
+
+    /// GET /api/system/socket-summary - Get the number of TCP sockets in each state
+    async fn get_socket_summary(
+        State(server): State<Arc<MCPServer>>,
+    ) -> Result<Json<Value>, ApiError> {
+        Self::call_method(
+            &server,
+            METHOD_GET_SOCKET_SUMMARY,
+            json!({}),
+            "socket summary",
+        )
+        .await
+    }
+
+    /// GET /api/system/disk-io - Get disk I/O counters
+    async fn get_disk_io(
+        State(server): State<Arc<MCPServer>>,
+        Query(human): Query<HumanQuery>,
+    ) -> Result<Json<Value>, ApiError> {
+        let result =
+            Self::call_method(&server, METHOD_GET_DISK_IO, json!({}), "disk I/O stats").await;
+        Self::human_sizes(result, human, DISK_IO_BYTE_FIELDS)
+    }
+
+    /// GET /api/system/temperatures - Get all temperature sensor readings
+    async fn get_temperatures(
+        State(server): State<Arc<MCPServer>>,
+    ) -> Result<Json<Value>, ApiError> {
+        Self::call_method(&server, METHOD_GET_TEMPERATURES, json!({}), "temperatures").await
+    }
+
+    /// GET /api/system/battery - Get battery charge state
+    async fn get_battery_info(
+        State(server): State<Arc<MCPServer>>,
+    ) -> Result<Json<Value>, ApiError> {
+        Self::call_method(&server, METHOD_GET_BATTERY_INFO, json!({}), "battery info").await
+    }
+
+    /// GET /api/system/gpu - Get NVIDIA GPU utilization and memory
+    async fn get_gpu_info(
+        State(server): State<Arc<MCPServer>>,
+        Query(human): Query<HumanQuery>,
+    ) -> Result<Json<Value>, ApiError> {
+        let result = Self::call_method(&server, METHOD_GET_GPU_INFO, json!({}), "GPU info").await;
+        Self::human_sizes(result, human, GPU_BYTE_FIELDS)
+    }
+
+    /// GET /api/system/smart - Get SMART health of each drive
+    async fn get_disk_smart(State(server): State<Arc<MCPServer>>) -> Result<Json<Value>, ApiError> {
+        Self::call_method(&server, METHOD_GET_DISK_SMART, json!({}), "SMART data").await
+    }
+
+    /// GET /api/system/services - Get systemd service units
+    async fn get_services(
+        State(server): State<Arc<MCPServer>>,
+        Query(query): Query<ServiceQuery>,
+    ) -> Result<Json<Value>, ApiError> {
+        Self::call_method(&server, METHOD_GET_SERVICES, json!(query), "services").await
     }
 
-    /// GET /api/system/processes - Get all processes
+    /// GET /api/system/logs - Get the most recent journal or syslog entries
+    async fn get_recent_logs(
+        State(server): State<Arc<MCPServer>>,
+        Query(query): Query<LogQuery>,
+    ) -> Result<Json<Value>, ApiError> {
+        Self::call_method(&server, METHOD_GET_RECENT_LOGS, json!(query), "recent logs").await
+    }
+
+    /// GET /api/system/history - Get recent samples, optionally one metric only
+    async fn get_history(
+        State(server): State<Arc<MCPServer>>,
+        Query(query): Query<HistoryQuery>,
+    ) -> Result<Json<Value>, ApiError> {
+        Self::call_method(&server, METHOD_GET_HISTORY, json!(query), "history").await
+    }
+
+    /// GET /api/system/stats - Get summary statistics of a metric over the history
+    async fn get_history_stats(
+        State(server): State<Arc<MCPServer>>,
+        Query(query): Query<StatsQuery>,
+    ) -> Result<Json<Value>, ApiError> {
+        Self::call_method(
+            &server,
+            METHOD_GET_HISTORY_STATS,
+            json!(query),
+            "history stats",
+        )
+        .await
+    }
+
+    /// GET /api/system/processes - Get processes, optionally sorted and paginated
     async fn get_processes(
         State(server): State<Arc<MCPServer>>,
-    ) -> Result<Json<Value>, StatusCode> {
-        let request = MCPRequest {
-            jsonrpc: "2.0".to_string(),
-            id: Some(uuid::Uuid::new_v4().to_string()),
-            method: METHOD_GET_PROCESSES.to_string(),
-            params: json!({}),
-        };
+        Query(query): Query<ProcessQuery>,
+        Query(human): Query<HumanQuery>,
+    ) -> Result<Json<Value>, ApiError> {
+        let result =
+            Self::call_method(&server, METHOD_GET_PROCESSES, json!(query), "processes").await;
+        Self::human_sizes(result, human, PROCESS_BYTE_FIELDS)
+    }
 
-        let response = server.handle_request(request).await;
-        match response.result {
-            Some(result) => Ok(Json(result)),
-            None => {
-                error!("Failed to get processes: {:?}", response.error);
-                Err(StatusCode::INTERNAL_SERVER_ERROR)
-            }
-        }
+    /// GET /api/system/top - Get the processes using the most CPU, memory or I/O
+    async fn get_top_processes(
+        State(server): State<Arc<MCPServer>>,
+        Query(query): Query<TopQuery>,
+    ) -> Result<Json<Value>, ApiError> {
+        Self::call_method(
+            &server,
+            METHOD_GET_TOP_PROCESSES,
+            json!(query),
+            "top processes",
+        )
+        .await
     }
 
     /// GET /api/system/processes/{pid} - Get specific process by PID
     async fn get_process_by_pid(
         State(server): State<Arc<MCPServer>>,
         axum::extract::Path(pid): axum::extract::Path<u32>,
-    ) -> Result<Json<Value>, StatusCode> {
-        let request = MCPRequest {
-            jsonrpc: "2.0".to_string(),
-            id: Some(uuid::Uuid::new_v4().to_string()),
-            method: METHOD_GET_PROCESS_BY_PID.to_string(),
-            params: json!({"pid": pid}),
-        };
+        Query(human): Query<HumanQuery>,
+    ) -> Result<Json<Value>, ApiError> {
+        let result = Self::call_method(
+            &server,
+            METHOD_GET_PROCESS_BY_PID,
+            json!({"pid": pid}),
+            "process",
+        )
+        .await;
+        Self::human_sizes(result, human, PROCESS_BYTE_FIELDS)
+    }
 
-        let response = server.handle_request(request).await;
-        match response.result {
-            Some(result) => Ok(Json(result)),
-            None => {
-                error!("Failed to get process by PID {}: {:?}", pid, response.error);
-                Err(StatusCode::NOT_FOUND)
-            }
-        }
+    /// GET /api/system/processes/{pid}/tree - Get a process and its descendants
+    async fn get_process_tree(
+        State(server): State<Arc<MCPServer>>,
+        axum::extract::Path(pid): axum::extract::Path<u32>,
+    ) -> Result<Json<Value>, ApiError> {
+        Self::call_method(
+            &server,
+            METHOD_GET_PROCESS_TREE,
+            json!({"pid": pid}),
+            "process tree",
+        )
+        .await
+    }
+
+    /// GET /api/system/processes/{pid}/memory - Get the memory breakdown of a process
+    async fn get_process_memory(
+        State(server): State<Arc<MCPServer>>,
+        axum::extract::Path(pid): axum::extract::Path<u32>,
+        Query(human): Query<HumanQuery>,
+    ) -> Result<Json<Value>, ApiError> {
+        let result = Self::call_method(
+            &server,
+            METHOD_GET_PROCESS_MEMORY,
+            json!({"pid": pid}),
+            "process memory",
+        )
+        .await;
+        Self::human_sizes(result, human, PROCESS_MEMORY_BYTE_FIELDS)
     }
 
-    /// GET /api/system/metrics - Get complete system metrics
+    /// GET /api/system/processes/{pid}/limits - Get the resource limits of a process
+    async fn get_process_limits(
+        State(server): State<Arc<MCPServer>>,
+        axum::extract::Path(pid): axum::extract::Path<u32>,
+    ) -> Result<Json<Value>, ApiError> {
+        Self::call_method(
+            &server,
+            METHOD_GET_PROCESS_LIMITS,
+            json!({"pid": pid}),
+            "process limits",
+        )
+        .await
+    }
+
+    /// GET /api/self - Get the monitor's own resource use and request counts
+    async fn get_self(State(server): State<Arc<MCPServer>>) -> Result<Json<Value>, ApiError> {
+        Self::call_method(&server, METHOD_GET_SELF, json!({}), "own process info").await
+    }
+
+    /// POST /api/system/processes/{pid}/signal - Send a signal to a process
+    async fn signal_process(
+        State(server): State<Arc<MCPServer>>,
+        axum::extract::Path(pid): axum::extract::Path<u32>,
+        body: Option<Json<Value>>,
+    ) -> Result<Json<Value>, ApiError> {
+        Self::call_process_action(&server, METHOD_KILL_PROCESS, pid, body).await
+    }
+
+    /// POST /api/system/processes/{pid}/nice - Change the nice value of a process
+    async fn renice_process(
+        State(server): State<Arc<MCPServer>>,
+        axum::extract::Path(pid): axum::extract::Path<u32>,
+        body: Option<Json<Value>>,
+    ) -> Result<Json<Value>, ApiError> {
+        Self::call_process_action(&server, METHOD_RENICE, pid, body).await
+    }
+
+    /// POST /api/system/diff - Compare two system metrics snapshots
+    async fn diff_metrics(
+        State(server): State<Arc<MCPServer>>,
+        Json(body): Json<Value>,
+    ) -> Result<Json<Value>, ApiError> {
+        Self::call_method(&server, METHOD_DIFF_METRICS, body, "metrics diff").await
+    }
+
+    /// GET /api/system/metrics - Get system metrics, optionally only some components
     async fn get_system_metrics(
         State(server): State<Arc<MCPServer>>,
-    ) -> Result<Json<Value>, StatusCode> {
-        let request = MCPRequest {
-            jsonrpc: "2.0".to_string(),
-            id: Some(uuid::Uuid::new_v4().to_string()),
-            method: METHOD_GET_SYSTEM_METRICS.to_string(),
-            params: json!({}),
-        };
+        Query(query): Query<MetricsQuery>,
+        Query(human): Query<HumanQuery>,
+    ) -> Result<Json<Value>, ApiError> {
+        let Json(mut metrics) = Self::call_method(
+            &server,
+            METHOD_GET_SYSTEM_METRICS,
+            json!(query),
+            "system metrics",
+        )
+        .await?;
 
-        let response = server.handle_request(request).await;
-        match response.result {
-            Some(result) => Ok(Json(result)),
-            None => {
-                error!("Failed to get system metrics: {:?}", response.error);
-                Err(StatusCode::INTERNAL_SERVER_ERROR)
+        if human.human {
+            for (section, fields) in [
+                ("memory_info", MEMORY_BYTE_FIELDS),
+                ("disks", DISK_BYTE_FIELDS),
+                ("networks", NETWORK_BYTE_FIELDS),
+                ("processes", PROCESS_BYTE_FIELDS),
+                ("gpus", GPU_BYTE_FIELDS),
+            ] {
+                if let Some(value) = metrics.get_mut(section) {
+                    add_human_sizes(value, fields);
+                }
             }
         }
+
+        Ok(Json(metrics))
     }
 
     /// POST /api/monitoring/start - Start continuous monitoring
     async fn start_monitoring(
         State(server): State<Arc<MCPServer>>,
-    ) -> Result<Json<Value>, StatusCode> {
+    ) -> Result<Json<Value>, ApiError> {
+        Self::call_method(
+            &server,
+            METHOD_START_MONITORING,
+            json!({}),
+            "start monitoring",
+        )
+        .await
+    }
+
+    /// POST /api/monitoring/stop - Stop continuous monitoring
+    async fn stop_monitoring(
+        State(server): State<Arc<MCPServer>>,
+    ) -> Result<Json<Value>, ApiError> {
+        Self::call_method(
+            &server,
+            METHOD_STOP_MONITORING,
+            json!({}),
+            "stop monitoring",
+        )
+        .await
+    }
+
+    /// POST /api/monitoring/interval - Set the monitoring interval
+    async fn set_monitoring_interval(
+        State(server): State<Arc<MCPServer>>,
+        Json(body): Json<Value>,
+    ) -> Result<Json<Value>, ApiError> {
+        Self::call_method(
+            &server,
+            METHOD_SET_MONITORING_INTERVAL,
+            body,
+            "monitoring interval",
+        )
+        .await
+    }
+
+    /// GET /api/monitoring/status - Get monitoring status
+    async fn get_monitoring_status(State(server): State<Arc<MCPServer>>) -> Json<Value> {
+        let monitor = server.system_monitor();
+        let monitor = monitor.read().await;
+        Json(json!({
+            "monitoring_active": monitor.is_monitoring_active(),
+            "last_update": monitor.last_update(),
+            "interval_ms": monitor.monitoring_interval().as_millis() as u64,
+            "service_status": "running"
+        }))
+    }
+
+    /// GET /api/alerts - Get currently firing threshold alerts
+    async fn get_alerts(State(server): State<Arc<MCPServer>>) -> Result<Json<Value>, ApiError> {
+        Self::call_method(&server, METHOD_GET_ALERTS, json!({}), "alerts").await
+    }
+
+    /// Adds `<field>_human` strings to a successful result when `?human=true`
+    fn human_sizes(
+        result: Result<Json<Value>, ApiError>,
+        human: HumanQuery,
+        fields: &[&str],
+    ) -> Result<Json<Value>, ApiError> {
+        let Json(mut value) = result?;
+        if human.human {
+            add_human_sizes(&mut value, fields);
+        }
+        Ok(Json(value))
+    }
+
+    /// Forwards a REST call to the MCP server and unwraps the result
+    ///
+    /// A failed call is answered with the status matching its MCP error code
+    /// and the MCP error as the JSON body.
+    async fn call_method(
+        server: &MCPServer,
+        method: &str,
+        params: Value,
+        what: &str,
+    ) -> Result<Json<Value>, ApiError> {
         let request = MCPRequest {
             jsonrpc: "2.0".to_string(),
             id: Some(uuid::Uuid::new_v4().to_string()),
-            method: METHOD_START_MONITORING.to_string(),
-            params: json!({}),
+            method: method.to_string(),
+            params,
         };
 
         let response = server.handle_request(request).await;
         match response.result {
             Some(result) => Ok(Json(result)),
             None => {
-                error!("Failed to start monitoring: {:?}", response.error);
-                Err(StatusCode::INTERNAL_SERVER_ERROR)
+                error!("Failed to get {}: {:?}", what, response.error);
+                Err(ApiError::from_mcp(response.error))
             }
         }
     }
 
-    /// POST /api/monitoring/stop - Stop continuous monitoring
-    async fn stop_monitoring(
-        State(server): State<Arc<MCPServer>>,
-    ) -> Result<Json<Value>, StatusCode> {
+    /// Forwards an action on a single process, merging the path PID into the body
+    async fn call_process_action(
+        server: &MCPServer,
+        method: &str,
+        pid: u32,
+        body: Option<Json<Value>>,
+    ) -> Result<Json<Value>, ApiError> {
+        let mut params = body.map(|Json(body)| body).unwrap_or_else(|| json!({}));
+        if let Some(params) = params.as_object_mut() {
+            params.insert("pid".to_string(), json!(pid));
+        }
+
         let request = MCPRequest {
             jsonrpc: "2.0".to_string(),
             id: Some(uuid::Uuid::new_v4().to_string()),
-            method: METHOD_STOP_MONITORING.to_string(),
-            params: json!({}),
+            method: method.to_string(),
+            params,
         };
 
         let response = server.handle_request(request).await;
-        match response.result {
-            Some(result) => Ok(Json(result)),
-            None => {
-                error!("Failed to stop monitoring: {:?}", response.error);
-                Err(StatusCode::INTERNAL_SERVER_ERROR)
+        match (response.result, response.error) {
+            (Some(result), _) => Ok(Json(result)),
+            (None, error) => {
+                error!("Failed to {} PID {}: {:?}", method, pid, error);
+                Err(ApiError::from_mcp(error))
             }
         }
     }
 
-    /// GET /api/monitoring/status - Get monitoring status
-    async fn get_monitoring_status(State(_server): State<Arc<MCPServer>>) -> Json<Value> {
-        // This endpoint provides monitoring status information
-        // For now, we'll return a simple status response
-        Json(json!({
-            "monitoring_active": true, // This would need to be implemented
-            "last_update": chrono::Utc::now(),
-            "service_status": "running"
-        }))
-    }
-
     /// POST / - Handle MCP JSON-RPC requests (return JSON, not SSE)
     async fn handle_mcp_request(
         State(server): State<Arc<MCPServer>>,
@@ -377,29 +1265,873 @@ impl HTTPServer {
     }
 
     /// GET / - SSE stream for MCP clients
+    ///
+    /// Pushes a `LiveMetrics` sample as a `metrics` event every `interval_secs`
+    /// (clamped to the monitor's interval floor). MCP clients only consume
+    /// unnamed `message` events, so the named events do not disturb them. With
+    /// `patch=true` samples are sent as JSON merge patches against the previous
+    /// one. A comment heartbeat keeps proxies from timing out during slow
+    /// collections, and the stream ends after repeated collection failures.
     async fn handle_sse(
+        State(server): State<Arc<MCPServer>>,
+        Query(query): Query<SseQuery>,
     ) -> Sse<impl futures::Stream<Item = Result<Event, axum::Error>>> {
         let timestamp = chrono::Utc::now().format("%H:%M:%S%.3f");
         info!("[{}] 🔌 GET / - SSE Connection opened", timestamp);
-        info!("🔧 Creating SSE stream with 10-second heartbeat interval");
-        
-        // Create a stream that sends heartbeat events every 10 seconds
-        let stream = IntervalStream::new(interval(std::time::Duration::from_secs(10)))
-            .enumerate()
-            .map(|(idx, _)| {
-                if idx == 0 {
-                    info!("💓 Sending first keep-alive message");
-                    // Send an empty message to signal connection is alive
-                    Ok::<Event, axum::Error>(Event::default().data(""))
-                } else {
-                    info!("💓 Sending heartbeat #{}", idx);
-                    // Send heartbeat comments for subsequent intervals
-                    Ok::<Event, axum::Error>(Event::default().comment("heartbeat"))
+
+        let monitor = server.system_monitor();
+        // Tokio intervals panic on a zero period
+        let period = monitor
+            .read()
+            .await
+            .clamp_interval(Duration::from_secs(query.interval_secs))
+            .max(MIN_INTERVAL_FLOOR);
+        info!("🔧 Creating SSE stream with {:?} metrics interval", period);
+
+        let state = SseState {
+            ticker: interval(period),
+            monitor,
+            timeout: server.collection_timeout(),
+            tracker: query.patch.then(|| PatchTracker::new(SSE_PATCH_FULL_EVERY)),
+            failures: 0,
+            done: false,
+        };
+
+        // Send an empty message first to signal the connection is alive
+        let hello = futures::stream::once(async { Ok(Event::default().data("")) });
+        let samples = futures::stream::unfold(state, Self::next_sse_event);
+
+        Sse::new(hello.chain(samples)).keep_alive(
+            KeepAlive::new()
+                .interval(SSE_HEARTBEAT_INTERVAL)
+                .text("heartbeat"),
+        )
+    }
+
+    /// Waits for the next tick and produces the event to send, or None to end the stream
+    async fn next_sse_event(mut state: SseState) -> Option<(Result<Event, axum::Error>, SseState)> {
+        loop {
+            if state.done {
+                return None;
+            }
+
+            state.ticker.tick().await;
+            let sample = SystemMonitor::run_blocking(&state.monitor, state.timeout, |monitor| {
+                monitor.get_live_metrics()
+            })
+            .await;
+
+            match sample {
+                Ok(sample) => {
+                    state.failures = 0;
+                    let value = serde_json::to_value(sample).unwrap_or_default();
+                    let data = match state.tracker.as_mut() {
+                        Some(tracker) => match tracker.next_update(value) {
+                            Some(update) => serde_json::to_string(&update),
+                            // Nothing changed since the last sample
+                            None => continue,
+                        },
+                        None => serde_json::to_string(&value),
+                    }
+                    .unwrap_or_default();
+
+                    return Some((Ok(Event::default().event("metrics").data(data)), state));
                 }
-            });
-         
-        info!("🚀 SSE stream created and ready");
-        Sse::new(stream)
+                Err(e) => {
+                    state.failures += 1;
+                    error!(
+                        "SSE metrics collection failed ({}/{}): {}",
+                        state.failures, SSE_MAX_CONSECUTIVE_FAILURES, e
+                    );
+
+                    if state.failures >= SSE_MAX_CONSECUTIVE_FAILURES {
+                        state.done = true;
+                        let event = Event::default()
+                            .event("error")
+                            .data(format!("Metrics collection failed: {}", e));
+                        return Some((Ok(event), state));
+                    }
+                }
+            }
+        }
     }
 
+    /// GET /ws - WebSocket stream of metrics frames with control messages
+    ///
+    /// Sends `{"type":"metrics","data":{...}}` frames shaped like
+    /// `SystemMetrics` every `interval_secs` (clamped to the monitoring
+    /// interval floor). With `patch=true` the frame data is a full snapshot or
+    /// a JSON merge patch against the previous one, as on the SSE stream.
+    /// Clients can send `{"action":"set_interval","secs":2}` or
+    /// `{"action":"subscribe","metrics":["cpu","memory"]}`; unparseable
+    /// control frames and a zero `secs` get an error frame and the connection
+    /// stays open.
+    async fn handle_ws(
+        ws: WebSocketUpgrade,
+        State(server): State<Arc<MCPServer>>,
+        Query(query): Query<WsQuery>,
+    ) -> Response {
+        let monitor = server.system_monitor();
+        let timeout = server.collection_timeout();
+        ws.on_upgrade(move |socket| Self::stream_ws(socket, monitor, timeout, query))
+    }
+
+    /// Drives one WebSocket connection until the client goes away
+    ///
+    /// Runs as the connection's upgrade future, so returning on a closed
+    /// socket or failed send is all it takes to release the connection.
+    async fn stream_ws(
+        mut socket: WebSocket,
+        monitor: Arc<RwLock<SystemMonitor>>,
+        timeout: Duration,
+        query: WsQuery,
+    ) {
+        info!("🔌 WebSocket connection opened");
+
+        let mut sections: BTreeSet<MetricSection> = MetricSection::ALL.into_iter().collect();
+        let mut ticker = Self::ws_ticker(&monitor, query.interval_secs).await;
+        let mut tracker = query.patch.then(|| PatchTracker::new(WS_PATCH_FULL_EVERY));
+
+        loop {
+            let reply = tokio::select! {
+                _ = ticker.tick() => {
+                    match Self::ws_metrics_frame(&monitor, timeout, &sections, &mut tracker).await {
+                        Some(frame) => frame,
+                        // Nothing changed since the last patch
+                        None => continue,
+                    }
+                }
+                message = socket.recv() => match message {
+                    Some(Ok(Message::Text(text))) => {
+                        Self::ws_control_reply(&text, &monitor, &mut ticker, &mut sections).await
+                    }
+                    Some(Ok(Message::Close(_))) | None => break,
+                    Some(Ok(_)) => continue,
+                    Some(Err(e)) => {
+                        info!("WebSocket receive failed: {}", e);
+                        break;
+                    }
+                },
+            };
+
+            if socket.send(Message::Text(reply.to_string())).await.is_err() {
+                break;
+            }
+        }
+
+        info!("🔌 WebSocket connection closed");
+    }
+
+    /// Collects the subscribed sections into a metrics frame, or an error frame
+    ///
+    /// Returns None if the subscriber takes patches and nothing changed.
+    async fn ws_metrics_frame(
+        monitor: &Arc<RwLock<SystemMonitor>>,
+        timeout: Duration,
+        sections: &BTreeSet<MetricSection>,
+        tracker: &mut Option<PatchTracker>,
+    ) -> Option<Value> {
+        let sections = sections.clone();
+        let data = SystemMonitor::run_blocking(monitor, timeout, move |monitor| {
+            collect_sections(monitor, &sections)
+        })
+        .await;
+        match data {
+            Ok(data) => match tracker.as_mut() {
+                Some(tracker) => {
+                    let update = tracker.next_update(data)?;
+                    Some(json!({"type": "metrics", "data": update}))
+                }
+                None => Some(json!({"type": "metrics", "data": data})),
+            },
+            Err(e) => {
+                error!("WebSocket metrics collection failed: {}", e);
+                Some(json!({
+                    "type": "error",
+                    "message": format!("Metrics collection failed: {}", e)
+                }))
+            }
+        }
+    }
+
+    /// Applies a control message and returns the ack, or an error frame if it is invalid
+    async fn ws_control_reply(
+        text: &str,
+        monitor: &RwLock<SystemMonitor>,
+        ticker: &mut tokio::time::Interval,
+        sections: &mut BTreeSet<MetricSection>,
+    ) -> Value {
+        match serde_json::from_str::<WsControl>(text) {
+            Ok(WsControl::SetInterval { secs: 0 }) => json!({
+                "type": "error",
+                "message": "Invalid control message: secs must be greater than 0"
+            }),
+            Ok(WsControl::SetInterval { secs }) => {
+                *ticker = Self::ws_ticker(monitor, secs).await;
+                json!({
+                    "type": "ack",
+                    "action": "set_interval",
+                    "interval_secs": ticker.period().as_secs_f64()
+                })
+            }
+            Ok(WsControl::Subscribe { metrics }) => {
+                *sections = metrics.into_iter().collect();
+                json!({"type": "ack", "action": "subscribe", "metrics": sections})
+            }
+            Err(e) => json!({
+                "type": "error",
+                "message": format!("Invalid control message: {}", e)
+            }),
+        }
+    }
+
+    /// Creates a metrics ticker for the requested period, clamped to the floor
+    async fn ws_ticker(monitor: &RwLock<SystemMonitor>, secs: u64) -> tokio::time::Interval {
+        // Tokio intervals panic on a zero period
+        let period = monitor
+            .read()
+            .await
+            .clamp_interval(Duration::from_secs(secs))
+            .max(MIN_INTERVAL_FLOOR);
+        let mut ticker = interval(period);
+        // A slow collection should delay the next frame, not trigger a burst
+        ticker.set_missed_tick_behavior(MissedTickBehavior::Delay);
+        ticker
+    }
+}
+
+/// Query parameters for the SSE stream
+#[derive(Debug, Deserialize)]
+#[serde(default)]
+struct SseQuery {
+    /// Seconds between metrics samples
+    interval_secs: u64,
+    /// Send merge patches instead of full samples
+    patch: bool,
+}
+
+impl Default for SseQuery {
+    fn default() -> Self {
+        Self {
+            interval_secs: SSE_DEFAULT_INTERVAL_SECS,
+            patch: false,
+        }
+    }
+}
+
+/// Per-connection state of an SSE metrics stream
+struct SseState {
+    /// Fires once per sample
+    ticker: tokio::time::Interval,
+    /// Monitor to sample
+    monitor: Arc<RwLock<SystemMonitor>>,
+    /// Time a single sample may take
+    timeout: Duration,
+    /// Patch state when the subscriber asked for merge patches
+    tracker: Option<PatchTracker>,
+    /// Consecutive failed collections
+    failures: u32,
+    /// Set once the final error event has been sent
+    done: bool,
+}
+
+/// Query parameters for the WebSocket stream
+#[derive(Debug, Deserialize)]
+#[serde(default)]
+struct WsQuery {
+    /// Seconds between metrics frames
+    interval_secs: u64,
+    /// Send merge patches instead of full frames
+    patch: bool,
+}
+
+impl Default for WsQuery {
+    fn default() -> Self {
+        Self {
+            interval_secs: WS_DEFAULT_INTERVAL_SECS,
+            patch: false,
+        }
+    }
+}
+
+/// Control message sent by a WebSocket client
+#[derive(Debug, Deserialize)]
+#[serde(tag = "action", rename_all = "snake_case")]
+enum WsControl {
+    /// Change the seconds between metrics frames
+    SetInterval { secs: u64 },
+    /// Replace the set of sections included in metrics frames
+    Subscribe { metrics: Vec<MetricSection> },
+}
+
+/// Collects only the requested sections, keyed by their `SystemMetrics` field names
+fn collect_sections(
+    monitor: &mut SystemMonitor,
+    sections: &BTreeSet<MetricSection>,
+) -> Result<Value, MonitorError> {
+    let mut data = serde_json::Map::new();
+    data.insert("timestamp".to_string(), json!(chrono::Utc::now()));
+
+    for section in sections {
+        let (key, value) = match section {
+            MetricSection::System => ("system_info", json!(monitor.get_system_info()?)),
+            MetricSection::Cpu => ("cpu_info", json!(monitor.get_cpu_info()?)),
+            MetricSection::Memory => ("memory_info", json!(monitor.get_memory_info()?)),
+            MetricSection::Load => ("load_average", json!(monitor.get_load_average()?)),
+            MetricSection::Disks => ("disks", json!(monitor.get_disk_info()?)),
+            MetricSection::Networks => (
+                "networks",
+                json!(monitor.get_network_info(&NetworkQuery::default())?),
+            ),
+            MetricSection::Processes => ("processes", json!(monitor.get_processes()?)),
+        };
+        data.insert(key.to_string(), value);
+    }
+
+    Ok(Value::Object(data))
+}
+
+/// Prometheus metric type
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum MetricKind {
+    /// Value that can go up and down
+    Gauge,
+    /// Monotonically increasing total
+    Counter,
+}
+
+impl MetricKind {
+    fn as_str(self) -> &'static str {
+        match self {
+            MetricKind::Gauge => "gauge",
+            MetricKind::Counter => "counter",
+        }
+    }
+}
+
+/// A named metric with its help text and labeled samples
+#[derive(Debug)]
+struct MetricFamily {
+    /// Metric name
+    name: &'static str,
+    /// Help text
+    help: &'static str,
+    /// Metric type
+    kind: MetricKind,
+    /// Label pairs and value of each sample
+    samples: Vec<(Vec<(&'static str, String)>, f64)>,
+}
+
+impl MetricFamily {
+    /// Creates a family with a single unlabeled sample
+    fn single(name: &'static str, help: &'static str, kind: MetricKind, value: f64) -> Self {
+        Self {
+            name,
+            help,
+            kind,
+            samples: vec![(Vec::new(), value)],
+        }
+    }
+
+    /// Returns the family name without the `_total` suffix of counters
+    ///
+    /// OpenMetrics names counter families this way; their samples keep it.
+    fn base_name(&self) -> &'static str {
+        match self.kind {
+            MetricKind::Counter => self.name.strip_suffix("_total").unwrap_or(self.name),
+            MetricKind::Gauge => self.name,
+        }
+    }
+
+    /// Returns the InfluxDB measurement and field key of the family
+    ///
+    /// The `node_` prefix is dropped and the first word of the name becomes
+    /// the measurement, so `node_cpu_usage_percent` is the `usage_percent`
+    /// field of `cpu`. One-word names such as `node_load1` belong to
+    /// `system`, as in Telegraf.
+    fn influx_key(&self) -> (&'static str, &'static str) {
+        let name = self.name.strip_prefix("node_").unwrap_or(self.name);
+        name.split_once('_').unwrap_or(("system", name))
+    }
+
+    /// Returns the unit the family name ends in, if it is a known one
+    fn unit(&self) -> Option<&'static str> {
+        let name = self.base_name();
+        OPENMETRICS_UNITS.iter().copied().find(|unit| {
+            name.strip_suffix(unit)
+                .is_some_and(|rest| rest.ends_with('_'))
+        })
+    }
+}
+
+/// Builds the counters of MCP requests handled by `server`
+fn request_families(server: &MCPServer) -> [MetricFamily; 2] {
+    let (requests, errors) = server.request_counts();
+    [
+        MetricFamily::single(
+            "mcp_requests_total",
+            "MCP requests handled over all transports.",
+            MetricKind::Counter,
+            requests as f64,
+        ),
+        MetricFamily::single(
+            "mcp_request_errors_total",
+            "MCP requests answered with an error.",
+            MetricKind::Counter,
+            errors as f64,
+        ),
+    ]
+}
+
+/// Collects the exported metric families from the monitor
+fn metric_families(monitor: &mut SystemMonitor) -> Result<Vec<MetricFamily>, MonitorError> {
+    use MetricKind::{Counter, Gauge};
+
+    let cpu = monitor.get_cpu_info()?;
+    let memory = monitor.get_memory_info()?;
+    let load = monitor.get_load_average()?;
+    let disks = monitor.get_disk_info()?;
+    let networks = monitor.get_network_info(&NetworkQuery::default())?;
+
+    let mut families = vec![
+        MetricFamily::single(
+            "node_cpu_usage_percent",
+            "CPU usage percentage across all cores.",
+            Gauge,
+            cpu.usage_percent as f64,
+        ),
+        MetricFamily::single(
+            "node_cpu_cores",
+            "Number of logical CPU cores.",
+            Gauge,
+            cpu.cores as f64,
+        ),
+        MetricFamily::single(
+            "node_cpu_physical_cores",
+            "Number of physical CPU cores.",
+            Gauge,
+            cpu.physical_cores as f64,
+        ),
+        MetricFamily::single(
+            "node_cpu_sockets",
+            "Number of CPU sockets.",
+            Gauge,
+            cpu.sockets as f64,
+        ),
+        MetricFamily::single(
+            "node_cpu_frequency_mhz",
+            "CPU frequency in MHz.",
+            Gauge,
+            cpu.frequency as f64,
+        ),
+    ];
+
+    families.push(MetricFamily::single(
+        "node_cpu_throttles_total",
+        "Thermal throttle events since boot, summed over logical CPUs.",
+        Counter,
+        cpu.throttle_count as f64,
+    ));
+
+    if cpu.max_frequency > 0 {
+        families.push(MetricFamily::single(
+            "node_cpu_max_frequency_mhz",
+            "Maximum CPU frequency in MHz.",
+            Gauge,
+            cpu.max_frequency as f64,
+        ));
+    }
+
+    if let Some(temperature) = cpu.temperature {
+        families.push(MetricFamily::single(
+            "node_cpu_temperature_celsius",
+            "CPU temperature in degrees Celsius.",
+            Gauge,
+            temperature as f64,
+        ));
+    }
+
+    families.extend([
+        MetricFamily::single(
+            "node_memory_total_bytes",
+            "Total physical memory in bytes.",
+            Gauge,
+            memory.total as f64,
+        ),
+        MetricFamily::single(
+            "node_memory_used_bytes",
+            "Used physical memory in bytes.",
+            Gauge,
+            memory.used as f64,
+        ),
+        MetricFamily::single(
+            "node_memory_available_bytes",
+            "Available physical memory in bytes.",
+            Gauge,
+            memory.available as f64,
+        ),
+        MetricFamily::single(
+            "node_memory_free_bytes",
+            "Free physical memory in bytes.",
+            Gauge,
+            memory.free as f64,
+        ),
+        MetricFamily::single(
+            "node_memory_swap_total_bytes",
+            "Total swap space in bytes.",
+            Gauge,
+            memory.swap_total as f64,
+        ),
+        MetricFamily::single(
+            "node_memory_swap_used_bytes",
+            "Used swap space in bytes.",
+            Gauge,
+            memory.swap_used as f64,
+        ),
+        MetricFamily::single("node_load1", "1-minute load average.", Gauge, load.one),
+        MetricFamily::single("node_load5", "5-minute load average.", Gauge, load.five),
+        MetricFamily::single(
+            "node_load15",
+            "15-minute load average.",
+            Gauge,
+            load.fifteen,
+        ),
+    ]);
+
+    let disk_labels = |disk: &DiskInfo| {
+        vec![
+            ("device", disk.name.clone()),
+            ("mount", disk.mount_point.clone()),
+            ("fstype", disk.file_system.clone()),
+        ]
+    };
+    for (name, help, value) in [
+        (
+            "node_disk_total_bytes",
+            "Filesystem size in bytes.",
+            (|d: &DiskInfo| d.total_space) as fn(&DiskInfo) -> u64,
+        ),
+        (
+            "node_disk_used_bytes",
+            "Filesystem space used in bytes.",
+            |d| d.used_space,
+        ),
+        (
+            "node_disk_free_bytes",
+            "Filesystem space free in bytes.",
+            |d| d.free_space,
+        ),
+    ] {
+        families.push(MetricFamily {
+            name,
+            help,
+            kind: Gauge,
+            samples: disks
+                .iter()
+                .map(|disk| (disk_labels(disk), value(disk) as f64))
+                .collect(),
+        });
+    }
+
+    for (name, help, value) in [
+        (
+            "node_network_receive_bytes_total",
+            "Total bytes received.",
+            (|n: &NetworkInfo| n.bytes_received) as fn(&NetworkInfo) -> u64,
+        ),
+        (
+            "node_network_transmit_bytes_total",
+            "Total bytes transmitted.",
+            |n| n.bytes_transmitted,
+        ),
+        (
+            "node_network_receive_packets_total",
+            "Total packets received.",
+            |n| n.packets_received,
+        ),
+        (
+            "node_network_transmit_packets_total",
+            "Total packets transmitted.",
+            |n| n.packets_transmitted,
+        ),
+        (
+            "node_network_receive_errors_total",
+            "Total receive errors.",
+            |n| n.errors_received,
+        ),
+        (
+            "node_network_transmit_errors_total",
+            "Total transmit errors.",
+            |n| n.errors_transmitted,
+        ),
+    ] {
+        families.push(MetricFamily {
+            name,
+            help,
+            kind: Counter,
+            samples: networks
+                .iter()
+                .map(|network| {
+                    (
+                        vec![("device", network.interface.clone())],
+                        value(network) as f64,
+                    )
+                })
+                .collect(),
+        });
+    }
+
+    Ok(families)
+}
+
+/// Renders metric families in the Prometheus text exposition format (0.0.4)
+fn render_prometheus(families: &[MetricFamily]) -> String {
+    let mut out = String::new();
+
+    for family in families {
+        out.push_str(&format!("# HELP {} {}\n", family.name, family.help));
+        out.push_str(&format!(
+            "# TYPE {} {}\n",
+            family.name,
+            family.kind.as_str()
+        ));
+
+        render_samples(&mut out, family);
+    }
+
+    out
+}
+
+/// Renders metric families in the OpenMetrics 1.0 text format
+///
+/// Counter families are declared without their `_total` suffix, known units
+/// get a `# UNIT` line, and the output ends with the required `# EOF`.
+fn render_openmetrics(families: &[MetricFamily]) -> String {
+    let mut out = String::new();
+
+    for family in families {
+        let name = family.base_name();
+        out.push_str(&format!("# TYPE {} {}\n", name, family.kind.as_str()));
+        if let Some(unit) = family.unit() {
+            out.push_str(&format!("# UNIT {} {}\n", name, unit));
+        }
+        out.push_str(&format!(
+            "# HELP {} {}\n",
+            name,
+            escape_label_value(family.help)
+        ));
+
+        render_samples(&mut out, family);
+    }
+
+    out.push_str("# EOF\n");
+    out
+}
+
+/// Appends one line per sample of `family`, shared by both text formats
+fn render_samples(out: &mut String, family: &MetricFamily) {
+    for (labels, value) in &family.samples {
+        out.push_str(family.name);
+        if !labels.is_empty() {
+            let labels: Vec<String> = labels
+                .iter()
+                .map(|(name, value)| format!("{}=\"{}\"", name, escape_label_value(value)))
+                .collect();
+            out.push_str(&format!("{{{}}}", labels.join(",")));
+        }
+        out.push_str(&format!(" {}\n", format_sample_value(*value)));
+    }
+}
+
+/// Renders metric families in InfluxDB line protocol
+///
+/// Samples with the same measurement and labels become the fields of one
+/// line, tagged with `host` and their labels and stamped with `timestamp` in
+/// nanoseconds. Line protocol has no NaN or infinity, so non-finite samples
+/// are left out.
+fn render_influx(families: &[MetricFamily], host: &str, timestamp: DateTime<Utc>) -> String {
+    let mut lines: Vec<(String, Vec<String>)> = Vec::new();
+
+    for family in families {
+        let (measurement, field) = family.influx_key();
+        for (labels, value) in &family.samples {
+            if !value.is_finite() {
+                continue;
+            }
+
+            let mut tags: Vec<(&str, &str)> = vec![("host", host)];
+            tags.extend(labels.iter().map(|(name, value)| (*name, value.as_str())));
+            // Tags are sorted by key, as InfluxDB stores them; empty values are not allowed
+            tags.sort_by_key(|(name, _)| *name);
+            let mut series = escape_influx(measurement, &[',', ' ']);
+            for (name, value) in tags.into_iter().filter(|(_, value)| !value.is_empty()) {
+                series.push_str(&format!(
+                    ",{}={}",
+                    escape_influx(name, &[',', '=', ' ']),
+                    escape_influx(value, &[',', '=', ' '])
+                ));
+            }
+
+            let field = format!("{}={}", escape_influx(field, &[',', '=', ' ']), value);
+            match lines.iter_mut().find(|(key, _)| *key == series) {
+                Some((_, fields)) => fields.push(field),
+                None => lines.push((series, vec![field])),
+            }
+        }
+    }
+
+    let nanos = timestamp.timestamp_nanos_opt().unwrap_or_default();
+    lines
+        .into_iter()
+        .map(|(series, fields)| format!("{} {} {}\n", series, fields.join(","), nanos))
+        .collect()
+}
+
+/// Escapes a measurement, tag or field key with backslashes
+///
+/// Line protocol also treats backslashes and newlines specially; newlines
+/// cannot be escaped and become spaces.
+fn escape_influx(value: &str, special: &[char]) -> String {
+    let mut escaped = String::with_capacity(value.len());
+    for c in value.chars() {
+        let c = if c == '\n' { ' ' } else { c };
+        if c == '\\' || special.contains(&c) {
+            escaped.push('\\');
+        }
+        escaped.push(c);
+    }
+    escaped
+}
+
+/// Formats a sample value, spelling non-finite values as both formats expect
+fn format_sample_value(value: f64) -> String {
+    if value.is_nan() {
+        "NaN".to_string()
+    } else if value.is_infinite() {
+        if value > 0.0 { "+Inf" } else { "-Inf" }.to_string()
+    } else {
+        value.to_string()
+    }
+}
+
+/// Escapes a label value per the exposition format (backslash, quote, newline)
+fn escape_label_value(value: &str) -> String {
+    value
+        .replace('\\', "\\\\")
+        .replace('"', "\\\"")
+        .replace('\n', "\\n")
+}
+
+/// A bound Unix socket file, removed when dropped
+#[cfg(unix)]
+struct SocketFile(std::path::PathBuf);
+
+#[cfg(unix)]
+impl Drop for SocketFile {
+    fn drop(&mut self) {
+        let _ = std::fs::remove_file(&self.0);
+    }
+}
+
+/// Binds a Unix socket at `path`, replacing a stale socket file
+///
+/// # Errors
+///
+/// Returns an error if `path` exists and is not a socket, another server is
+/// listening on it, or it cannot be bound or given `mode`
+#[cfg(unix)]
+fn bind_unix_socket(
+    path: &Path,
+    mode: u32,
+) -> anyhow::Result<(tokio::net::UnixListener, SocketFile)> {
+    use std::os::unix::fs::{FileTypeExt, PermissionsExt};
+    use std::os::unix::net::UnixStream;
+
+    if let Ok(metadata) = std::fs::symlink_metadata(path) {
+        if !metadata.file_type().is_socket() {
+            return Err(anyhow::anyhow!(
+                "{} exists and is not a socket",
+                path.display()
+            ));
+        }
+        if UnixStream::connect(path).is_ok() {
+            return Err(anyhow::anyhow!(
+                "Socket {} is already in use by another server",
+                path.display()
+            ));
+        }
+        std::fs::remove_file(path).map_err(|e| {
+            anyhow::anyhow!("Failed to remove stale socket {}: {}", path.display(), e)
+        })?;
+    }
+
+    let listener = tokio::net::UnixListener::bind(path)
+        .map_err(|e| anyhow::anyhow!("Failed to bind {}: {}", path.display(), e))?;
+    let socket_file = SocketFile(path.to_path_buf());
+    std::fs::set_permissions(path, std::fs::Permissions::from_mode(mode))
+        .map_err(|e| anyhow::anyhow!("Failed to set permissions of {}: {}", path.display(), e))?;
+    Ok((listener, socket_file))
+}
+
+/// Completes on Ctrl-C or SIGTERM
+#[cfg(unix)]
+async fn shutdown_signal() {
+    use tokio::signal::unix::{signal, SignalKind};
+
+    let terminate = async {
+        match signal(SignalKind::terminate()) {
+            Ok(mut terminate) => {
+                terminate.recv().await;
+            }
+            Err(_) => std::future::pending().await,
+        }
+    };
+    tokio::select! {
+        _ = tokio::signal::ctrl_c() => {}
+        () = terminate => {}
+    }
+}
+
+/// Loads a rustls server configuration from PEM certificate chain and key files
+///
+/// # Arguments
+///
+/// * `cert_path` - PEM file with the certificate chain, leaf first
+/// * `key_path` - PEM file with the private key (PKCS#8, PKCS#1 or SEC1)
+///
+/// # Errors
+///
+/// Returns an error naming the offending file if it is missing, unreadable,
+/// contains no certificate or key, or the key does not match the certificate
+pub fn load_tls_config(cert_path: &Path, key_path: &Path) -> anyhow::Result<rustls::ServerConfig> {
+    let open = |path: &Path, what: &str| {
+        File::open(path)
+            .map(BufReader::new)
+            .map_err(|e| anyhow::anyhow!("Failed to open TLS {} {}: {}", what, path.display(), e))
+    };
+
+    let certs = rustls_pemfile::certs(&mut open(cert_path, "certificate")?)
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(|e| anyhow::anyhow!("Malformed TLS certificate {}: {}", cert_path.display(), e))?;
+    if certs.is_empty() {
+        return Err(anyhow::anyhow!(
+            "No PEM certificates found in {}",
+            cert_path.display()
+        ));
+    }
+
+    let key = rustls_pemfile::private_key(&mut open(key_path, "private key")?)
+        .map_err(|e| anyhow::anyhow!("Malformed TLS private key {}: {}", key_path.display(), e))?
+        .ok_or_else(|| anyhow::anyhow!("No PEM private key found in {}", key_path.display()))?;
+
+    let mut config = rustls::ServerConfig::builder_with_provider(Arc::new(
+        rustls::crypto::ring::default_provider(),
+    ))
+    .with_safe_default_protocol_versions()?
+    .with_no_client_auth()
+    .with_single_cert(certs, key)
+    .map_err(|e| anyhow::anyhow!("Invalid TLS certificate/key pair: {}", e))?;
+    config.alpn_protocols = vec![b"h2".to_vec(), b"http/1.1".to_vec()];
+
+    Ok(config)
 }
@@ -13,28 +13,39 @@
 //!
 //! ## Example
 //!
-//! ```rust
+//! ```rust,no_run
 //! use mcp_system_monitor::{SystemMonitor, MCPServer, HTTPServer};
 //! use std::sync::Arc;
 //! use tokio::sync::RwLock;
 //!
 //! #[tokio::main]
-//! async fn main() -> anyhow::Result<()> {
+//! async fn main() -> Result<(), Box<dyn std::error::Error>> {
 //!     let system_monitor = Arc::new(RwLock::new(SystemMonitor::new()?));
-//!     let mcp_server = MCPServer::new(system_monitor.clone());
+//!     let _mcp_server = MCPServer::new(system_monitor.clone());
 //!     let http_server = HTTPServer::new(system_monitor);
 //!     http_server.run(8080).await?;
 //!     Ok(())
 //! }
 //! ```
 
+// The tools/list schema is a single `json!` literal deeper than the default limit
+#![recursion_limit = "256"]
+
+pub mod alerts;
+pub mod config;
+pub mod error;
 pub mod http_server;
+pub mod patch;
+pub mod rate_limit;
 pub mod server;
 pub mod system_monitor;
 pub mod types;
+pub mod webhook;
 
 // Re-export main types
+pub use config::Config;
+pub use error::MonitorError;
 pub use http_server::HTTPServer;
-pub use server::MCPServer;
-pub use system_monitor::SystemMonitor;
+pub use server::{LogLevelHandle, MCPServer};
+pub use system_monitor::{SystemInfoProvider, SystemMonitor};
 pub use types::*;
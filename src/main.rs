@@ -1,16 +1,15 @@
 use std::sync::Arc;
+use std::time::Duration;
 use tokio::sync::RwLock;
-use tracing::{error, info};
+use tracing::{error, info, warn};
+use tracing_subscriber::filter::LevelFilter;
+use tracing_subscriber::prelude::*;
+use tracing_subscriber::reload;
 
-mod server;
-mod system_monitor;
-mod types;
-mod http_server;
 mod stdio_server;
 
-use server::MCPServer;
-use system_monitor::SystemMonitor;
-use http_server::HTTPServer;
+use mcp_system_monitor::config::LogFormat;
+use mcp_system_monitor::{Config, HTTPServer, MCPServer, SystemMonitor};
 use stdio_server::StdioServer;
 
 #[tokio::main]
@@ -19,25 +18,47 @@ async fn main() -> anyhow::Result<()> {
     let args: Vec<String> = std::env::args().collect();
     let use_stdio = args.contains(&"--stdio".to_string());
     
-    // Initialize logging - redirect to stderr for both modes
-    let subscriber = tracing_subscriber::fmt().with_writer(std::io::stderr);
-    let subscriber = if use_stdio {
-        subscriber.with_max_level(tracing::Level::ERROR)
+    // Resolve configuration: file, then environment, then command-line flags
+    let (config, sources) = Config::resolve(&args, |name| std::env::var(name).ok())?;
+
+    // Initialize logging - redirect to stderr for both modes. The level
+    // filter is reloadable so clients can change it with logging/setLevel.
+    let level = if use_stdio {
+        LevelFilter::ERROR
     } else {
-        subscriber.with_max_level(tracing::Level::INFO)
+        LevelFilter::INFO
+    };
+    let (level_filter, log_level) = reload::Layer::new(level);
+    let registry = tracing_subscriber::registry().with(level_filter);
+    let fmt_layer = tracing_subscriber::fmt::layer().with_writer(std::io::stderr);
+    let _ = match config.log_format {
+        LogFormat::Text => registry.with(fmt_layer).try_init(),
+        LogFormat::Json => registry.with(fmt_layer.json()).try_init(),
     };
-    let _ = subscriber.try_init();
     
     if !use_stdio {
         info!("Starting MCP System Monitor Server...");
     }
-    
+    config.log_sources(&sources);
+
     // Initialize system monitor
-    let system_monitor = Arc::new(RwLock::new(SystemMonitor::new()?));
+    let mut monitor =
+        SystemMonitor::with_command_timeout(Duration::from_millis(config.command_timeout_ms))?;
+    monitor.set_min_monitoring_interval(Duration::from_millis(config.min_interval_ms));
+    monitor.set_monitoring_interval(Duration::from_millis(config.monitoring_interval_ms));
+    monitor.set_collectors(config.collectors);
+    monitor.set_history_capacity(config.history_size);
+    monitor.set_alert_rules(config.alerts.clone());
+    monitor.set_webhooks(config.webhook.clone());
+    let system_monitor = Arc::new(RwLock::new(monitor));
+    let collection_timeout = Duration::from_millis(config.collection_timeout_ms);
     
     if use_stdio {
-        // Don't initialize logging for stdio mode - interferes with MCP protocol
-        let stdio_server = StdioServer::new(system_monitor);
+        // Stdio mode logs only errors, to stderr so stdout stays the MCP
+        // channel; clients can raise the level with logging/setLevel
+        let stdio_server = StdioServer::new(system_monitor)
+            .with_collection_timeout(collection_timeout)
+            .with_log_level_handle(log_level);
         if let Err(e) = stdio_server.run().await {
             return Err(anyhow::anyhow!("STDIO server failed: {}", e));
         }
@@ -46,13 +67,36 @@ async fn main() -> anyhow::Result<()> {
         let _mcp_server = MCPServer::new(system_monitor.clone());
         
         // Create HTTP server
-        let http_server = HTTPServer::new(system_monitor);
-        
+        let mut http_server = HTTPServer::new(system_monitor)
+            .with_bind_addr(config.bind_addr)
+            .with_collection_timeout(collection_timeout)
+            .with_log_level_handle(log_level);
+        match config.auth_token {
+            Some(token) => {
+                info!("Bearer token authentication enabled");
+                http_server = http_server.with_auth_token(token);
+            }
+            None => warn!("No auth token is configured; the HTTP API is unauthenticated"),
+        }
+        if config.rate_limit_per_sec > 0 {
+            http_server =
+                http_server.with_rate_limit(config.rate_limit_per_sec, config.rate_limit_burst);
+        }
+
         info!("MCP Server initialized successfully");
-        info!("Starting HTTP server on port 57996...");
 
-        // Run HTTP server
-        if let Err(e) = http_server.run(57996).await {
+        // Serve on a Unix socket if one is configured, otherwise HTTPS if a
+        // certificate and key are configured, plain HTTP otherwise
+        let result = match (config.socket_path, config.tls_cert, config.tls_key) {
+            #[cfg(unix)]
+            (Some(path), _, _) => http_server.run_uds(path).await,
+            #[cfg(not(unix))]
+            (Some(_), _, _) => Err("Unix sockets are not supported on this platform".into()),
+            (None, Some(cert), Some(key)) => http_server.run_tls(config.port, cert, key).await,
+            _ => http_server.run(config.port).await,
+        };
+
+        if let Err(e) = result {
             error!("HTTP server error: {}", e);
             return Err(anyhow::anyhow!("HTTP server failed: {}", e));
         }
@@ -0,0 +1,139 @@
+//! JSON Merge Patch Support
+//!
+//! RFC 7386 merge patch generation for streaming transports, so a subscriber
+//! that already holds the previous snapshot only receives what changed.
+
+use serde::Serialize;
+use serde_json::{Map, Value};
+
+/// Computes an RFC 7386 merge patch that turns `previous` into `current`
+///
+/// Object members are diffed recursively; removed members become `null`.
+/// Arrays and scalars cannot be patched partially and are replaced whole.
+/// Because `null` means "remove" in a merge patch, a member whose new value
+/// is `null` is removed on the receiving side rather than set to `null`.
+///
+/// # Arguments
+///
+/// * `previous` - The document the subscriber currently holds
+/// * `current` - The new document
+///
+/// # Returns
+///
+/// Returns the patch, or None if the documents are identical
+pub fn merge_patch_diff(previous: &Value, current: &Value) -> Option<Value> {
+    if previous == current {
+        return None;
+    }
+
+    match (previous, current) {
+        (Value::Object(prev), Value::Object(curr)) => {
+            let mut patch = Map::new();
+
+            for key in prev.keys().filter(|key| !curr.contains_key(*key)) {
+                patch.insert(key.clone(), Value::Null);
+            }
+
+            for (key, value) in curr {
+                match prev.get(key) {
+                    Some(old) => {
+                        if let Some(child) = merge_patch_diff(old, value) {
+                            patch.insert(key.clone(), child);
+                        }
+                    }
+                    None => {
+                        patch.insert(key.clone(), value.clone());
+                    }
+                }
+            }
+
+            Some(Value::Object(patch))
+        }
+        _ => Some(current.clone()),
+    }
+}
+
+/// Applies an RFC 7386 merge patch to `target` in place
+///
+/// # Arguments
+///
+/// * `target` - The document to patch
+/// * `patch` - The merge patch to apply
+pub fn apply_merge_patch(target: &mut Value, patch: &Value) {
+    let Value::Object(patch) = patch else {
+        *target = patch.clone();
+        return;
+    };
+
+    if !target.is_object() {
+        *target = Value::Object(Map::new());
+    }
+
+    if let Value::Object(target) = target {
+        for (key, value) in patch {
+            if value.is_null() {
+                target.remove(key);
+            } else {
+                apply_merge_patch(target.entry(key.clone()).or_insert(Value::Null), value);
+            }
+        }
+    }
+}
+
+/// A single update to push to a streaming subscriber
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(tag = "type", content = "data", rename_all = "lowercase")]
+pub enum StreamUpdate {
+    /// The complete document, sent first and periodically for resync
+    Full(Value),
+    /// An RFC 7386 merge patch against the previously sent document
+    Patch(Value),
+}
+
+/// Per-subscriber record of what was last sent on a streaming connection
+///
+/// Each connection owns one tracker. The first sample is sent in full, later
+/// samples as merge patches, and every `full_every` samples a full snapshot
+/// is sent again so a subscriber that missed or misapplied a patch resyncs.
+#[derive(Debug, Clone)]
+pub struct PatchTracker {
+    /// Document last sent to the subscriber
+    last_sent: Option<Value>,
+    /// Samples sent since the last full snapshot
+    since_full: u32,
+    /// Send a full snapshot after this many samples (0 disables resync)
+    full_every: u32,
+}
+
+impl PatchTracker {
+    /// Creates a tracker that resends a full snapshot every `full_every` samples
+    pub fn new(full_every: u32) -> Self {
+        Self {
+            last_sent: None,
+            since_full: 0,
+            full_every,
+        }
+    }
+
+    /// Produces the update to send for a new sample
+    ///
+    /// Returns None if nothing changed since the last update and no resync is due.
+    pub fn next_update(&mut self, current: Value) -> Option<StreamUpdate> {
+        let resync_due = self.full_every > 0 && self.since_full + 1 >= self.full_every;
+
+        let update = match &self.last_sent {
+            Some(previous) if !resync_due => {
+                let patch = merge_patch_diff(previous, &current)?;
+                self.since_full += 1;
+                StreamUpdate::Patch(patch)
+            }
+            _ => {
+                self.since_full = 0;
+                StreamUpdate::Full(current.clone())
+            }
+        };
+
+        self.last_sent = Some(current);
+        Some(update)
+    }
+}
@@ -0,0 +1,110 @@
+//! Request Rate Limiting
+//!
+//! A token bucket per client address, so a misconfigured scraper polling the
+//! HTTP API in a tight loop cannot make the monitor overload the host it is
+//! watching.
+
+use std::collections::HashMap;
+use std::net::IpAddr;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+/// Most clients tracked at once
+///
+/// Past it, buckets that have refilled are dropped, and if every client is
+/// still limited, the one idle longest makes room for the new one.
+pub const MAX_TRACKED_CLIENTS: usize = 4096;
+
+/// Tokens left for one client
+#[derive(Debug, Clone, Copy)]
+struct Bucket {
+    /// Requests the client may still make right away
+    tokens: f64,
+    /// When `tokens` was last brought up to date
+    updated: Instant,
+}
+
+/// Per-client token bucket limiter
+///
+/// Each client may make `burst` requests at once, then one more every
+/// `1 / per_second` seconds. Requests without a known address share a
+/// single bucket.
+#[derive(Debug)]
+pub struct RateLimiter {
+    /// Tokens added per second
+    per_second: f64,
+    /// Bucket capacity
+    burst: f64,
+    /// Bucket of each client seen recently
+    buckets: Mutex<HashMap<Option<IpAddr>, Bucket>>,
+}
+
+impl RateLimiter {
+    /// Creates a limiter allowing `per_second` requests per second per client,
+    /// with bursts of up to `burst` requests
+    pub fn new(per_second: u32, burst: u32) -> Self {
+        Self {
+            per_second: f64::from(per_second.max(1)),
+            burst: f64::from(burst.max(1)),
+            buckets: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Takes a token for a request from `client` arriving now
+    ///
+    /// # Errors
+    ///
+    /// Returns how long the client has to wait for the next token if its
+    /// bucket is empty
+    pub fn check(&self, client: Option<IpAddr>) -> Result<(), Duration> {
+        self.check_at(client, Instant::now())
+    }
+
+    /// Takes a token for a request from `client` arriving at `now`
+    ///
+    /// # Errors
+    ///
+    /// Returns how long the client has to wait for the next token if its
+    /// bucket is empty
+    pub fn check_at(&self, client: Option<IpAddr>, now: Instant) -> Result<(), Duration> {
+        let mut buckets = self
+            .buckets
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner());
+
+        if buckets.len() >= MAX_TRACKED_CLIENTS && !buckets.contains_key(&client) {
+            buckets.retain(|_, bucket| self.refill(*bucket, now) < self.burst);
+            if buckets.len() >= MAX_TRACKED_CLIENTS {
+                let oldest = buckets
+                    .iter()
+                    .min_by_key(|(_, bucket)| bucket.updated)
+                    .map(|(client, _)| *client);
+                if let Some(oldest) = oldest {
+                    buckets.remove(&oldest);
+                }
+            }
+        }
+
+        let bucket = buckets.entry(client).or_insert(Bucket {
+            tokens: self.burst,
+            updated: now,
+        });
+        bucket.tokens = self.refill(*bucket, now);
+        bucket.updated = now;
+
+        if bucket.tokens >= 1.0 {
+            bucket.tokens -= 1.0;
+            Ok(())
+        } else {
+            Err(Duration::from_secs_f64(
+                (1.0 - bucket.tokens) / self.per_second,
+            ))
+        }
+    }
+
+    /// Returns the tokens in `bucket` at `now`, capped at the burst size
+    fn refill(&self, bucket: Bucket, now: Instant) -> f64 {
+        let elapsed = now.saturating_duration_since(bucket.updated).as_secs_f64();
+        (bucket.tokens + elapsed * self.per_second).min(self.burst)
+    }
+}
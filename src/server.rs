@@ -2,14 +2,148 @@
 //!
 //! Handles JSON-RPC requests for system monitoring operations.
 
+use serde::Serialize;
 use serde_json::Value;
-use std::sync::Arc;
+use std::path::Path;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, OnceLock};
+use std::time::Duration;
 use tokio::sync::RwLock;
 use tracing::error;
+use tracing_subscriber::filter::LevelFilter;
+use tracing_subscriber::{reload, Registry};
 
-use crate::system_monitor::SystemMonitor;
+use crate::error::MonitorError;
+use crate::system_monitor::core::{
+    DEFAULT_COLLECTION_TIMEOUT, DEFAULT_DIR_USAGE_DEPTH, DEFAULT_DIR_USAGE_LIMIT,
+    MAX_DIR_USAGE_FILES,
+};
+use crate::system_monitor::dir_usage;
+use crate::system_monitor::helpers::{diff_metrics, history_points, metric_stats};
+use crate::system_monitor::{SystemInfoProvider, SystemMonitor};
 use crate::types::*;
 
+/// Handle that changes the maximum level of the server's log output
+///
+/// Created with `reload::Layer::new` and installed as the first layer of the
+/// `tracing` registry.
+pub type LogLevelHandle = reload::Handle<LevelFilter, Registry>;
+
+/// Metrics served as MCP resources: URI, name and description
+const RESOURCES: &[(&str, &str, &str)] = &[
+    (
+        "system://info",
+        "System information",
+        "Hostname, OS, kernel version and uptime",
+    ),
+    (
+        "system://uptime",
+        "Uptime",
+        "Seconds since boot and the boot time",
+    ),
+    (
+        "system://summary",
+        "Summary",
+        "Uptime, CPU, load, memory, root disk usage and process count",
+    ),
+    (
+        "system://cpu",
+        "CPU",
+        "CPU usage, frequency, core counts and temperature",
+    ),
+    ("system://memory", "Memory", "RAM and swap usage"),
+    (
+        "system://loadavg",
+        "Load average",
+        "1/5/15-minute load averages",
+    ),
+    (
+        "system://kernel-stats",
+        "Kernel statistics",
+        "Context switch, interrupt and fork counters",
+    ),
+    (
+        "system://fd-usage",
+        "File handles",
+        "System-wide file handles in use against the kernel limit",
+    ),
+    (
+        "system://entropy",
+        "Entropy",
+        "Bits of entropy available in the kernel pool",
+    ),
+    (
+        "system://pressure",
+        "Pressure",
+        "Pressure stall information for CPU, memory and I/O",
+    ),
+    (
+        "system://virtualization",
+        "Virtualization",
+        "Hypervisor or container environment the monitor runs in",
+    ),
+    (
+        "system://swaps",
+        "Swap devices",
+        "Usage of each swap partition, file and zram device",
+    ),
+    (
+        "system://numa",
+        "NUMA topology",
+        "NUMA nodes with their CPUs and memory",
+    ),
+    (
+        "system://block-devices",
+        "Block devices",
+        "Disks and their partitions",
+    ),
+    (
+        "system://disks",
+        "Disks",
+        "Usage of all mounted filesystems",
+    ),
+    (
+        "system://networks",
+        "Networks",
+        "Network interfaces and traffic counters",
+    ),
+    (
+        "system://socket-summary",
+        "Socket summary",
+        "Number of TCP sockets in each state",
+    ),
+    (
+        "system://disk-io",
+        "Disk I/O",
+        "I/O counters per block device",
+    ),
+    (
+        "system://temperatures",
+        "Temperatures",
+        "Temperature sensor readings",
+    ),
+    ("system://battery", "Battery", "Battery charge state"),
+    ("system://gpu", "GPU", "NVIDIA GPU utilization and memory"),
+    ("system://smart", "SMART", "Drive health from smartctl"),
+    ("system://services", "Services", "systemd service units"),
+    (
+        "system://alerts",
+        "Alerts",
+        "Threshold alerts that are currently firing",
+    ),
+    ("system://processes", "Processes", "All running processes"),
+    (
+        "system://top",
+        "Top processes",
+        "The 10 processes using the most CPU",
+    ),
+    (
+        "system://metrics",
+        "System metrics",
+        "Combined snapshot of every metric",
+    ),
+];
+
 /// MCP Server for system monitoring requests
 ///
 /// Implements Model Context Protocol server that processes JSON-RPC requests
@@ -18,12 +152,82 @@ use crate::types::*;
 pub struct MCPServer {
     /// Shared reference to system monitor
     system_monitor: Arc<RwLock<SystemMonitor>>,
+    /// The monitor's provider, kept so health probes need not take the lock
+    provider: OnceLock<Arc<dyn SystemInfoProvider>>,
+    /// Longest a single request may wait for a collection
+    collection_timeout: Duration,
+    /// Requests handled so far
+    requests: AtomicU64,
+    /// Requests answered with an error so far
+    request_errors: AtomicU64,
+    /// Log level filter changed by `logging/setLevel`, if logging is set up
+    log_level: Option<LogLevelHandle>,
 }
 
 impl MCPServer {
     /// Creates new MCP server instance
     pub fn new(system_monitor: Arc<RwLock<SystemMonitor>>) -> Self {
-        Self { system_monitor }
+        let provider = OnceLock::new();
+        if let Ok(monitor) = system_monitor.try_read() {
+            provider.get_or_init(|| monitor.provider());
+        }
+        Self {
+            system_monitor,
+            provider,
+            collection_timeout: DEFAULT_COLLECTION_TIMEOUT,
+            requests: AtomicU64::new(0),
+            request_errors: AtomicU64::new(0),
+            log_level: None,
+        }
+    }
+
+    /// Fails collections that take longer than `timeout` with ERROR_SYSTEM_COMMAND_FAILED
+    pub fn with_collection_timeout(mut self, timeout: Duration) -> Self {
+        self.collection_timeout = timeout;
+        self
+    }
+
+    /// Lets clients change the log level with `logging/setLevel`
+    ///
+    /// Without a handle the method fails, since the server does not know how
+    /// its embedder set up logging.
+    pub fn with_log_level_handle(mut self, handle: LogLevelHandle) -> Self {
+        self.log_level = Some(handle);
+        self
+    }
+
+    /// Returns the longest a single request may wait for a collection
+    pub fn collection_timeout(&self) -> Duration {
+        self.collection_timeout
+    }
+
+    /// Returns the number of requests handled and of those answered with an error
+    pub fn request_counts(&self) -> (u64, u64) {
+        (
+            self.requests.load(Ordering::Relaxed),
+            self.request_errors.load(Ordering::Relaxed),
+        )
+    }
+
+    /// Returns the shared system monitor, for transports that sample it directly
+    pub fn system_monitor(&self) -> Arc<RwLock<SystemMonitor>> {
+        self.system_monitor.clone()
+    }
+
+    /// Runs the provider's health probe, bounded by the collection timeout
+    ///
+    /// The provider is captured when the server is built, so the probe does
+    /// not wait for collections holding the monitor lock. Only if the monitor
+    /// was locked at that moment does the first probe take the lock to get it.
+    pub async fn probe(&self) -> Result<(), MonitorError> {
+        let provider = match self.provider.get() {
+            Some(provider) => Arc::clone(provider),
+            None => {
+                let provider = self.system_monitor.read().await.provider();
+                Arc::clone(self.provider.get_or_init(|| provider))
+            }
+        };
+        SystemMonitor::probe(provider, self.collection_timeout).await
     }
 
     /// Handles MCP request and returns response
@@ -33,27 +237,83 @@ impl MCPServer {
         use tracing::info;
         info!("🔍 MCP Handler - Method: {}, ID: {:?}", request.method, id);
 
-        match request.method.as_str() {
+        let mut response = match request.method.as_str() {
             "initialize" => self.handle_initialize(id, request.params).await,
             "initialized" => {
                 info!("✅ Received initialized notification");
                 // Notifications don't have responses, but return a dummy for consistency
                 self.create_success_response(id, serde_json::json!({}))
             },
+            // Liveness check; answers without touching the system monitor
+            "ping" => self.create_success_response(id, serde_json::json!({})),
             "tools/list" => self.handle_tools_list(id).await,
             "tools/call" => self.handle_tools_call(id, request.params).await,
+            "resources/list" => self.handle_resources_list(id),
+            "resources/read" => self.handle_resources_read(id, request.params).await,
+            "logging/setLevel" => self.handle_set_log_level(id, request.params),
             METHOD_GET_SYSTEM_INFO => self.handle_get_system_info(id).await,
+            METHOD_GET_UPTIME => self.handle_get_uptime(id).await,
+            METHOD_GET_SUMMARY => self.handle_get_summary(id).await,
             METHOD_GET_CPU_INFO => self.handle_get_cpu_info(id).await,
             METHOD_GET_MEMORY_INFO => self.handle_get_memory_info(id).await,
+            METHOD_GET_PRESSURE => self.handle_get_pressure(id).await,
+            METHOD_GET_LOAD_AVERAGE => self.handle_get_load_average(id).await,
+            METHOD_GET_KERNEL_STATS => self.handle_get_kernel_stats(id).await,
+            METHOD_GET_FD_USAGE => self.handle_get_fd_usage(id).await,
+            METHOD_GET_ENTROPY => self.handle_get_entropy(id).await,
+            METHOD_GET_VIRTUALIZATION => self.handle_get_virtualization(id).await,
+            METHOD_GET_SWAPS => self.handle_get_swaps(id).await,
+            METHOD_GET_NUMA_TOPOLOGY => self.handle_get_numa_topology(id).await,
+            METHOD_GET_BLOCK_DEVICES => self.handle_get_block_devices(id).await,
             METHOD_GET_DISK_INFO => self.handle_get_disk_info(id).await,
-            METHOD_GET_NETWORK_INFO => self.handle_get_network_info(id).await,
-            METHOD_GET_PROCESSES => self.handle_get_processes(id).await,
+            METHOD_GET_DIRECTORY_USAGE => self.handle_get_directory_usage(id, request.params).await,
+            METHOD_GET_NETWORK_INFO => self.handle_get_network_info(id, request.params).await,
+            METHOD_GET_NETWORK_RATES => self.handle_get_network_rates(id).await,
+            METHOD_GET_NETWORK_CONNECTIONS => {
+                self.handle_get_network_connections(id, request.params)
+                    .await
+            }
+            METHOD_GET_SOCKET_SUMMARY => self.handle_get_socket_summary(id).await,
+            METHOD_GET_DISK_IO => self.handle_get_disk_io(id).await,
+            METHOD_GET_TEMPERATURES => self.handle_get_temperatures(id).await,
+            METHOD_GET_BATTERY_INFO => self.handle_get_battery_info(id).await,
+            METHOD_GET_GPU_INFO => self.handle_get_gpu_info(id).await,
+            METHOD_GET_DISK_SMART => self.handle_get_disk_smart(id).await,
+            METHOD_GET_SERVICES => self.handle_get_services(id, request.params).await,
+            METHOD_GET_RECENT_LOGS => self.handle_get_recent_logs(id, request.params).await,
+            METHOD_GET_HISTORY => self.handle_get_history(id, request.params).await,
+            METHOD_GET_HISTORY_STATS => self.handle_get_history_stats(id, request.params).await,
+            METHOD_GET_ALERTS => self.handle_get_alerts(id).await,
+            METHOD_GET_PROCESSES => self.handle_get_processes(id, request.params).await,
+            METHOD_GET_TOP_PROCESSES => self.handle_get_top_processes(id, request.params).await,
             METHOD_GET_PROCESS_BY_PID => self.handle_get_process_by_pid(id, request.params).await,
-            METHOD_GET_SYSTEM_METRICS => self.handle_get_system_metrics(id).await,
+            METHOD_GET_PROCESS_TREE => self.handle_get_process_tree(id, request.params).await,
+            METHOD_GET_PROCESS_MEMORY => self.handle_get_process_memory(id, request.params).await,
+            METHOD_GET_PROCESS_LIMITS => self.handle_get_process_limits(id, request.params).await,
+            METHOD_GET_SELF => self.handle_get_self(id).await,
+            METHOD_KILL_PROCESS => self.handle_kill_process(id, request.params).await,
+            METHOD_RENICE => self.handle_renice(id, request.params).await,
+            METHOD_GET_SYSTEM_METRICS => self.handle_get_system_metrics(id, request.params).await,
+            METHOD_DIFF_METRICS => self.handle_diff_metrics(id, request.params),
             METHOD_START_MONITORING => self.handle_start_monitoring(id).await,
             METHOD_STOP_MONITORING => self.handle_stop_monitoring(id).await,
+            METHOD_SET_MONITORING_INTERVAL => {
+                self.handle_set_monitoring_interval(id, request.params)
+                    .await
+            }
             _ => self.create_error_response(id, ERROR_METHOD_NOT_FOUND, "Method not found"),
+        };
+
+        // Name the method in collector failure data
+        if let Some(Value::Object(data)) = response.error.as_mut().and_then(|e| e.data.as_mut()) {
+            data.insert("method".to_string(), Value::from(request.method));
         }
+
+        self.requests.fetch_add(1, Ordering::Relaxed);
+        if response.error.is_some() {
+            self.request_errors.fetch_add(1, Ordering::Relaxed);
+        }
+        response
     }
 
     /// Handles initialize method (MCP spec requirement)
@@ -67,16 +327,66 @@ impl MCPServer {
         let result = serde_json::json!({
             "protocolVersion": requested_version,
             "capabilities": {
-                "tools": {}
+                "tools": {},
+                "resources": {},
+                "logging": {}
             },
             "serverInfo": {
                 "name": "mcp-system-monitor",
-                "version": "0.1.0"
+                "version": env!("CARGO_PKG_VERSION")
             }
         });
         self.create_success_response(id, result)
     }
 
+    /// Handles logging/setLevel method
+    ///
+    /// Accepts the syslog severities MCP defines. `tracing` has fewer levels,
+    /// so notice logs like info, and critical, alert and emergency like error.
+    fn handle_set_log_level(&self, id: Option<String>, params: Value) -> MCPResponse {
+        let Some(level) = params.get("level").and_then(Value::as_str) else {
+            return self.create_error_response(id, ERROR_INVALID_PARAMS, "Missing level parameter");
+        };
+        let filter = match level {
+            "debug" => LevelFilter::DEBUG,
+            "info" | "notice" => LevelFilter::INFO,
+            "warning" => LevelFilter::WARN,
+            "error" | "critical" | "alert" | "emergency" => LevelFilter::ERROR,
+            _ => {
+                return self.create_error_response(
+                    id,
+                    ERROR_INVALID_PARAMS,
+                    &format!(
+                        "Invalid log level: {} (expected debug, info, notice, warning, error, critical, alert or emergency)",
+                        level
+                    ),
+                );
+            }
+        };
+
+        let Some(handle) = &self.log_level else {
+            return self.create_error_response(
+                id,
+                ERROR_INTERNAL_ERROR,
+                "The log level cannot be changed: logging is not managed by this server",
+            );
+        };
+        match handle.reload(filter) {
+            Ok(()) => {
+                tracing::info!("Log level set to {}", level);
+                self.create_success_response(id, serde_json::json!({}))
+            }
+            Err(e) => {
+                error!("Failed to set log level: {}", e);
+                self.create_error_response(
+                    id,
+                    ERROR_INTERNAL_ERROR,
+                    &format!("Failed to set log level: {}", e),
+                )
+            }
+        }
+    }
+
     /// Handles tools/list method (MCP spec requirement)
     async fn handle_tools_list(&self, id: Option<String>) -> MCPResponse {
         let result = serde_json::json!({
@@ -89,6 +399,22 @@ impl MCPServer {
                         "properties": {}
                     }
                 },
+                {
+                    "name": "get_uptime",
+                    "description": "Get the system uptime in seconds and for display, and the boot time, without the rest of the system information",
+                    "inputSchema": {
+                        "type": "object",
+                        "properties": {}
+                    }
+                },
+                {
+                    "name": "get_summary",
+                    "description": "Get headline numbers without any lists: uptime, CPU usage, load average, memory and swap usage, root filesystem usage and process count",
+                    "inputSchema": {
+                        "type": "object",
+                        "properties": {}
+                    }
+                },
                 {
                     "name": "get_cpu_info",
                     "description": "Get CPU information and usage statistics",
@@ -105,6 +431,78 @@ impl MCPServer {
                         "properties": {}
                     }
                 },
+                {
+                    "name": "get_load_average",
+                    "description": "Get 1/5/15-minute load averages and runnable process counts",
+                    "inputSchema": {
+                        "type": "object",
+                        "properties": {}
+                    }
+                },
+                {
+                    "name": "get_kernel_stats",
+                    "description": "Get context switch, interrupt and fork counters since boot and running/blocked process counts",
+                    "inputSchema": {
+                        "type": "object",
+                        "properties": {}
+                    }
+                },
+                {
+                    "name": "get_fd_usage",
+                    "description": "Get system-wide file handles in use, the kernel limit (fs.file-max) and the usage percentage",
+                    "inputSchema": {
+                        "type": "object",
+                        "properties": {}
+                    }
+                },
+                {
+                    "name": "get_entropy",
+                    "description": "Get the bits of entropy available in the kernel random pool and the pool size",
+                    "inputSchema": {
+                        "type": "object",
+                        "properties": {}
+                    }
+                },
+                {
+                    "name": "get_pressure",
+                    "description": "Get pressure stall information (PSI): share of time tasks waited on CPU, memory or I/O over 10/60/300 seconds",
+                    "inputSchema": {
+                        "type": "object",
+                        "properties": {}
+                    }
+                },
+                {
+                    "name": "get_virtualization",
+                    "description": "Detect whether the host is bare metal, a virtual machine or a container",
+                    "inputSchema": {
+                        "type": "object",
+                        "properties": {}
+                    }
+                },
+                {
+                    "name": "get_swaps",
+                    "description": "Get usage and priority of each swap partition, file and zram device",
+                    "inputSchema": {
+                        "type": "object",
+                        "properties": {}
+                    }
+                },
+                {
+                    "name": "get_numa_topology",
+                    "description": "Get NUMA nodes with their CPUs and total and free memory",
+                    "inputSchema": {
+                        "type": "object",
+                        "properties": {}
+                    }
+                },
+                {
+                    "name": "get_block_devices",
+                    "description": "Get disks and their partitions with size, model and whether each is a spinning disk",
+                    "inputSchema": {
+                        "type": "object",
+                        "properties": {}
+                    }
+                },
                 {
                     "name": "get_disk_info",
                     "description": "Get disk usage information for all mounted filesystems",
@@ -113,9 +511,195 @@ impl MCPServer {
                         "properties": {}
                     }
                 },
+                {
+                    "name": "get_directory_usage",
+                    "description": "Get the total size of a directory and its largest subdirectories, like du",
+                    "inputSchema": {
+                        "type": "object",
+                        "properties": {
+                            "path": {
+                                "type": "string",
+                                "description": "Directory to measure, e.g. /var"
+                            },
+                            "depth": {
+                                "type": "integer",
+                                "minimum": 0,
+                                "description": "Levels of subdirectories to report (default: 1)"
+                            },
+                            "limit": {
+                                "type": "integer",
+                                "minimum": 0,
+                                "description": "Number of largest subdirectories to return (default: 10)"
+                            },
+                            "cross_devices": {
+                                "type": "boolean",
+                                "description": "Descend into directories on other filesystems (default: false)"
+                            }
+                        },
+                        "required": ["path"]
+                    }
+                },
                 {
                     "name": "get_network_info",
                     "description": "Get network interface information and statistics",
+                    "inputSchema": {
+                        "type": "object",
+                        "properties": {
+                            "include_loopback": {
+                                "type": "boolean",
+                                "description": "Include the loopback interface (default false)"
+                            },
+                            "include_link_local": {
+                                "type": "boolean",
+                                "description": "Include link-local IPv6 addresses (fe80::/10) (default false)"
+                            }
+                        }
+                    }
+                },
+                {
+                    "name": "get_network_rates",
+                    "description": "Get per-interface network throughput (bytes/sec) since the previous call",
+                    "inputSchema": {
+                        "type": "object",
+                        "properties": {}
+                    }
+                },
+                {
+                    "name": "get_network_connections",
+                    "description": "Get TCP and UDP sockets with local/remote addresses, ports and states",
+                    "inputSchema": {
+                        "type": "object",
+                        "properties": {
+                            "state": {
+                                "type": "string",
+                                "description": "Only return sockets in this state, e.g. LISTEN or ESTABLISHED"
+                            }
+                        }
+                    }
+                },
+                {
+                    "name": "get_socket_summary",
+                    "description": "Get the number of TCP sockets in each state, e.g. ESTABLISHED, TIME_WAIT or CLOSE_WAIT",
+                    "inputSchema": {
+                        "type": "object",
+                        "properties": {}
+                    }
+                },
+                {
+                    "name": "get_disk_io",
+                    "description": "Get cumulative disk I/O counters (reads, writes, bytes) per block device",
+                    "inputSchema": {
+                        "type": "object",
+                        "properties": {}
+                    }
+                },
+                {
+                    "name": "get_temperatures",
+                    "description": "Get readings (chip, label, °C) from all temperature sensors",
+                    "inputSchema": {
+                        "type": "object",
+                        "properties": {}
+                    }
+                },
+                {
+                    "name": "get_battery_info",
+                    "description": "Get battery charge level, status and estimated time remaining",
+                    "inputSchema": {
+                        "type": "object",
+                        "properties": {}
+                    }
+                },
+                {
+                    "name": "get_gpu_info",
+                    "description": "Get NVIDIA GPU utilization, memory and temperature (empty without nvidia-smi)",
+                    "inputSchema": {
+                        "type": "object",
+                        "properties": {}
+                    }
+                },
+                {
+                    "name": "get_disk_smart",
+                    "description": "Get SMART health, temperature, power-on hours and reallocated sectors of each drive (requires smartctl and root)",
+                    "inputSchema": {
+                        "type": "object",
+                        "properties": {}
+                    }
+                },
+                {
+                    "name": "get_services",
+                    "description": "Get systemd service units with their load, active and sub states (empty on hosts without systemd)",
+                    "inputSchema": {
+                        "type": "object",
+                        "properties": {
+                            "state": {
+                                "type": "string",
+                                "description": "Only return units in this active or sub state, e.g. failed or running"
+                            }
+                        }
+                    }
+                },
+                {
+                    "name": "get_recent_logs",
+                    "description": "Get the most recent systemd journal entries, or syslog lines on hosts without the journal",
+                    "inputSchema": {
+                        "type": "object",
+                        "properties": {
+                            "lines": {
+                                "type": "integer",
+                                "minimum": 0,
+                                "maximum": 1000,
+                                "description": "Number of most recent entries to return (default 50)"
+                            },
+                            "priority": {
+                                "type": "integer",
+                                "minimum": 0,
+                                "maximum": 7,
+                                "description": "Only return entries of this syslog priority or more severe, e.g. 3 for errors"
+                            }
+                        }
+                    }
+                },
+                {
+                    "name": "get_history",
+                    "description": "Get recent CPU, memory and load samples recorded while monitoring is active",
+                    "inputSchema": {
+                        "type": "object",
+                        "properties": {
+                            "metric": {
+                                "type": "string",
+                                "enum": ["cpu", "memory", "load"],
+                                "description": "Return only this metric as timestamp/value points"
+                            },
+                            "points": {
+                                "type": "integer",
+                                "minimum": 0,
+                                "description": "Number of most recent samples to return"
+                            }
+                        }
+                    }
+                },
+                {
+                    "name": "get_history_stats",
+                    "description": "Get min, max, mean, p50/p95/p99 percentiles and standard deviation of a metric over the recent history",
+                    "inputSchema": {
+                        "type": "object",
+                        "properties": {
+                            "metric": {
+                                "type": "string",
+                                "enum": ["cpu", "memory", "load"],
+                                "description": "Metric to summarize (default: cpu)"
+                            },
+                            "window": {
+                                "type": "integer",
+                                "minimum": 0,
+                                "description": "Number of most recent samples to summarize (default: all)"
+                            }
+                        }
+                    }
+                },
+                {
+                    "name": "get_alerts",
+                    "description": "Get threshold alerts that are currently firing (evaluated while monitoring is active)",
                     "inputSchema": {
                         "type": "object",
                         "properties": {}
@@ -124,6 +708,120 @@ impl MCPServer {
                 {
                     "name": "get_processes",
                     "description": "Get list of all running processes",
+                    "inputSchema": {
+                        "type": "object",
+                        "properties": {
+                            "sort_by": {
+                                "type": "string",
+                                "enum": ["cpu", "memory", "pid", "name", "io"],
+                                "description": "Field to sort by (default cpu); io reads the I/O counters of every process"
+                            },
+                            "order": {
+                                "type": "string",
+                                "enum": ["asc", "desc"],
+                                "description": "Sort direction (default desc)"
+                            },
+                            "user": {
+                                "type": "string",
+                                "description": "Only return processes owned by this user"
+                            },
+                            "name_contains": {
+                                "type": "string",
+                                "description": "Only return processes whose name contains this (case-insensitive)"
+                            },
+                            "limit": {
+                                "type": "integer",
+                                "minimum": 0,
+                                "description": "Maximum number of processes to return"
+                            },
+                            "offset": {
+                                "type": "integer",
+                                "minimum": 0,
+                                "description": "Number of sorted processes to skip"
+                            },
+                            "include_io": {
+                                "type": "boolean",
+                                "description": "Read per-process I/O counters from /proc/<pid>/io (default false)"
+                            },
+                            "fields": {
+                                "type": "array",
+                                "items": {
+                                    "type": "string",
+                                    "enum": ["wchan", "session", "tty", "cgroup"]
+                                },
+                                "description": "Supplemental fields to read from /proc into each process's extra map"
+                            }
+                        }
+                    }
+                },
+                {
+                    "name": "get_top_processes",
+                    "description": "Get the processes using the most CPU, memory or I/O, highest first, with totals over all processes",
+                    "inputSchema": {
+                        "type": "object",
+                        "properties": {
+                            "by": {
+                                "type": "string",
+                                "enum": ["cpu", "memory", "io"],
+                                "description": "Resource to rank by (default cpu)"
+                            },
+                            "n": {
+                                "type": "integer",
+                                "minimum": 1,
+                                "maximum": 100,
+                                "description": "Number of processes to return (default 10, at most 100)"
+                            }
+                        }
+                    }
+                },
+                {
+                    "name": "get_process_by_pid",
+                    "description": "Get a single process by its PID",
+                    "inputSchema": {
+                        "type": "object",
+                        "properties": {
+                            "pid": {
+                                "type": "integer",
+                                "minimum": 0,
+                                "description": "Process ID to look up"
+                            }
+                        },
+                        "required": ["pid"]
+                    }
+                },
+                {
+                    "name": "get_process_memory",
+                    "description": "Get the memory breakdown of a process: virtual size, RSS split into anonymous, file and shared memory, swap, heap, stack and code",
+                    "inputSchema": {
+                        "type": "object",
+                        "properties": {
+                            "pid": {
+                                "type": "integer",
+                                "minimum": 0,
+                                "description": "Process ID to inspect"
+                            }
+                        },
+                        "required": ["pid"]
+                    }
+                },
+                {
+                    "name": "get_process_limits",
+                    "description": "Get the soft and hard resource limits of a process, such as max open files, processes, address space and locked memory",
+                    "inputSchema": {
+                        "type": "object",
+                        "properties": {
+                            "pid": {
+                                "type": "integer",
+                                "minimum": 0,
+                                "description": "Process ID to inspect"
+                            }
+                        },
+                        "required": ["pid"]
+                    }
+                },
+                {
+                    "name": "get_self",
+                    "description": "Get the monitor's own CPU time, memory, open file descriptors, threads, uptime and request counts",
                     "inputSchema": {
                         "type": "object",
                         "properties": {}
@@ -132,6 +830,54 @@ impl MCPServer {
                 {
                     "name": "get_system_metrics",
                     "description": "Get comprehensive system metrics",
+                    "inputSchema": {
+                        "type": "object",
+                        "properties": {
+                            "components": {
+                                "type": "array",
+                                "items": {
+                                    "type": "string",
+                                    "enum": ["system", "cpu", "memory", "load", "disks", "networks", "processes"]
+                                },
+                                "description": "Sections to collect (default all)"
+                            },
+                            "max_processes": {
+                                "type": "integer",
+                                "minimum": 1,
+                                "description": "Most processes to include (default the configured max_processes, or all)"
+                            }
+                        }
+                    }
+                },
+                {
+                    "name": "diff_metrics",
+                    "description": "Compare two get_system_metrics snapshots: CPU and memory deltas, started and exited processes, and per-interface traffic rates",
+                    "inputSchema": {
+                        "type": "object",
+                        "properties": {
+                            "before": {
+                                "type": "object",
+                                "description": "The earlier get_system_metrics result"
+                            },
+                            "after": {
+                                "type": "object",
+                                "description": "The later get_system_metrics result"
+                            }
+                        },
+                        "required": ["before", "after"]
+                    }
+                },
+                {
+                    "name": "start_monitoring",
+                    "description": "Start background sampling for history and alerts",
+                    "inputSchema": {
+                        "type": "object",
+                        "properties": {}
+                    }
+                },
+                {
+                    "name": "stop_monitoring",
+                    "description": "Stop background sampling",
                     "inputSchema": {
                         "type": "object",
                         "properties": {}
@@ -151,14 +897,63 @@ impl MCPServer {
             info!("🔧 Calling tool: {}", name);
         }
         
+        // Tool inputs live under `arguments`, not at the top level of params
+        let arguments = params.get("arguments").cloned().unwrap_or(Value::Null);
+
         let tool_response = match tool_name {
             Some("get_system_info") => self.handle_get_system_info(id.clone()).await,
+            Some("get_uptime") => self.handle_get_uptime(id.clone()).await,
+            Some("get_summary") => self.handle_get_summary(id.clone()).await,
             Some("get_cpu_info") => self.handle_get_cpu_info(id.clone()).await,
             Some("get_memory_info") => self.handle_get_memory_info(id.clone()).await,
+            Some("get_load_average") => self.handle_get_load_average(id.clone()).await,
+            Some("get_kernel_stats") => self.handle_get_kernel_stats(id.clone()).await,
+            Some("get_fd_usage") => self.handle_get_fd_usage(id.clone()).await,
+            Some("get_entropy") => self.handle_get_entropy(id.clone()).await,
+            Some("get_pressure") => self.handle_get_pressure(id.clone()).await,
+            Some("get_virtualization") => self.handle_get_virtualization(id.clone()).await,
+            Some("get_swaps") => self.handle_get_swaps(id.clone()).await,
+            Some("get_numa_topology") => self.handle_get_numa_topology(id.clone()).await,
+            Some("get_block_devices") => self.handle_get_block_devices(id.clone()).await,
             Some("get_disk_info") => self.handle_get_disk_info(id.clone()).await,
-            Some("get_network_info") => self.handle_get_network_info(id.clone()).await,
-            Some("get_processes") => self.handle_get_processes(id.clone()).await,
-            Some("get_system_metrics") => self.handle_get_system_metrics(id.clone()).await,
+            Some("get_directory_usage") => {
+                self.handle_get_directory_usage(id.clone(), arguments).await
+            }
+            Some("get_network_info") => self.handle_get_network_info(id.clone(), arguments).await,
+            Some("get_network_rates") => self.handle_get_network_rates(id.clone()).await,
+            Some("get_network_connections") => {
+                self.handle_get_network_connections(id.clone(), arguments)
+                    .await
+            }
+            Some("get_socket_summary") => self.handle_get_socket_summary(id.clone()).await,
+            Some("get_disk_io") => self.handle_get_disk_io(id.clone()).await,
+            Some("get_temperatures") => self.handle_get_temperatures(id.clone()).await,
+            Some("get_battery_info") => self.handle_get_battery_info(id.clone()).await,
+            Some("get_gpu_info") => self.handle_get_gpu_info(id.clone()).await,
+            Some("get_disk_smart") => self.handle_get_disk_smart(id.clone()).await,
+            Some("get_services") => self.handle_get_services(id.clone(), arguments).await,
+            Some("get_recent_logs") => self.handle_get_recent_logs(id.clone(), arguments).await,
+            Some("get_history") => self.handle_get_history(id.clone(), arguments).await,
+            Some("get_history_stats") => self.handle_get_history_stats(id.clone(), arguments).await,
+            Some("get_alerts") => self.handle_get_alerts(id.clone()).await,
+            Some("get_processes") => self.handle_get_processes(id.clone(), arguments).await,
+            Some("get_top_processes") => self.handle_get_top_processes(id.clone(), arguments).await,
+            Some("get_process_by_pid") => {
+                self.handle_get_process_by_pid(id.clone(), arguments).await
+            }
+            Some("get_process_memory") => {
+                self.handle_get_process_memory(id.clone(), arguments).await
+            }
+            Some("get_process_limits") => {
+                self.handle_get_process_limits(id.clone(), arguments).await
+            }
+            Some("get_self") => self.handle_get_self(id.clone()).await,
+            Some("get_system_metrics") => {
+                self.handle_get_system_metrics(id.clone(), arguments).await
+            }
+            Some("diff_metrics") => self.handle_diff_metrics(id.clone(), arguments),
+            Some("start_monitoring") => self.handle_start_monitoring(id.clone()).await,
+            Some("stop_monitoring") => self.handle_stop_monitoring(id.clone()).await,
             _ => return self.create_error_response(id, ERROR_METHOD_NOT_FOUND, "Tool not found"),
         };
         
@@ -175,144 +970,520 @@ impl MCPServer {
         }
     }
 
-    /// Handles getSystemInfo method
-    async fn handle_get_system_info(&self, id: Option<String>) -> MCPResponse {
-        let mut monitor = self.system_monitor.write().await;
-        match monitor.get_system_info() {
-            Ok(info) => {
-                let result = serde_json::to_value(info).unwrap_or_default();
-                self.create_success_response(id, result)
+    /// Handles resources/list method
+    fn handle_resources_list(&self, id: Option<String>) -> MCPResponse {
+        let resources: Vec<Value> = RESOURCES
+            .iter()
+            .map(|(uri, name, description)| {
+                serde_json::json!({
+                    "uri": uri,
+                    "name": name,
+                    "description": description,
+                    "mimeType": "application/json"
+                })
+            })
+            .collect();
+        self.create_success_response(id, serde_json::json!({ "resources": resources }))
+    }
+
+    /// Handles resources/read method, returning the resource's JSON snapshot
+    async fn handle_resources_read(&self, id: Option<String>, params: Value) -> MCPResponse {
+        let Some(uri) = params.get("uri").and_then(|v| v.as_str()) else {
+            return self.create_error_response(id, ERROR_INVALID_PARAMS, "Missing uri parameter");
+        };
+
+        let response = match uri {
+            "system://info" => self.handle_get_system_info(id.clone()).await,
+            "system://uptime" => self.handle_get_uptime(id.clone()).await,
+            "system://summary" => self.handle_get_summary(id.clone()).await,
+            "system://cpu" => self.handle_get_cpu_info(id.clone()).await,
+            "system://memory" => self.handle_get_memory_info(id.clone()).await,
+            "system://loadavg" => self.handle_get_load_average(id.clone()).await,
+            "system://kernel-stats" => self.handle_get_kernel_stats(id.clone()).await,
+            "system://fd-usage" => self.handle_get_fd_usage(id.clone()).await,
+            "system://entropy" => self.handle_get_entropy(id.clone()).await,
+            "system://pressure" => self.handle_get_pressure(id.clone()).await,
+            "system://virtualization" => self.handle_get_virtualization(id.clone()).await,
+            "system://swaps" => self.handle_get_swaps(id.clone()).await,
+            "system://numa" => self.handle_get_numa_topology(id.clone()).await,
+            "system://block-devices" => self.handle_get_block_devices(id.clone()).await,
+            "system://disks" => self.handle_get_disk_info(id.clone()).await,
+            "system://networks" => self.handle_get_network_info(id.clone(), Value::Null).await,
+            "system://socket-summary" => self.handle_get_socket_summary(id.clone()).await,
+            "system://disk-io" => self.handle_get_disk_io(id.clone()).await,
+            "system://temperatures" => self.handle_get_temperatures(id.clone()).await,
+            "system://battery" => self.handle_get_battery_info(id.clone()).await,
+            "system://gpu" => self.handle_get_gpu_info(id.clone()).await,
+            "system://smart" => self.handle_get_disk_smart(id.clone()).await,
+            "system://services" => self.handle_get_services(id.clone(), Value::Null).await,
+            "system://alerts" => self.handle_get_alerts(id.clone()).await,
+            "system://processes" => self.handle_get_processes(id.clone(), Value::Null).await,
+            "system://top" => self.handle_get_top_processes(id.clone(), Value::Null).await,
+            "system://metrics" => {
+                self.handle_get_system_metrics(id.clone(), Value::Null)
+                    .await
             }
-            Err(e) => {
-                error!("Failed to get system info: {}", e);
-                self.create_error_response(
+            _ => {
+                return self.create_error_response(
                     id,
-                    ERROR_INTERNAL_ERROR,
-                    &format!("Failed to get system info: {}", e),
-                )
+                    ERROR_INVALID_PARAMS,
+                    &format!("Unknown resource: {}", uri),
+                );
+            }
+        };
+
+        match response.result {
+            Some(result) => {
+                let contents = vec![serde_json::json!({
+                    "uri": uri,
+                    "mimeType": "application/json",
+                    "text": serde_json::to_string_pretty(&result).unwrap_or_else(|_| "{}".to_string())
+                })];
+                self.create_success_response(id, serde_json::json!({ "contents": contents }))
+            }
+            None => response,
+        }
+    }
+
+    /// Handles getUptime method
+    async fn handle_get_uptime(&self, id: Option<String>) -> MCPResponse {
+        let result = self.collect(|monitor| monitor.get_uptime()).await;
+        self.collector_response(id, result, "uptime")
+    }
+
+    /// Handles getSummary method
+    async fn handle_get_summary(&self, id: Option<String>) -> MCPResponse {
+        let result = self.collect(|monitor| monitor.get_summary()).await;
+        self.collector_response(id, result, "summary")
+    }
+
+    /// Handles getSystemInfo method
+    async fn handle_get_system_info(&self, id: Option<String>) -> MCPResponse {
+        match self.collect(|monitor| monitor.get_system_info()).await {
+            Ok(info) => {
+                let result = serde_json::to_value(info).unwrap_or_default();
+                self.create_success_response(id, result)
+            }
+            Err(e) => {
+                error!("Failed to get system info: {}", e);
+                self.monitor_error_response(id, &e, &format!("Failed to get system info: {}", e))
             }
         }
     }
 
     /// Handles getCPUInfo method
     async fn handle_get_cpu_info(&self, id: Option<String>) -> MCPResponse {
-        let mut monitor = self.system_monitor.write().await;
-        match monitor.get_cpu_info() {
+        match self.collect(|monitor| monitor.get_cpu_info()).await {
             Ok(info) => {
                 let result = serde_json::to_value(info).unwrap_or_default();
                 self.create_success_response(id, result)
             }
             Err(e) => {
                 error!("Failed to get CPU info: {}", e);
-                self.create_error_response(
-                    id,
-                    ERROR_INTERNAL_ERROR,
-                    &format!("Failed to get CPU info: {}", e),
-                )
+                self.monitor_error_response(id, &e, &format!("Failed to get CPU info: {}", e))
             }
         }
     }
 
     /// Handles getMemoryInfo method
     async fn handle_get_memory_info(&self, id: Option<String>) -> MCPResponse {
-        let mut monitor = self.system_monitor.write().await;
-        match monitor.get_memory_info() {
+        match self.collect(|monitor| monitor.get_memory_info()).await {
             Ok(info) => {
                 let result = serde_json::to_value(info).unwrap_or_default();
                 self.create_success_response(id, result)
             }
             Err(e) => {
                 error!("Failed to get memory info: {}", e);
-                self.create_error_response(
-                    id,
-                    ERROR_INTERNAL_ERROR,
-                    &format!("Failed to get memory info: {}", e),
-                )
+                self.monitor_error_response(id, &e, &format!("Failed to get memory info: {}", e))
             }
         }
     }
 
+    /// Handles getLoadAverage method
+    async fn handle_get_load_average(&self, id: Option<String>) -> MCPResponse {
+        let result = self.collect(|monitor| monitor.get_load_average()).await;
+        self.collector_response(id, result, "load average")
+    }
+
+    /// Handles getKernelStats method
+    async fn handle_get_kernel_stats(&self, id: Option<String>) -> MCPResponse {
+        let result = self.collect(|monitor| monitor.get_kernel_stats()).await;
+        self.collector_response(id, result, "kernel stats")
+    }
+
+    /// Handles getFdUsage method
+    async fn handle_get_fd_usage(&self, id: Option<String>) -> MCPResponse {
+        let result = self.collect(|monitor| monitor.get_fd_usage()).await;
+        self.collector_response(id, result, "file handle usage")
+    }
+
+    /// Handles getEntropy method
+    async fn handle_get_entropy(&self, id: Option<String>) -> MCPResponse {
+        let result = self.collect(|monitor| monitor.get_entropy()).await;
+        self.collector_response(id, result, "entropy")
+    }
+
+    /// Handles getPressure method
+    async fn handle_get_pressure(&self, id: Option<String>) -> MCPResponse {
+        let result = self.collect(|monitor| monitor.get_pressure()).await;
+        self.collector_response(id, result, "pressure stall information")
+    }
+
+    /// Handles getVirtualization method
+    async fn handle_get_virtualization(&self, id: Option<String>) -> MCPResponse {
+        let result = self.collect(|monitor| monitor.get_virtualization()).await;
+        self.collector_response(id, result, "virtualization info")
+    }
+
+    /// Handles getSwaps method
+    async fn handle_get_swaps(&self, id: Option<String>) -> MCPResponse {
+        let result = self.collect(|monitor| monitor.get_swaps()).await;
+        self.collector_response(id, result, "swap devices")
+    }
+
+    /// Handles getNUMATopology method
+    async fn handle_get_numa_topology(&self, id: Option<String>) -> MCPResponse {
+        let result = self.collect(|monitor| monitor.get_numa_topology()).await;
+        self.collector_response(id, result, "NUMA topology")
+    }
+
+    /// Handles getBlockDevices method
+    async fn handle_get_block_devices(&self, id: Option<String>) -> MCPResponse {
+        let result = self.collect(|monitor| monitor.get_block_devices()).await;
+        self.collector_response(id, result, "block devices")
+    }
+
     /// Handles getDiskInfo method
     async fn handle_get_disk_info(&self, id: Option<String>) -> MCPResponse {
-        let mut monitor = self.system_monitor.write().await;
-        match monitor.get_disk_info() {
-            Ok(disks) => {
-                let result = serde_json::to_value(disks).unwrap_or_default();
-                self.create_success_response(id, result)
-            }
-            Err(e) => {
-                error!("Failed to get disk info: {}", e);
-                self.create_error_response(
-                    id,
-                    ERROR_INTERNAL_ERROR,
-                    &format!("Failed to get disk info: {}", e),
-                )
+        let result = self.collect(|monitor| monitor.get_disk_info()).await;
+        self.collector_response(id, result, "disk info")
+    }
+
+    /// Handles getDirectoryUsage method
+    async fn handle_get_directory_usage(&self, id: Option<String>, params: Value) -> MCPResponse {
+        // Absent params are rejected by the collector for lacking a path
+        let query = if params.is_null() {
+            DirUsageQuery::default()
+        } else {
+            match serde_json::from_value::<DirUsageQuery>(params) {
+                Ok(query) => query,
+                Err(e) => {
+                    return self.create_error_response(
+                        id,
+                        ERROR_INVALID_PARAMS,
+                        &format!("Invalid directory usage query: {}", e),
+                    );
+                }
             }
-        }
+        };
+
+        let Some(path) = query.path.filter(|path| !path.is_empty()) else {
+            let error = MonitorError::InvalidArgument("path is required".to_string());
+            return self.collector_response::<DirectoryUsage>(id, Err(error), "directory usage");
+        };
+
+        // The walk needs neither the collector nor the monitor, so it runs
+        // without the monitor lock and cannot stall other requests
+        let walk = tokio::task::spawn_blocking(move || {
+            dir_usage::get_directory_usage(
+                Path::new(&path),
+                query.depth.unwrap_or(DEFAULT_DIR_USAGE_DEPTH),
+                query.limit.unwrap_or(DEFAULT_DIR_USAGE_LIMIT),
+                query.cross_devices,
+                MAX_DIR_USAGE_FILES,
+            )
+        });
+        let result = match tokio::time::timeout(self.collection_timeout, walk).await {
+            Ok(walked) => walked.unwrap_or_else(|e| Err(MonitorError::Panicked(e.to_string()))),
+            Err(_) => Err(MonitorError::Timeout(self.collection_timeout)),
+        };
+        self.collector_response(id, result, "directory usage")
     }
 
     /// Handles getNetworkInfo method
-    async fn handle_get_network_info(&self, id: Option<String>) -> MCPResponse {
-        let mut monitor = self.system_monitor.write().await;
-        match monitor.get_network_info() {
+    async fn handle_get_network_info(&self, id: Option<String>, params: Value) -> MCPResponse {
+        // Absent params mean the default listing
+        let query = if params.is_null() {
+            NetworkQuery::default()
+        } else {
+            match serde_json::from_value::<NetworkQuery>(params) {
+                Ok(query) => query,
+                Err(e) => {
+                    return self.create_error_response(
+                        id,
+                        ERROR_INVALID_PARAMS,
+                        &format!("Invalid network query: {}", e),
+                    );
+                }
+            }
+        };
+
+        match self
+            .collect(move |monitor| monitor.get_network_info(&query))
+            .await
+        {
             Ok(networks) => {
                 let result = serde_json::to_value(networks).unwrap_or_default();
                 self.create_success_response(id, result)
             }
             Err(e) => {
                 error!("Failed to get network info: {}", e);
-                self.create_error_response(
-                    id,
-                    ERROR_INTERNAL_ERROR,
-                    &format!("Failed to get network info: {}", e),
-                )
+                self.monitor_error_response(id, &e, &format!("Failed to get network info: {}", e))
             }
         }
     }
 
+    /// Handles getNetworkRates method
+    async fn handle_get_network_rates(&self, id: Option<String>) -> MCPResponse {
+        let result = self.collect(|monitor| monitor.get_network_rates()).await;
+        self.collector_response(id, result, "network rates")
+    }
+
+    /// Handles getDiskIO method
+    async fn handle_get_disk_io(&self, id: Option<String>) -> MCPResponse {
+        let result = self.collect(|monitor| monitor.get_disk_io()).await;
+        self.collector_response(id, result, "disk I/O stats")
+    }
+
+    /// Handles getTemperatures method
+    async fn handle_get_temperatures(&self, id: Option<String>) -> MCPResponse {
+        let result = self.collect(|monitor| Ok(monitor.get_temperatures())).await;
+        self.collector_response(id, result, "temperatures")
+    }
+
+    /// Handles getBatteryInfo method
+    async fn handle_get_battery_info(&self, id: Option<String>) -> MCPResponse {
+        let result = self.collect(|monitor| monitor.get_battery_info()).await;
+        self.collector_response(id, result, "battery info")
+    }
+
+    /// Handles getGPUInfo method
+    async fn handle_get_gpu_info(&self, id: Option<String>) -> MCPResponse {
+        let result = self.collect(|monitor| monitor.get_gpu_info()).await;
+        self.collector_response(id, result, "GPU info")
+    }
+
+    /// Handles getDiskSMART method
+    async fn handle_get_disk_smart(&self, id: Option<String>) -> MCPResponse {
+        let result = self.collect(|monitor| monitor.get_disk_smart()).await;
+        self.collector_response(id, result, "SMART data")
+    }
+
+    /// Handles getNetworkConnections method
+    async fn handle_get_network_connections(
+        &self,
+        id: Option<String>,
+        params: Value,
+    ) -> MCPResponse {
+        // Absent params mean every socket
+        let query = if params.is_null() {
+            ConnectionQuery::default()
+        } else {
+            match serde_json::from_value::<ConnectionQuery>(params) {
+                Ok(query) => query,
+                Err(e) => {
+                    return self.create_error_response(
+                        id,
+                        ERROR_INVALID_PARAMS,
+                        &format!("Invalid connection query: {}", e),
+                    );
+                }
+            }
+        };
+
+        let result = self
+            .collect(move |monitor| monitor.get_network_connections(&query))
+            .await;
+        self.collector_response(id, result, "network connections")
+    }
+
+    /// Handles getSocketSummary method
+    async fn handle_get_socket_summary(&self, id: Option<String>) -> MCPResponse {
+        let result = self.collect(|monitor| monitor.get_socket_summary()).await;
+        self.collector_response(id, result, "socket summary")
+    }
+
+    /// Handles getServices method
+    async fn handle_get_services(&self, id: Option<String>, params: Value) -> MCPResponse {
+        // Absent params mean every unit
+        let query = if params.is_null() {
+            ServiceQuery::default()
+        } else {
+            match serde_json::from_value::<ServiceQuery>(params) {
+                Ok(query) => query,
+                Err(e) => {
+                    return self.create_error_response(
+                        id,
+                        ERROR_INVALID_PARAMS,
+                        &format!("Invalid service query: {}", e),
+                    );
+                }
+            }
+        };
+
+        let result = self
+            .collect(move |monitor| monitor.get_services(&query))
+            .await;
+        self.collector_response(id, result, "services")
+    }
+
+    /// Handles getRecentLogs method
+    async fn handle_get_recent_logs(&self, id: Option<String>, params: Value) -> MCPResponse {
+        // Absent params mean the default number of entries of any priority
+        let query = if params.is_null() {
+            LogQuery::default()
+        } else {
+            match serde_json::from_value::<LogQuery>(params) {
+                Ok(query) => query,
+                Err(e) => {
+                    return self.create_error_response(
+                        id,
+                        ERROR_INVALID_PARAMS,
+                        &format!("Invalid log query: {}", e),
+                    );
+                }
+            }
+        };
+
+        let result = self
+            .collect(move |monitor| monitor.get_recent_logs(&query))
+            .await;
+        self.collector_response(id, result, "recent logs")
+    }
+
+    /// Handles getHistory method
+    async fn handle_get_history(&self, id: Option<String>, params: Value) -> MCPResponse {
+        // Absent params mean every field of every sample
+        let query = if params.is_null() {
+            HistoryQuery::default()
+        } else {
+            match serde_json::from_value::<HistoryQuery>(params) {
+                Ok(query) => query,
+                Err(e) => {
+                    return self.create_error_response(
+                        id,
+                        ERROR_INVALID_PARAMS,
+                        &format!("Invalid history query: {}", e),
+                    );
+                }
+            }
+        };
+
+        let samples = self.system_monitor.read().await.history(query.points);
+        let result = match query.metric {
+            Some(metric) => serde_json::to_value(history_points(&samples, metric)),
+            None => serde_json::to_value(samples),
+        };
+        self.create_success_response(id, result.unwrap_or_default())
+    }
+
+    /// Handles getHistoryStats method
+    ///
+    /// The result is null while the history is still empty.
+    async fn handle_get_history_stats(&self, id: Option<String>, params: Value) -> MCPResponse {
+        // Absent params mean CPU usage over the whole history
+        let query = if params.is_null() {
+            StatsQuery::default()
+        } else {
+            match serde_json::from_value::<StatsQuery>(params) {
+                Ok(query) => query,
+                Err(e) => {
+                    return self.create_error_response(
+                        id,
+                        ERROR_INVALID_PARAMS,
+                        &format!("Invalid stats query: {}", e),
+                    );
+                }
+            }
+        };
+
+        // A window longer than the history summarizes every sample there is
+        let samples = self.system_monitor.read().await.history(query.window);
+        let values: Vec<f64> = history_points(&samples, query.metric)
+            .into_iter()
+            .map(|point| point.value)
+            .collect();
+        let result = serde_json::to_value(metric_stats(&values)).unwrap_or_default();
+        self.create_success_response(id, result)
+    }
+
+    /// Handles getAlerts method
+    async fn handle_get_alerts(&self, id: Option<String>) -> MCPResponse {
+        let alerts = self.system_monitor.read().await.firing_alerts();
+        let result = serde_json::to_value(alerts).unwrap_or_default();
+        self.create_success_response(id, result)
+    }
+
     /// Handles getProcesses method
-    async fn handle_get_processes(&self, id: Option<String>) -> MCPResponse {
-        let mut monitor = self.system_monitor.write().await;
-        match monitor.get_processes() {
+    async fn handle_get_processes(&self, id: Option<String>, params: Value) -> MCPResponse {
+        // Absent params mean the default query
+        let query = if params.is_null() {
+            ProcessQuery::default()
+        } else {
+            match serde_json::from_value::<ProcessQuery>(params) {
+                Ok(query) => query,
+                Err(e) => {
+                    return self.create_error_response(
+                        id,
+                        ERROR_INVALID_PARAMS,
+                        &format!("Invalid process query: {}", e),
+                    );
+                }
+            }
+        };
+
+        let result = self
+            .collect(move |monitor| monitor.query_processes(&query))
+            .await;
+        match result {
             Ok(processes) => {
                 let result = serde_json::to_value(processes).unwrap_or_default();
                 self.create_success_response(id, result)
             }
             Err(e) => {
                 error!("Failed to get processes: {}", e);
-                self.create_error_response(
-                    id,
-                    ERROR_INTERNAL_ERROR,
-                    &format!("Failed to get processes: {}", e),
-                )
+                self.monitor_error_response(id, &e, &format!("Failed to get processes: {}", e))
             }
         }
     }
 
-    /// Handles getProcessByPID method
-    async fn handle_get_process_by_pid(&self, id: Option<String>, params: Value) -> MCPResponse {
-        let pid = match params.get("pid") {
-            Some(pid_value) => match pid_value.as_u64() {
-                Some(pid) => pid as u32,
-                None => {
+    /// Handles getTopProcesses method
+    async fn handle_get_top_processes(&self, id: Option<String>, params: Value) -> MCPResponse {
+        // Absent params mean the 10 processes using the most CPU
+        let query = if params.is_null() {
+            TopQuery::default()
+        } else {
+            match serde_json::from_value::<TopQuery>(params) {
+                Ok(query) => query,
+                Err(e) => {
                     return self.create_error_response(
                         id,
                         ERROR_INVALID_PARAMS,
-                        "Invalid PID parameter",
+                        &format!("Invalid top query: {}", e),
                     );
                 }
-            },
-            None => {
-                return self.create_error_response(
-                    id,
-                    ERROR_INVALID_PARAMS,
-                    "Missing PID parameter",
-                );
             }
         };
 
-        let mut monitor = self.system_monitor.write().await;
-        match monitor.get_process_by_pid(pid) {
+        let result = self
+            .collect(move |monitor| monitor.get_top_processes(&query))
+            .await;
+        self.collector_response(id, result, "top processes")
+    }
+
+    /// Handles getProcessByPID method
+    async fn handle_get_process_by_pid(&self, id: Option<String>, params: Value) -> MCPResponse {
+        let pid = match Self::pid_param(&params) {
+            Ok(pid) => pid,
+            Err(message) => {
+                return self.create_error_response(id, ERROR_INVALID_PARAMS, message);
+            }
+        };
+
+        let result = self
+            .collect(move |monitor| monitor.get_process_by_pid(pid))
+            .await;
+        match result {
             Ok(Some(process)) => {
                 let result = serde_json::to_value(process).unwrap_or_default();
                 self.create_success_response(id, result)
@@ -324,38 +1495,274 @@ impl MCPServer {
             ),
             Err(e) => {
                 error!("Failed to get process by PID {}: {}", pid, e);
-                self.create_error_response(
+                self.monitor_error_response(id, &e, &format!("Failed to get process: {}", e))
+            }
+        }
+    }
+
+    /// Handles getSelf method
+    async fn handle_get_self(&self, id: Option<String>) -> MCPResponse {
+        let (requests_total, request_errors_total) = self.request_counts();
+        let result = self
+            .collect(|monitor| monitor.get_self_process())
+            .await
+            .map(|process| SelfInfo {
+                uptime_secs: (chrono::Utc::now() - process.start_time)
+                    .num_seconds()
+                    .max(0) as u64,
+                process,
+                requests_total,
+                request_errors_total,
+            });
+        self.collector_response(id, result, "own process info")
+    }
+
+    /// Handles getProcessMemory method
+    async fn handle_get_process_memory(&self, id: Option<String>, params: Value) -> MCPResponse {
+        let pid = match Self::pid_param(&params) {
+            Ok(pid) => pid,
+            Err(message) => {
+                return self.create_error_response(id, ERROR_INVALID_PARAMS, message);
+            }
+        };
+
+        let result = self
+            .collect(move |monitor| monitor.get_process_memory(pid))
+            .await;
+        match result {
+            Ok(Some(memory)) => {
+                let result = serde_json::to_value(memory).unwrap_or_default();
+                self.create_success_response(id, result)
+            }
+            Ok(None) => self.create_error_response(
+                id,
+                ERROR_PROCESS_NOT_FOUND,
+                &format!("Process with PID {} not found", pid),
+            ),
+            Err(e) => {
+                error!("Failed to get memory of PID {}: {}", pid, e);
+                self.monitor_error_response(id, &e, &format!("Failed to get process memory: {}", e))
+            }
+        }
+    }
+
+    /// Handles getProcessLimits method
+    async fn handle_get_process_limits(&self, id: Option<String>, params: Value) -> MCPResponse {
+        let pid = match Self::pid_param(&params) {
+            Ok(pid) => pid,
+            Err(message) => {
+                return self.create_error_response(id, ERROR_INVALID_PARAMS, message);
+            }
+        };
+
+        let result = self
+            .collect(move |monitor| monitor.get_process_limits(pid))
+            .await;
+        match result {
+            Ok(Some(limits)) => {
+                let result = serde_json::to_value(limits).unwrap_or_default();
+                self.create_success_response(id, result)
+            }
+            Ok(None) => self.create_error_response(
+                id,
+                ERROR_PROCESS_NOT_FOUND,
+                &format!("Process with PID {} not found", pid),
+            ),
+            Err(e) => {
+                error!("Failed to get limits of PID {}: {}", pid, e);
+                self.monitor_error_response(id, &e, &format!("Failed to get process limits: {}", e))
+            }
+        }
+    }
+
+    /// Handles getProcessTree method
+    async fn handle_get_process_tree(&self, id: Option<String>, params: Value) -> MCPResponse {
+        let pid = match Self::pid_param(&params) {
+            Ok(pid) => pid,
+            Err(message) => {
+                return self.create_error_response(id, ERROR_INVALID_PARAMS, message);
+            }
+        };
+
+        let result = self
+            .collect(move |monitor| monitor.get_process_tree(pid))
+            .await;
+        match result {
+            Ok(Some(tree)) => {
+                let result = serde_json::to_value(tree).unwrap_or_default();
+                self.create_success_response(id, result)
+            }
+            Ok(None) => self.create_error_response(
+                id,
+                ERROR_PROCESS_NOT_FOUND,
+                &format!("Process with PID {} not found", pid),
+            ),
+            Err(e) => {
+                error!("Failed to get process tree for PID {}: {}", pid, e);
+                self.monitor_error_response(id, &e, &format!("Failed to get process tree: {}", e))
+            }
+        }
+    }
+
+    /// Handles killProcess method
+    async fn handle_kill_process(&self, id: Option<String>, params: Value) -> MCPResponse {
+        let pid = match Self::pid_param(&params) {
+            Ok(pid) => pid,
+            Err(message) => {
+                return self.create_error_response(id, ERROR_INVALID_PARAMS, message);
+            }
+        };
+
+        let signal = match params.get("signal") {
+            Some(value) => match value.as_i64().and_then(|v| i32::try_from(v).ok()) {
+                Some(signal) => signal,
+                None => {
+                    return self.create_error_response(
+                        id,
+                        ERROR_INVALID_PARAMS,
+                        "Invalid signal parameter",
+                    );
+                }
+            },
+            None => DEFAULT_KILL_SIGNAL,
+        };
+
+        let result = self
+            .collect(move |monitor| monitor.signal_process(pid, signal))
+            .await;
+        match result {
+            Ok(signaled) => {
+                let result = serde_json::json!({
+                    "pid": pid,
+                    "signal": signal,
+                    "signaled": signaled,
+                    "message": if signaled { "Signal sent" } else { "Process exited before it could be signaled" }
+                });
+                self.create_success_response(id, result)
+            }
+            Err(e) => {
+                error!("Failed to send signal {} to PID {}: {}", signal, pid, e);
+                self.monitor_error_response(
                     id,
-                    ERROR_INTERNAL_ERROR,
-                    &format!("Failed to get process: {}", e),
+                    &e,
+                    &format!("Failed to send signal {} to PID {}: {}", signal, pid, e),
+                )
+            }
+        }
+    }
+
+    /// Handles renice method
+    async fn handle_renice(&self, id: Option<String>, params: Value) -> MCPResponse {
+        let pid = match Self::pid_param(&params) {
+            Ok(pid) => pid,
+            Err(message) => {
+                return self.create_error_response(id, ERROR_INVALID_PARAMS, message);
+            }
+        };
+
+        let nice = match params.get("nice").and_then(|v| v.as_i64()) {
+            Some(nice) if (-20..=19).contains(&nice) => nice as i32,
+            _ => {
+                return self.create_error_response(
+                    id,
+                    ERROR_INVALID_PARAMS,
+                    "Missing or invalid nice parameter (must be -20 to 19)",
+                );
+            }
+        };
+
+        let result = self
+            .collect(move |monitor| monitor.set_process_priority(pid, nice))
+            .await;
+        match result {
+            Ok(()) => {
+                let result = serde_json::json!({
+                    "pid": pid,
+                    "nice": nice
+                });
+                self.create_success_response(id, result)
+            }
+            Err(e) => {
+                error!("Failed to set nice {} for PID {}: {}", nice, pid, e);
+                self.monitor_error_response(
+                    id,
+                    &e,
+                    &format!("Failed to set nice {} for PID {}: {}", nice, pid, e),
                 )
             }
         }
     }
 
     /// Handles getSystemMetrics method
-    async fn handle_get_system_metrics(&self, id: Option<String>) -> MCPResponse {
-        let mut monitor = self.system_monitor.write().await;
-        match monitor.get_system_metrics() {
+    async fn handle_get_system_metrics(&self, id: Option<String>, params: Value) -> MCPResponse {
+        // Absent params mean every section
+        let query = if params.is_null() {
+            MetricsQuery::default()
+        } else {
+            match serde_json::from_value::<MetricsQuery>(params) {
+                Ok(query) => query,
+                Err(e) => {
+                    return self.create_error_response(
+                        id,
+                        ERROR_INVALID_PARAMS,
+                        &format!("Invalid metrics query: {}", e),
+                    );
+                }
+            }
+        };
+        if query.max_processes == Some(0) {
+            return self.create_error_response(
+                id,
+                ERROR_INVALID_PARAMS,
+                "max_processes must be greater than 0",
+            );
+        }
+
+        let collection = async {
+            self.system_monitor
+                .write()
+                .await
+                .get_system_metrics(query)
+                .await
+        };
+        let result = tokio::time::timeout(self.collection_timeout, collection)
+            .await
+            .unwrap_or(Err(MonitorError::Timeout(self.collection_timeout)));
+
+        match result {
             Ok(metrics) => {
                 let result = serde_json::to_value(metrics).unwrap_or_default();
                 self.create_success_response(id, result)
             }
             Err(e) => {
+                // The message names the failing collector and its cause
                 error!("Failed to get system metrics: {}", e);
-                self.create_error_response(
-                    id,
-                    ERROR_INTERNAL_ERROR,
-                    &format!("Failed to get system metrics: {}", e),
-                )
+                self.monitor_error_response(id, &e, &format!("Failed to get system metrics: {}", e))
             }
         }
     }
 
+    /// Handles diffMetrics method
+    fn handle_diff_metrics(&self, id: Option<String>, params: Value) -> MCPResponse {
+        let query = match serde_json::from_value::<DiffQuery>(params) {
+            Ok(query) => query,
+            Err(e) => {
+                return self.create_error_response(
+                    id,
+                    ERROR_INVALID_PARAMS,
+                    &format!("Invalid diff query: {}", e),
+                );
+            }
+        };
+
+        let diff = diff_metrics(&query.before, &query.after);
+        let result = serde_json::to_value(diff).unwrap_or_default();
+        self.create_success_response(id, result)
+    }
+
     /// Handles startMonitoring method
     async fn handle_start_monitoring(&self, id: Option<String>) -> MCPResponse {
-        let mut monitor = self.system_monitor.write().await;
-        match monitor.start_monitoring() {
+        match SystemMonitor::start_monitoring(&self.system_monitor, self.collection_timeout).await {
             Ok(started) => {
                 let result = serde_json::json!({
                     "started": started,
@@ -396,6 +1803,166 @@ impl MCPServer {
         }
     }
 
+    /// Handles setMonitoringInterval method
+    async fn handle_set_monitoring_interval(
+        &self,
+        id: Option<String>,
+        params: Value,
+    ) -> MCPResponse {
+        let requested_ms = match params.get("interval_ms") {
+            Some(value) => match value.as_u64() {
+                Some(ms) => ms,
+                None => {
+                    return self.create_error_response(
+                        id,
+                        ERROR_INVALID_PARAMS,
+                        "Invalid interval_ms parameter",
+                    );
+                }
+            },
+            None => {
+                return self.create_error_response(
+                    id,
+                    ERROR_INVALID_PARAMS,
+                    "Missing interval_ms parameter",
+                );
+            }
+        };
+
+        let mut monitor = self.system_monitor.write().await;
+        let effective = monitor.set_monitoring_interval(Duration::from_millis(requested_ms));
+        let effective_ms = effective.as_millis() as u64;
+
+        let result = serde_json::json!({
+            "interval_ms": effective_ms,
+            "min_interval_ms": monitor.min_monitoring_interval().as_millis() as u64,
+            "clamped": effective_ms != requested_ms
+        });
+        self.create_success_response(id, result)
+    }
+
+    /// Extracts the required `pid` parameter, or the invalid-params message
+    ///
+    /// PIDs that do not fit in 32 bits are rejected rather than truncated,
+    /// which would target an unrelated process, and so is PID 0.
+    fn pid_param(params: &Value) -> Result<u32, &'static str> {
+        let pid_value = params.get("pid").ok_or("Missing PID parameter")?;
+        pid_value
+            .as_u64()
+            .and_then(|pid| u32::try_from(pid).ok())
+            .filter(|&pid| pid != 0)
+            .ok_or("Invalid PID parameter")
+    }
+
+    /// Runs a collection off the async runtime, bounded by the collection timeout
+    async fn collect<T, F>(&self, collect: F) -> Result<T, MonitorError>
+    where
+        T: Send + 'static,
+        F: FnOnce(&mut SystemMonitor) -> Result<T, MonitorError> + Send + 'static,
+    {
+        SystemMonitor::run_blocking(&self.system_monitor, self.collection_timeout, collect).await
+    }
+
+    /// Maps a failed collection or system call to the matching MCP error code
+    ///
+    /// A failed snapshot is classified by the collector that failed it.
+    fn error_code(error: &MonitorError) -> i32 {
+        match error.root() {
+            MonitorError::CommandFailed { .. } | MonitorError::Timeout(_) => {
+                ERROR_SYSTEM_COMMAND_FAILED
+            }
+            MonitorError::PermissionDenied(_) => ERROR_PERMISSION_DENIED,
+            MonitorError::NotFound(_) => ERROR_PROCESS_NOT_FOUND,
+            MonitorError::InvalidArgument(_) => ERROR_INVALID_PARAMS,
+            MonitorError::Unsupported(_) => ERROR_UNSUPPORTED,
+            MonitorError::ParseError(_)
+            | MonitorError::Io { .. }
+            | MonitorError::Panicked(_)
+            | MonitorError::Collector { .. } => ERROR_INTERNAL_ERROR,
+        }
+    }
+
+    /// Returns machine-readable context for a collector failure
+    ///
+    /// Always has the error `kind`; adds the failing `collector`, `command`,
+    /// `os_error` number or `timeout_ms` when the error carries them.
+    fn error_data(error: &MonitorError) -> Value {
+        let mut data = serde_json::Map::new();
+        if let MonitorError::Collector { name, .. } = error {
+            data.insert("collector".to_string(), Value::from(*name));
+        }
+
+        let root = error.root();
+        let kind = match root {
+            MonitorError::CommandFailed { .. } => "command_failed",
+            MonitorError::PermissionDenied(_) => "permission_denied",
+            MonitorError::ParseError(_) => "parse_error",
+            MonitorError::NotFound(_) => "not_found",
+            MonitorError::InvalidArgument(_) => "invalid_argument",
+            MonitorError::Unsupported(_) => "unsupported",
+            MonitorError::Io { .. } => "io",
+            MonitorError::Timeout(_) => "timeout",
+            MonitorError::Panicked(_) => "panicked",
+            MonitorError::Collector { .. } => "collector",
+        };
+        data.insert("kind".to_string(), Value::from(kind));
+
+        match root {
+            MonitorError::CommandFailed { command, error } => {
+                data.insert("command".to_string(), Value::from(command.as_str()));
+                if let Some(code) = error.raw_os_error() {
+                    data.insert("os_error".to_string(), Value::from(code));
+                }
+            }
+            MonitorError::Io { error, .. } => {
+                if let Some(code) = error.raw_os_error() {
+                    data.insert("os_error".to_string(), Value::from(code));
+                }
+            }
+            MonitorError::Timeout(timeout) => {
+                data.insert(
+                    "timeout_ms".to_string(),
+                    Value::from(timeout.as_millis() as u64),
+                );
+            }
+            _ => {}
+        }
+        Value::Object(data)
+    }
+
+    /// Creates an error response for a collector failure, with its error data
+    fn monitor_error_response(
+        &self,
+        id: Option<String>,
+        error: &MonitorError,
+        message: &str,
+    ) -> MCPResponse {
+        let mut response = self.create_error_response(id, Self::error_code(error), message);
+        if let Some(mcp_error) = response.error.as_mut() {
+            mcp_error.data = Some(Self::error_data(error));
+        }
+        response
+    }
+
+    /// Converts a collector result into a success or error response
+    fn collector_response<T: Serialize>(
+        &self,
+        id: Option<String>,
+        result: Result<T, MonitorError>,
+        what: &str,
+    ) -> MCPResponse {
+        match result {
+            Ok(value) => {
+                let result = serde_json::to_value(value).unwrap_or_default();
+                self.create_success_response(id, result)
+            }
+            Err(e) => {
+                error!("Failed to get {}: {}", what, e);
+                self.monitor_error_response(id, &e, &format!("Failed to get {}: {}", what, e))
+            }
+        }
+    }
+
     /// Creates successful MCP response
     fn create_success_response(&self, id: Option<String>, result: Value) -> MCPResponse {
         MCPResponse {
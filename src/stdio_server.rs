@@ -5,12 +5,12 @@
 use serde_json::json;
 use std::io::{BufRead, BufReader, Read, Write};
 use std::sync::Arc;
+use std::time::Duration;
 use tokio::sync::RwLock;
 use tracing::error;
 
-use crate::server::MCPServer;
-use crate::system_monitor::SystemMonitor;
-use crate::types::*;
+use mcp_system_monitor::types::*;
+use mcp_system_monitor::{LogLevelHandle, MCPServer, SystemMonitor};
 
 /// STDIO Server for stdin/stdout communication
 pub struct StdioServer {
@@ -25,6 +25,18 @@ impl StdioServer {
         }
     }
 
+    /// Fails collections that take longer than `timeout` instead of the default
+    pub fn with_collection_timeout(mut self, timeout: Duration) -> Self {
+        self.mcp_server = self.mcp_server.with_collection_timeout(timeout);
+        self
+    }
+
+    /// Lets clients change the log level with `logging/setLevel`
+    pub fn with_log_level_handle(mut self, handle: LogLevelHandle) -> Self {
+        self.mcp_server = self.mcp_server.with_log_level_handle(handle);
+        self
+    }
+
     /// Runs STDIO server, reading from stdin and writing to stdout
     pub async fn run(self) -> Result<(), Box<dyn std::error::Error>> {
         let stdin = std::io::stdin();
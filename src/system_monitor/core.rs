@@ -2,12 +2,78 @@
 //!
 //! Main SystemMonitor struct and public API methods.
 
-use anyhow::Result;
 use chrono::{DateTime, Utc};
-use tracing::info;
+use std::collections::{HashMap, VecDeque};
+use std::future::Future;
+use std::sync::{Arc, Weak};
+use std::time::{Duration, Instant};
+use tokio::sync::RwLock;
+use tokio::task::JoinHandle;
+use tracing::{info, warn};
 
-use super::linux::LinuxSystemInfo;
+use super::gpu;
+use super::helpers::{
+    apply_network_query, apply_process_query, build_process_tree, counter_rate, top_processes,
+};
+#[cfg(all(target_os = "linux", not(feature = "sysinfo-backend")))]
+use super::linux::LinuxSystemInfo as Collector;
+use super::provider::SystemInfoProvider;
+use super::smart;
+#[cfg(any(not(target_os = "linux"), feature = "sysinfo-backend"))]
+use super::sysinfo_collector::SysinfoCollector as Collector;
+use crate::alerts::{
+    default_rules, Alert, AlertEvaluator, AlertMetric, AlertRule, AlertTransition,
+};
+use crate::config::{CollectorConfig, WebhookConfig};
+use crate::error::{MonitorError, Result};
 use crate::types::*;
+use crate::webhook::WebhookNotifier;
+
+/// Default interval between continuous monitoring samples
+pub const DEFAULT_MONITORING_INTERVAL: Duration = Duration::from_secs(5);
+
+/// Default lower bound on the monitoring interval
+///
+/// Every sample shells out to `ps`, `df` and friends, so a client asking for
+/// sub-second cadence could make the monitor the busiest process on the host.
+pub const DEFAULT_MIN_MONITORING_INTERVAL: Duration = Duration::from_secs(1);
+
+/// Lowest floor for monitoring intervals; a zero interval would busy-loop
+/// the sampler and cannot drive a Tokio interval
+pub const MIN_INTERVAL_FLOOR: Duration = Duration::from_millis(1);
+
+/// Default upper bound on a collection started from an async context
+pub const DEFAULT_COLLECTION_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Default time an external command such as `ps` or `df` may run before it is killed
+pub const DEFAULT_COMMAND_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Default number of samples kept in the metrics history
+pub const DEFAULT_HISTORY_CAPACITY: usize = 300;
+
+/// Number of log entries returned when the caller does not ask for a count
+pub const DEFAULT_LOG_LINES: usize = 50;
+
+/// Most log entries returned by one call, however many are asked for
+pub const MAX_LOG_LINES: usize = 1000;
+
+/// Least severe syslog priority (debug)
+const MAX_LOG_PRIORITY: u8 = 7;
+
+/// Levels of subdirectories reported by directory usage when none is asked for
+pub const DEFAULT_DIR_USAGE_DEPTH: u32 = 1;
+
+/// Number of subdirectories reported by directory usage when none is asked for
+pub const DEFAULT_DIR_USAGE_LIMIT: usize = 10;
+
+/// Most files and directories one directory usage walk visits
+pub const MAX_DIR_USAGE_FILES: u64 = 200_000;
+
+/// Number of top processes returned when the caller does not ask for a count
+pub const DEFAULT_TOP_PROCESSES: usize = 10;
+
+/// Most top processes returned by one call, however many are asked for
+pub const MAX_TOP_PROCESSES: usize = 100;
 
 /// System Monitor for collecting real-time system information
 ///
@@ -15,23 +81,79 @@ use crate::types::*;
 /// network, and process information from Linux systems.
 #[derive(Debug)]
 pub struct SystemMonitor {
-    /// Whether continuous monitoring is currently active
-    monitoring_active: bool,
+    /// Background sampler task, present while continuous monitoring is active
+    sampler: Option<JoinHandle<()>>,
+    /// Interval between continuous monitoring samples
+    monitoring_interval: Duration,
+    /// Floor that requested monitoring intervals are clamped to
+    min_monitoring_interval: Duration,
+    /// Which collectors are sampled; disabled ones report empty lists
+    collectors: CollectorConfig,
+    /// How long an external command run outside the collector, such as
+    /// `nvidia-smi`, may take before it is killed
+    command_timeout: Duration,
     /// Timestamp of the last data refresh
     last_update: DateTime<Utc>,
-    /// Linux-specific system information collector
-    linux_info: LinuxSystemInfo,
+    /// Source of system information: by default the platform collector,
+    /// procfs on Linux and `sysinfo` elsewhere
+    ///
+    /// Shared so independent collections can run on the blocking thread pool.
+    collector: Arc<dyn SystemInfoProvider>,
+    /// Host and CPU details collected once, see `refresh_static`
+    static_info: Arc<StaticSystemInfo>,
+    /// Previous /proc/net/dev counters used to compute network rates
+    last_network_sample: Option<(Instant, Vec<InterfaceCounters>)>,
+    /// Recent CPU, memory and load samples, oldest first
+    history: VecDeque<HistorySample>,
+    /// Number of samples kept before the oldest is dropped
+    history_capacity: usize,
+    /// Threshold rules checked against each sampler snapshot
+    alerts: AlertEvaluator,
+    /// Notifies webhooks of alert transitions, if configured
+    webhooks: Option<WebhookNotifier>,
 }
 
 impl SystemMonitor {
     /// Creates new SystemMonitor instance
     pub fn new() -> Result<Self> {
-        let linux_info = LinuxSystemInfo::new()?;
+        Self::with_command_timeout(DEFAULT_COMMAND_TIMEOUT)
+    }
+
+    /// Creates a SystemMonitor with the platform collector, killing external
+    /// commands that run longer than `timeout`
+    pub fn with_command_timeout(timeout: Duration) -> Result<Self> {
+        #[cfg(all(target_os = "linux", not(feature = "sysinfo-backend")))]
+        let collector = Collector::new()?.with_command_timeout(timeout);
+        #[cfg(any(not(target_os = "linux"), feature = "sysinfo-backend"))]
+        let collector = Collector::new()?;
+
+        let mut monitor = Self::with_provider(collector)?;
+        monitor.command_timeout = timeout;
+        Ok(monitor)
+    }
+
+    /// Creates a SystemMonitor that collects through `provider`
+    ///
+    /// Lets tests and embedders substitute the platform collector, e.g. with
+    /// one returning canned data.
+    pub fn with_provider(provider: impl SystemInfoProvider + 'static) -> Result<Self> {
+        let collector: Arc<dyn SystemInfoProvider> = Arc::new(provider);
+        let static_info = Arc::new(collector.get_static_info()?);
 
         Ok(Self {
-            monitoring_active: false,
+            sampler: None,
+            monitoring_interval: DEFAULT_MONITORING_INTERVAL,
+            min_monitoring_interval: DEFAULT_MIN_MONITORING_INTERVAL,
+            collectors: CollectorConfig::default(),
+            command_timeout: DEFAULT_COMMAND_TIMEOUT,
             last_update: Utc::now(),
-            linux_info,
+            collector,
+            static_info,
+            last_network_sample: None,
+            history: VecDeque::with_capacity(DEFAULT_HISTORY_CAPACITY),
+            history_capacity: DEFAULT_HISTORY_CAPACITY,
+            alerts: AlertEvaluator::new(default_rules()),
+            webhooks: None,
         })
     }
 
@@ -40,93 +162,873 @@ impl SystemMonitor {
         self.last_update = Utc::now();
     }
 
+    /// Re-reads the cached host and CPU details
+    ///
+    /// Hostname, OS, kernel and CPU model are collected once in `new()`; call
+    /// this after changing one of them, e.g. renaming the host.
+    pub fn refresh_static(&mut self) -> Result<()> {
+        self.static_info = Arc::new(self.collector.get_static_info()?);
+        Ok(())
+    }
+
+    /// Returns the cached host and CPU details
+    pub fn static_info(&self) -> &StaticSystemInfo {
+        &self.static_info
+    }
+
     /// Gets comprehensive system information
     pub fn get_system_info(&mut self) -> Result<SystemInfo> {
         self.refresh();
-        self.linux_info.get_system_info()
+        self.collector.get_system_info(&self.static_info)
+    }
+
+    /// Gets the headline numbers of the system for status pages
+    ///
+    /// Processes are counted rather than listed, and only the root
+    /// filesystem is checked.
+    pub fn get_summary(&mut self) -> Result<SystemSummary> {
+        let system = self.get_system_info()?;
+        let cpu = self.get_cpu_info()?;
+        let memory = self.get_memory_info()?;
+        let load_average = self.get_load_average()?;
+        let root_disk_usage_percent = if self.collectors.disks {
+            self.collector.get_root_disk_usage()?
+        } else {
+            None
+        };
+        let process_count = if self.collectors.processes {
+            self.collector.get_process_count()?
+        } else {
+            0
+        };
+        let fd_usage_percent = self.fd_usage_percent()?;
+
+        Ok(SystemSummary {
+            timestamp: Utc::now(),
+            hostname: system.hostname,
+            uptime: system.uptime,
+            uptime_human: system.uptime_human,
+            cpu_usage_percent: cpu.usage_percent,
+            load_average,
+            memory_used: memory.used,
+            memory_total: memory.total,
+            memory_usage_percent: memory.usage_percent,
+            swap_usage_percent: memory.swap_usage_percent,
+            root_disk_usage_percent,
+            process_count,
+            fd_usage_percent,
+        })
+    }
+
+    /// Gets the uptime and boot time without the rest of the system info
+    pub fn get_uptime(&mut self) -> Result<UptimeInfo> {
+        self.refresh();
+        self.collector.get_uptime()
     }
 
     /// Gets CPU information and usage statistics
     pub fn get_cpu_info(&mut self) -> Result<CPUInfo> {
         self.refresh();
-        self.linux_info.get_cpu_info()
+        self.collector.get_cpu_info(&self.static_info)
+    }
+
+    /// Gets the 1/5/15-minute load averages
+    pub fn get_load_average(&mut self) -> Result<LoadAverage> {
+        self.refresh();
+        self.collector.get_load_average()
+    }
+
+    /// Gets context switch, interrupt and fork counters and process counts
+    pub fn get_kernel_stats(&mut self) -> Result<KernelStats> {
+        self.refresh();
+        self.collector.get_kernel_stats()
+    }
+
+    /// Gets system-wide file handle usage against the kernel limit
+    pub fn get_fd_usage(&mut self) -> Result<FdUsage> {
+        self.refresh();
+        self.collector.get_fd_usage()
+    }
+
+    /// Returns the share of file handles in use, None if the collector
+    /// cannot tell
+    fn fd_usage_percent(&self) -> Result<Option<f32>> {
+        match self.collector.get_fd_usage() {
+            Ok(usage) => Ok(Some(usage.usage_percent)),
+            Err(MonitorError::Unsupported(_)) => Ok(None),
+            Err(e) => Err(e),
+        }
+    }
+
+    /// Gets the bits of entropy available in the kernel pool
+    pub fn get_entropy(&mut self) -> Result<EntropyInfo> {
+        self.refresh();
+        self.collector.get_entropy()
+    }
+
+    /// Gets pressure stall information for CPU, memory and I/O
+    pub fn get_pressure(&mut self) -> Result<PressureInfo> {
+        self.refresh();
+        self.collector.get_pressure()
     }
 
     /// Gets memory information including RAM and swap usage
     pub fn get_memory_info(&mut self) -> Result<MemoryInfo> {
         self.refresh();
-        self.linux_info.get_memory_info()
+        self.collector.get_memory_info()
+    }
+
+    /// Gets the virtualization or container environment
+    pub fn get_virtualization(&mut self) -> Result<VirtualizationInfo> {
+        self.refresh();
+        self.collector.get_virtualization()
+    }
+
+    /// Gets usage of each swap partition, file and zram device
+    pub fn get_swaps(&mut self) -> Result<Vec<SwapDevice>> {
+        self.refresh();
+        self.collector.get_swaps()
+    }
+
+    /// Gets NUMA nodes with their CPUs and memory
+    ///
+    /// Hosts whose kernel or collector reports no nodes are treated as a
+    /// single node holding every CPU and all memory.
+    pub fn get_numa_topology(&mut self) -> Result<Vec<NumaNode>> {
+        self.refresh();
+        let nodes = self.collector.get_numa_topology()?;
+        if !nodes.is_empty() {
+            return Ok(nodes);
+        }
+
+        let memory = self.collector.get_memory_info()?;
+        Ok(vec![NumaNode {
+            id: 0,
+            cpus: (0..self.static_info.logical_cores).collect(),
+            mem_total: memory.total,
+            mem_free: memory.free,
+        }])
+    }
+
+    /// Gets disks and their partitions, regardless of what is mounted
+    pub fn get_block_devices(&mut self) -> Result<Vec<BlockDevice>> {
+        self.refresh();
+        self.collector.get_block_devices()
     }
 
     /// Gets disk information for all mounted filesystems
     pub fn get_disk_info(&mut self) -> Result<Vec<DiskInfo>> {
         self.refresh();
-        self.linux_info.get_disk_info()
+        if !self.collectors.disks {
+            return Ok(Vec::new());
+        }
+        self.collector.get_disk_info()
     }
 
     /// Gets network interface information and statistics
-    pub fn get_network_info(&mut self) -> Result<Vec<NetworkInfo>> {
+    ///
+    /// The loopback interface and link-local IPv6 addresses are dropped unless
+    /// `query` includes them.
+    pub fn get_network_info(&mut self, query: &NetworkQuery) -> Result<Vec<NetworkInfo>> {
+        self.refresh();
+        if !self.collectors.networks {
+            return Ok(Vec::new());
+        }
+        let networks = self.collector.get_network_info()?;
+        Ok(apply_network_query(networks, query))
+    }
+
+    /// Gets per-interface network throughput since the previous call
+    ///
+    /// Rates are None on the first call, or for interfaces that were not
+    /// present in the previous sample.
+    pub fn get_network_rates(&mut self) -> Result<Vec<NetworkRate>> {
+        self.refresh();
+        if !self.collectors.networks {
+            return Ok(Vec::new());
+        }
+        let counters = self.collector.get_network_counters()?;
+        let now = Instant::now();
+
+        let rates = counters
+            .iter()
+            .map(|curr| {
+                let previous = self.last_network_sample.as_ref().and_then(|(at, sample)| {
+                    sample
+                        .iter()
+                        .find(|prev| prev.interface == curr.interface)
+                        .map(|prev| (now.duration_since(*at), prev))
+                });
+
+                NetworkRate {
+                    interface: curr.interface.clone(),
+                    rx_bytes_per_sec: previous.map(|(elapsed, prev)| {
+                        counter_rate(prev.bytes_received, curr.bytes_received, elapsed)
+                    }),
+                    tx_bytes_per_sec: previous.map(|(elapsed, prev)| {
+                        counter_rate(prev.bytes_transmitted, curr.bytes_transmitted, elapsed)
+                    }),
+                }
+            })
+            .collect();
+
+        self.last_network_sample = Some((now, counters));
+        Ok(rates)
+    }
+
+    /// Gets TCP and UDP sockets, optionally only those in `query.state`
+    pub fn get_network_connections(&mut self, query: &ConnectionQuery) -> Result<Vec<Connection>> {
         self.refresh();
-        self.linux_info.get_network_info()
+        if !self.collectors.networks {
+            return Ok(Vec::new());
+        }
+        let mut connections = self.collector.get_network_connections()?;
+        if let Some(state) = &query.state {
+            connections.retain(|connection| connection.state.eq_ignore_ascii_case(state));
+        }
+        Ok(connections)
+    }
+
+    /// Gets the number of TCP sockets in each state, e.g. "TIME_WAIT"
+    ///
+    /// States without sockets are left out.
+    pub fn get_socket_summary(&mut self) -> Result<HashMap<String, u32>> {
+        self.refresh();
+        if !self.collectors.networks {
+            return Ok(HashMap::new());
+        }
+        self.collector.get_socket_summary()
+    }
+
+    /// Gets disk I/O counters for all active block devices
+    ///
+    /// The counters are cumulative since boot; diff two samples to get rates.
+    pub fn get_disk_io(&mut self) -> Result<Vec<DiskIOStats>> {
+        self.refresh();
+        if !self.collectors.disks {
+            return Ok(Vec::new());
+        }
+        self.collector.get_disk_io()
+    }
+
+    /// Gets readings from all temperature sensors
+    pub fn get_temperatures(&mut self) -> Vec<TemperatureSensor> {
+        self.refresh();
+        self.collector.get_temperatures()
+    }
+
+    /// Gets battery charge state; `present` is false on hosts without one
+    pub fn get_battery_info(&mut self) -> Result<BatteryInfo> {
+        self.refresh();
+        self.collector.get_battery_info()
+    }
+
+    /// Gets all NVIDIA GPUs; empty when `nvidia-smi` is unavailable
+    pub fn get_gpu_info(&mut self) -> Result<Vec<GpuInfo>> {
+        self.refresh();
+        gpu::get_gpu_info(self.command_timeout)
+    }
+
+    /// Gets systemd service units, keeping those in `query.state` if given
+    pub fn get_services(&mut self, query: &ServiceQuery) -> Result<ServiceList> {
+        self.refresh();
+        let mut list = self.collector.get_services()?;
+        if let Some(state) = &query.state {
+            list.services.retain(|service| {
+                service.active_state.eq_ignore_ascii_case(state)
+                    || service.sub_state.eq_ignore_ascii_case(state)
+            });
+        }
+        Ok(list)
+    }
+
+    /// Gets the most recent log entries, oldest first
+    ///
+    /// Returns `query.lines` entries, capped at `MAX_LOG_LINES`.
+    pub fn get_recent_logs(&mut self, query: &LogQuery) -> Result<Vec<LogEntry>> {
+        if let Some(priority) = query.priority.filter(|p| *p > MAX_LOG_PRIORITY) {
+            return Err(MonitorError::InvalidArgument(format!(
+                "priority must be between 0 and {}, got {}",
+                MAX_LOG_PRIORITY, priority
+            )));
+        }
+        self.refresh();
+        let lines = query.lines.unwrap_or(DEFAULT_LOG_LINES).min(MAX_LOG_LINES);
+        self.collector.get_recent_logs(lines, query.priority)
+    }
+
+    /// Gets the SMART health of each drive through `smartctl`
+    pub fn get_disk_smart(&mut self) -> Result<Vec<SmartInfo>> {
+        self.refresh();
+        smart::get_disk_smart(self.command_timeout)
     }
 
     /// Gets information about all running processes
     pub fn get_processes(&mut self) -> Result<Vec<ProcessInfo>> {
         self.refresh();
-        self.linux_info.get_processes()
+        if !self.collectors.processes {
+            return Ok(Vec::new());
+        }
+        self.collector.get_processes(self.collectors.process_fds)
+    }
+
+    /// Gets running processes sorted and paginated according to `query`
+    ///
+    /// I/O counters are only read for the returned page, and only if
+    /// `query.include_io` is set; the same goes for the supplemental
+    /// `query.fields`. Sorting by I/O reads the counters of every process
+    /// first.
+    pub fn query_processes(&mut self, query: &ProcessQuery) -> Result<Vec<ProcessInfo>> {
+        let mut processes = self.get_processes()?;
+        let sort_by_io = query.sort_by == ProcessSortKey::Io;
+        if sort_by_io {
+            for process in &mut processes {
+                self.fill_process_io(process);
+            }
+        }
+
+        let mut processes = apply_process_query(processes, query);
+        if query.include_io && !sort_by_io {
+            for process in &mut processes {
+                self.fill_process_io(process);
+            }
+        }
+        if !query.fields.is_empty() {
+            for process in &mut processes {
+                process.extra = self
+                    .collector
+                    .get_process_extra(process.pid, &query.fields)?;
+            }
+        }
+        Ok(processes)
+    }
+
+    /// Gets the processes using the most of a resource, with totals over all processes
+    ///
+    /// The count is clamped to 1..=`MAX_TOP_PROCESSES`. Ranking by I/O reads
+    /// the counters of every process.
+    pub fn get_top_processes(&mut self, query: &TopQuery) -> Result<TopProcesses> {
+        let n = query
+            .n
+            .unwrap_or(DEFAULT_TOP_PROCESSES)
+            .clamp(1, MAX_TOP_PROCESSES);
+        let mut processes = self.get_processes()?;
+        if query.by == TopMetric::Io {
+            for process in &mut processes {
+                self.fill_process_io(process);
+            }
+        }
+        Ok(top_processes(processes, query.by, n))
     }
 
-    /// Gets information about a specific process by PID
+    /// Gets information about a specific process by PID, with its I/O counters
     pub fn get_process_by_pid(&mut self, pid: u32) -> Result<Option<ProcessInfo>> {
         self.refresh();
-        self.linux_info.get_process_by_pid(pid)
+        let mut process = self.collector.get_process_by_pid(pid)?;
+        if let Some(process) = &mut process {
+            self.fill_process_io(process);
+        }
+        Ok(process)
+    }
+
+    /// Gets the memory breakdown of a process, or `None` if it does not exist
+    pub fn get_process_memory(&mut self, pid: u32) -> Result<Option<ProcessMemory>> {
+        self.refresh();
+        self.collector.get_process_memory(pid)
+    }
+
+    /// Gets the resource limits of a process, or `None` if it does not exist
+    pub fn get_process_limits(&mut self, pid: u32) -> Result<Option<ProcessLimits>> {
+        self.refresh();
+        self.collector.get_process_limits(pid)
+    }
+
+    /// Gets the monitor's own process
+    pub fn get_self_process(&mut self) -> Result<ProcessInfo> {
+        let pid = std::process::id();
+        self.get_process_by_pid(pid)?
+            .ok_or_else(|| MonitorError::NotFound(format!("Own process {} is not listed", pid)))
+    }
+
+    /// Sets the I/O counters of a process, leaving them 0 if unreadable
+    ///
+    /// /proc/<pid>/io of other users' processes needs root, so a permission
+    /// error must not fail the whole listing.
+    fn fill_process_io(&self, process: &mut ProcessInfo) {
+        if let Ok(io) = self.collector.get_process_io(process.pid) {
+            process.set_io(io);
+        }
+    }
+
+    /// Sends a signal (e.g., 15 for SIGTERM) to a process
+    ///
+    /// Returns false if the process exited before it could be signaled.
+    pub fn signal_process(&mut self, pid: u32, signal: i32) -> Result<bool> {
+        self.refresh();
+        self.collector.signal_process(pid, signal)
     }
 
-    /// Gets a complete snapshot of all system metrics
-    pub fn get_system_metrics(&mut self) -> Result<SystemMetrics> {
+    /// Sets the nice value (-20 to 19) of a process
+    pub fn set_process_priority(&mut self, pid: u32, nice: i32) -> Result<()> {
         self.refresh();
+        self.collector.set_process_priority(pid, nice)
+    }
 
-        let system_info = self.get_system_info()?;
-        let cpu_info = self.get_cpu_info()?;
-        let memory_info = self.get_memory_info()?;
-        let disks = self.get_disk_info()?;
-        let networks = self.get_network_info()?;
+    /// Gets a process and all of its descendants
+    ///
+    /// Returns None if no process with `root_pid` exists.
+    pub fn get_process_tree(&mut self, root_pid: u32) -> Result<Option<ProcessNode>> {
         let processes = self.get_processes()?;
+        Ok(build_process_tree(processes, root_pid))
+    }
+
+    /// Runs a synchronous collection on a shared monitor off the async runtime
+    ///
+    /// The write lock is awaited asynchronously and `collect` runs on the
+    /// blocking thread pool, so a slow `ps` or `df` never stalls a Tokio
+    /// worker. If waiting for the lock and collecting take longer than
+    /// `timeout`, the caller gets `MonitorError::Timeout`; the collection itself
+    /// cannot be cancelled and keeps the lock until it returns.
+    pub async fn run_blocking<T, F>(
+        monitor: &Arc<RwLock<Self>>,
+        timeout: Duration,
+        collect: F,
+    ) -> Result<T>
+    where
+        T: Send + 'static,
+        F: FnOnce(&mut Self) -> Result<T> + Send + 'static,
+    {
+        let monitor = Arc::clone(monitor);
+        let collection = async move {
+            let mut guard = monitor.write_owned().await;
+            tokio::task::spawn_blocking(move || collect(&mut guard))
+                .await
+                .map_err(|e| MonitorError::Panicked(e.to_string()))?
+        };
+
+        tokio::time::timeout(timeout, collection)
+            .await
+            .map_err(|_| MonitorError::Timeout(timeout))?
+    }
+
+    /// Returns the provider the monitor collects through
+    ///
+    /// The provider never changes, so callers may keep it and use it without
+    /// taking the monitor lock.
+    pub fn provider(&self) -> Arc<dyn SystemInfoProvider> {
+        Arc::clone(&self.collector)
+    }
+
+    /// Runs a provider's health probe without blocking other requests
+    ///
+    /// Takes the provider rather than the monitor, so the probe never queues
+    /// behind a collection holding the monitor lock, and runs it on the
+    /// blocking thread pool. Fails with `MonitorError::Timeout` if probing
+    /// takes longer than `timeout`.
+    pub async fn probe(provider: Arc<dyn SystemInfoProvider>, timeout: Duration) -> Result<()> {
+        let probe = async move {
+            tokio::task::spawn_blocking(move || provider.probe())
+                .await
+                .map_err(|e| MonitorError::Panicked(e.to_string()))?
+        };
+
+        tokio::time::timeout(timeout, probe)
+            .await
+            .map_err(|_| MonitorError::Timeout(timeout))?
+    }
+
+    /// Gets a snapshot of the sections of the system metrics in `query`
+    ///
+    /// The collectors are independent, so each runs concurrently on the
+    /// blocking thread pool. A failing collector fails the snapshot with an
+    /// error naming it, except the optional GPU collector, whose failure is
+    /// logged and leaves `gpus` empty. GPUs follow the collector configuration
+    /// only, since the selector has no GPU section.
+    ///
+    /// With more processes than `query.max_processes`, or else the configured
+    /// `max_processes`, only the highest by the configured `process_sort_by`
+    /// key are kept, and `total_count` tells how many there were.
+    pub async fn get_system_metrics(&mut self, query: MetricsQuery) -> Result<SystemMetrics> {
+        self.refresh();
+        let selector = query.components;
+        let collectors = self.collectors;
+        let max_processes = query.max_processes.or(collectors.max_processes);
+        let command_timeout = self.command_timeout;
+        let static_info = Arc::clone(&self.static_info);
+        let static_cpu = Arc::clone(&self.static_info);
+
+        let system = self.spawn_collector("system info", move |c| {
+            selector
+                .system
+                .then(|| c.get_system_info(&static_info))
+                .transpose()
+        });
+        let cpu = self.spawn_collector("CPU", move |c| {
+            selector
+                .cpu
+                .then(|| c.get_cpu_info(&static_cpu))
+                .transpose()
+        });
+        let memory = self.spawn_collector("memory", move |c| {
+            selector.memory.then(|| c.get_memory_info()).transpose()
+        });
+        let load = self.spawn_collector("load average", move |c| {
+            selector.load.then(|| c.get_load_average()).transpose()
+        });
+        let disks = self.spawn_collector("disk", move |c| {
+            if collectors.disks && selector.disks {
+                c.get_disk_info()
+            } else {
+                Ok(Vec::new())
+            }
+        });
+        let networks = self.spawn_collector("network", move |c| {
+            if collectors.networks && selector.networks {
+                let networks = c.get_network_info()?;
+                Ok(apply_network_query(networks, &NetworkQuery::default()))
+            } else {
+                Ok(Vec::new())
+            }
+        });
+        let processes = self.spawn_collector("process", move |c| {
+            if !(collectors.processes && selector.processes) {
+                return Ok((Vec::new(), None));
+            }
+            let mut processes = c.get_processes(collectors.process_fds)?;
+            match max_processes {
+                Some(max) if processes.len() > max => {
+                    let total_count = processes.len();
+                    if collectors.process_sort_by == ProcessSortKey::Io {
+                        for process in &mut processes {
+                            if let Ok(io) = c.get_process_io(process.pid) {
+                                process.set_io(io);
+                            }
+                        }
+                    }
+                    let query = ProcessQuery {
+                        sort_by: collectors.process_sort_by,
+                        limit: Some(max),
+                        ..ProcessQuery::default()
+                    };
+                    Ok((apply_process_query(processes, &query), Some(total_count)))
+                }
+                _ => Ok((processes, None)),
+            }
+        });
+        let gpus = self.spawn_collector("GPU", move |_| {
+            if collectors.gpus {
+                gpu::get_gpu_info(command_timeout).map(Some)
+            } else {
+                Ok(None)
+            }
+        });
+
+        let (system, cpu, memory, load, disks, networks, processes, gpus) =
+            tokio::join!(system, cpu, memory, load, disks, networks, processes, gpus);
+
+        let gpus = gpus.unwrap_or_else(|e| {
+            warn!("{}", e);
+            None
+        });
+        let (processes, total_count) = processes?;
 
         Ok(SystemMetrics {
             timestamp: Utc::now(),
-            system_info,
-            cpu_info,
-            memory_info,
-            disks,
-            networks,
+            system_info: system?,
+            cpu_info: cpu?,
+            memory_info: memory?,
+            load_average: load?,
+            disks: disks?,
+            networks: networks?,
             processes,
+            total_count,
+            gpus,
         })
     }
 
+    /// Runs one collector on the blocking thread pool, naming it in any error
+    fn spawn_collector<T, F>(
+        &self,
+        name: &'static str,
+        collect: F,
+    ) -> impl Future<Output = Result<T>>
+    where
+        T: Send + 'static,
+        F: FnOnce(&dyn SystemInfoProvider) -> Result<T> + Send + 'static,
+    {
+        let collector = Arc::clone(&self.collector);
+        async move {
+            tokio::task::spawn_blocking(move || collect(collector.as_ref()))
+                .await
+                .map_err(|e| MonitorError::Panicked(e.to_string()))
+                .and_then(|result| result)
+                .map_err(|error| MonitorError::Collector {
+                    name,
+                    error: Box::new(error),
+                })
+        }
+    }
+
+    /// Gets the CPU, memory and load subset of the metrics for streaming
+    pub fn get_live_metrics(&mut self) -> Result<LiveMetrics> {
+        self.refresh();
+
+        Ok(LiveMetrics {
+            timestamp: Utc::now(),
+            cpu_info: self.get_cpu_info()?,
+            memory_info: self.get_memory_info()?,
+            load_average: self.get_load_average()?,
+        })
+    }
+
+    /// Collects a history sample and appends it, dropping the oldest when full
+    pub fn record_history_sample(&mut self) -> Result<HistorySample> {
+        let live = self.get_live_metrics()?;
+        let sample = HistorySample {
+            timestamp: live.timestamp,
+            cpu_percent: live.cpu_info.usage_percent,
+            memory_percent: live.memory_info.usage_percent,
+            load_one: live.load_average.one,
+        };
+
+        while self.history.len() >= self.history_capacity {
+            self.history.pop_front();
+        }
+        self.history.push_back(sample);
+        Ok(sample)
+    }
+
+    /// Returns the most recent `points` history samples, oldest first
+    ///
+    /// None returns the whole history.
+    pub fn history(&self, points: Option<usize>) -> Vec<HistorySample> {
+        let points = points.unwrap_or(self.history.len()).min(self.history.len());
+        self.history
+            .iter()
+            .skip(self.history.len() - points)
+            .copied()
+            .collect()
+    }
+
+    /// Sets how many history samples are kept, dropping the oldest if needed
+    pub fn set_history_capacity(&mut self, capacity: usize) {
+        self.history_capacity = capacity.max(1);
+        while self.history.len() > self.history_capacity {
+            self.history.pop_front();
+        }
+    }
+
+    /// Evaluates the alert rules against a history sample
+    ///
+    /// Disk usage, pressure, file handles and entropy are only collected when
+    /// a rule watches them; pressure rules never fire on kernels without PSI,
+    /// nor fd and entropy rules where the collector cannot read them. Rules
+    /// that start or stop firing are sent to the webhooks, if configured, and
+    /// returned.
+    pub fn evaluate_alerts(&mut self, sample: &HistorySample) -> Result<Vec<AlertTransition>> {
+        let disks =
+            if self.alerts.watches(AlertMetric::Disk) || self.alerts.watches(AlertMetric::Inodes) {
+                self.get_disk_info()?
+            } else {
+                Vec::new()
+            };
+        let fullest = |usage: fn(&DiskInfo) -> f32| {
+            disks.iter().map(|disk| usage(disk) as f64).reduce(f64::max)
+        };
+        let disk = fullest(|disk| disk.usage_percent);
+        let inodes = fullest(|disk| disk.inodes_usage_percent);
+
+        let pressure = if self.alerts.watches(AlertMetric::CpuPressure)
+            || self.alerts.watches(AlertMetric::MemoryPressure)
+            || self.alerts.watches(AlertMetric::IoPressure)
+        {
+            self.get_pressure()?
+        } else {
+            PressureInfo::default()
+        };
+        let stalled = |resource: Option<ResourcePressure>| resource.map(|r| r.some.avg10);
+
+        let fd = if self.alerts.watches(AlertMetric::Fd) {
+            self.fd_usage_percent()?.map(f64::from)
+        } else {
+            None
+        };
+        let entropy = if self.alerts.watches(AlertMetric::Entropy) {
+            match self.collector.get_entropy() {
+                Ok(entropy) => Some(entropy.available as f64),
+                Err(MonitorError::Unsupported(_)) => None,
+                Err(e) => return Err(e),
+            }
+        } else {
+            None
+        };
+
+        let transitions = self
+            .alerts
+            .evaluate(sample.timestamp, |metric| match metric {
+                AlertMetric::Cpu => Some(sample.cpu_percent as f64),
+                AlertMetric::Memory => Some(sample.memory_percent as f64),
+                AlertMetric::Load => Some(sample.load_one),
+                AlertMetric::Disk => disk,
+                AlertMetric::Inodes => inodes,
+                AlertMetric::CpuPressure => stalled(pressure.cpu),
+                AlertMetric::MemoryPressure => stalled(pressure.memory),
+                AlertMetric::IoPressure => stalled(pressure.io),
+                AlertMetric::Fd => fd,
+                AlertMetric::Entropy => entropy,
+            });
+        for transition in &transitions {
+            info!(
+                "Alert {} is {} ({} = {})",
+                transition.rule.name, transition.state, transition.rule.metric, transition.value
+            );
+        }
+        if let Some(webhooks) = &mut self.webhooks {
+            webhooks.notify(&transitions);
+        }
+        Ok(transitions)
+    }
+
+    /// Returns the alerts firing as of the latest sampler snapshot
+    pub fn firing_alerts(&self) -> Vec<Alert> {
+        self.alerts.firing()
+    }
+
+    /// Replaces the alert rules, clearing any pending or firing state
+    pub fn set_alert_rules(&mut self, rules: Vec<AlertRule>) {
+        self.alerts = AlertEvaluator::new(rules);
+    }
+
+    /// Sends alert transitions to the webhooks in `config`
+    ///
+    /// Without a global URL, only rules with their own `webhook_url` notify.
+    pub fn set_webhooks(&mut self, config: WebhookConfig) {
+        let hostname = self.static_info.hostname.clone();
+        self.webhooks = Some(WebhookNotifier::new(config, hostname));
+    }
+
     /// Starts continuous monitoring
-    pub fn start_monitoring(&mut self) -> Result<bool> {
-        if self.monitoring_active {
+    ///
+    /// Spawns a background task that records a history sample and evaluates
+    /// the alert rules once per monitoring interval, each bounded by
+    /// `timeout`. Returns false without spawning a second task if monitoring
+    /// is already active.
+    pub async fn start_monitoring(monitor: &Arc<RwLock<Self>>, timeout: Duration) -> Result<bool> {
+        let mut guard = monitor.write().await;
+        if guard.sampler.is_some() {
             info!("Monitoring already active");
-            Ok(false)
-        } else {
-            self.monitoring_active = true;
-            info!("Monitoring started");
-            Ok(true)
+            return Ok(false);
         }
+
+        guard.sampler = Some(tokio::spawn(Self::run_sampler(
+            Arc::downgrade(monitor),
+            timeout,
+        )));
+        info!("Monitoring started");
+        Ok(true)
     }
 
-    /// Stops continuous monitoring
+    /// Stops continuous monitoring, cancelling the background sampler
     pub fn stop_monitoring(&mut self) -> Result<bool> {
-        if self.monitoring_active {
-            self.monitoring_active = false;
-            info!("Monitoring stopped");
-            Ok(true)
-        } else {
-            info!("Monitoring not active");
-            Ok(false)
+        match self.sampler.take() {
+            Some(sampler) => {
+                sampler.abort();
+                info!("Monitoring stopped");
+                Ok(true)
+            }
+            None => {
+                info!("Monitoring not active");
+                Ok(false)
+            }
         }
     }
 
+    /// Returns whether continuous monitoring is currently active
+    pub fn is_monitoring_active(&self) -> bool {
+        self.sampler.is_some()
+    }
+
+    /// Returns the timestamp of the last data refresh
+    pub fn last_update(&self) -> DateTime<Utc> {
+        self.last_update
+    }
+
+    /// Records history samples until aborted or the monitor is dropped
+    ///
+    /// Sleeps for the monitoring interval before each sample, so interval
+    /// changes apply from the next tick. Holds only a weak reference so the
+    /// task does not keep the monitor alive.
+    async fn run_sampler(monitor: Weak<RwLock<Self>>, timeout: Duration) {
+        loop {
+            let Some(shared) = monitor.upgrade() else {
+                return;
+            };
+            let interval = shared.read().await.monitoring_interval();
+            drop(shared);
+            tokio::time::sleep(interval).await;
 
+            let Some(shared) = monitor.upgrade() else {
+                return;
+            };
+            let sample = Self::run_blocking(&shared, timeout, |monitor| {
+                let sample = monitor.record_history_sample()?;
+                monitor.evaluate_alerts(&sample).map(drop)
+            })
+            .await;
+            if let Err(e) = sample {
+                warn!("Failed to record monitoring sample: {}", e);
+            }
+        }
+    }
+
+    /// Returns which collectors are enabled
+    pub fn collectors(&self) -> CollectorConfig {
+        self.collectors
+    }
+
+    /// Enables or disables individual collectors
+    pub fn set_collectors(&mut self, collectors: CollectorConfig) {
+        self.collectors = collectors;
+    }
+
+    /// Returns the interval between continuous monitoring samples
+    pub fn monitoring_interval(&self) -> Duration {
+        self.monitoring_interval
+    }
+
+    /// Sets the monitoring interval, clamped to the configured floor
+    ///
+    /// Returns the effective interval, which may be longer than requested.
+    pub fn set_monitoring_interval(&mut self, requested: Duration) -> Duration {
+        self.monitoring_interval = self.clamp_interval(requested);
+        self.monitoring_interval
+    }
+
+    /// Returns the floor that monitoring intervals are clamped to
+    pub fn min_monitoring_interval(&self) -> Duration {
+        self.min_monitoring_interval
+    }
+
+    /// Sets the floor that monitoring intervals are clamped to
+    ///
+    /// The current interval is raised to the new floor if it is below it. A
+    /// floor below `MIN_INTERVAL_FLOOR` is raised to it.
+    pub fn set_min_monitoring_interval(&mut self, floor: Duration) {
+        let floor = floor.max(MIN_INTERVAL_FLOOR);
+        self.min_monitoring_interval = floor;
+        self.monitoring_interval = self.monitoring_interval.max(floor);
+    }
+
+    /// Clamps a requested collection interval to the configured floor
+    pub fn clamp_interval(&self, requested: Duration) -> Duration {
+        if requested < self.min_monitoring_interval {
+            warn!(
+                "Requested interval {:?} is below the {:?} floor, using the floor",
+                requested, self.min_monitoring_interval
+            );
+            self.min_monitoring_interval
+        } else {
+            requested
+        }
+    }
 }
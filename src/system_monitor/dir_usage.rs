@@ -0,0 +1,165 @@
+//! Directory Usage Collector
+//!
+//! Sums file sizes below a directory, like `du`, by walking the filesystem
+//! directly, so it is shared by all system collectors.
+
+use std::cmp::Reverse;
+use std::fs::{self, Metadata};
+use std::io::ErrorKind;
+use std::path::Path;
+
+use crate::error::{MonitorError, Result};
+use crate::types::{DirectoryEntry, DirectoryUsage};
+
+/// Gets the total size of `path` and its largest subdirectories
+///
+/// The whole tree below `path` is walked, but only subdirectories at most
+/// `depth` levels down are reported, largest first and at most `limit` of
+/// them. Only regular files count towards sizes, and symbolic links are never
+/// followed, so links cannot form loops. Unless `cross_devices` is set,
+/// directories on another filesystem, such as a mount under `/var`, are
+/// skipped as `du -x` does. The walk stops after `max_files` entries and
+/// marks the result as truncated.
+///
+/// # Errors
+///
+/// Returns `MonitorError::NotFound` if `path` does not exist,
+/// `MonitorError::InvalidArgument` if it is not a directory, and
+/// `MonitorError::PermissionDenied` if it cannot be read. Unreadable
+/// subdirectories are reported as inaccessible instead.
+pub fn get_directory_usage(
+    path: &Path,
+    depth: u32,
+    limit: usize,
+    cross_devices: bool,
+    max_files: u64,
+) -> Result<DirectoryUsage> {
+    let metadata = fs::metadata(path).map_err(|e| match e.kind() {
+        ErrorKind::NotFound => MonitorError::NotFound(path.display().to_string()),
+        _ => MonitorError::io(format!("Failed to read {}", path.display()), e),
+    })?;
+    if !metadata.is_dir() {
+        return Err(MonitorError::InvalidArgument(format!(
+            "{} is not a directory",
+            path.display()
+        )));
+    }
+    fs::read_dir(path)
+        .map_err(|e| MonitorError::io(format!("Failed to read {}", path.display()), e))?;
+
+    let mut walker = Walker {
+        device: device(&metadata),
+        cross_devices,
+        report_depth: depth,
+        max_files,
+        files_scanned: 0,
+        truncated: false,
+        entries: Vec::new(),
+    };
+    let (size_bytes, _) = walker.walk(path, 0);
+
+    let mut entries = walker.entries;
+    entries.sort_by_key(|entry| Reverse(entry.size_bytes));
+    entries.truncate(limit);
+
+    Ok(DirectoryUsage {
+        path: path.display().to_string(),
+        size_bytes,
+        files_scanned: walker.files_scanned,
+        truncated: walker.truncated,
+        entries,
+    })
+}
+
+/// State of one directory walk
+struct Walker {
+    /// Device of the starting directory, None where devices are unknown
+    device: Option<u64>,
+    /// Descend into directories on other filesystems
+    cross_devices: bool,
+    /// Deepest level of subdirectories that is reported
+    report_depth: u32,
+    /// Entries to visit before giving up
+    max_files: u64,
+    /// Entries visited so far
+    files_scanned: u64,
+    /// Whether the walk stopped at `max_files`
+    truncated: bool,
+    /// Reported subdirectories
+    entries: Vec<DirectoryEntry>,
+}
+
+impl Walker {
+    /// Walks `dir`, `depth` levels below the start, reporting it if shallow
+    /// enough
+    ///
+    /// Returns the total size of the files below `dir` and whether any part
+    /// of it could not be read.
+    fn walk(&mut self, dir: &Path, depth: u32) -> (u64, bool) {
+        let Ok(entries) = fs::read_dir(dir) else {
+            self.report(dir, depth, 0, true);
+            return (0, true);
+        };
+
+        let mut size_bytes = 0;
+        let mut inaccessible = false;
+        for entry in entries {
+            if self.files_scanned >= self.max_files {
+                self.truncated = true;
+                break;
+            }
+            self.files_scanned += 1;
+
+            // DirEntry::metadata does not follow symbolic links
+            let Ok((entry, metadata)) =
+                entry.and_then(|entry| entry.metadata().map(|metadata| (entry, metadata)))
+            else {
+                inaccessible = true;
+                continue;
+            };
+            if metadata.is_file() {
+                size_bytes += metadata.len();
+                continue;
+            }
+            if !metadata.is_dir() {
+                continue;
+            }
+            if !self.cross_devices && device(&metadata) != self.device {
+                continue;
+            }
+
+            let (child_size, child_inaccessible) = self.walk(&entry.path(), depth + 1);
+            size_bytes += child_size;
+            inaccessible |= child_inaccessible;
+        }
+
+        self.report(dir, depth, size_bytes, inaccessible);
+        (size_bytes, inaccessible)
+    }
+
+    /// Records a subdirectory if it is within the reported depth
+    fn report(&mut self, dir: &Path, depth: u32, size_bytes: u64, inaccessible: bool) {
+        if depth == 0 || depth > self.report_depth {
+            return;
+        }
+        self.entries.push(DirectoryEntry {
+            path: dir.display().to_string(),
+            depth,
+            size_bytes,
+            inaccessible,
+        });
+    }
+}
+
+/// Returns the device a file lives on
+#[cfg(unix)]
+fn device(metadata: &Metadata) -> Option<u64> {
+    use std::os::unix::fs::MetadataExt;
+    Some(metadata.dev())
+}
+
+/// Returns the device a file lives on, which is unknown on this platform
+#[cfg(not(unix))]
+fn device(_metadata: &Metadata) -> Option<u64> {
+    None
+}
@@ -0,0 +1,47 @@
+//! GPU Collector
+//!
+//! Queries NVIDIA GPUs through `nvidia-smi`, which ships with the driver on
+//! every platform, so it is shared by all system collectors.
+
+use std::io::ErrorKind;
+use std::process::Command;
+use std::time::Duration;
+use tracing::warn;
+
+use super::helpers::{parse_nvidia_smi_csv, run_command_with_timeout};
+use crate::error::{MonitorError, Result};
+use crate::types::GpuInfo;
+
+/// Columns requested from `nvidia-smi`, in the order `parse_nvidia_smi_csv` expects
+const NVIDIA_SMI_QUERY: &str =
+    "index,name,utilization.gpu,memory.used,memory.total,temperature.gpu";
+
+/// Gets all NVIDIA GPUs
+///
+/// Returns an empty list if `nvidia-smi` is not installed or finds no usable
+/// driver, so callers can query GPUs unconditionally. `nvidia-smi` is killed
+/// if it runs longer than `timeout`.
+pub fn get_gpu_info(timeout: Duration) -> Result<Vec<GpuInfo>> {
+    let mut command = Command::new("nvidia-smi");
+    command
+        .arg(format!("--query-gpu={}", NVIDIA_SMI_QUERY))
+        .arg("--format=csv,noheader,nounits");
+    let output = match run_command_with_timeout(&mut command, timeout) {
+        Ok(output) => output,
+        Err(MonitorError::CommandFailed { error, .. }) if error.kind() == ErrorKind::NotFound => {
+            return Ok(Vec::new())
+        }
+        Err(e) => return Err(e),
+    };
+
+    if !output.status.success() {
+        warn!(
+            "nvidia-smi failed: {}",
+            String::from_utf8_lossy(&output.stderr).trim()
+        );
+        return Ok(Vec::new());
+    }
+
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    Ok(parse_nvidia_smi_csv(&stdout))
+}
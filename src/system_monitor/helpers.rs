@@ -3,10 +3,37 @@
 //! This module contains utility functions for parsing system data,
 //! converting between formats, and other helper operations.
 
-use anyhow::{anyhow, Result};
-use chrono::{DateTime, Utc};
+use chrono::{DateTime, Datelike, Local, NaiveDateTime, TimeZone, Utc};
+use serde_json::Value;
+use std::collections::{HashMap, HashSet};
+use std::io::{self, Read};
+use std::net::{IpAddr, Ipv4Addr, Ipv6Addr};
+use std::process::{Command, Output, Stdio};
+use std::thread::{self, JoinHandle};
+use std::time::{Duration, Instant};
 
-/// Parses a size string (e.g., "1K", "2M", "3G") into bytes
+use crate::error::{MonitorError, Result};
+use crate::types::{
+    Connection, DiskIORate, DiskIOStats, FdUsage, GpuInfo, HistoryMetric, HistoryPoint,
+    HistorySample, KernelStats, LoadAverage, LogEntry, MemoryInfo, MetricStats, MetricsDiff,
+    NetworkDelta, NetworkInfo, NetworkQuery, PressureStall, ProcessIO, ProcessInfo, ProcessLimit,
+    ProcessLimits, ProcessMemory, ProcessNode, ProcessQuery, ProcessRef, ProcessSortKey,
+    ResourcePressure, SchedPolicy, ServiceInfo, SmartInfo, SortOrder, SwapDevice, SystemMetrics,
+    TemperatureSensor, TopMetric, TopProcesses, VirtualizationInfo,
+};
+
+/// Size of a sector as reported by `/proc/diskstats`, independent of the device
+const DISKSTATS_SECTOR_SIZE: u64 = 512;
+
+/// How often `run_command_with_timeout` checks whether the child has exited
+const COMMAND_POLL_INTERVAL: Duration = Duration::from_millis(10);
+
+/// Parses a size string with binary units (e.g., "1K", "2.5M", "1.5P"), as
+/// printed by `df -h`
+///
+/// A unit is one of K, M, G, T, P or E, optionally followed by "B" or "iB";
+/// a bare number or "B" suffix is a plain byte count. A decimal comma
+/// (e.g., "4,6G") is accepted.
 ///
 /// # Arguments
 ///
@@ -14,45 +41,207 @@ use chrono::{DateTime, Utc};
 ///
 /// # Returns
 ///
-/// Returns the size in bytes
+/// Returns the size in bytes, or 0 for an empty string
 ///
 /// # Errors
 ///
-/// Returns an error if the size string cannot be parsed
+/// Returns an error if the size string cannot be parsed or does not fit in
+/// 64 bits
 pub fn parse_size(size_str: &str) -> Result<u64> {
+    parse_size_with_base(size_str, 1024)
+}
+
+/// Parses a size string with decimal units (e.g., "1K" = 1000), as printed
+/// by `df -H`
+///
+/// Accepts the same syntax as `parse_size`; an explicit "iB" suffix (e.g.,
+/// "1KiB") still means a binary unit.
+///
+/// # Arguments
+///
+/// * `size_str` - The size string to parse
+///
+/// # Returns
+///
+/// Returns the size in bytes, or 0 for an empty string
+///
+/// # Errors
+///
+/// Returns an error if the size string cannot be parsed or does not fit in
+/// 64 bits
+pub fn parse_size_decimal(size_str: &str) -> Result<u64> {
+    parse_size_with_base(size_str, 1000)
+}
+
+/// Parses a size string, scaling units by powers of `base`
+fn parse_size_with_base(size_str: &str, base: u32) -> Result<u64> {
     let size_str = size_str.trim();
 
     if size_str.is_empty() {
         return Ok(0);
     }
 
-    // Remove commas (locale-specific formatting like "4,6G")
-    let size_str = size_str.replace(",", ".");
-    
-    // Try to parse as a number first
-    if let Ok(size) = size_str.parse::<u64>() {
-        return Ok(size);
-    }
+    // Accept a decimal comma (locale-specific formatting like "4,6G")
+    let size_str = size_str.replace(',', ".");
+    let invalid = || MonitorError::ParseError(format!("Invalid size string: {}", size_str));
 
-    // Parse with unit suffix
-    if size_str.len() < 2 {
-        return Err(anyhow!("Invalid number in size string: {}", size_str));
+    let unit_start = size_str
+        .find(|c: char| c.is_ascii_alphabetic())
+        .unwrap_or(size_str.len());
+    let (number_str, unit) = size_str.split_at(unit_start);
+    let number_str = number_str.trim();
+    let unit = unit.to_ascii_uppercase();
+
+    // Plain byte counts are exact, without a round trip through f64
+    if unit.is_empty() || unit == "B" {
+        if let Ok(size) = number_str.parse::<u64>() {
+            return Ok(size);
+        }
     }
-    
-    let (number_str, unit) = size_str.split_at(size_str.len() - 1);
-    let number = number_str
-        .parse::<f64>()
-        .map_err(|_| anyhow!("Invalid number in size string: {}", size_str))?;
 
-    let multiplier = match unit.to_uppercase().as_str() {
-        "K" | "KB" => 1024.0,
-        "M" | "MB" => 1024.0 * 1024.0,
-        "G" | "GB" => 1024.0 * 1024.0 * 1024.0,
-        "T" | "TB" => 1024.0 * 1024.0 * 1024.0 * 1024.0,
-        _ => return Err(anyhow!("Unknown size unit: {}", unit)),
+    let (prefix, suffix) = unit.split_at(unit.len().min(1));
+    let (exponent, suffix) = match prefix {
+        "" => (0, ""),
+        "B" => (0, "B"),
+        "K" => (1, suffix),
+        "M" => (2, suffix),
+        "G" => (3, suffix),
+        "T" => (4, suffix),
+        "P" => (5, suffix),
+        "E" => (6, suffix),
+        _ => {
+            return Err(MonitorError::ParseError(format!(
+                "Unknown size unit: {}",
+                unit
+            )))
+        }
+    };
+    let base = match suffix {
+        "" | "B" => base,
+        "IB" if exponent > 0 => 1024,
+        _ => {
+            return Err(MonitorError::ParseError(format!(
+                "Unknown size unit: {}",
+                unit
+            )))
+        }
     };
 
-    Ok((number * multiplier) as u64)
+    let number = number_str.parse::<f64>().map_err(|_| invalid())?;
+    if !number.is_finite() || number < 0.0 {
+        return Err(invalid());
+    }
+
+    let bytes = number * f64::from(base).powi(exponent);
+    // u64::MAX rounds up to 2^64 as an f64, so anything at or above it overflows
+    if bytes >= u64::MAX as f64 {
+        return Err(MonitorError::ParseError(format!(
+            "Size out of range: {}",
+            size_str
+        )));
+    }
+
+    Ok(bytes as u64)
+}
+
+/// Formats a byte count with binary units (e.g., "1.5 GiB")
+///
+/// Counts below 1 KiB are printed exactly; larger ones use the largest unit
+/// that keeps the value at or above 1, with one decimal.
+///
+/// # Arguments
+///
+/// * `bytes` - The number of bytes
+///
+/// # Returns
+///
+/// Returns the human-readable size
+pub fn format_bytes(bytes: u64) -> String {
+    const UNITS: [&str; 6] = ["KiB", "MiB", "GiB", "TiB", "PiB", "EiB"];
+
+    if bytes < 1024 {
+        return format!("{} B", bytes);
+    }
+
+    let mut value = bytes as f64 / 1024.0;
+    let mut unit = 0;
+    // Step up before rounding could print "1024.0" of the smaller unit
+    while value >= 1023.95 && unit < UNITS.len() - 1 {
+        value /= 1024.0;
+        unit += 1;
+    }
+
+    format!("{:.1} {}", value, UNITS[unit])
+}
+
+/// Formats an uptime in seconds for display (e.g., "3d 4h 12m")
+///
+/// Uses years of 365 days, days, hours and minutes, printing at most the three
+/// most significant units and leaving out zero ones. Uptimes below a minute
+/// are printed in seconds.
+///
+/// # Arguments
+///
+/// * `secs` - The uptime in seconds
+///
+/// # Returns
+///
+/// Returns the human-readable uptime
+pub fn format_uptime(secs: u64) -> String {
+    const UNITS: [(u64, &str); 4] = [(365 * 86400, "y"), (86400, "d"), (3600, "h"), (60, "m")];
+
+    if secs < 60 {
+        return format!("{}s", secs);
+    }
+
+    let mut rest = secs;
+    let mut parts = Vec::new();
+    for (size, suffix) in UNITS {
+        let count = rest / size;
+        rest %= size;
+        // Count units from the first non-zero one, so "1y 0d 3h 5m" stops at hours
+        if count > 0 || !parts.is_empty() {
+            parts.push((count, suffix));
+        }
+    }
+
+    parts
+        .into_iter()
+        .take(3)
+        .filter(|(count, _)| *count > 0)
+        .map(|(count, suffix)| format!("{}{}", count, suffix))
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+/// Adds a `<field>_human` string next to each listed byte count
+///
+/// Applies to a single object or to every object in an array; fields that
+/// are missing or not unsigned integers are left alone.
+///
+/// # Arguments
+///
+/// * `value` - The serialized object or list of objects
+/// * `fields` - Names of the fields holding byte counts
+pub fn add_human_sizes(value: &mut Value, fields: &[&str]) {
+    match value {
+        Value::Array(items) => {
+            for item in items {
+                add_human_sizes(item, fields);
+            }
+        }
+        Value::Object(object) => {
+            for field in fields {
+                if let Some(bytes) = object.get(*field).and_then(Value::as_u64) {
+                    object.insert(
+                        format!("{}_human", field),
+                        Value::String(format_bytes(bytes)),
+                    );
+                }
+            }
+        }
+        _ => {}
+    }
 }
 
 /// Parses elapsed time string from ps command into DateTime
@@ -76,24 +265,27 @@ pub fn parse_etime(etime: &str) -> Result<DateTime<Utc>> {
     }
 
     let parts: Vec<&str> = etime.split(':').collect();
-    
+
     // Handle both "48:38" (2 parts: minutes:seconds) and "1-02:30:45" (3+ parts)
     if parts.len() < 2 || parts.len() > 3 {
-        return Err(anyhow!("Invalid elapsed time format: {}", etime));
+        return Err(MonitorError::ParseError(format!(
+            "Invalid elapsed time format: {}",
+            etime
+        )));
     }
 
     let mut total_seconds = 0i64;
 
     if parts.len() == 2 {
         // Format: "MM:SS" (just minutes and seconds)
-        let minutes: i64 = parts[0]
-            .parse()
-            .map_err(|_| anyhow!("Invalid minutes in elapsed time: {}", etime))?;
+        let minutes: i64 = parts[0].parse().map_err(|_| {
+            MonitorError::ParseError(format!("Invalid minutes in elapsed time: {}", etime))
+        })?;
         total_seconds += minutes * 60;
-        
-        let seconds: i64 = parts[1]
-            .parse()
-            .map_err(|_| anyhow!("Invalid seconds in elapsed time: {}", etime))?;
+
+        let seconds: i64 = parts[1].parse().map_err(|_| {
+            MonitorError::ParseError(format!("Invalid seconds in elapsed time: {}", etime))
+        })?;
         total_seconds += seconds;
     } else {
         // Format: "HH:MM:SS" or "D-HH:MM:SS"
@@ -101,39 +293,46 @@ pub fn parse_etime(etime: &str) -> Result<DateTime<Utc>> {
         if time_part.contains('-') {
             let day_parts: Vec<&str> = time_part.split('-').collect();
             if day_parts.len() == 2 {
-                let days: i64 = day_parts[0]
-                    .parse()
-                    .map_err(|_| anyhow!("Invalid days in elapsed time: {}", etime))?;
+                let days: i64 = day_parts[0].parse().map_err(|_| {
+                    MonitorError::ParseError(format!("Invalid days in elapsed time: {}", etime))
+                })?;
                 total_seconds += days * 24 * 3600;
 
-                let hours: i64 = day_parts[1]
-                    .parse()
-                    .map_err(|_| anyhow!("Invalid hours in elapsed time: {}", etime))?;
+                let hours: i64 = day_parts[1].parse().map_err(|_| {
+                    MonitorError::ParseError(format!("Invalid hours in elapsed time: {}", etime))
+                })?;
                 total_seconds += hours * 3600;
             } else {
-                return Err(anyhow!("Invalid elapsed time format: {}", etime));
+                return Err(MonitorError::ParseError(format!(
+                    "Invalid elapsed time format: {}",
+                    etime
+                )));
             }
         } else {
-            let hours: i64 = time_part
-                .parse()
-                .map_err(|_| anyhow!("Invalid hours in elapsed time: {}", etime))?;
+            let hours: i64 = time_part.parse().map_err(|_| {
+                MonitorError::ParseError(format!("Invalid hours in elapsed time: {}", etime))
+            })?;
             total_seconds += hours * 3600;
         }
 
         // Parse minutes
-        let minutes: i64 = parts[1]
-            .parse()
-            .map_err(|_| anyhow!("Invalid minutes in elapsed time: {}", etime))?;
+        let minutes: i64 = parts[1].parse().map_err(|_| {
+            MonitorError::ParseError(format!("Invalid minutes in elapsed time: {}", etime))
+        })?;
         total_seconds += minutes * 60;
 
         // Parse seconds
-        let seconds: i64 = parts[2]
-            .parse()
-            .map_err(|_| anyhow!("Invalid seconds in elapsed time: {}", etime))?;
+        let seconds: i64 = parts[2].parse().map_err(|_| {
+            MonitorError::ParseError(format!("Invalid seconds in elapsed time: {}", etime))
+        })?;
         total_seconds += seconds;
     }
 
-    Ok(now - chrono::Duration::seconds(total_seconds))
+    // A process forked while ps is sampling can report a wrapped, absurdly
+    // large elapsed time; treat anything unrepresentable as "just started"
+    Ok(chrono::Duration::try_seconds(total_seconds)
+        .and_then(|elapsed| now.checked_sub_signed(elapsed))
+        .unwrap_or(now))
 }
 
 /// Safely parses a string to u32, returning 0 if parsing fails
@@ -188,16 +387,18 @@ pub fn safe_parse_i32(s: &str) -> i32 {
     s.trim().parse().unwrap_or(0)
 }
 
-/// Extracts a value from a key-value line (e.g., "MemTotal: 16384 kB")
+/// Checks whether a `/proc` entry name is a process ID
 ///
 /// # Arguments
 ///
-/// * `line` - The line to parse
-/// * `key` - The key to look for
+/// * `name` - The file name of the entry
 ///
 /// # Returns
 ///
-
+/// Returns true if the name is a non-empty string of ASCII digits
+pub fn is_pid(name: &str) -> bool {
+    !name.is_empty() && name.bytes().all(|byte| byte.is_ascii_digit())
+}
 
 /// Calculates percentage with safe division
 ///
@@ -216,3 +417,2024 @@ pub fn calculate_percentage(part: u64, total: u64) -> f32 {
         (part as f32 / total as f32) * 100.0
     }
 }
+
+/// Parses the contents of `/proc/diskstats` into per-device I/O counters
+///
+/// Devices that have never completed a read or write (unused loop and ram
+/// devices, for example) are skipped.
+///
+/// # Arguments
+///
+/// * `content` - The raw contents of `/proc/diskstats`
+///
+/// # Returns
+///
+/// Returns the I/O counters for every active block device
+pub fn parse_diskstats(content: &str) -> Vec<DiskIOStats> {
+    let mut stats = Vec::new();
+
+    for line in content.lines() {
+        let parts: Vec<&str> = line.split_whitespace().collect();
+        // major minor name reads merged sectors ms writes merged sectors ...
+        if parts.len() < 10 {
+            continue;
+        }
+
+        let reads_completed = safe_parse_u64(parts[3]);
+        let writes_completed = safe_parse_u64(parts[7]);
+        if reads_completed == 0 && writes_completed == 0 {
+            continue;
+        }
+
+        let sectors_read = safe_parse_u64(parts[5]);
+        let sectors_written = safe_parse_u64(parts[9]);
+
+        stats.push(DiskIOStats {
+            device: parts[2].to_string(),
+            reads_completed,
+            writes_completed,
+            sectors_read,
+            sectors_written,
+            read_bytes: sectors_read * DISKSTATS_SECTOR_SIZE,
+            write_bytes: sectors_written * DISKSTATS_SECTOR_SIZE,
+        });
+    }
+
+    stats
+}
+
+/// Computes per-device disk throughput from two `DiskIOStats` samples
+///
+/// Devices missing from either sample are skipped. A counter that went
+/// backwards (device re-attached, counter reset) yields a rate of 0.
+///
+/// # Arguments
+///
+/// * `previous` - The earlier sample
+/// * `current` - The later sample
+/// * `elapsed` - Time between the two samples
+///
+/// # Returns
+///
+/// Returns read/write bytes per second for each device present in both samples
+pub fn calculate_disk_io_rates(
+    previous: &[DiskIOStats],
+    current: &[DiskIOStats],
+    elapsed: Duration,
+) -> Vec<DiskIORate> {
+    current
+        .iter()
+        .filter_map(|curr| {
+            let prev = previous.iter().find(|p| p.device == curr.device)?;
+
+            Some(DiskIORate {
+                device: curr.device.clone(),
+                read_bytes_per_sec: counter_rate(prev.read_bytes, curr.read_bytes, elapsed),
+                write_bytes_per_sec: counter_rate(prev.write_bytes, curr.write_bytes, elapsed),
+            })
+        })
+        .collect()
+}
+
+/// Computes the per-second rate of a monotonic counter between two samples
+///
+/// A counter that decreased (wraparound of 32-bit kernel counters, interface
+/// reset) is treated as a reset and yields 0 for that interval.
+///
+/// # Arguments
+///
+/// * `previous` - The counter value at the earlier sample
+/// * `current` - The counter value at the later sample
+/// * `elapsed` - Time between the two samples
+///
+/// # Returns
+///
+/// Returns the rate per second, or 0.0 if no time elapsed
+pub fn counter_rate(previous: u64, current: u64, elapsed: Duration) -> f64 {
+    let secs = elapsed.as_secs_f64();
+    if secs > 0.0 && current >= previous {
+        (current - previous) as f64 / secs
+    } else {
+        0.0
+    }
+}
+
+/// Estimates seconds until a battery is empty or full
+///
+/// `now`, `full` and `rate` must share a unit family: energy (µWh) with power
+/// (µW), or charge (µAh) with current (µA).
+///
+/// # Arguments
+///
+/// * `status` - The sysfs `status` value
+/// * `now` - Remaining energy or charge
+/// * `full` - Energy or charge when full
+/// * `rate` - Current power draw or current
+///
+/// # Returns
+///
+/// Returns the estimate, or None when idle, full, or the rate is zero
+pub fn estimate_battery_time(status: &str, now: u64, full: u64, rate: u64) -> Option<u64> {
+    if rate == 0 {
+        return None;
+    }
+
+    let remaining = match status {
+        "Discharging" => now,
+        "Charging" => full.saturating_sub(now),
+        _ => return None,
+    };
+
+    Some((remaining as f64 / rate as f64 * 3600.0).round() as u64)
+}
+
+/// Parses `nvidia-smi --format=csv,noheader,nounits` output
+///
+/// Expects the columns index, name, utilization.gpu, memory.used,
+/// memory.total and temperature.gpu, with memory in MiB. Values such as
+/// "[N/A]" or "[Not Supported]" become None.
+///
+/// # Arguments
+///
+/// * `content` - The command output
+///
+/// # Returns
+///
+/// Returns one entry per well-formed line
+pub fn parse_nvidia_smi_csv(content: &str) -> Vec<GpuInfo> {
+    const MIB: u64 = 1024 * 1024;
+
+    content
+        .lines()
+        .filter_map(|line| {
+            let fields: Vec<&str> = line.split(',').map(str::trim).collect();
+            let [index, name, utilization, memory_used, memory_total, temperature] = fields[..]
+            else {
+                return None;
+            };
+
+            Some(GpuInfo {
+                index: index.parse().ok()?,
+                name: name.to_string(),
+                utilization_percent: utilization.parse().ok(),
+                memory_used: memory_used.parse::<u64>().ok().map(|mib| mib * MIB),
+                memory_total: memory_total.parse::<u64>().ok().map(|mib| mib * MIB),
+                temperature: temperature.parse().ok(),
+            })
+        })
+        .collect()
+}
+
+/// Parses the drives listed by `smartctl --scan -j`
+///
+/// # Arguments
+///
+/// * `output` - The command output
+///
+/// # Returns
+///
+/// Returns the device path and `smartctl` device type of each drive
+///
+/// # Errors
+///
+/// Returns an error if the output is not JSON
+pub fn parse_smartctl_scan(output: &str) -> Result<Vec<(String, String)>> {
+    let json: Value = serde_json::from_str(output)
+        .map_err(|e| MonitorError::ParseError(format!("Invalid smartctl scan output: {}", e)))?;
+
+    Ok(json["devices"]
+        .as_array()
+        .map(|devices| {
+            devices
+                .iter()
+                .filter_map(|device| {
+                    Some((
+                        device["name"].as_str()?.to_string(),
+                        device["type"].as_str().unwrap_or("auto").to_string(),
+                    ))
+                })
+                .collect()
+        })
+        .unwrap_or_default())
+}
+
+/// Parses `smartctl -H -A -j` output for one drive
+///
+/// The reallocated sector count is ATA attribute 5; NVMe drives have no
+/// equivalent and report None.
+///
+/// # Arguments
+///
+/// * `device` - The device path, used if the output does not name it
+/// * `output` - The command output
+///
+/// # Returns
+///
+/// Returns the drive's health, temperature and wear counters
+///
+/// # Errors
+///
+/// Returns `MonitorError::PermissionDenied` if `smartctl` could not open the
+/// drive for lack of privileges, `MonitorError::Unsupported` if it reported
+/// no SMART data for another reason, and a parse error if the output is not JSON
+pub fn parse_smartctl_json(device: &str, output: &str) -> Result<SmartInfo> {
+    /// ATA attribute counting sectors remapped to the spare area
+    const REALLOCATED_SECTOR_COUNT: u64 = 5;
+
+    let json: Value = serde_json::from_str(output).map_err(|e| {
+        MonitorError::ParseError(format!("Invalid smartctl output for {}: {}", device, e))
+    })?;
+
+    // smartctl explains why it could not read a drive in its messages
+    let errors: Vec<&str> = json["smartctl"]["messages"]
+        .as_array()
+        .map(|messages| {
+            messages
+                .iter()
+                .filter(|message| message["severity"] == "error")
+                .filter_map(|message| message["string"].as_str())
+                .collect()
+        })
+        .unwrap_or_default();
+    if let Some(denied) = errors
+        .iter()
+        .find(|error| error.contains("Permission denied"))
+    {
+        return Err(MonitorError::PermissionDenied(denied.to_string()));
+    }
+    if json.get("smart_status").is_none() && !errors.is_empty() {
+        return Err(MonitorError::Unsupported(errors.join("; ")));
+    }
+
+    let reallocated_sectors = json["ata_smart_attributes"]["table"]
+        .as_array()
+        .and_then(|attributes| {
+            attributes
+                .iter()
+                .find(|attribute| attribute["id"].as_u64() == Some(REALLOCATED_SECTOR_COUNT))
+        })
+        .and_then(|attribute| attribute["raw"]["value"].as_u64());
+
+    Ok(SmartInfo {
+        device: json["device"]["name"]
+            .as_str()
+            .unwrap_or(device)
+            .to_string(),
+        health_passed: json["smart_status"]["passed"].as_bool(),
+        temperature: json["temperature"]["current"]
+            .as_f64()
+            .map(|celsius| celsius as f32),
+        power_on_hours: json["power_on_time"]["hours"].as_u64(),
+        reallocated_sectors,
+    })
+}
+
+/// Parses `systemctl list-units --output=json` output
+///
+/// # Arguments
+///
+/// * `output` - The command output
+///
+/// # Returns
+///
+/// Returns one entry per listed unit
+///
+/// # Errors
+///
+/// Returns an error if the output is not a JSON array, as with systemd
+/// versions before 246 that ignore `--output=json` for unit lists
+pub fn parse_systemctl_units(output: &str) -> Result<Vec<ServiceInfo>> {
+    let units: Vec<Value> = serde_json::from_str(output)
+        .map_err(|e| MonitorError::ParseError(format!("Invalid systemctl output: {}", e)))?;
+
+    let field = |unit: &Value, name: &str| unit[name].as_str().unwrap_or_default().to_string();
+    Ok(units
+        .iter()
+        .filter(|unit| unit["unit"].is_string())
+        .map(|unit| ServiceInfo {
+            name: field(unit, "unit"),
+            load_state: field(unit, "load"),
+            active_state: field(unit, "active"),
+            sub_state: field(unit, "sub"),
+            description: field(unit, "description"),
+        })
+        .collect())
+}
+
+/// Parses one line of `journalctl -o json` output
+///
+/// Messages the journal stores as bytes, because they are not valid UTF-8,
+/// are decoded lossily.
+///
+/// # Arguments
+///
+/// * `line` - One JSON object
+///
+/// # Returns
+///
+/// Returns the entry, or None if the line is not a journal entry
+pub fn parse_journal_entry(line: &str) -> Option<LogEntry> {
+    let entry: Value = serde_json::from_str(line).ok()?;
+    let message = match &entry["MESSAGE"] {
+        Value::String(message) => message.clone(),
+        Value::Array(bytes) => {
+            let bytes: Vec<u8> = bytes
+                .iter()
+                .filter_map(|byte| byte.as_u64().and_then(|b| u8::try_from(b).ok()))
+                .collect();
+            String::from_utf8_lossy(&bytes).into_owned()
+        }
+        _ => return None,
+    };
+
+    Some(LogEntry {
+        timestamp: entry["__REALTIME_TIMESTAMP"]
+            .as_str()
+            .and_then(|usec| usec.parse::<i64>().ok())
+            .and_then(DateTime::from_timestamp_micros),
+        unit: entry["_SYSTEMD_UNIT"]
+            .as_str()
+            .or_else(|| entry["SYSLOG_IDENTIFIER"].as_str())
+            .map(str::to_string),
+        priority: entry["PRIORITY"].as_str().and_then(|p| p.parse().ok()),
+        message,
+    })
+}
+
+/// Parses one line of a syslog file such as `/var/log/syslog`
+///
+/// Accepts RFC 3339 timestamps as written by recent rsyslog defaults and the
+/// traditional "Oct 16 12:34:56" form, which has no year or zone: it is read
+/// as local time in the year of `now`, or the year before if that would put
+/// it in the future.
+///
+/// # Arguments
+///
+/// * `line` - The log line, e.g. "Oct 16 12:34:56 host sshd[812]: Accepted key"
+/// * `now` - The current time
+///
+/// # Returns
+///
+/// Returns the entry; lines in neither format keep the whole line as message
+pub fn parse_syslog_line(line: &str, now: DateTime<Local>) -> LogEntry {
+    let (first, rest) = split_field(line);
+    let (timestamp, rest) = if let Ok(timestamp) = DateTime::parse_from_rfc3339(first) {
+        (timestamp.with_timezone(&Utc), rest)
+    } else {
+        let (day, rest) = split_field(rest);
+        let (time, rest) = split_field(rest);
+        let stamp = |year: i32| {
+            let text = format!("{} {} {} {}", year, first, day, time);
+            NaiveDateTime::parse_from_str(&text, "%Y %b %e %H:%M:%S")
+                .ok()
+                .and_then(|naive| Local.from_local_datetime(&naive).earliest())
+        };
+        let Some(mut timestamp) = stamp(now.year()) else {
+            return LogEntry {
+                timestamp: None,
+                unit: None,
+                priority: None,
+                message: line.to_string(),
+            };
+        };
+        if timestamp > now + chrono::Duration::days(1) {
+            timestamp = stamp(now.year() - 1).unwrap_or(timestamp);
+        }
+        (timestamp.with_timezone(&Utc), rest)
+    };
+
+    // The hostname, then "program[pid]:" or "program:"
+    let (_host, rest) = split_field(rest);
+    let (tag, message) = split_field(rest);
+    let (unit, message) = match tag.strip_suffix(':') {
+        Some(program) => (program.split('[').next().map(str::to_string), message),
+        None => (None, rest),
+    };
+
+    LogEntry {
+        timestamp: Some(timestamp),
+        unit,
+        priority: None,
+        message: message.to_string(),
+    }
+}
+
+/// Splits off the first whitespace-separated field, returning it and the rest
+fn split_field(text: &str) -> (&str, &str) {
+    let text = text.trim_start();
+    let end = text.find(char::is_whitespace).unwrap_or(text.len());
+    (&text[..end], text[end..].trim_start())
+}
+
+/// Extracts the cgroup of a process from a `/proc/<pid>/cgroup` file
+///
+/// Uses the unified (`0::`) hierarchy on cgroup v2 or the `name=systemd`
+/// hierarchy on v1, which systemd organizes by unit.
+///
+/// # Arguments
+///
+/// * `content` - The raw contents of `/proc/<pid>/cgroup`
+///
+/// # Returns
+///
+/// Returns the path (e.g., "/system.slice/nginx.service"), or None if
+/// neither hierarchy is listed
+pub fn parse_cgroup_path(content: &str) -> Option<&str> {
+    content.lines().find_map(|line| {
+        let mut fields = line.splitn(3, ':');
+        let _id = fields.next()?;
+        let controllers = fields.next()?;
+        let path = fields.next()?;
+        (controllers.is_empty() || controllers == "name=systemd").then_some(path)
+    })
+}
+
+/// Extracts the owning systemd unit from a `/proc/<pid>/cgroup` file
+///
+/// Uses the unified (`0::`) hierarchy on cgroup v2 or the `name=systemd`
+/// hierarchy on v1, and returns the innermost `.service` or `.scope`
+/// component of the path (e.g., `system.slice/nginx.service` → `nginx.service`).
+///
+/// # Arguments
+///
+/// * `content` - The raw contents of `/proc/<pid>/cgroup`
+///
+/// # Returns
+///
+/// Returns the unit name, or None if the process is not inside a unit
+pub fn parse_systemd_unit(content: &str) -> Option<String> {
+    parse_cgroup_path(content)?
+        .rsplit('/')
+        .find(|component| component.ends_with(".service") || component.ends_with(".scope"))
+        .map(|unit| unit.to_string())
+}
+
+/// Container technologies as named by `systemd-detect-virt --container`
+const CONTAINER_VIRT_TYPES: &[&str] = &[
+    "docker",
+    "podman",
+    "lxc",
+    "lxc-libvirt",
+    "systemd-nspawn",
+    "openvz",
+    "rkt",
+    "wsl",
+    "proot",
+    "pouch",
+    "container-other",
+];
+
+/// Finds the container runtime in a `/proc/<pid>/cgroup` file
+///
+/// Recognizes the cgroup paths Docker, Kubernetes, LXC and Podman place
+/// container processes in (e.g., `/docker/<id>`, `/kubepods/...`).
+///
+/// # Arguments
+///
+/// * `content` - The raw contents of `/proc/<pid>/cgroup`
+///
+/// # Returns
+///
+/// Returns the runtime name, or None if no path looks like a container
+pub fn parse_cgroup_container(content: &str) -> Option<&'static str> {
+    content.lines().find_map(|line| {
+        let path = line.splitn(3, ':').nth(2)?;
+        if path.contains("kubepods") {
+            Some("kubernetes")
+        } else if path.contains("/docker") || path.contains("docker-") {
+            Some("docker")
+        } else if path.contains("libpod") {
+            Some("podman")
+        } else if path.contains("/lxc") {
+            Some("lxc")
+        } else {
+            None
+        }
+    })
+}
+
+/// Combines the available signals into the virtualization environment
+///
+/// Container markers take precedence, since `systemd-detect-virt` may be
+/// missing from container images.
+///
+/// # Arguments
+///
+/// * `cgroup` - Contents of `/proc/1/cgroup`, if readable
+/// * `dockerenv` - Whether `/.dockerenv` exists
+/// * `detect_virt` - Output of `systemd-detect-virt`, if it ran
+///
+/// # Returns
+///
+/// Returns the environment, with virt_type "none" if nothing was detected
+pub fn detect_virtualization(
+    cgroup: Option<&str>,
+    dockerenv: bool,
+    detect_virt: Option<&str>,
+) -> VirtualizationInfo {
+    let container = cgroup
+        .and_then(parse_cgroup_container)
+        .or(dockerenv.then_some("docker"));
+    if let Some(runtime) = container {
+        return VirtualizationInfo {
+            virt_type: runtime.to_string(),
+            is_container: true,
+        };
+    }
+
+    let virt_type = detect_virt
+        .map(str::trim)
+        .filter(|output| !output.is_empty())
+        .unwrap_or("none");
+    VirtualizationInfo {
+        virt_type: virt_type.to_string(),
+        is_container: CONTAINER_VIRT_TYPES.contains(&virt_type),
+    }
+}
+
+/// Parses the contents of `/proc/<pid>/cmdline` into a command line
+///
+/// Arguments are NUL-separated, usually with a trailing NUL; they are joined
+/// with spaces.
+///
+/// # Arguments
+///
+/// * `content` - The raw bytes of `/proc/<pid>/cmdline`
+///
+/// # Returns
+///
+/// Returns the command line, or None if it is empty, as for kernel threads
+/// and zombies
+pub fn parse_cmdline(content: &[u8]) -> Option<String> {
+    let args: Vec<String> = content
+        .strip_suffix(b"\0")
+        .unwrap_or(content)
+        .split(|&byte| byte == 0)
+        .map(|arg| String::from_utf8_lossy(arg).into_owned())
+        .collect();
+    let command = args.join(" ");
+    (!command.trim().is_empty()).then_some(command)
+}
+
+/// Parses a numeric field of `/proc/<pid>/status` (e.g., "Threads:\t4")
+///
+/// # Arguments
+///
+/// * `content` - The raw contents of `/proc/<pid>/status`
+/// * `key` - Field name without the colon, e.g. "Threads" or "VmRSS"
+///
+/// # Returns
+///
+/// Returns the first number of the field, without any unit, or None if the
+/// field is missing or not numeric
+pub fn parse_status_value(content: &str, key: &str) -> Option<u64> {
+    content.lines().find_map(|line| {
+        let (name, value) = line.split_once(':')?;
+        if name != key {
+            return None;
+        }
+        value.split_whitespace().next()?.parse().ok()
+    })
+}
+
+/// Parses the memory breakdown of `/proc/<pid>/status`
+///
+/// # Arguments
+///
+/// * `pid` - Process ID the status belongs to
+/// * `content` - The raw contents of `/proc/<pid>/status`
+///
+/// # Returns
+///
+/// Returns the sizes in bytes, with None for fields the status lacks
+pub fn parse_process_memory(pid: u32, content: &str) -> ProcessMemory {
+    let kib = |key: &str| parse_status_value(content, key).map(|value| value * 1024);
+    ProcessMemory {
+        pid,
+        vm_size: kib("VmSize"),
+        vm_rss: kib("VmRSS"),
+        vm_data: kib("VmData"),
+        vm_stk: kib("VmStk"),
+        vm_exe: kib("VmExe"),
+        vm_lib: kib("VmLib"),
+        vm_swap: kib("VmSwap"),
+        rss_anon: kib("RssAnon"),
+        rss_file: kib("RssFile"),
+        rss_shmem: kib("RssShmem"),
+    }
+}
+
+/// Parses the resource limits of `/proc/<pid>/limits`
+///
+/// Columns are padded to fixed widths that differ between kernels, so lines
+/// are split on whitespace instead: the limit name runs up to the first
+/// number or "unlimited", followed by the soft limit, the hard limit and an
+/// optional unit.
+///
+/// # Arguments
+///
+/// * `pid` - Process ID the limits belong to
+/// * `content` - The raw contents of `/proc/<pid>/limits`
+///
+/// # Returns
+///
+/// Returns the limits in file order, skipping the header and malformed lines
+pub fn parse_process_limits(pid: u32, content: &str) -> ProcessLimits {
+    let value = |field: &str| match field {
+        "unlimited" => Some(None),
+        field => field.parse::<u64>().ok().map(Some),
+    };
+
+    let limits = content
+        .lines()
+        .filter_map(|line| {
+            let fields: Vec<&str> = line.split_whitespace().collect();
+            let start = fields.iter().position(|field| value(field).is_some())?;
+            if start == 0 {
+                return None;
+            }
+            Some(ProcessLimit {
+                name: fields[..start].join(" "),
+                soft: value(fields[start])?,
+                hard: value(fields.get(start + 1)?)?,
+                units: fields.get(start + 2).map(|units| units.to_string()),
+            })
+        })
+        .collect();
+
+    ProcessLimits { pid, limits }
+}
+
+/// Parses the I/O counters of `/proc/<pid>/io`
+///
+/// # Arguments
+///
+/// * `content` - The raw contents of `/proc/<pid>/io`
+///
+/// # Returns
+///
+/// Returns the storage bytes (`read_bytes`, `write_bytes`) and system call
+/// counts (`syscr`, `syscw`); missing fields are 0
+pub fn parse_process_io(content: &str) -> ProcessIO {
+    let field = |key| parse_status_value(content, key).unwrap_or(0);
+    ProcessIO {
+        read_bytes: field("read_bytes"),
+        write_bytes: field("write_bytes"),
+        read_syscalls: field("syscr"),
+        write_syscalls: field("syscw"),
+    }
+}
+
+/// Splits `/proc/<pid>/stat` into its fields
+///
+/// The command name in field 2 may contain spaces and parentheses, so it is
+/// taken as everything between the first `(` and the last `)`.
+///
+/// # Arguments
+///
+/// * `content` - The raw contents of `/proc/<pid>/stat`
+///
+/// # Returns
+///
+/// Returns the fields with field `n` (numbered as in `proc(5)`) at index
+/// `n - 1`, or None if the command name is not parenthesized
+fn stat_fields(content: &str) -> Option<Vec<&str>> {
+    let (pid, rest) = content.split_once('(')?;
+    let (comm, rest) = rest.rsplit_once(')')?;
+    let mut fields = vec![pid.trim(), comm];
+    fields.extend(rest.split_whitespace());
+    Some(fields)
+}
+
+/// Parses the CPU time and start time of a process from `/proc/<pid>/stat`
+///
+/// `utime` and `stime` (fields 14 and 15) and
+/// `starttime` (field 22) are in clock ticks.
+///
+/// # Arguments
+///
+/// * `content` - The raw contents of `/proc/<pid>/stat`
+/// * `boot_time` - System boot time, the `btime` of `/proc/stat`
+/// * `clock_ticks` - Clock ticks per second (`sysconf(_SC_CLK_TCK)`)
+///
+/// # Returns
+///
+/// Returns the user plus system CPU time in seconds and the exact start time,
+/// or None if the line is malformed
+pub fn parse_process_stat(
+    content: &str,
+    boot_time: DateTime<Utc>,
+    clock_ticks: u64,
+) -> Option<(f64, DateTime<Utc>)> {
+    if clock_ticks == 0 {
+        return None;
+    }
+
+    let fields = stat_fields(content)?;
+    let field = |n: usize| fields.get(n - 1)?.parse::<u64>().ok();
+
+    let cpu_ticks = field(14)? + field(15)?;
+    let start_ticks = field(22)?;
+    let start_time =
+        boot_time + chrono::Duration::microseconds((start_ticks * 1_000_000 / clock_ticks) as i64);
+
+    Some((cpu_ticks as f64 / clock_ticks as f64, start_time))
+}
+
+/// Parses the scheduling policy and real-time priority from `/proc/<pid>/stat`
+///
+/// # Arguments
+///
+/// * `content` - The raw contents of `/proc/<pid>/stat`
+///
+/// # Returns
+///
+/// Returns the policy (field 41) and `rt_priority` (field 40), or None if the
+/// line is malformed or the policy unknown
+pub fn parse_process_sched(content: &str) -> Option<(SchedPolicy, u32)> {
+    let fields = stat_fields(content)?;
+    let rt_priority = fields.get(40 - 1)?.parse().ok()?;
+    let policy = SchedPolicy::from_number(fields.get(41 - 1)?.parse().ok()?)?;
+    Some((policy, rt_priority))
+}
+
+/// Parses the session ID and controlling terminal from `/proc/<pid>/stat`
+///
+/// # Arguments
+///
+/// * `content` - The raw contents of `/proc/<pid>/stat`
+///
+/// # Returns
+///
+/// Returns the session ID (field 6) and the terminal device number
+/// (`tty_nr`, field 7), or None if the line is malformed
+pub fn parse_process_session_tty(content: &str) -> Option<(u32, u64)> {
+    let fields = stat_fields(content)?;
+    let session = fields.get(6 - 1)?.parse().ok()?;
+    let tty_nr = fields.get(7 - 1)?.parse().ok()?;
+    Some((session, tty_nr))
+}
+
+/// Names a terminal by its device number, as `ps` does
+///
+/// Pseudo-terminals (majors 136-143) become "pts/N", virtual consoles
+/// "ttyN" and serial ports "ttySN"; other devices are printed as
+/// "major:minor".
+///
+/// # Arguments
+///
+/// * `tty_nr` - The `tty_nr` field of `/proc/<pid>/stat`
+///
+/// # Returns
+///
+/// Returns the terminal name, or "?" if the process has none
+pub fn tty_name(tty_nr: u64) -> String {
+    if tty_nr == 0 {
+        return "?".to_string();
+    }
+
+    let major = (tty_nr >> 8) & 0xfff;
+    let minor = (tty_nr & 0xff) | ((tty_nr >> 12) & 0xfff00);
+    match major {
+        136..=143 => format!("pts/{}", (major - 136) * 256 + minor),
+        4 if minor < 64 => format!("tty{}", minor),
+        4 => format!("ttyS{}", minor - 64),
+        _ => format!("{}:{}", major, minor),
+    }
+}
+
+/// Kernel TCP state names, indexed by the `st` code of `/proc/net/tcp`
+const TCP_STATES: [&str; 13] = [
+    "UNKNOWN",
+    "ESTABLISHED",
+    "SYN_SENT",
+    "SYN_RECV",
+    "FIN_WAIT1",
+    "FIN_WAIT2",
+    "TIME_WAIT",
+    "CLOSE",
+    "CLOSE_WAIT",
+    "LAST_ACK",
+    "LISTEN",
+    "CLOSING",
+    "NEW_SYN_RECV",
+];
+
+/// Returns the name of a kernel TCP state code (e.g., 0x0A → "LISTEN")
+///
+/// # Arguments
+///
+/// * `code` - The `st` field of `/proc/net/tcp`
+///
+/// # Returns
+///
+/// Returns the state name, or "UNKNOWN" for codes the kernel does not define
+pub fn tcp_state_name(code: u8) -> &'static str {
+    TCP_STATES.get(code as usize).copied().unwrap_or("UNKNOWN")
+}
+
+/// Decodes an `address:port` field of `/proc/net/{tcp,udp}[6]`
+///
+/// The address is the raw in-memory network-order bytes printed as native
+/// 32-bit hex words (one word for IPv4, four for IPv6), so each word is
+/// converted back with the host's byte order. The port is plain hex.
+///
+/// # Arguments
+///
+/// * `field` - The field, e.g. "0100007F:0035" for 127.0.0.1:53
+///
+/// # Returns
+///
+/// Returns the address and port, or None if the field is malformed
+pub fn parse_socket_address(field: &str) -> Option<(IpAddr, u16)> {
+    let (address, port) = field.split_once(':')?;
+    let port = u16::from_str_radix(port, 16).ok()?;
+
+    let words = (0..address.len())
+        .step_by(8)
+        .map(|start| {
+            let word = address.get(start..start + 8)?;
+            u32::from_str_radix(word, 16).ok().map(u32::to_ne_bytes)
+        })
+        .collect::<Option<Vec<[u8; 4]>>>()?;
+
+    let address = match words.as_slice() {
+        [word] => IpAddr::V4(Ipv4Addr::from(*word)),
+        [_, _, _, _] => {
+            let bytes: [u8; 16] = words.concat().try_into().ok()?;
+            IpAddr::V6(Ipv6Addr::from(bytes))
+        }
+        _ => return None,
+    };
+    Some((address, port))
+}
+
+/// Parses the contents of `/proc/net/{tcp,tcp6,udp,udp6}`
+///
+/// Unconnected UDP sockets, which the kernel reports as CLOSE, are named
+/// "UNCONN" as `ss` does.
+///
+/// # Arguments
+///
+/// * `content` - The raw file contents, including the header line
+/// * `proto` - Protocol name reported on each connection, e.g. "tcp6"
+///
+/// # Returns
+///
+/// Returns one connection per well-formed socket line
+pub fn parse_proc_net_sockets(content: &str, proto: &str) -> Vec<Connection> {
+    content
+        .lines()
+        .skip(1)
+        .filter_map(|line| {
+            let fields: Vec<&str> = line.split_whitespace().collect();
+            if fields.len() < 10 {
+                return None;
+            }
+            let (local_addr, local_port) = parse_socket_address(fields[1])?;
+            let (remote_addr, remote_port) = parse_socket_address(fields[2])?;
+            let code = u8::from_str_radix(fields[3], 16).ok()?;
+            let state = match tcp_state_name(code) {
+                "CLOSE" if proto.starts_with("udp") => "UNCONN",
+                state => state,
+            };
+
+            Some(Connection {
+                proto: proto.to_string(),
+                local_addr,
+                local_port,
+                remote_addr,
+                remote_port,
+                state: state.to_string(),
+                inode: fields[9].parse().ok()?,
+            })
+        })
+        .collect()
+}
+
+/// Counts the sockets of `/proc/net/{tcp,tcp6}` in each state
+///
+/// Only the state column is decoded, so large tables are cheap to tally.
+///
+/// # Arguments
+///
+/// * `content` - The raw file contents, including the header line
+/// * `counts` - Socket counts by state name, added to in place
+pub fn tally_tcp_states(content: &str, counts: &mut HashMap<String, u32>) {
+    for line in content.lines().skip(1) {
+        let Some(code) = line.split_whitespace().nth(3) else {
+            continue;
+        };
+        if let Ok(code) = u8::from_str_radix(code, 16) {
+            *counts.entry(tcp_state_name(code).to_string()).or_insert(0) += 1;
+        }
+    }
+}
+
+/// Parses a single-valued line of `/proc/stat` (e.g., "btime 1700000000")
+///
+/// # Arguments
+///
+/// * `content` - The raw contents of `/proc/stat`
+/// * `key` - First word of the line, e.g. "btime" or "ctxt"
+///
+/// # Returns
+///
+/// Returns the first value of the line, or None if the line is missing or
+/// not numeric
+pub fn parse_stat_value(content: &str, key: &str) -> Option<u64> {
+    content.lines().find_map(|line| {
+        let mut fields = line.split_whitespace();
+        if fields.next()? != key {
+            return None;
+        }
+        fields.next()?.parse().ok()
+    })
+}
+
+/// Parses the OS name and version from an `os-release` file
+///
+/// `PRETTY_NAME` and `VERSION_ID` are preferred, falling back to `NAME` and
+/// then `ID` for the name and to `VERSION` for the version, since minimal
+/// images often set only some of them. Values may be quoted with double or
+/// single quotes; empty values count as missing.
+///
+/// # Arguments
+///
+/// * `content` - The raw contents of `/etc/os-release`, or an empty string
+///   if it could not be read
+///
+/// # Returns
+///
+/// Returns the name, or the compile-time OS such as "linux" if none is set,
+/// and the version, or "Unknown"
+pub fn parse_os_release(content: &str) -> (String, String) {
+    let values: HashMap<&str, &str> = content
+        .lines()
+        .filter_map(|line| line.trim().split_once('='))
+        .map(|(key, value)| {
+            let value = value.trim();
+            let value = value
+                .strip_prefix('"')
+                .and_then(|value| value.strip_suffix('"'))
+                .or_else(|| {
+                    value
+                        .strip_prefix('\'')
+                        .and_then(|value| value.strip_suffix('\''))
+                })
+                .unwrap_or(value);
+            (key.trim(), value)
+        })
+        .filter(|(_, value)| !value.is_empty())
+        .collect();
+    let first = |keys: &[&str]| keys.iter().find_map(|key| values.get(key).copied());
+
+    let name = first(&["PRETTY_NAME", "NAME", "ID"]).unwrap_or(std::env::consts::OS);
+    let version = first(&["VERSION_ID", "VERSION"]).unwrap_or("Unknown");
+    (name.to_string(), version.to_string())
+}
+
+/// Counts the physical cores and sockets listed in `/proc/cpuinfo`
+///
+/// Each processor block names its socket in `physical id` and its core within
+/// the socket in `core id`; hyperthreads share both. A processor without a
+/// `core id` is counted as a core of its own.
+///
+/// # Arguments
+///
+/// * `cpuinfo` - The raw contents of `/proc/cpuinfo`
+///
+/// # Returns
+///
+/// Returns the number of distinct `(physical id, core id)` pairs and of
+/// distinct `physical id` values, or None if no processor has a `physical id`,
+/// as in many VMs
+pub fn parse_cpu_topology(cpuinfo: &str) -> Option<(u32, u32)> {
+    let mut cores = HashSet::new();
+    let mut sockets = HashSet::new();
+
+    for block in cpuinfo.split("\n\n") {
+        let field = |name: &str| {
+            block.lines().find_map(|line| {
+                let (key, value) = line.split_once(':')?;
+                (key.trim() == name).then(|| value.trim())
+            })
+        };
+        let Some(socket) = field("physical id") else {
+            continue;
+        };
+        let core = field("core id")
+            .or_else(|| field("processor"))
+            .unwrap_or("");
+        cores.insert((socket, core));
+        sockets.insert(socket);
+    }
+
+    (!sockets.is_empty()).then_some((cores.len() as u32, sockets.len() as u32))
+}
+
+/// Parses the contents of `/proc/meminfo`
+///
+/// Used memory is total minus available, so reclaimable cache does not count
+/// as used. Missing lines read as 0.
+///
+/// # Arguments
+///
+/// * `content` - The raw contents of `/proc/meminfo`
+///
+/// # Returns
+///
+/// Returns the memory and swap usage, with every size in bytes
+pub fn parse_meminfo(content: &str) -> MemoryInfo {
+    let mut total = 0u64;
+    let mut free = 0u64;
+    let mut available = 0u64;
+    let mut swap_total = 0u64;
+    let mut swap_free = 0u64;
+    let mut buffers = 0u64;
+    let mut cached = 0u64;
+    let mut slab = 0u64;
+    let mut shmem = 0u64;
+    let mut dirty = 0u64;
+
+    for line in content.lines() {
+        let parts: Vec<&str> = line.split_whitespace().collect();
+        if parts.len() >= 2 {
+            let value: u64 = parts[1].parse().unwrap_or(0) * 1024; // Convert KB to bytes
+            match parts[0] {
+                "MemTotal:" => total = value,
+                "MemFree:" => free = value,
+                "MemAvailable:" => available = value,
+                "SwapTotal:" => swap_total = value,
+                "SwapFree:" => swap_free = value,
+                "Buffers:" => buffers = value,
+                "Cached:" => cached = value,
+                "Slab:" => slab = value,
+                "Shmem:" => shmem = value,
+                "Dirty:" => dirty = value,
+                _ => {}
+            }
+        }
+    }
+
+    let used = total.saturating_sub(available);
+    let swap_used = swap_total.saturating_sub(swap_free);
+
+    MemoryInfo {
+        total,
+        used,
+        free,
+        available,
+        swap_total,
+        swap_used,
+        swap_free,
+        usage_percent: calculate_percentage(used, total),
+        swap_usage_percent: calculate_percentage(swap_used, swap_total),
+        buffers,
+        cached,
+        slab,
+        shmem,
+        dirty,
+        limit_bytes: None,
+        limit_usage_percent: None,
+    }
+}
+
+/// Parses a cgroup memory limit (`memory.max` or `memory.limit_in_bytes`)
+///
+/// cgroup v2 writes "max" for no limit; v1 writes the largest page-aligned
+/// 64-bit value instead, so anything that large is treated as unlimited too.
+///
+/// # Arguments
+///
+/// * `content` - The raw contents of the limit file
+///
+/// # Returns
+///
+/// Returns the limit in bytes, or None if unlimited or unparseable
+pub fn parse_cgroup_memory_limit(content: &str) -> Option<u64> {
+    let limit = content.trim().parse::<u64>().ok()?;
+    (limit < 1 << 62).then_some(limit)
+}
+
+/// Parses a cgroup CPU quota in the `cpu.max` format (e.g., "150000 100000")
+///
+/// The cgroup v1 `cpu.cfs_quota_us` and `cpu.cfs_period_us` values can be
+/// joined with a space to use the same format.
+///
+/// # Arguments
+///
+/// * `content` - The quota and period in microseconds
+///
+/// # Returns
+///
+/// Returns the quota in cores, or None if unlimited ("max" or -1)
+pub fn parse_cpu_max(content: &str) -> Option<f64> {
+    let mut fields = content.split_whitespace();
+    let quota = fields.next()?.parse::<i64>().ok()?;
+    let period = fields.next()?.parse::<u64>().ok()?;
+    (quota > 0 && period > 0).then(|| quota as f64 / period as f64)
+}
+
+/// Parses the scheduler and process counters of `/proc/stat`
+///
+/// # Arguments
+///
+/// * `content` - The raw contents of `/proc/stat`
+///
+/// # Returns
+///
+/// Returns the counters; `interrupts` is the total from the `intr` line
+///
+/// # Errors
+///
+/// Returns an error if any of the counter lines is missing
+pub fn parse_kernel_stats(content: &str) -> Result<KernelStats> {
+    let value = |key: &str| {
+        parse_stat_value(content, key)
+            .ok_or_else(|| MonitorError::ParseError(format!("{} not found in /proc/stat", key)))
+    };
+
+    Ok(KernelStats {
+        context_switches: value("ctxt")?,
+        interrupts: value("intr")?,
+        forks: value("processes")?,
+        procs_running: value("procs_running")?,
+        procs_blocked: value("procs_blocked")?,
+    })
+}
+
+/// Parses `/proc/sys/fs/file-nr`
+///
+/// # Arguments
+///
+/// * `content` - The raw contents: allocated handles, allocated but unused
+///   handles (always 0 since Linux 2.6) and the maximum, e.g. "3072\t0\t4096"
+///
+/// # Returns
+///
+/// Returns the handles in use and the limit
+///
+/// # Errors
+///
+/// Returns an error if the file does not hold three numbers
+pub fn parse_file_nr(content: &str) -> Result<FdUsage> {
+    let invalid = || MonitorError::ParseError(format!("Invalid file-nr: {}", content.trim()));
+    let fields: Vec<u64> = content
+        .split_whitespace()
+        .map(|field| field.parse::<u64>())
+        .collect::<std::result::Result<_, _>>()
+        .map_err(|_| invalid())?;
+    let [allocated, unused, max] = fields[..] else {
+        return Err(invalid());
+    };
+
+    let allocated = allocated.saturating_sub(unused);
+    Ok(FdUsage {
+        allocated,
+        max,
+        usage_percent: calculate_percentage(allocated, max),
+    })
+}
+
+/// Parses the contents of a `/proc/pressure` file
+///
+/// # Arguments
+///
+/// * `content` - The raw contents, e.g. of `/proc/pressure/memory`:
+///   "some avg10=0.00 avg60=0.00 avg300=0.00 total=0\nfull avg10=..."
+///
+/// # Returns
+///
+/// Returns the `some` stall and, if present, the `full` stall
+///
+/// # Errors
+///
+/// Returns an error if the `some` line is missing or a line is malformed
+pub fn parse_pressure(content: &str) -> Result<ResourcePressure> {
+    let mut some = None;
+    let mut full = None;
+
+    for line in content.lines() {
+        let mut fields = line.split_whitespace();
+        let slot = match fields.next() {
+            Some("some") => &mut some,
+            Some("full") => &mut full,
+            _ => continue,
+        };
+
+        let mut values: HashMap<&str, &str> = fields.filter_map(|f| f.split_once('=')).collect();
+        let mut avg = |key: &str| {
+            values
+                .remove(key)
+                .and_then(|v| v.parse::<f64>().ok())
+                .ok_or_else(|| MonitorError::ParseError(format!("Invalid PSI line: {}", line)))
+        };
+        let (avg10, avg60, avg300) = (avg("avg10")?, avg("avg60")?, avg("avg300")?);
+        let total = values
+            .get("total")
+            .and_then(|v| v.parse::<u64>().ok())
+            .ok_or_else(|| MonitorError::ParseError(format!("Invalid PSI line: {}", line)))?;
+
+        *slot = Some(PressureStall {
+            avg10,
+            avg60,
+            avg300,
+            total,
+        });
+    }
+
+    let some =
+        some.ok_or_else(|| MonitorError::ParseError("PSI \"some\" line not found".to_string()))?;
+    Ok(ResourcePressure { some, full })
+}
+
+/// Parses the contents of `/proc/swaps`
+///
+/// Spaces in file names are escaped as `\040`, so every line splits into
+/// exactly five fields.
+///
+/// # Arguments
+///
+/// * `content` - The raw contents of `/proc/swaps`
+///
+/// # Returns
+///
+/// Returns one entry per active swap device, empty if none is configured
+pub fn parse_swaps(content: &str) -> Vec<SwapDevice> {
+    content
+        .lines()
+        .skip(1)
+        .filter_map(|line| {
+            let fields: Vec<&str> = line.split_whitespace().collect();
+            if fields.len() < 5 {
+                return None;
+            }
+            Some(SwapDevice {
+                name: fields[0].replace("\\040", " "),
+                kind: fields[1].to_string(),
+                size_bytes: safe_parse_u64(fields[2]) * 1024,
+                used_bytes: safe_parse_u64(fields[3]) * 1024,
+                priority: safe_parse_i32(fields[4]),
+            })
+        })
+        .collect()
+}
+
+/// Parses one interface line of `/proc/net/dev`
+///
+/// The name is split off at its colon before the statistics are split on
+/// whitespace, since large receive counters can run into the colon with no
+/// space (e.g., "eth0:123456789012 ...").
+///
+/// # Arguments
+///
+/// * `line` - A line of `/proc/net/dev`
+///
+/// # Returns
+///
+/// Returns the interface name and its 16 counters (8 receive, then 8
+/// transmit), or None for header lines and lines with fewer counters
+pub fn parse_net_dev_line(line: &str) -> Option<(&str, Vec<u64>)> {
+    // Counters never contain a colon, so the last one ends the name
+    let (name, stats) = line.rsplit_once(':')?;
+    let name = name.trim();
+    let stats: Vec<u64> = stats.split_whitespace().map(safe_parse_u64).collect();
+    (!name.is_empty() && stats.len() >= 16).then_some((name, stats))
+}
+
+/// Parses the addresses of one interface from `ip addr show <interface>`
+///
+/// # Arguments
+///
+/// * `output` - Standard output of `ip addr show`
+///
+/// # Returns
+///
+/// Returns the first IPv4 address, if any, and every IPv6 address in the
+/// order listed, all without their prefix length
+pub fn parse_interface_addresses(output: &str) -> (Option<String>, Vec<String>) {
+    let mut ipv4 = None;
+    let mut ipv6 = Vec::new();
+
+    for line in output.lines() {
+        let mut fields = line.split_whitespace();
+        let (Some(family), Some(address)) = (fields.next(), fields.next()) else {
+            continue;
+        };
+        let address = address.split('/').next().unwrap_or(address);
+        match family {
+            "inet" if ipv4.is_none() => ipv4 = Some(address.to_string()),
+            "inet6" => ipv6.push(address.to_string()),
+            _ => {}
+        }
+    }
+
+    (ipv4, ipv6)
+}
+
+/// Decides whether a link is up from its sysfs `operstate` and `flags`
+///
+/// Interfaces that do not track their operational state, like loopback and
+/// many tunnels, report "unknown"; for those the administrative IFF_UP flag
+/// decides.
+///
+/// # Arguments
+///
+/// * `operstate` - The contents of `/sys/class/net/<if>/operstate`
+/// * `flags` - The contents of `/sys/class/net/<if>/flags` (e.g., "0x1003"), if readable
+///
+/// # Returns
+///
+/// Returns whether the link is up, or None if the state cannot be determined
+pub fn parse_link_state(operstate: &str, flags: Option<&str>) -> Option<bool> {
+    const IFF_UP: u32 = 0x1;
+
+    match operstate.trim() {
+        "up" => Some(true),
+        "unknown" => flags
+            .and_then(|flags| u32::from_str_radix(flags.trim().trim_start_matches("0x"), 16).ok())
+            .map(|flags| flags & IFF_UP != 0),
+        "" => None,
+        _ => Some(false),
+    }
+}
+
+/// Parses a sysfs link speed in Mbit/s
+///
+/// # Arguments
+///
+/// * `content` - The contents of `/sys/class/net/<if>/speed`
+///
+/// # Returns
+///
+/// Returns the speed, or None for the -1 drivers report without a link
+pub fn parse_link_speed(content: &str) -> Option<u32> {
+    content
+        .trim()
+        .parse::<i64>()
+        .ok()
+        .filter(|speed| *speed > 0)
+        .and_then(|speed| u32::try_from(speed).ok())
+}
+
+/// Returns whether `interface` is the loopback interface ("lo", or "lo0" on macOS and BSDs)
+pub fn is_loopback_interface(interface: &str) -> bool {
+    interface == "lo" || interface == "lo0"
+}
+
+/// Returns whether `address` is an IPv6 link-local address (fe80::/10)
+pub fn is_link_local_ipv6(address: &str) -> bool {
+    address
+        .parse::<Ipv6Addr>()
+        .is_ok_and(|address| address.segments()[0] & 0xffc0 == 0xfe80)
+}
+
+/// Parses the contents of `/proc/loadavg` (e.g., "0.50 0.75 1.00 2/345 6789")
+///
+/// # Arguments
+///
+/// * `content` - The raw contents of `/proc/loadavg`
+///
+/// # Returns
+///
+/// Returns the load averages and runnable/total entity counts
+///
+/// # Errors
+///
+/// Returns an error if the line does not have the expected fields
+pub fn parse_loadavg(content: &str) -> Result<LoadAverage> {
+    let parts: Vec<&str> = content.split_whitespace().collect();
+    if parts.len() < 4 {
+        return Err(MonitorError::ParseError(format!(
+            "Invalid /proc/loadavg format: {}",
+            content.trim()
+        )));
+    }
+
+    let parse_load = |s: &str| {
+        s.parse::<f64>().map_err(|_| {
+            MonitorError::ParseError(format!("Invalid load value in /proc/loadavg: {}", s))
+        })
+    };
+
+    let (runnable, total) = parts[3].split_once('/').ok_or_else(|| {
+        MonitorError::ParseError(format!(
+            "Invalid process counts in /proc/loadavg: {}",
+            parts[3]
+        ))
+    })?;
+
+    Ok(LoadAverage {
+        one: parse_load(parts[0])?,
+        five: parse_load(parts[1])?,
+        fifteen: parse_load(parts[2])?,
+        runnable: safe_parse_u32(runnable),
+        total: safe_parse_u32(total),
+    })
+}
+
+/// Parses the contents of `/proc/uptime` (e.g., "12345.67 45678.90")
+///
+/// # Arguments
+///
+/// * `content` - The raw contents of `/proc/uptime`
+///
+/// # Returns
+///
+/// Returns the seconds since boot
+///
+/// # Errors
+///
+/// Returns an error if the first field is missing or not a number
+pub fn parse_uptime(content: &str) -> Result<f64> {
+    content
+        .split_whitespace()
+        .next()
+        .and_then(|s| s.parse::<f64>().ok())
+        .ok_or_else(|| {
+            MonitorError::ParseError(format!("Invalid /proc/uptime format: {}", content.trim()))
+        })
+}
+
+/// Parses a kernel CPU list (e.g., "0-3,8,10-11") into individual CPU ids
+///
+/// # Arguments
+///
+/// * `list` - The CPU list string as found in sysfs
+///
+/// # Returns
+///
+/// Returns the CPU ids in the order listed; malformed ranges are skipped
+pub fn parse_cpu_list(list: &str) -> Vec<u32> {
+    let mut cpus = Vec::new();
+
+    for range in list.trim().split(',').filter(|r| !r.is_empty()) {
+        match range.split_once('-') {
+            Some((start, end)) => {
+                if let (Ok(start), Ok(end)) = (start.parse::<u32>(), end.parse::<u32>()) {
+                    cpus.extend(start..=end);
+                }
+            }
+            None => {
+                if let Ok(cpu) = range.parse::<u32>() {
+                    cpus.push(cpu);
+                }
+            }
+        }
+    }
+
+    cpus
+}
+
+/// Classifies CPUs as "performance" or "efficiency" from their relative capacity
+///
+/// Used for ARM big.LITTLE systems, which expose `cpu_capacity` per CPU.
+/// CPUs with the highest capacity are performance cores, the rest efficiency.
+///
+/// # Arguments
+///
+/// * `capacities` - The capacity of each CPU, in CPU order
+///
+/// # Returns
+///
+/// Returns one core type per CPU, or an empty vector if all capacities are equal
+pub fn classify_core_capacities(capacities: &[u64]) -> Vec<String> {
+    let Some(&max) = capacities.iter().max() else {
+        return Vec::new();
+    };
+
+    if capacities.iter().all(|&c| c == max) {
+        return Vec::new();
+    }
+
+    capacities
+        .iter()
+        .map(|&c| {
+            if c == max {
+                "performance"
+            } else {
+                "efficiency"
+            }
+            .to_string()
+        })
+        .collect()
+}
+
+/// Parses a sysfs temperature reading in millidegrees Celsius
+///
+/// # Arguments
+///
+/// * `content` - The contents of a `tempN_input` file
+///
+/// # Returns
+///
+/// Returns the temperature in degrees Celsius, or None if unparsable
+pub fn parse_millidegrees(content: &str) -> Option<f32> {
+    content
+        .trim()
+        .parse::<i64>()
+        .ok()
+        .map(|millidegrees| millidegrees as f32 / 1000.0)
+}
+
+/// Picks the CPU package temperature from a list of sensors
+///
+/// Intel reports the package as "Package id N" and AMD as "Tctl"; the first
+/// matching sensor wins.
+///
+/// # Arguments
+///
+/// * `sensors` - All sensor readings
+///
+/// # Returns
+///
+/// Returns the package temperature, or None if no sensor is identifiable
+pub fn package_temperature(sensors: &[TemperatureSensor]) -> Option<f32> {
+    sensors
+        .iter()
+        .find(|sensor| sensor.label.contains("Package") || sensor.label.contains("Tctl"))
+        .map(|sensor| sensor.celsius)
+}
+
+/// Parses the total and free memory of a NUMA node's `meminfo`
+///
+/// Lines look like `/proc/meminfo` with a node prefix, e.g.
+/// "Node 0 MemTotal:       16318680 kB". Missing lines read as 0.
+///
+/// # Arguments
+///
+/// * `content` - The raw contents of `/sys/devices/system/node/nodeN/meminfo`
+///
+/// # Returns
+///
+/// Returns the total and free memory in bytes
+pub fn parse_node_meminfo(content: &str) -> (u64, u64) {
+    let mut total = 0u64;
+    let mut free = 0u64;
+
+    for line in content.lines() {
+        let parts: Vec<&str> = line.split_whitespace().collect();
+        if parts.len() >= 4 && parts[0] == "Node" {
+            let value = safe_parse_u64(parts[3]) * 1024; // Convert KB to bytes
+            match parts[2] {
+                "MemTotal:" => total = value,
+                "MemFree:" => free = value,
+                _ => {}
+            }
+        }
+    }
+
+    (total, free)
+}
+
+/// Parses a sysfs flag such as `queue/rotational`
+///
+/// # Arguments
+///
+/// * `content` - The raw file contents
+///
+/// # Returns
+///
+/// Returns true if the flag is set to 1
+pub fn parse_sysfs_flag(content: &str) -> bool {
+    content.trim() == "1"
+}
+
+/// Parses the flags printed by `vcgencmd get_throttled` on a Raspberry Pi
+///
+/// # Arguments
+///
+/// * `output` - Output such as "throttled=0x50005"
+///
+/// # Returns
+///
+/// Returns the throttling bit field, or None if the output is not recognized
+pub fn parse_vcgencmd_throttled(output: &str) -> Option<u32> {
+    let flags = output.trim().strip_prefix("throttled=")?;
+    let flags = flags.strip_prefix("0x").unwrap_or(flags);
+    u32::from_str_radix(flags, 16).ok()
+}
+
+/// Extracts the IANA zone name from a zoneinfo file path
+///
+/// # Arguments
+///
+/// * `path` - The /etc/localtime symlink target
+///
+/// # Returns
+///
+/// Returns the zone name (e.g., "Europe/Berlin"), or None outside a zoneinfo directory
+pub fn parse_zoneinfo_path(path: &str) -> Option<String> {
+    let (_, zone) = path.rsplit_once("zoneinfo/")?;
+    // Some distributions link into the posix/ or right/ variants of the database
+    let zone = zone
+        .strip_prefix("posix/")
+        .or_else(|| zone.strip_prefix("right/"))
+        .unwrap_or(zone);
+
+    if zone.is_empty() {
+        None
+    } else {
+        Some(zone.to_string())
+    }
+}
+
+/// Filters, sorts and paginates a process list according to a query
+///
+/// # Arguments
+///
+/// * `processes` - The processes to filter and sort
+/// * `query` - Filters, sort key, direction, limit and offset
+///
+/// # Returns
+///
+/// Returns the requested page of matching processes, sorted
+pub fn apply_process_query(
+    mut processes: Vec<ProcessInfo>,
+    query: &ProcessQuery,
+) -> Vec<ProcessInfo> {
+    if let Some(user) = &query.user {
+        processes.retain(|process| &process.user == user);
+    }
+    if let Some(needle) = &query.name_contains {
+        let needle = needle.to_lowercase();
+        processes.retain(|process| process.name.to_lowercase().contains(&needle));
+    }
+
+    processes.sort_by(|a, b| {
+        let ordering = match query.sort_by {
+            ProcessSortKey::Cpu => a.cpu_usage.total_cmp(&b.cpu_usage),
+            ProcessSortKey::Memory => a.memory_usage.cmp(&b.memory_usage),
+            ProcessSortKey::Pid => a.pid.cmp(&b.pid),
+            ProcessSortKey::Name => a.name.cmp(&b.name),
+            ProcessSortKey::Io => process_io_bytes(a).cmp(&process_io_bytes(b)),
+        };
+
+        match query.order {
+            SortOrder::Asc => ordering,
+            SortOrder::Desc => ordering.reverse(),
+        }
+    });
+
+    processes
+        .into_iter()
+        .skip(query.offset)
+        .take(query.limit.unwrap_or(usize::MAX))
+        .collect()
+}
+
+/// Returns the bytes a process has read plus written
+fn process_io_bytes(process: &ProcessInfo) -> u64 {
+    process.read_bytes.saturating_add(process.write_bytes)
+}
+
+/// Ranks processes by a resource and totals it over all of them
+///
+/// # Arguments
+///
+/// * `processes` - Every process, with I/O counters set when ranking by I/O
+/// * `by` - The resource to rank by
+/// * `n` - Number of top processes to keep
+///
+/// # Returns
+///
+/// Returns the `n` highest processes with totals over the top and all processes
+pub fn top_processes(processes: Vec<ProcessInfo>, by: TopMetric, n: usize) -> TopProcesses {
+    let with_io = by == TopMetric::Io;
+    let process_count = processes.len();
+    let total_cpu_usage = processes.iter().map(|process| process.cpu_usage).sum();
+    let total_memory = processes.iter().map(|process| process.memory_usage).sum();
+    let total_io_bytes = with_io.then(|| processes.iter().map(process_io_bytes).sum());
+
+    let query = ProcessQuery {
+        sort_by: by.sort_key(),
+        order: SortOrder::Desc,
+        limit: Some(n),
+        ..Default::default()
+    };
+    let processes = apply_process_query(processes, &query);
+
+    TopProcesses {
+        process_count,
+        total_cpu_usage,
+        total_memory,
+        total_io_bytes,
+        top_cpu_usage: processes.iter().map(|process| process.cpu_usage).sum(),
+        top_memory: processes.iter().map(|process| process.memory_usage).sum(),
+        top_io_bytes: with_io.then(|| processes.iter().map(process_io_bytes).sum()),
+        processes,
+    }
+}
+
+/// Applies the listing options of a query to network interfaces
+///
+/// # Arguments
+///
+/// * `networks` - The interfaces as collected
+/// * `query` - Which interfaces and addresses to keep
+///
+/// # Returns
+///
+/// Returns the interfaces without loopback and link-local IPv6 addresses,
+/// unless the query includes them
+pub fn apply_network_query(
+    mut networks: Vec<NetworkInfo>,
+    query: &NetworkQuery,
+) -> Vec<NetworkInfo> {
+    if !query.include_loopback {
+        networks.retain(|network| !is_loopback_interface(&network.interface));
+    }
+    if !query.include_link_local {
+        for network in &mut networks {
+            network
+                .ipv6_addresses
+                .retain(|address| !is_link_local_ipv6(address));
+        }
+    }
+    networks
+}
+
+/// Computes what changed between two metrics snapshots
+///
+/// Processes are matched by PID; a PID whose process name changed counts as
+/// one process exiting and another starting. Network counters that went
+/// backwards are treated as resets, like in `counter_rate`. Memory deltas
+/// beyond the range of `i64` saturate.
+///
+/// # Arguments
+///
+/// * `before` - The earlier snapshot
+/// * `after` - The later snapshot
+///
+/// # Returns
+///
+/// Returns the deltas from `before` to `after`
+pub fn diff_metrics(before: &SystemMetrics, after: &SystemMetrics) -> MetricsDiff {
+    let elapsed = after.timestamp - before.timestamp;
+    let elapsed_secs = elapsed.num_milliseconds() as f64 / 1000.0;
+    let interval = elapsed.to_std().ok().filter(|interval| !interval.is_zero());
+    // Posted snapshots may hold any u64, so saturate instead of overflowing
+    let signed_delta = |before: u64, after: u64| {
+        let delta = i128::from(after) - i128::from(before);
+        delta.clamp(i64::MIN.into(), i64::MAX.into()) as i64
+    };
+
+    let cpu = before.cpu_info.as_ref().zip(after.cpu_info.as_ref());
+    let memory = before.memory_info.as_ref().zip(after.memory_info.as_ref());
+    let load = before
+        .load_average
+        .as_ref()
+        .zip(after.load_average.as_ref());
+
+    let pids = |processes: &[ProcessInfo]| -> HashMap<u32, String> {
+        processes
+            .iter()
+            .map(|process| (process.pid, process.name.clone()))
+            .collect()
+    };
+    let (before_pids, after_pids) = (pids(&before.processes), pids(&after.processes));
+    let only_in = |processes: &[ProcessInfo], other: &HashMap<u32, String>| {
+        let mut only: Vec<ProcessRef> = processes
+            .iter()
+            .filter(|process| other.get(&process.pid) != Some(&process.name))
+            .map(|process| ProcessRef {
+                pid: process.pid,
+                name: process.name.clone(),
+            })
+            .collect();
+        only.sort_by_key(|process| process.pid);
+        only
+    };
+
+    let networks = after
+        .networks
+        .iter()
+        .filter_map(|curr| {
+            let prev = before
+                .networks
+                .iter()
+                .find(|prev| prev.interface == curr.interface)?;
+            Some(NetworkDelta {
+                interface: curr.interface.clone(),
+                bytes_received: curr.bytes_received.saturating_sub(prev.bytes_received),
+                bytes_transmitted: curr
+                    .bytes_transmitted
+                    .saturating_sub(prev.bytes_transmitted),
+                rx_bytes_per_sec: interval.map(|interval| {
+                    counter_rate(prev.bytes_received, curr.bytes_received, interval)
+                }),
+                tx_bytes_per_sec: interval.map(|interval| {
+                    counter_rate(prev.bytes_transmitted, curr.bytes_transmitted, interval)
+                }),
+            })
+        })
+        .collect();
+
+    MetricsDiff {
+        from: before.timestamp,
+        to: after.timestamp,
+        elapsed_secs,
+        cpu_usage_delta: cpu.map(|(prev, curr)| curr.usage_percent - prev.usage_percent),
+        memory_used_delta: memory.map(|(prev, curr)| signed_delta(prev.used, curr.used)),
+        memory_usage_delta: memory.map(|(prev, curr)| curr.usage_percent - prev.usage_percent),
+        swap_used_delta: memory.map(|(prev, curr)| signed_delta(prev.swap_used, curr.swap_used)),
+        load_one_delta: load.map(|(prev, curr)| curr.one - prev.one),
+        processes_started: only_in(&after.processes, &before_pids),
+        processes_exited: only_in(&before.processes, &after_pids),
+        networks,
+    }
+}
+
+/// Extracts one metric from history samples as timestamp/value points
+///
+/// # Arguments
+///
+/// * `samples` - History samples, oldest first
+/// * `metric` - The metric to extract
+///
+/// # Returns
+///
+/// Returns one point per sample, in the same order
+pub fn history_points(samples: &[HistorySample], metric: HistoryMetric) -> Vec<HistoryPoint> {
+    samples
+        .iter()
+        .map(|sample| HistoryPoint {
+            timestamp: sample.timestamp,
+            value: match metric {
+                HistoryMetric::Cpu => sample.cpu_percent as f64,
+                HistoryMetric::Memory => sample.memory_percent as f64,
+                HistoryMetric::Load => sample.load_one,
+            },
+        })
+        .collect()
+}
+
+/// Computes summary statistics of a series of metric values
+///
+/// Percentiles interpolate linearly between the two nearest ranks, so the
+/// median of `[1, 2, 3, 4]` is 2.5. NaN values are ignored.
+///
+/// # Arguments
+///
+/// * `values` - Metric values in any order
+///
+/// # Returns
+///
+/// Returns None if there are no values to summarize
+pub fn metric_stats(values: &[f64]) -> Option<MetricStats> {
+    let mut sorted: Vec<f64> = values.iter().copied().filter(|v| !v.is_nan()).collect();
+    if sorted.is_empty() {
+        return None;
+    }
+    sorted.sort_by(f64::total_cmp);
+
+    let count = sorted.len() as f64;
+    let mean = sorted.iter().sum::<f64>() / count;
+    let variance = sorted.iter().map(|v| (v - mean).powi(2)).sum::<f64>() / count;
+
+    Some(MetricStats {
+        samples: sorted.len(),
+        min: sorted[0],
+        max: sorted[sorted.len() - 1],
+        mean,
+        p50: percentile(&sorted, 50.0),
+        p95: percentile(&sorted, 95.0),
+        p99: percentile(&sorted, 99.0),
+        stddev: variance.sqrt(),
+    })
+}
+
+/// Returns the `p`th percentile of non-empty sorted values, interpolating
+/// linearly between the closest ranks
+fn percentile(sorted: &[f64], p: f64) -> f64 {
+    let rank = p / 100.0 * (sorted.len() - 1) as f64;
+    let lower = rank.floor() as usize;
+    let upper = rank.ceil() as usize;
+    sorted[lower] + (sorted[upper] - sorted[lower]) * (rank - lower as f64)
+}
+
+/// Builds the process tree rooted at `root_pid` from a flat process list
+///
+/// Parent links come from each process's PPID. PIDs already placed in the
+/// tree are skipped, so a PPID cycle (e.g., from a reparenting race between
+/// `ps` rows) cannot recurse forever.
+///
+/// # Arguments
+///
+/// * `processes` - A snapshot of all processes
+/// * `root_pid` - The PID to root the tree at
+///
+/// # Returns
+///
+/// Returns the tree, or None if `root_pid` is not in the snapshot
+pub fn build_process_tree(processes: Vec<ProcessInfo>, root_pid: u32) -> Option<ProcessNode> {
+    let mut root = None;
+    let mut children: HashMap<u32, Vec<ProcessInfo>> = HashMap::new();
+
+    for process in processes {
+        if process.pid == root_pid {
+            root = Some(process);
+        } else {
+            children.entry(process.ppid).or_default().push(process);
+        }
+    }
+
+    let mut visited = HashSet::new();
+    root.map(|info| attach_children(info, &mut children, &mut visited))
+}
+
+/// Recursively moves the children of `info` out of `children` into a node
+fn attach_children(
+    info: ProcessInfo,
+    children: &mut HashMap<u32, Vec<ProcessInfo>>,
+    visited: &mut HashSet<u32>,
+) -> ProcessNode {
+    visited.insert(info.pid);
+
+    let mut node = ProcessNode {
+        info,
+        children: Vec::new(),
+    };
+
+    for child in children.remove(&node.info.pid).unwrap_or_default() {
+        if !visited.contains(&child.pid) {
+            node.children
+                .push(attach_children(child, children, visited));
+        }
+    }
+
+    node
+}
+
+/// Runs a command, killing it if it does not exit within `timeout`
+///
+/// Like `Command::output`, stdout and stderr are captured and stdin is
+/// closed. A command stuck on a wedged filesystem or NFS mount would
+/// otherwise block the collection forever.
+///
+/// # Arguments
+///
+/// * `command` - The command to run
+/// * `timeout` - How long the command may run
+///
+/// # Returns
+///
+/// Returns the exit status and captured output of the command
+///
+/// # Errors
+///
+/// Returns `MonitorError::CommandFailed` if the command cannot be started or
+/// is killed after `timeout`, with an error of kind `TimedOut` in that case
+pub fn run_command_with_timeout(command: &mut Command, timeout: Duration) -> Result<Output> {
+    let name = command.get_program().to_string_lossy().into_owned();
+    let mut child = command
+        .stdin(Stdio::null())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .map_err(|e| MonitorError::command(&name, e))?;
+
+    // Drain both pipes while waiting, so a chatty child cannot block on a full pipe
+    let stdout = child.stdout.take().map(read_pipe);
+    let stderr = child.stderr.take().map(read_pipe);
+
+    let deadline = Instant::now() + timeout;
+    let status = loop {
+        match child.try_wait() {
+            Ok(Some(status)) => break status,
+            Ok(None) if Instant::now() >= deadline => {
+                let _ = child.kill();
+                // A child in uninterruptible sleep only dies once its I/O
+                // completes, so reap it in the background
+                thread::spawn(move || child.wait());
+                let error = io::Error::new(
+                    io::ErrorKind::TimedOut,
+                    format!("timed out after {:?}", timeout),
+                );
+                return Err(MonitorError::command(&name, error));
+            }
+            Ok(None) => thread::sleep(COMMAND_POLL_INTERVAL),
+            Err(e) => return Err(MonitorError::command(&name, e)),
+        }
+    };
+
+    let collect = |pipe: Option<JoinHandle<Vec<u8>>>| {
+        pipe.and_then(|handle| handle.join().ok())
+            .unwrap_or_default()
+    };
+    Ok(Output {
+        status,
+        stdout: collect(stdout),
+        stderr: collect(stderr),
+    })
+}
+
+/// Reads a child's output pipe to the end on its own thread
+fn read_pipe(mut pipe: impl Read + Send + 'static) -> JoinHandle<Vec<u8>> {
+    thread::spawn(move || {
+        let mut buffer = Vec::new();
+        let _ = pipe.read_to_end(&mut buffer);
+        buffer
+    })
+}
@@ -3,64 +3,196 @@
 //! This module contains Linux-specific implementations for collecting
 //! system information using Linux commands and procfs.
 
-use anyhow::{anyhow, Result};
-use chrono::Utc;
+use chrono::{DateTime, Local, Utc};
+use nix::errno::Errno;
+use nix::sys::signal::{kill, Signal};
+use nix::unistd::Pid;
 use num_cpus;
+use std::collections::HashMap;
 use std::env;
-use std::process::Command;
-
+use std::fs;
+use std::io::{self, Read, Seek, SeekFrom};
+use std::path::{Path, PathBuf};
+use std::process::{Command, Output};
+use std::sync::Mutex;
+use std::time::Duration;
+
+use super::core::DEFAULT_COMMAND_TIMEOUT;
 use super::helpers::*;
+use super::provider::SystemInfoProvider;
+use crate::error::{MonitorError, Result};
 use crate::types::*;
 
+/// Default mount point of procfs
+pub const DEFAULT_PROC_ROOT: &str = "/proc";
+
+/// MAC address reported for the loopback interface
+const LOOPBACK_MAC_ADDRESS: &str = "00:00:00:00:00:00";
+
+/// Default mount point of the cgroup filesystem
+pub const DEFAULT_CGROUP_ROOT: &str = "/sys/fs/cgroup";
+
+/// Size of the sectors block device sizes are counted in, regardless of the hardware
+const SYSFS_SECTOR_SIZE: u64 = 512;
+
+/// Files naming the OS release, in order of preference
+const OS_RELEASE_FILES: &[&str] = &["/etc/os-release", "/usr/lib/os-release"];
+
+/// Syslog files tailed on hosts without the journal, in order of preference
+const SYSLOG_FILES: &[&str] = &["/var/log/syslog", "/var/log/messages"];
+
+/// Bytes read from the end of a syslog file per requested line
+const SYSLOG_BYTES_PER_LINE: u64 = 1024;
+
+/// Bit set in `vcgencmd get_throttled` while the Raspberry Pi is throttled
+const VCGENCMD_CURRENTLY_THROTTLED: u32 = 0x4;
+
 /// Linux-specific system information collector
 ///
 /// This struct provides methods to collect system information from Linux systems
 /// using standard Linux commands and procfs files.
+///
+/// procfs files are read below `proc_root`, so a container can monitor its
+/// host through a bind mount such as `/host/proc`. Data that comes from `ps`,
+/// `df` and `ip` still reflects the collector's own namespaces.
+///
+/// Memory and CPU limits are read from the cgroup mounted at `cgroup_root`,
+/// which inside a container is the container's own cgroup.
+///
+/// External commands are killed after `command_timeout`, so a hung mount
+/// cannot stall collection.
 #[derive(Debug)]
-pub struct LinuxSystemInfo;
+pub struct LinuxSystemInfo {
+    /// Where procfs is mounted
+    proc_root: PathBuf,
+    /// Where the cgroup filesystem is mounted
+    cgroup_root: PathBuf,
+    /// How long an external command may run before it is killed
+    command_timeout: Duration,
+    /// Throttle count seen by the previous CPU read, to tell recent throttling apart
+    last_throttle_count: Mutex<Option<u64>>,
+}
 
 impl LinuxSystemInfo {
     /// Creates a new LinuxSystemInfo instance
+    ///
+    /// Reads procfs below `$PROC_ROOT` if set, otherwise below `/proc`.
     pub fn new() -> Result<Self> {
-        Ok(Self)
+        let proc_root = env::var_os("PROC_ROOT")
+            .filter(|root| !root.is_empty())
+            .map_or_else(|| PathBuf::from(DEFAULT_PROC_ROOT), PathBuf::from);
+        Ok(Self::with_proc_root(proc_root))
     }
 
-    /// Gets system information including hostname, OS details, and uptime
-    pub fn get_system_info(&self) -> Result<SystemInfo> {
-        // Get hostname
-        let hostname = self.get_hostname()?;
+    /// Creates a collector reading procfs below `proc_root`
+    pub fn with_proc_root(proc_root: impl Into<PathBuf>) -> Self {
+        Self {
+            proc_root: proc_root.into(),
+            cgroup_root: PathBuf::from(DEFAULT_CGROUP_ROOT),
+            command_timeout: DEFAULT_COMMAND_TIMEOUT,
+            last_throttle_count: Mutex::new(None),
+        }
+    }
 
-        // Get OS information
-        let (os_name, os_version) = self.get_os_info()?;
+    /// Reads cgroup limits below `cgroup_root` instead of `/sys/fs/cgroup`
+    pub fn with_cgroup_root(mut self, cgroup_root: impl Into<PathBuf>) -> Self {
+        self.cgroup_root = cgroup_root.into();
+        self
+    }
 
-        // Get kernel version
-        let kernel_version = self.get_kernel_version()?;
+    /// Kills external commands such as `ps` and `df` after `timeout`
+    /// instead of the default 5 seconds
+    pub fn with_command_timeout(mut self, timeout: Duration) -> Self {
+        self.command_timeout = timeout;
+        self
+    }
 
-        // Get uptime
-        let uptime = self.get_uptime()?;
-        let boot_time = Utc::now() - chrono::Duration::seconds(uptime as i64);
+    /// Returns where procfs is read from
+    pub fn proc_root(&self) -> &Path {
+        &self.proc_root
+    }
+}
 
-        Ok(SystemInfo {
-            hostname,
+impl SystemInfoProvider for LinuxSystemInfo {
+    /// Gets the host and CPU details that do not change between calls
+    fn get_static_info(&self) -> Result<StaticSystemInfo> {
+        let (os_name, os_version) = self.get_os_info();
+        let cpu_name = self.get_cpu_model();
+        let logical_cores = num_cpus::get() as u32;
+        let (physical_cores, sockets) = fs::read_to_string(self.proc_path("cpuinfo"))
+            .ok()
+            .and_then(|cpuinfo| parse_cpu_topology(&cpuinfo))
+            .unwrap_or((logical_cores, logical_cores));
+
+        Ok(StaticSystemInfo {
+            hostname: self.get_hostname()?,
             os_name,
             os_version,
-            kernel_version,
+            kernel_version: self.get_kernel_version()?,
+            cpu_brand: cpu_name.clone(),
+            cpu_name,
+            logical_cores,
+            physical_cores,
+            sockets,
+            core_types: self.get_core_types(),
+        })
+    }
+
+    /// Checks that `/proc/uptime` can be read and parsed
+    fn probe(&self) -> Result<()> {
+        parse_uptime(&self.read_proc("uptime")?).map(|_| ())
+    }
+
+    /// Gets system information including hostname, OS details, and uptime
+    ///
+    /// The hostname, OS and kernel come from `static_info`.
+    fn get_system_info(&self, static_info: &StaticSystemInfo) -> Result<SystemInfo> {
+        let UptimeInfo {
+            uptime_secs: uptime,
+            uptime_human,
+            boot_time,
+        } = self.get_uptime()?;
+
+        // Get configured timezone
+        let timezone = self.get_timezone();
+        let local_time = Local::now().fixed_offset();
+
+        Ok(SystemInfo {
+            hostname: static_info.hostname.clone(),
+            os_name: static_info.os_name.clone(),
+            os_version: static_info.os_version.clone(),
+            kernel_version: static_info.kernel_version.clone(),
             uptime,
+            uptime_human,
             boot_time,
+            timezone,
+            local_time,
         })
     }
 
-    /// Gets CPU information and usage statistics
-    pub fn get_cpu_info(&self) -> Result<CPUInfo> {
-        // Get CPU cores
-        let cores = num_cpus::get() as u32;
+    /// Gets the uptime from /proc/uptime and the boot time from /proc/stat
+    fn get_uptime(&self) -> Result<UptimeInfo> {
+        let uptime_secs = parse_uptime(&self.read_proc("uptime")?)? as u64;
 
-        // Get CPU model name
-        let name = self.get_cpu_model()?;
-        let brand = name.clone();
+        Ok(UptimeInfo {
+            uptime_secs,
+            uptime_human: format_uptime(uptime_secs),
+            boot_time: self.get_boot_time()?,
+        })
+    }
 
+    /// Gets CPU information and usage statistics
+    ///
+    /// The model, core count and core types come from `static_info`.
+    fn get_cpu_info(&self, static_info: &StaticSystemInfo) -> Result<CPUInfo> {
         // Get CPU frequency
-        let frequency = self.get_cpu_frequency()?;
+        let frequency = self.get_cpu_frequency();
+        let mut per_core_freq_mhz = self.get_per_core_frequencies();
+        if per_core_freq_mhz.is_empty() {
+            per_core_freq_mhz.push(frequency);
+        }
+        let max_frequency = self.get_max_frequency();
+        let (throttled, throttle_count) = self.get_throttle_state();
 
         // Get CPU usage from /proc/stat
         let usage_percent = self.get_cpu_usage()?;
@@ -69,66 +201,128 @@ impl LinuxSystemInfo {
         let temperature = self.get_cpu_temperature();
 
         Ok(CPUInfo {
-            name,
-            brand,
+            name: static_info.cpu_name.clone(),
+            brand: static_info.cpu_brand.clone(),
             frequency,
-            cores,
+            max_frequency,
+            per_core_freq_mhz,
+            core_types: static_info.core_types.clone(),
+            cores: static_info.logical_cores,
+            logical_cores: static_info.logical_cores,
+            physical_cores: static_info.physical_cores,
+            sockets: static_info.sockets,
             usage_percent,
             temperature,
+            quota_cores: self.get_cgroup_cpu_quota(),
+            throttled,
+            throttle_count,
         })
     }
 
-    /// Gets memory information including RAM and swap usage
-    pub fn get_memory_info(&self) -> Result<MemoryInfo> {
-        let meminfo = self.read_proc_meminfo()?;
+    /// Gets the 1/5/15-minute load averages from /proc/loadavg
+    fn get_load_average(&self) -> Result<LoadAverage> {
+        let content = self.read_proc("loadavg")?;
 
-        let mut total = 0u64;
-        let mut free = 0u64;
-        let mut available = 0u64;
-        let mut swap_total = 0u64;
-        let mut swap_free = 0u64;
+        parse_loadavg(&content)
+    }
 
-        for line in meminfo.lines() {
-            let parts: Vec<&str> = line.split_whitespace().collect();
-            if parts.len() >= 2 {
-                let value: u64 = parts[1].parse().unwrap_or(0) * 1024; // Convert KB to bytes
-                match parts[0] {
-                    "MemTotal:" => total = value,
-                    "MemFree:" => free = value,
-                    "MemAvailable:" => available = value,
-                    "SwapTotal:" => swap_total = value,
-                    "SwapFree:" => swap_free = value,
-                    _ => {}
-                }
-            }
-        }
+    /// Gets scheduler and process counters from a single read of /proc/stat
+    fn get_kernel_stats(&self) -> Result<KernelStats> {
+        let content = self.read_proc("stat")?;
 
-        let used = total - available;
-        let swap_used = swap_total - swap_free;
+        parse_kernel_stats(&content)
+    }
 
-        let usage_percent = calculate_percentage(used, total);
-        let swap_usage_percent = calculate_percentage(swap_used, swap_total);
+    /// Gets system-wide file handle usage from /proc/sys/fs/file-nr
+    fn get_fd_usage(&self) -> Result<FdUsage> {
+        let content = self.read_proc("sys/fs/file-nr")?;
 
-        Ok(MemoryInfo {
-            total,
-            used,
-            free,
-            available,
-            swap_total,
-            swap_used,
-            swap_free,
-            usage_percent,
-            swap_usage_percent,
+        parse_file_nr(&content)
+    }
+
+    /// Gets the kernel entropy pool from /proc/sys/kernel/random
+    fn get_entropy(&self) -> Result<EntropyInfo> {
+        let read = |name: &str| -> Result<u64> {
+            let content = self.read_proc(&format!("sys/kernel/random/{}", name))?;
+            content.trim().parse().map_err(|_| {
+                MonitorError::ParseError(format!("Invalid {}: {}", name, content.trim()))
+            })
+        };
+
+        Ok(EntropyInfo {
+            available: read("entropy_avail")?,
+            pool_size: read("poolsize")?,
+        })
+    }
+
+    /// Gets pressure stall information from /proc/pressure
+    ///
+    /// The files are missing without `CONFIG_PSI` and unreadable (EOPNOTSUPP)
+    /// when booted with `psi=0`; either leaves the resource None.
+    fn get_pressure(&self) -> Result<PressureInfo> {
+        let read = |resource: &str| {
+            fs::read_to_string(self.proc_path(format!("pressure/{}", resource)))
+                .ok()
+                .map(|content| parse_pressure(&content))
+                .transpose()
+        };
+
+        Ok(PressureInfo {
+            cpu: read("cpu")?,
+            memory: read("memory")?,
+            io: read("io")?,
         })
     }
 
+    /// Gets memory information including RAM and swap usage
+    fn get_memory_info(&self) -> Result<MemoryInfo> {
+        let meminfo = self.read_proc("meminfo")?;
+        let mut memory = parse_meminfo(&meminfo);
+
+        if let Some((limit, usage)) = self.get_cgroup_memory() {
+            memory.limit_bytes = Some(limit);
+            memory.limit_usage_percent = usage.map(|usage| calculate_percentage(usage, limit));
+        }
+
+        Ok(memory)
+    }
+
+    /// Detects containers from /proc/1/cgroup and /.dockerenv, and
+    /// hypervisors with `systemd-detect-virt`
+    ///
+    /// With a custom proc root only the cgroup markers are checked: the
+    /// others would describe the monitor's own container, not the host.
+    fn get_virtualization(&self) -> Result<VirtualizationInfo> {
+        let cgroup = self.read_proc("1/cgroup").ok();
+        if self.proc_root != Path::new(DEFAULT_PROC_ROOT) {
+            return Ok(detect_virtualization(cgroup.as_deref(), false, None));
+        }
+
+        // Exits non-zero when it prints "none", so only the output matters
+        let detect_virt = self
+            .run(&mut Command::new("systemd-detect-virt"))
+            .ok()
+            .map(|output| String::from_utf8_lossy(&output.stdout).into_owned());
+        Ok(detect_virtualization(
+            cgroup.as_deref(),
+            Path::new("/.dockerenv").exists(),
+            detect_virt.as_deref(),
+        ))
+    }
+
+    /// Gets the active swap devices from /proc/swaps
+    fn get_swaps(&self) -> Result<Vec<SwapDevice>> {
+        let content = self.read_proc("swaps")?;
+        Ok(parse_swaps(&content))
+    }
+
     /// Gets disk information for all mounted filesystems
-    pub fn get_disk_info(&self) -> Result<Vec<DiskInfo>> {
-        let output = Command::new("df")
-            .arg("-h")
-            .arg("--output=source,target,fstype,size,used,avail")
-            .output()
-            .map_err(|e| anyhow!("Failed to execute df command: {}", e))?;
+    fn get_disk_info(&self) -> Result<Vec<DiskInfo>> {
+        let output = self.run(
+            Command::new("df")
+                .arg("-h")
+                .arg("--output=source,target,fstype,size,used,avail"),
+        )?;
 
         let output_str = String::from_utf8_lossy(&output.stdout);
         let mut disks = Vec::new();
@@ -147,6 +341,8 @@ impl LinuxSystemInfo {
                 let free_space = parse_size(parts[5])?;
 
                 let usage_percent = calculate_percentage(used_space, total_space);
+                let (inodes_total, inodes_free) = self.get_inode_counts(&mount_point);
+                let inodes_used = inodes_total.saturating_sub(inodes_free);
 
                 disks.push(DiskInfo {
                     name,
@@ -156,6 +352,10 @@ impl LinuxSystemInfo {
                     used_space,
                     free_space,
                     usage_percent,
+                    inodes_total,
+                    inodes_used,
+                    inodes_free,
+                    inodes_usage_percent: calculate_percentage(inodes_used, inodes_total),
                 });
             }
         }
@@ -163,76 +363,245 @@ impl LinuxSystemInfo {
         Ok(disks)
     }
 
-    /// Gets network interface information and statistics
-    pub fn get_network_info(&self) -> Result<Vec<NetworkInfo>> {
-        let output = Command::new("cat")
-            .arg("/proc/net/dev")
-            .output()
-            .map_err(|e| anyhow!("Failed to read /proc/net/dev: {}", e))?;
+    /// Gets the usage of the root filesystem with a single `statvfs` call
+    // fsblkcnt_t is only 32 bits wide on some targets
+    #[allow(clippy::useless_conversion)]
+    fn get_root_disk_usage(&self) -> Result<Option<f32>> {
+        let stat = nix::sys::statvfs::statvfs("/")
+            .map_err(|e| MonitorError::io("Failed to stat /", io::Error::from(e)))?;
+        let fragment_size = u64::from(stat.fragment_size());
+        let total = u64::from(stat.blocks()) * fragment_size;
+        let free = u64::from(stat.blocks_free()) * fragment_size;
+        Ok(Some(calculate_percentage(
+            total.saturating_sub(free),
+            total,
+        )))
+    }
 
-        let output_str = String::from_utf8_lossy(&output.stdout);
+    /// Gets network interface information and statistics
+    fn get_network_info(&self) -> Result<Vec<NetworkInfo>> {
+        let content = self.read_proc("net/dev")?;
         let mut networks = Vec::new();
 
-        for line in output_str.lines().skip(2) {
-            // Skip header lines
-            let parts: Vec<&str> = line.split_whitespace().collect();
-            if parts.len() >= 17 {
-                let interface = parts[0].trim_end_matches(':').to_string();
+        for (interface, stats) in content.lines().filter_map(parse_net_dev_line) {
+            let (ip_address, ipv6_addresses) = self
+                .get_interface_addresses(interface)
+                .unwrap_or_else(|_| ("N/A".to_string(), Vec::new()));
+            // Loopback has no hardware address
+            let mac_address = if is_loopback_interface(interface) {
+                LOOPBACK_MAC_ADDRESS.to_string()
+            } else {
+                self.get_interface_mac(interface)
+                    .unwrap_or_else(|_| "N/A".to_string())
+            };
+            let operstate = self.read_interface_attribute(interface, "operstate");
+            let flags = self.read_interface_attribute(interface, "flags");
+
+            networks.push(NetworkInfo {
+                interface: interface.to_string(),
+                ip_address,
+                ipv6_addresses,
+                mac_address,
+                bytes_received: stats[0],
+                bytes_transmitted: stats[8],
+                packets_received: stats[1],
+                packets_transmitted: stats[9],
+                errors_received: stats[2],
+                errors_transmitted: stats[10],
+                is_up: operstate
+                    .and_then(|operstate| parse_link_state(&operstate, flags.as_deref())),
+                speed_mbps: self
+                    .read_interface_attribute(interface, "speed")
+                    .and_then(|speed| parse_link_speed(&speed)),
+                mtu: self
+                    .read_interface_attribute(interface, "mtu")
+                    .and_then(|mtu| mtu.trim().parse().ok()),
+            });
+        }
 
-                // Skip loopback interface
-                if interface == "lo" {
-                    continue;
+        Ok(networks)
+    }
+
+    /// Gets cumulative byte counters per interface from /proc/net/dev
+    fn get_network_counters(&self) -> Result<Vec<InterfaceCounters>> {
+        let content = self.read_proc("net/dev")?;
+        let mut counters = Vec::new();
+
+        for (interface, stats) in content.lines().filter_map(parse_net_dev_line) {
+            if is_loopback_interface(interface) {
+                continue;
+            }
+
+            counters.push(InterfaceCounters {
+                interface: interface.to_string(),
+                bytes_received: stats[0],
+                bytes_transmitted: stats[8],
+            });
+        }
+
+        Ok(counters)
+    }
+
+    /// Gets TCP and UDP sockets from /proc/net
+    ///
+    /// Tables that do not exist, e.g. tcp6 with IPv6 disabled, are skipped.
+    fn get_network_connections(&self) -> Result<Vec<Connection>> {
+        let mut connections = Vec::new();
+        for proto in ["tcp", "tcp6", "udp", "udp6"] {
+            let path = self.proc_path(format!("net/{}", proto));
+            match fs::read_to_string(&path) {
+                Ok(content) => connections.extend(parse_proc_net_sockets(&content, proto)),
+                Err(e) if e.kind() == std::io::ErrorKind::NotFound => {}
+                Err(e) => {
+                    return Err(MonitorError::io(
+                        format!("Failed to read {}", path.display()),
+                        e,
+                    ))
                 }
+            }
+        }
+        Ok(connections)
+    }
 
-                let bytes_received = safe_parse_u64(parts[1]);
-                let packets_received = safe_parse_u64(parts[2]);
-                let errors_received = safe_parse_u64(parts[3]);
-                let bytes_transmitted = safe_parse_u64(parts[9]);
-                let packets_transmitted = safe_parse_u64(parts[10]);
-                let errors_transmitted = safe_parse_u64(parts[11]);
-
-                let ip_address = self
-                    .get_interface_ip(&interface)
-                    .unwrap_or_else(|_| "N/A".to_string());
-                let mac_address = self
-                    .get_interface_mac(&interface)
-                    .unwrap_or_else(|_| "N/A".to_string());
-
-                networks.push(NetworkInfo {
-                    interface,
-                    ip_address,
-                    mac_address,
-                    bytes_received,
-                    bytes_transmitted,
-                    packets_received,
-                    packets_transmitted,
-                    errors_received,
-                    errors_transmitted,
-                });
+    /// Counts TCP sockets by state from /proc/net without listing them
+    fn get_socket_summary(&self) -> Result<HashMap<String, u32>> {
+        let mut counts = HashMap::new();
+        for proto in ["tcp", "tcp6"] {
+            let path = self.proc_path(format!("net/{}", proto));
+            match fs::read_to_string(&path) {
+                Ok(content) => tally_tcp_states(&content, &mut counts),
+                Err(e) if e.kind() == std::io::ErrorKind::NotFound => {}
+                Err(e) => {
+                    return Err(MonitorError::io(
+                        format!("Failed to read {}", path.display()),
+                        e,
+                    ))
+                }
             }
         }
+        Ok(counts)
+    }
 
-        Ok(networks)
+    /// Gets disk I/O counters for all active block devices
+    fn get_disk_io(&self) -> Result<Vec<DiskIOStats>> {
+        let content = self.read_proc("diskstats")?;
+
+        Ok(parse_diskstats(&content))
+    }
+
+    /// Gets the NUMA nodes listed in /sys/devices/system/node
+    ///
+    /// Kernels built without NUMA support have no such directory, which
+    /// yields an empty list.
+    fn get_numa_topology(&self) -> Result<Vec<NumaNode>> {
+        let Ok(entries) = fs::read_dir("/sys/devices/system/node") else {
+            return Ok(Vec::new());
+        };
+
+        let mut nodes: Vec<NumaNode> = entries
+            .filter_map(|entry| entry.ok())
+            .filter_map(|entry| {
+                let name = entry.file_name();
+                let id = name.to_str()?.strip_prefix("node")?.parse::<u32>().ok()?;
+                let path = entry.path();
+                let cpus = fs::read_to_string(path.join("cpulist"))
+                    .map(|list| parse_cpu_list(&list))
+                    .unwrap_or_default();
+                let (mem_total, mem_free) = fs::read_to_string(path.join("meminfo"))
+                    .map(|meminfo| parse_node_meminfo(&meminfo))
+                    .unwrap_or_default();
+                Some(NumaNode {
+                    id,
+                    cpus,
+                    mem_total,
+                    mem_free,
+                })
+            })
+            .collect();
+        nodes.sort_by_key(|node| node.id);
+        Ok(nodes)
+    }
+
+    /// Gets the disks listed in /sys/block with their partitions as children
+    fn get_block_devices(&self) -> Result<Vec<BlockDevice>> {
+        let sys_block = Path::new("/sys/block");
+        let entries = fs::read_dir(sys_block)
+            .map_err(|e| MonitorError::io(format!("Failed to read {}", sys_block.display()), e))?;
+
+        let mut disks: Vec<BlockDevice> = entries
+            .filter_map(|entry| entry.ok())
+            .map(|entry| self.read_block_device(&entry.path()))
+            .collect();
+        disks.sort_by(|a, b| a.name.cmp(&b.name));
+        Ok(disks)
+    }
+
+    /// Gets the service units systemd knows about, loaded or not
+    ///
+    /// Hosts without systemd, or without `systemctl` as in many containers,
+    /// get an empty list with `systemd` false.
+    fn get_services(&self) -> Result<ServiceList> {
+        if !self.is_systemd_host() {
+            return Ok(ServiceList::default());
+        }
+
+        let output = match self.run(Command::new("systemctl").args([
+            "list-units",
+            "--type=service",
+            "--all",
+            "--no-pager",
+            "--output=json",
+        ])) {
+            Ok(output) => output,
+            Err(MonitorError::CommandFailed { error, .. })
+                if error.kind() == io::ErrorKind::NotFound =>
+            {
+                return Ok(ServiceList::default())
+            }
+            Err(e) => return Err(e),
+        };
+
+        Ok(ServiceList {
+            systemd: true,
+            services: parse_systemctl_units(&String::from_utf8_lossy(&output.stdout))?,
+        })
+    }
+
+    /// Gets recent entries from the journal, or from the syslog file on hosts
+    /// without systemd or `journalctl`
+    ///
+    /// Syslog lines carry no priority, so `priority` only filters journal entries.
+    fn get_recent_logs(&self, lines: usize, priority: Option<u8>) -> Result<Vec<LogEntry>> {
+        if self.is_systemd_host() {
+            match self.get_journal_entries(lines, priority) {
+                Err(MonitorError::CommandFailed { error, .. })
+                    if error.kind() == io::ErrorKind::NotFound => {}
+                result => return result,
+            }
+        }
+        self.get_syslog_entries(lines)
     }
 
     /// Gets information about all running processes
-    pub fn get_processes(&self) -> Result<Vec<ProcessInfo>> {
-        let output = Command::new("ps")
-            .args(&[
-                "-eo",
-                "pid,ppid,user,pcpu,pmem,vsz,rss,stat,etime,comm,args,pri",
-            ])
-            .output()
-            .map_err(|e| anyhow!("Failed to execute ps command: {}", e))?;
+    ///
+    /// Open file descriptors are only counted when `count_fds` is set.
+    fn get_processes(&self, count_fds: bool) -> Result<Vec<ProcessInfo>> {
+        let output = self.run(Command::new("ps").args([
+            "-eo",
+            "pid,ppid,user,pcpu,pmem,vsz,rss,stat,etime,comm,args,pri",
+        ]))?;
 
         let output_str = String::from_utf8_lossy(&output.stdout);
         let mut processes = Vec::new();
+        let systemd = self.is_systemd_host();
+        let boot_time = self.get_boot_time().ok();
 
         for line in output_str.lines().skip(1) {
             // Skip header
             let parts: Vec<&str> = line.split_whitespace().collect();
             if parts.len() >= 12 {
                 let pid = safe_parse_u32(parts[0]);
+                let ppid = safe_parse_u32(parts[1]);
                 let user = parts[2].to_string();
                 let cpu_usage = safe_parse_f32(parts[3]);
                 let memory_usage_percent = safe_parse_f32(parts[4]);
@@ -241,14 +610,31 @@ impl LinuxSystemInfo {
                 let status = parts[7].to_string();
                 let etime = parts[8].to_string();
                 let name = parts[9].to_string();
-                let command = parts[10..].join(" ");
+                // ps truncates args to its column width; prefer the full command line
+                let command = self
+                    .get_process_cmdline(pid)
+                    .unwrap_or_else(|| parts[10..].join(" "));
                 let priority = safe_parse_i32(parts[11]);
 
-                let start_time = parse_etime(&etime)?;
+                let stat = self.read_process_stat(pid);
+                let times = stat
+                    .as_deref()
+                    .and_then(|stat| Self::process_times(stat, boot_time));
+                let sched = stat.as_deref().and_then(parse_process_sched);
+                let start_time = match times {
+                    Some((_, start_time)) => start_time,
+                    None => parse_etime(&etime)?,
+                };
                 let memory_usage = physical_memory;
+                let systemd_unit = if systemd {
+                    self.get_process_systemd_unit(pid)
+                } else {
+                    None
+                };
 
                 processes.push(ProcessInfo {
                     pid,
+                    ppid,
                     name,
                     command,
                     cpu_usage,
@@ -256,8 +642,23 @@ impl LinuxSystemInfo {
                     memory_usage_percent,
                     status,
                     start_time,
+                    cpu_time_secs: times.map(|(cpu_time, _)| cpu_time),
                     user,
                     priority,
+                    sched_policy: sched.map(|(policy, _)| policy),
+                    rt_priority: sched.map(|(_, rt_priority)| rt_priority),
+                    systemd_unit,
+                    open_fds: if count_fds {
+                        self.get_process_open_fds(pid)
+                    } else {
+                        0
+                    },
+                    threads: self.get_process_threads(pid),
+                    read_bytes: 0,
+                    write_bytes: 0,
+                    read_syscalls: 0,
+                    write_syscalls: 0,
+                    extra: HashMap::new(),
                 });
             }
         }
@@ -266,16 +667,13 @@ impl LinuxSystemInfo {
     }
 
     /// Gets information about a specific process by PID
-    pub fn get_process_by_pid(&self, pid: u32) -> Result<Option<ProcessInfo>> {
-        let output = Command::new("ps")
-            .args(&[
-                "-p",
-                &pid.to_string(),
-                "-o",
-                "pid,ppid,user,pcpu,pmem,vsz,rss,stat,etime,comm,args,pri",
-            ])
-            .output()
-            .map_err(|e| anyhow!("Failed to execute ps command: {}", e))?;
+    fn get_process_by_pid(&self, pid: u32) -> Result<Option<ProcessInfo>> {
+        let output = self.run(Command::new("ps").args([
+            "-p",
+            &pid.to_string(),
+            "-o",
+            "pid,ppid,user,pcpu,pmem,vsz,rss,stat,etime,comm,args,pri",
+        ]))?;
 
         let output_str = String::from_utf8_lossy(&output.stdout);
         let lines: Vec<&str> = output_str.lines().collect();
@@ -289,6 +687,7 @@ impl LinuxSystemInfo {
 
         if parts.len() >= 12 {
             let pid = safe_parse_u32(parts[0]);
+            let ppid = safe_parse_u32(parts[1]);
             let user = parts[2].to_string();
             let cpu_usage = safe_parse_f32(parts[3]);
             let memory_usage_percent = safe_parse_f32(parts[4]);
@@ -297,14 +696,33 @@ impl LinuxSystemInfo {
             let status = parts[7].to_string();
             let etime = parts[8].to_string();
             let name = parts[9].to_string();
-            let command = parts[10..].join(" ");
+            // ps truncates args to its column width; prefer the full command line
+            let command = self
+                .get_process_cmdline(pid)
+                .unwrap_or_else(|| parts[10..].join(" "));
             let priority = safe_parse_i32(parts[11]);
 
-            let start_time = parse_etime(&etime)?;
+            // /proc/<pid>/stat gives the exact start time; the rounded ps
+            // elapsed time is only a fallback if it cannot be read
+            let stat = self.read_process_stat(pid);
+            let times = stat
+                .as_deref()
+                .and_then(|stat| Self::process_times(stat, self.get_boot_time().ok()));
+            let sched = stat.as_deref().and_then(parse_process_sched);
+            let start_time = match times {
+                Some((_, start_time)) => start_time,
+                None => parse_etime(&etime)?,
+            };
             let memory_usage = physical_memory;
+            let systemd_unit = if self.is_systemd_host() {
+                self.get_process_systemd_unit(pid)
+            } else {
+                None
+            };
 
             Ok(Some(ProcessInfo {
                 pid,
+                ppid,
                 name,
                 command,
                 cpu_usage,
@@ -312,122 +730,682 @@ impl LinuxSystemInfo {
                 memory_usage_percent,
                 status,
                 start_time,
+                cpu_time_secs: times.map(|(cpu_time, _)| cpu_time),
                 user,
                 priority,
+                sched_policy: sched.map(|(policy, _)| policy),
+                rt_priority: sched.map(|(_, rt_priority)| rt_priority),
+                systemd_unit,
+                open_fds: self.get_process_open_fds(pid),
+                threads: self.get_process_threads(pid),
+                read_bytes: 0,
+                write_bytes: 0,
+                read_syscalls: 0,
+                write_syscalls: 0,
+                extra: HashMap::new(),
             }))
         } else {
             Ok(None)
         }
     }
 
-    // Helper methods for collecting specific system information
+    /// Counts the numeric entries of /proc, one per process
+    fn get_process_count(&self) -> Result<usize> {
+        let entries = fs::read_dir(&self.proc_root).map_err(|e| {
+            MonitorError::io(format!("Failed to read {}", self.proc_root.display()), e)
+        })?;
+        Ok(entries
+            .filter_map(|entry| entry.ok())
+            .filter(|entry| entry.file_name().to_str().is_some_and(is_pid))
+            .count())
+    }
+
+    /// Reads supplemental fields of a process from /proc/<pid>
+    fn get_process_extra(
+        &self,
+        pid: u32,
+        fields: &ProcessFields,
+    ) -> Result<HashMap<String, String>> {
+        let read =
+            |name: &str| fs::read_to_string(self.proc_path(format!("{}/{}", pid, name))).ok();
+        let stat = if fields.contains(ProcessField::Session) || fields.contains(ProcessField::Tty) {
+            read("stat").and_then(|content| parse_process_session_tty(&content))
+        } else {
+            None
+        };
+
+        let mut extra = HashMap::new();
+        for field in fields.iter() {
+            let value = match field {
+                // A running process has no wait channel
+                ProcessField::Wchan => read("wchan").map(|wchan| match wchan.trim() {
+                    "" | "0" => "-".to_string(),
+                    wchan => wchan.to_string(),
+                }),
+                ProcessField::Session => stat.map(|(session, _)| session.to_string()),
+                ProcessField::Tty => stat.map(|(_, tty_nr)| tty_name(tty_nr)),
+                ProcessField::Cgroup => read("cgroup")
+                    .and_then(|content| parse_cgroup_path(&content).map(str::to_string)),
+            };
+            if let Some(value) = value {
+                extra.insert(field.name().to_string(), value);
+            }
+        }
+        Ok(extra)
+    }
+
+    /// Gets the memory breakdown of a process from /proc/<pid>/status
+    fn get_process_memory(&self, pid: u32) -> Result<Option<ProcessMemory>> {
+        let path = self.proc_path(format!("{}/status", pid));
+        match fs::read_to_string(&path) {
+            Ok(content) => Ok(Some(parse_process_memory(pid, &content))),
+            Err(e) if e.kind() == io::ErrorKind::NotFound => Ok(None),
+            Err(e) => Err(MonitorError::io(
+                format!("Failed to read {}", path.display()),
+                e,
+            )),
+        }
+    }
+
+    /// Gets the resource limits of a process from /proc/<pid>/limits
+    fn get_process_limits(&self, pid: u32) -> Result<Option<ProcessLimits>> {
+        let path = self.proc_path(format!("{}/limits", pid));
+        match fs::read_to_string(&path) {
+            Ok(content) => Ok(Some(parse_process_limits(pid, &content))),
+            Err(e) if e.kind() == io::ErrorKind::NotFound => Ok(None),
+            Err(e) => Err(MonitorError::io(
+                format!("Failed to read {}", path.display()),
+                e,
+            )),
+        }
+    }
+
+    /// Gets the I/O counters of a process from /proc/<pid>/io
+    ///
+    /// Only readable for the monitor's own processes unless it runs as root.
+    fn get_process_io(&self, pid: u32) -> Result<ProcessIO> {
+        let content = self.read_proc(&format!("{}/io", pid))?;
+        Ok(parse_process_io(&content))
+    }
+
+    /// Gets readings from every hwmon temperature sensor
+    ///
+    /// Walks `/sys/class/hwmon/hwmon*/`, pairing each `tempN_input` with its
+    /// `tempN_label` and the chip `name`.
+    fn get_temperatures(&self) -> Vec<TemperatureSensor> {
+        let Ok(entries) = fs::read_dir("/sys/class/hwmon") else {
+            return Vec::new();
+        };
+        let mut hwmons: Vec<_> = entries.flatten().map(|entry| entry.path()).collect();
+        hwmons.sort();
+
+        let mut sensors = Vec::new();
+        for hwmon in hwmons {
+            let chip = fs::read_to_string(hwmon.join("name"))
+                .map(|name| name.trim().to_string())
+                .unwrap_or_else(|_| "unknown".to_string());
+
+            let Ok(files) = fs::read_dir(&hwmon) else {
+                continue;
+            };
+            let mut inputs: Vec<String> = files
+                .flatten()
+                .filter_map(|file| file.file_name().into_string().ok())
+                .filter(|name| name.starts_with("temp") && name.ends_with("_input"))
+                .collect();
+            inputs.sort_by_key(|name| safe_parse_u32(&name[4..name.len() - 6]));
+
+            for input in inputs {
+                let Some(celsius) = fs::read_to_string(hwmon.join(&input))
+                    .ok()
+                    .and_then(|content| parse_millidegrees(&content))
+                else {
+                    continue;
+                };
+                let sensor = input.trim_end_matches("_input");
+                let label = fs::read_to_string(hwmon.join(format!("{}_label", sensor)))
+                    .map(|label| label.trim().to_string())
+                    .unwrap_or_else(|_| sensor.to_string());
+
+                sensors.push(TemperatureSensor {
+                    chip: chip.clone(),
+                    label,
+                    celsius,
+                });
+            }
+        }
+
+        sensors
+    }
+
+    /// Gets the charge state of `/sys/class/power_supply/BAT*`
+    ///
+    /// Multiple batteries are combined by summing their energy (or charge)
+    /// and power (or current); the status is taken from the first battery.
+    fn get_battery_info(&self) -> Result<BatteryInfo> {
+        let Ok(entries) = fs::read_dir("/sys/class/power_supply") else {
+            return Ok(BatteryInfo::default());
+        };
+        let mut batteries: Vec<_> = entries
+            .flatten()
+            .filter(|entry| entry.file_name().to_string_lossy().starts_with("BAT"))
+            .map(|entry| entry.path())
+            .collect();
+        batteries.sort();
+
+        if batteries.is_empty() {
+            return Ok(BatteryInfo::default());
+        }
+
+        let read = |battery: &Path, file: &str| {
+            fs::read_to_string(battery.join(file))
+                .ok()
+                .map(|value| value.trim().to_string())
+        };
+        let read_u64 = |battery: &Path, files: [&str; 2]| {
+            files
+                .iter()
+                .find_map(|file| read(battery, file))
+                .map(|value| safe_parse_u64(&value))
+                .unwrap_or(0)
+        };
+
+        let status = read(&batteries[0], "status");
+        let (mut now, mut full, mut rate) = (0u64, 0u64, 0u64);
+        for battery in &batteries {
+            now += read_u64(battery, ["energy_now", "charge_now"]);
+            full += read_u64(battery, ["energy_full", "charge_full"]);
+            // current_now is signed on some drivers; only its magnitude matters
+            rate += read(battery, "power_now")
+                .or_else(|| read(battery, "current_now"))
+                .and_then(|value| value.parse::<i64>().ok())
+                .map_or(0, i64::unsigned_abs);
+        }
+
+        let percent = if full > 0 {
+            Some(calculate_percentage(now, full).min(100.0))
+        } else {
+            read(&batteries[0], "capacity").map(|value| safe_parse_f32(&value))
+        };
+        let time_remaining_secs = status
+            .as_deref()
+            .and_then(|status| estimate_battery_time(status, now, full, rate));
+
+        Ok(BatteryInfo {
+            present: true,
+            percent,
+            status,
+            time_remaining_secs,
+        })
+    }
+
+    /// Sends a signal to a process
+    ///
+    /// Fails with `NotFound` if no such process exists, `PermissionDenied` if
+    /// the caller may not signal it, and `InvalidArgument` for an unknown
+    /// signal or a PID that `kill(2)` would treat as a process group.
+    ///
+    /// Returns false if the process exited between the lookup and the signal.
+    fn signal_process(&self, pid: u32, signal: i32) -> Result<bool> {
+        // 0 and values that wrap negative would address process groups
+        let raw_pid = match i32::try_from(pid) {
+            Ok(raw_pid) if raw_pid > 0 => raw_pid,
+            _ => return Err(Errno::EINVAL.into()),
+        };
+        let signal = Signal::try_from(signal)?;
+
+        // The namespace kill() acts in, whatever proc_root is monitored
+        if !Path::new(&format!("/proc/{}", pid)).exists() {
+            return Err(Errno::ESRCH.into());
+        }
+
+        match kill(Pid::from_raw(raw_pid), signal) {
+            Ok(()) => Ok(true),
+            Err(Errno::ESRCH) => Ok(false),
+            Err(e) => Err(e.into()),
+        }
+    }
+
+    /// Sets the nice value of a process
+    ///
+    /// Errors are classified like `signal_process`; lowering the nice value
+    /// below its current setting without privileges is `PermissionDenied`.
+    fn set_process_priority(&self, pid: u32, nice: i32) -> Result<()> {
+        if pid == 0 || !(-20..=19).contains(&nice) {
+            return Err(Errno::EINVAL.into());
+        }
+
+        // SAFETY: setpriority only reads its integer arguments
+        let ret = unsafe { libc::setpriority(libc::PRIO_PROCESS, pid as libc::id_t, nice) };
+        if ret == -1 {
+            return Err(Errno::last().into());
+        }
+
+        Ok(())
+    }
+}
+
+// Helper methods for collecting specific system information
+impl LinuxSystemInfo {
+    /// Returns the path of a file below the procfs root
+    fn proc_path(&self, relative: impl AsRef<Path>) -> PathBuf {
+        self.proc_root.join(relative)
+    }
+
+    /// Reads the first of `candidates` that exists below the cgroup root
+    ///
+    /// Lists the cgroup v2 file first, then its v1 equivalents.
+    fn read_cgroup(&self, candidates: &[&str]) -> Option<String> {
+        candidates
+            .iter()
+            .find_map(|relative| fs::read_to_string(self.cgroup_root.join(relative)).ok())
+    }
+
+    /// Gets the cgroup memory limit and current usage, None if unlimited
+    fn get_cgroup_memory(&self) -> Option<(u64, Option<u64>)> {
+        let limit = self
+            .read_cgroup(&["memory.max", "memory/memory.limit_in_bytes"])
+            .and_then(|content| parse_cgroup_memory_limit(&content))?;
+        let usage = self
+            .read_cgroup(&["memory.current", "memory/memory.usage_in_bytes"])
+            .and_then(|content| content.trim().parse().ok());
+        Some((limit, usage))
+    }
+
+    /// Gets the cgroup CPU quota in cores, None if unlimited
+    fn get_cgroup_cpu_quota(&self) -> Option<f64> {
+        if let Some(cpu_max) = self.read_cgroup(&["cpu.max"]) {
+            return parse_cpu_max(&cpu_max);
+        }
+
+        let quota = self.read_cgroup(&["cpu/cpu.cfs_quota_us"])?;
+        let period = self.read_cgroup(&["cpu/cpu.cfs_period_us"])?;
+        parse_cpu_max(&format!("{} {}", quota.trim(), period.trim()))
+    }
+
+    /// Reads a file below the procfs root
+    fn read_proc(&self, relative: &str) -> Result<String> {
+        let path = self.proc_path(relative);
+        fs::read_to_string(&path)
+            .map_err(|e| MonitorError::io(format!("Failed to read {}", path.display()), e))
+    }
+
+    /// Runs an external command, killing it after the command timeout
+    fn run(&self, command: &mut Command) -> Result<Output> {
+        run_command_with_timeout(command, self.command_timeout)
+    }
 
     fn get_hostname(&self) -> Result<String> {
-        Command::new("hostname")
-            .output()
+        self.run(&mut Command::new("hostname"))
             .map(|output| String::from_utf8_lossy(&output.stdout).trim().to_string())
             .or_else(|_| env::var("HOSTNAME"))
-            .map_err(|_| anyhow!("Failed to get hostname"))
-    }
-
-    fn get_os_info(&self) -> Result<(String, String)> {
-        let output = Command::new("cat")
-            .args(&["/etc/os-release"])
-            .output()
-            .map_err(|_| anyhow!("Failed to read /etc/os-release"))?;
-
-        let content = String::from_utf8_lossy(&output.stdout);
-        let mut os_name = env::consts::OS.to_string();
-        let mut os_version = "Unknown".to_string();
-
-        for line in content.lines() {
-            if line.starts_with("NAME=") {
-                os_name = line
-                    .split('=')
-                    .nth(1)
-                    .unwrap_or("Unknown")
-                    .trim_matches('"')
-                    .to_string();
-            } else if line.starts_with("VERSION=") {
-                os_version = line
-                    .split('=')
-                    .nth(1)
-                    .unwrap_or("Unknown")
-                    .trim_matches('"')
-                    .to_string();
-            }
-        }
+            .map_err(|_| MonitorError::Unsupported("Failed to get hostname".to_string()))
+    }
 
-        Ok((os_name, os_version))
+    /// Gets the OS name and version from os-release
+    ///
+    /// Never fails: without a readable os-release file, as in some minimal
+    /// containers, the name is the compile-time OS and the version "Unknown".
+    fn get_os_info(&self) -> (String, String) {
+        let content = OS_RELEASE_FILES
+            .iter()
+            .find_map(|path| fs::read_to_string(path).ok())
+            .unwrap_or_default();
+
+        parse_os_release(&content)
     }
 
     fn get_kernel_version(&self) -> Result<String> {
-        Command::new("uname")
-            .arg("-r")
-            .output()
+        self.run(Command::new("uname").arg("-r"))
             .map(|output| String::from_utf8_lossy(&output.stdout).trim().to_string())
-            .map_err(|_| anyhow!("Failed to get kernel version"))
-    }
-
-    fn get_uptime(&self) -> Result<u64> {
-        Command::new("cat")
-            .arg("/proc/uptime")
-            .output()
-            .map(|output| {
-                String::from_utf8_lossy(&output.stdout)
-                    .split_whitespace()
-                    .next()
-                    .and_then(|s| s.parse::<f64>().ok())
-                    .unwrap_or(0.0) as u64
-            })
-            .map_err(|_| anyhow!("Failed to read /proc/uptime"))
     }
 
-    fn get_cpu_model(&self) -> Result<String> {
-        Command::new("cat")
-            .arg("/proc/cpuinfo")
-            .output()
-            .map(|output| {
-                String::from_utf8_lossy(&output.stdout)
+    /// Gets the configured timezone name
+    ///
+    /// Prefers /etc/timezone (Debian-style), then the zone the /etc/localtime
+    /// symlink points at. Falls back to "UTC" when neither names a zone.
+    fn get_timezone(&self) -> String {
+        if let Ok(content) = fs::read_to_string("/etc/timezone") {
+            let zone = content.trim();
+            if !zone.is_empty() {
+                return zone.to_string();
+            }
+        }
+
+        fs::read_link("/etc/localtime")
+            .ok()
+            .and_then(|target| parse_zoneinfo_path(&target.to_string_lossy()))
+            .unwrap_or_else(|| "UTC".to_string())
+    }
+
+    /// Reads the boot time from the `btime` line of /proc/stat
+    ///
+    /// Unlike now − uptime, it does not drift with the time spent collecting.
+    fn get_boot_time(&self) -> Result<DateTime<Utc>> {
+        let content = self.read_proc("stat")?;
+        parse_stat_value(&content, "btime")
+            .and_then(|btime| DateTime::from_timestamp(btime as i64, 0))
+            .ok_or_else(|| MonitorError::ParseError("btime not found in /proc/stat".to_string()))
+    }
+
+    /// Gets the CPU model from /proc/cpuinfo, "Unknown CPU" if it cannot be read
+    fn get_cpu_model(&self) -> String {
+        self.read_proc("cpuinfo")
+            .ok()
+            .and_then(|content| {
+                content
                     .lines()
                     .find(|line| line.starts_with("model name"))
                     .and_then(|line| line.split(':').nth(1))
                     .map(|s| s.trim().to_string())
-                    .unwrap_or_else(|| "Unknown CPU".to_string())
             })
-            .map_err(|_| anyhow!("Failed to read /proc/cpuinfo"))
+            .unwrap_or_else(|| "Unknown CPU".to_string())
     }
 
-    fn get_cpu_frequency(&self) -> Result<u64> {
-        Command::new("cat")
-            .arg("/proc/cpuinfo")
-            .output()
-            .map(|output| {
-                String::from_utf8_lossy(&output.stdout)
+    /// Gets the first CPU's frequency in MHz from /proc/cpuinfo, 0 if unknown
+    fn get_cpu_frequency(&self) -> u64 {
+        self.read_proc("cpuinfo")
+            .ok()
+            .and_then(|content| {
+                content
                     .lines()
                     .find(|line| line.starts_with("cpu MHz"))
                     .and_then(|line| line.split(':').nth(1))
                     .and_then(|s| s.trim().parse::<f64>().ok())
-                    .unwrap_or(0.0) as u64
             })
-            .map_err(|_| anyhow!("Failed to read CPU frequency"))
+            .unwrap_or(0.0) as u64
+    }
+
+    /// Lists the logical CPU ids present under /sys/devices/system/cpu, in order
+    fn get_cpu_ids(&self) -> Vec<u32> {
+        let mut ids: Vec<u32> = fs::read_dir("/sys/devices/system/cpu")
+            .map(|entries| {
+                entries
+                    .filter_map(|entry| entry.ok())
+                    .filter_map(|entry| {
+                        entry
+                            .file_name()
+                            .to_str()
+                            .and_then(|name| name.strip_prefix("cpu"))
+                            .and_then(|id| id.parse::<u32>().ok())
+                    })
+                    .collect()
+            })
+            .unwrap_or_default();
+        ids.sort_unstable();
+        ids
+    }
+
+    /// Reads each CPU's current frequency from cpufreq, falling back to the
+    /// per-processor "cpu MHz" lines of /proc/cpuinfo
+    fn get_per_core_frequencies(&self) -> Vec<u64> {
+        let from_cpufreq: Vec<u64> = self
+            .get_cpu_ids()
+            .iter()
+            .filter_map(|id| {
+                fs::read_to_string(format!(
+                    "/sys/devices/system/cpu/cpu{}/cpufreq/scaling_cur_freq",
+                    id
+                ))
+                .ok()
+            })
+            .map(|khz| safe_parse_u64(&khz) / 1000)
+            .collect();
+
+        if !from_cpufreq.is_empty() {
+            return from_cpufreq;
+        }
+
+        fs::read_to_string(self.proc_path("cpuinfo"))
+            .map(|content| {
+                content
+                    .lines()
+                    .filter(|line| line.starts_with("cpu MHz"))
+                    .filter_map(|line| line.split(':').nth(1))
+                    .filter_map(|mhz| mhz.trim().parse::<f64>().ok())
+                    .map(|mhz| mhz as u64)
+                    .collect()
+            })
+            .unwrap_or_default()
+    }
+
+    /// Gets the last `lines` journal entries through `journalctl`
+    fn get_journal_entries(&self, lines: usize, priority: Option<u8>) -> Result<Vec<LogEntry>> {
+        let mut command = Command::new("journalctl");
+        command.args(["-n", &lines.to_string(), "-o", "json", "--no-pager"]);
+        if let Some(priority) = priority {
+            command.args(["-p", &priority.to_string()]);
+        }
+        let output = self.run(&mut command)?;
+
+        let entries: Vec<LogEntry> = String::from_utf8_lossy(&output.stdout)
+            .lines()
+            .filter_map(parse_journal_entry)
+            .collect();
+
+        // Users outside the systemd-journal group only see their own entries,
+        // or none at all on hosts without per-user journals
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        if entries.is_empty() && stderr.contains("insufficient permissions") {
+            return Err(MonitorError::PermissionDenied(stderr.trim().to_string()));
+        }
+        Ok(entries)
+    }
+
+    /// Gets the last `lines` lines of the first syslog file that exists
+    ///
+    /// Only the end of the file is read, so a large log costs no more than a small one.
+    fn get_syslog_entries(&self, lines: usize) -> Result<Vec<LogEntry>> {
+        let Some(path) = SYSLOG_FILES
+            .iter()
+            .map(Path::new)
+            .find(|path| path.exists())
+        else {
+            return Err(MonitorError::Unsupported(
+                "Neither the journal nor a syslog file is available".to_string(),
+            ));
+        };
+        let read_error = |e| MonitorError::io(format!("Failed to read {}", path.display()), e);
+
+        let mut file = fs::File::open(path).map_err(read_error)?;
+        let length = file.metadata().map_err(read_error)?.len();
+        let start = length.saturating_sub(lines as u64 * SYSLOG_BYTES_PER_LINE);
+        file.seek(SeekFrom::Start(start)).map_err(read_error)?;
+        let mut buffer = Vec::new();
+        file.read_to_end(&mut buffer).map_err(read_error)?;
+
+        let content = String::from_utf8_lossy(&buffer);
+        let mut tail: Vec<&str> = content.lines().collect();
+        // Reading from the middle of the file starts in the middle of a line
+        if start > 0 && !tail.is_empty() {
+            tail.remove(0);
+        }
+
+        let now = Local::now();
+        Ok(tail[tail.len().saturating_sub(lines)..]
+            .iter()
+            .map(|line| parse_syslog_line(line, now))
+            .collect())
+    }
+
+    /// Reads a disk and its partitions from its /sys/block/<disk> directory
+    fn read_block_device(&self, dir: &Path) -> BlockDevice {
+        let read = |path: &Path, attribute: &str| fs::read_to_string(path.join(attribute)).ok();
+        let size_bytes = |path: &Path| {
+            read(path, "size").map_or(0, |sectors| safe_parse_u64(&sectors) * SYSFS_SECTOR_SIZE)
+        };
+        let read_only = |path: &Path| read(path, "ro").is_some_and(|ro| parse_sysfs_flag(&ro));
+
+        let rotational = read(dir, "queue/rotational").is_some_and(|flag| parse_sysfs_flag(&flag));
+        let removable = read(dir, "removable").is_some_and(|flag| parse_sysfs_flag(&flag));
+
+        // Partitions are the subdirectories with a partition number
+        let mut partitions: Vec<(u64, BlockDevice)> = fs::read_dir(dir)
+            .map(|entries| {
+                entries
+                    .filter_map(|entry| entry.ok())
+                    .filter_map(|entry| {
+                        let path = entry.path();
+                        let number = safe_parse_u64(&read(&path, "partition")?);
+                        Some((
+                            number,
+                            BlockDevice {
+                                name: entry.file_name().to_string_lossy().into_owned(),
+                                size_bytes: size_bytes(&path),
+                                rotational,
+                                removable,
+                                read_only: read_only(&path),
+                                model: None,
+                                children: Vec::new(),
+                            },
+                        ))
+                    })
+                    .collect()
+            })
+            .unwrap_or_default();
+        partitions.sort_by_key(|(number, _)| *number);
+
+        BlockDevice {
+            name: dir
+                .file_name()
+                .map(|name| name.to_string_lossy().into_owned())
+                .unwrap_or_default(),
+            size_bytes: size_bytes(dir),
+            rotational,
+            removable,
+            read_only: read_only(dir),
+            model: read(dir, "device/model")
+                .map(|model| model.trim().to_string())
+                .filter(|model| !model.is_empty()),
+            children: partitions
+                .into_iter()
+                .map(|(_, partition)| partition)
+                .collect(),
+        }
+    }
+
+    /// Gets the highest frequency any CPU may run at, in MHz, from cpufreq
+    ///
+    /// Prefers the policy limit in scaling_max_freq over the hardware limit in
+    /// cpuinfo_max_freq. Returns 0 when cpufreq is not available, as in most VMs.
+    fn get_max_frequency(&self) -> u64 {
+        self.get_cpu_ids()
+            .iter()
+            .filter_map(|id| {
+                let cpufreq = format!("/sys/devices/system/cpu/cpu{}/cpufreq", id);
+                fs::read_to_string(format!("{}/scaling_max_freq", cpufreq))
+                    .or_else(|_| fs::read_to_string(format!("{}/cpuinfo_max_freq", cpufreq)))
+                    .ok()
+            })
+            .map(|khz| safe_parse_u64(&khz) / 1000)
+            .max()
+            .unwrap_or(0)
+    }
+
+    /// Gets whether the CPU was throttled since the previous call, and the
+    /// throttle events counted since boot
+    ///
+    /// Sums the thermal_throttle/core_throttle_count of each CPU. Without those
+    /// counters, a Raspberry Pi is asked through `vcgencmd` whether it is
+    /// throttled right now, with a count of 0.
+    fn get_throttle_state(&self) -> (bool, u64) {
+        let counts: Vec<u64> = self
+            .get_cpu_ids()
+            .iter()
+            .filter_map(|id| {
+                fs::read_to_string(format!(
+                    "/sys/devices/system/cpu/cpu{}/thermal_throttle/core_throttle_count",
+                    id
+                ))
+                .ok()
+            })
+            .map(|count| safe_parse_u64(&count))
+            .collect();
+
+        if counts.is_empty() {
+            // Only the Pi firmware interface answers vcgencmd; don't spawn it elsewhere
+            let throttled = Path::new("/dev/vchiq").exists()
+                && self
+                    .run(Command::new("vcgencmd").arg("get_throttled"))
+                    .ok()
+                    .and_then(|output| {
+                        parse_vcgencmd_throttled(&String::from_utf8_lossy(&output.stdout))
+                    })
+                    .is_some_and(|flags| flags & VCGENCMD_CURRENTLY_THROTTLED != 0);
+            return (throttled, 0);
+        }
+
+        let count = counts.iter().sum();
+        let mut last_count = self
+            .last_throttle_count
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner());
+        let throttled = last_count.is_some_and(|last| count > last);
+        *last_count = Some(count);
+        (throttled, count)
+    }
+
+    /// Derives per-CPU core types on hybrid CPUs
+    ///
+    /// Intel hybrid parts list their P-cores and E-cores under
+    /// /sys/devices/cpu_core and /sys/devices/cpu_atom; ARM big.LITTLE parts
+    /// expose a relative cpu_capacity per CPU. Returns an empty vector on
+    /// homogeneous CPUs.
+    fn get_core_types(&self) -> Vec<String> {
+        let ids = self.get_cpu_ids();
+
+        if let (Ok(p_cores), Ok(e_cores)) = (
+            fs::read_to_string("/sys/devices/cpu_core/cpus"),
+            fs::read_to_string("/sys/devices/cpu_atom/cpus"),
+        ) {
+            let p_cores = parse_cpu_list(&p_cores);
+            let e_cores = parse_cpu_list(&e_cores);
+            return ids
+                .iter()
+                .map(|id| {
+                    if p_cores.contains(id) {
+                        "performance"
+                    } else if e_cores.contains(id) {
+                        "efficiency"
+                    } else {
+                        "unknown"
+                    }
+                    .to_string()
+                })
+                .collect();
+        }
+
+        let capacities: Vec<u64> = ids
+            .iter()
+            .filter_map(|id| {
+                fs::read_to_string(format!("/sys/devices/system/cpu/cpu{}/cpu_capacity", id))
+                    .ok()
+                    .map(|c| safe_parse_u64(&c))
+            })
+            .collect();
+
+        if capacities.len() == ids.len() {
+            classify_core_capacities(&capacities)
+        } else {
+            Vec::new()
+        }
     }
 
     fn get_cpu_usage(&self) -> Result<f32> {
-        let output = Command::new("cat")
-            .arg("/proc/stat")
-            .output()
-            .map_err(|_| anyhow!("Failed to read /proc/stat"))?;
+        let content = self.read_proc("stat")?;
 
-        let content = String::from_utf8_lossy(&output.stdout);
         let cpu_line = content
             .lines()
             .find(|line| line.starts_with("cpu "))
-            .ok_or_else(|| anyhow!("CPU line not found in /proc/stat"))?;
+            .ok_or_else(|| {
+                MonitorError::ParseError("CPU line not found in /proc/stat".to_string())
+            })?;
 
         let parts: Vec<&str> = cpu_line.split_whitespace().collect();
         if parts.len() < 5 {
-            return Err(anyhow!("Invalid CPU line format"));
+            return Err(MonitorError::ParseError(
+                "Invalid CPU line format".to_string(),
+            ));
         }
 
         let user = safe_parse_u64(parts[1]);
@@ -446,20 +1424,22 @@ impl LinuxSystemInfo {
     }
 
     fn get_cpu_temperature(&self) -> Option<f32> {
-        // Try different temperature file locations
+        if let Some(temp) = package_temperature(&self.get_temperatures()) {
+            return Some(temp);
+        }
+
+        // Otherwise fall back to the first readable thermal file
         let temp_files = [
-            "/sys/class/thermal/thermal_zone0/temp",
-            "/proc/acpi/thermal_zone/THM0/temperature",
-            "/sys/class/hwmon/hwmon0/temp1_input",
+            PathBuf::from("/sys/class/thermal/thermal_zone0/temp"),
+            self.proc_path("acpi/thermal_zone/THM0/temperature"),
+            PathBuf::from("/sys/class/hwmon/hwmon0/temp1_input"),
         ];
 
         for temp_file in &temp_files {
-            if let Ok(output) = Command::new("cat").arg(temp_file).output() {
-                if let Ok(temp_str) = String::from_utf8(output.stdout) {
-                    if let Ok(temp) = temp_str.trim().parse::<f32>() {
-                        // Convert from millidegrees to degrees Celsius
-                        return Some(temp / 1000.0);
-                    }
+            if let Ok(temp_str) = fs::read_to_string(temp_file) {
+                if let Ok(temp) = temp_str.trim().parse::<f32>() {
+                    // Convert from millidegrees to degrees Celsius
+                    return Some(temp / 1000.0);
                 }
             }
         }
@@ -467,39 +1447,79 @@ impl LinuxSystemInfo {
         None
     }
 
-    fn read_proc_meminfo(&self) -> Result<String> {
-        Command::new("cat")
-            .arg("/proc/meminfo")
-            .output()
-            .map(|output| String::from_utf8_lossy(&output.stdout).to_string())
-            .map_err(|e| anyhow!("Failed to read /proc/meminfo: {}", e))
+    /// Checks whether the host was booted with systemd (same test as sd_booted(3))
+    fn is_systemd_host(&self) -> bool {
+        Path::new("/run/systemd/system").is_dir()
     }
 
-    fn get_interface_ip(&self, interface: &str) -> Result<String> {
-        let output = Command::new("ip")
-            .args(&["addr", "show", interface])
-            .output()
-            .map_err(|e| anyhow!("Failed to get IP for interface {}: {}", interface, e))?;
+    fn get_process_systemd_unit(&self, pid: u32) -> Option<String> {
+        fs::read_to_string(self.proc_path(format!("{}/cgroup", pid)))
+            .ok()
+            .and_then(|content| parse_systemd_unit(&content))
+    }
 
-        let output_str = String::from_utf8_lossy(&output.stdout);
+    fn get_process_cmdline(&self, pid: u32) -> Option<String> {
+        fs::read(self.proc_path(format!("{}/cmdline", pid)))
+            .ok()
+            .and_then(|content| parse_cmdline(&content))
+    }
 
-        for line in output_str.lines() {
-            if line.contains("inet ") && !line.contains("inet6") {
-                let parts: Vec<&str> = line.split_whitespace().collect();
-                if parts.len() >= 2 {
-                    return Ok(parts[1].split('/').next().unwrap_or("N/A").to_string());
-                }
-            }
-        }
+    /// Returns the total and free inodes of a mounted filesystem, zeros if
+    /// `statvfs` fails
+    ///
+    /// `df -h` would round inode counts to the same units as sizes.
+    // fsfilcnt_t is only 32 bits wide on some targets
+    #[allow(clippy::useless_conversion)]
+    fn get_inode_counts(&self, mount_point: &str) -> (u64, u64) {
+        nix::sys::statvfs::statvfs(mount_point)
+            .map(|stat| (u64::from(stat.files()), u64::from(stat.files_free())))
+            .unwrap_or((0, 0))
+    }
 
-        Ok("N/A".to_string())
+    /// Reads /proc/<pid>/stat, None if the process is gone
+    fn read_process_stat(&self, pid: u32) -> Option<String> {
+        fs::read_to_string(self.proc_path(format!("{}/stat", pid))).ok()
+    }
+
+    /// Gets the CPU time in seconds and exact start time from a process's stat
+    fn process_times(stat: &str, boot_time: Option<DateTime<Utc>>) -> Option<(f64, DateTime<Utc>)> {
+        // SAFETY: sysconf only reads its integer argument
+        let clock_ticks = unsafe { libc::sysconf(libc::_SC_CLK_TCK) };
+        parse_process_stat(stat, boot_time?, u64::try_from(clock_ticks).ok()?)
+    }
+
+    /// Counts the entries of `/proc/<pid>/fd`, 0 if unreadable (e.g. EACCES)
+    fn get_process_open_fds(&self, pid: u32) -> u32 {
+        fs::read_dir(self.proc_path(format!("{}/fd", pid)))
+            .map(|entries| entries.count() as u32)
+            .unwrap_or(0)
+    }
+
+    fn get_process_threads(&self, pid: u32) -> u32 {
+        fs::read_to_string(self.proc_path(format!("{}/status", pid)))
+            .ok()
+            .and_then(|content| parse_status_value(&content, "Threads"))
+            .unwrap_or(0) as u32
+    }
+
+    /// Gets the primary IPv4 address ("N/A" if none) and all IPv6 addresses
+    fn get_interface_addresses(&self, interface: &str) -> Result<(String, Vec<String>)> {
+        let output = self.run(Command::new("ip").args(["addr", "show", interface]))?;
+
+        let (ipv4, ipv6) = parse_interface_addresses(&String::from_utf8_lossy(&output.stdout));
+        Ok((ipv4.unwrap_or_else(|| "N/A".to_string()), ipv6))
+    }
+
+    /// Reads an attribute of an interface from `/sys/class/net/<interface>/`
+    ///
+    /// Returns None if the attribute is missing or, like `speed` on virtual
+    /// interfaces, cannot be read.
+    fn read_interface_attribute(&self, interface: &str, attribute: &str) -> Option<String> {
+        fs::read_to_string(format!("/sys/class/net/{}/{}", interface, attribute)).ok()
     }
 
     fn get_interface_mac(&self, interface: &str) -> Result<String> {
-        let output = Command::new("ip")
-            .args(&["link", "show", interface])
-            .output()
-            .map_err(|e| anyhow!("Failed to get MAC for interface {}: {}", interface, e))?;
+        let output = self.run(Command::new("ip").args(["link", "show", interface]))?;
 
         let output_str = String::from_utf8_lossy(&output.stdout);
 
@@ -3,7 +3,15 @@
 //! Comprehensive system monitoring capabilities for Linux servers.
 
 pub mod core;
+pub mod dir_usage;
+pub mod gpu;
 pub mod helpers;
+#[cfg(target_os = "linux")]
 pub mod linux;
+pub mod provider;
+pub mod smart;
+#[cfg(any(not(target_os = "linux"), feature = "sysinfo-backend"))]
+pub mod sysinfo_collector;
 
 pub use core::SystemMonitor;
+pub use provider::SystemInfoProvider;
@@ -0,0 +1,218 @@
+//! System Information Provider
+//!
+//! The interface `SystemMonitor` collects through. The platform collectors
+//! implement it, and tests can substitute a provider returning canned data.
+
+use std::collections::HashMap;
+use std::fmt::Debug;
+
+use crate::error::{MonitorError, Result};
+use crate::types::*;
+
+/// Source of raw system information
+///
+/// Methods take `&self` so a provider can be shared across the blocking
+/// thread pool; providers that keep state guard it internally. The optional
+/// collectors default to empty lists or `MonitorError::Unsupported`.
+pub trait SystemInfoProvider: Debug + Send + Sync {
+    /// Gets the host and CPU details that do not change between calls
+    fn get_static_info(&self) -> Result<StaticSystemInfo>;
+
+    /// Checks cheaply that the core data source is readable, for health probes
+    ///
+    /// Providers without such a check are always considered healthy.
+    fn probe(&self) -> Result<()> {
+        Ok(())
+    }
+
+    /// Gets system information; the hostname, OS and kernel come from `static_info`
+    fn get_system_info(&self, static_info: &StaticSystemInfo) -> Result<SystemInfo>;
+
+    /// Gets the time since boot
+    fn get_uptime(&self) -> Result<UptimeInfo> {
+        Err(MonitorError::Unsupported(
+            "Uptime is not available".to_string(),
+        ))
+    }
+
+    /// Gets CPU usage and frequency; the model and core counts come from `static_info`
+    fn get_cpu_info(&self, static_info: &StaticSystemInfo) -> Result<CPUInfo>;
+
+    /// Gets the load averages and scheduler entity counts
+    fn get_load_average(&self) -> Result<LoadAverage>;
+
+    /// Gets context switch, interrupt and fork counters
+    fn get_kernel_stats(&self) -> Result<KernelStats> {
+        Err(MonitorError::Unsupported(
+            "Kernel statistics are not available".to_string(),
+        ))
+    }
+
+    /// Gets system-wide file handle usage
+    fn get_fd_usage(&self) -> Result<FdUsage> {
+        Err(MonitorError::Unsupported(
+            "File handle usage is not available".to_string(),
+        ))
+    }
+
+    /// Gets the kernel entropy pool
+    fn get_entropy(&self) -> Result<EntropyInfo> {
+        Err(MonitorError::Unsupported(
+            "Entropy is not available".to_string(),
+        ))
+    }
+
+    /// Gets pressure stall information; resources the kernel lacks are None
+    fn get_pressure(&self) -> Result<PressureInfo> {
+        Ok(PressureInfo::default())
+    }
+
+    /// Gets the virtualization or container environment
+    fn get_virtualization(&self) -> Result<VirtualizationInfo> {
+        Err(MonitorError::Unsupported(
+            "Virtualization detection is not available".to_string(),
+        ))
+    }
+
+    /// Gets RAM and swap usage
+    fn get_memory_info(&self) -> Result<MemoryInfo>;
+
+    /// Gets per-device swap usage
+    fn get_swaps(&self) -> Result<Vec<SwapDevice>> {
+        Ok(Vec::new())
+    }
+
+    /// Gets NUMA nodes with their CPUs and memory, empty if unknown
+    fn get_numa_topology(&self) -> Result<Vec<NumaNode>> {
+        Ok(Vec::new())
+    }
+
+    /// Gets usage of all mounted filesystems
+    fn get_disk_info(&self) -> Result<Vec<DiskInfo>>;
+
+    /// Gets the usage percentage of the filesystem mounted at `/`, if any
+    ///
+    /// The default collects every filesystem.
+    fn get_root_disk_usage(&self) -> Result<Option<f32>> {
+        Ok(self
+            .get_disk_info()?
+            .into_iter()
+            .find(|disk| disk.mount_point == "/")
+            .map(|disk| disk.usage_percent))
+    }
+
+    /// Gets disks with their partitions as children
+    fn get_block_devices(&self) -> Result<Vec<BlockDevice>> {
+        Ok(Vec::new())
+    }
+
+    /// Gets network interfaces and their traffic counters, including loopback
+    fn get_network_info(&self) -> Result<Vec<NetworkInfo>>;
+
+    /// Gets the raw byte counters used to compute network rates
+    fn get_network_counters(&self) -> Result<Vec<InterfaceCounters>> {
+        Ok(Vec::new())
+    }
+
+    /// Gets TCP and UDP sockets
+    fn get_network_connections(&self) -> Result<Vec<Connection>> {
+        Ok(Vec::new())
+    }
+
+    /// Gets the number of TCP sockets in each state
+    fn get_socket_summary(&self) -> Result<HashMap<String, u32>> {
+        Ok(HashMap::new())
+    }
+
+    /// Gets cumulative I/O counters per block device
+    fn get_disk_io(&self) -> Result<Vec<DiskIOStats>> {
+        Ok(Vec::new())
+    }
+
+    /// Gets all running processes, counting open file descriptors if `count_fds`
+    fn get_processes(&self, count_fds: bool) -> Result<Vec<ProcessInfo>>;
+
+    /// Counts running processes
+    ///
+    /// The default collects every process.
+    fn get_process_count(&self) -> Result<usize> {
+        Ok(self.get_processes(false)?.len())
+    }
+
+    /// Gets one process, or `None` if it does not exist
+    fn get_process_by_pid(&self, pid: u32) -> Result<Option<ProcessInfo>>;
+
+    /// Reads supplemental fields of one process, keyed by field name
+    ///
+    /// Fields that cannot be read, e.g. because the process exited, are left
+    /// out.
+    fn get_process_extra(
+        &self,
+        _pid: u32,
+        _fields: &ProcessFields,
+    ) -> Result<HashMap<String, String>> {
+        Err(MonitorError::Unsupported(
+            "Extra process fields are not available".to_string(),
+        ))
+    }
+
+    /// Gets the memory breakdown of one process, or `None` if it does not exist
+    fn get_process_memory(&self, _pid: u32) -> Result<Option<ProcessMemory>> {
+        Err(MonitorError::Unsupported(
+            "Process memory details are not available".to_string(),
+        ))
+    }
+
+    /// Gets the resource limits of one process, or `None` if it does not exist
+    fn get_process_limits(&self, _pid: u32) -> Result<Option<ProcessLimits>> {
+        Err(MonitorError::Unsupported(
+            "Process limits are not available".to_string(),
+        ))
+    }
+
+    /// Gets the cumulative I/O counters of one process
+    fn get_process_io(&self, _pid: u32) -> Result<ProcessIO> {
+        Err(MonitorError::Unsupported(
+            "Process I/O statistics are not available".to_string(),
+        ))
+    }
+
+    /// Gets readings from all temperature sensors
+    fn get_temperatures(&self) -> Vec<TemperatureSensor> {
+        Vec::new()
+    }
+
+    /// Gets the battery charge state
+    fn get_battery_info(&self) -> Result<BatteryInfo> {
+        Err(MonitorError::Unsupported(
+            "Battery information is not available".to_string(),
+        ))
+    }
+
+    /// Gets the init system's service units; `systemd` is false without systemd
+    fn get_services(&self) -> Result<ServiceList> {
+        Ok(ServiceList::default())
+    }
+
+    /// Gets the last `lines` log entries, oldest first, of `priority` or more
+    /// severe if given
+    fn get_recent_logs(&self, _lines: usize, _priority: Option<u8>) -> Result<Vec<LogEntry>> {
+        Err(MonitorError::Unsupported(
+            "System logs are not available".to_string(),
+        ))
+    }
+
+    /// Sends `signal` to a process, returning false if it had already exited
+    fn signal_process(&self, _pid: u32, _signal: i32) -> Result<bool> {
+        Err(MonitorError::Unsupported(
+            "Signaling processes is not supported".to_string(),
+        ))
+    }
+
+    /// Sets the nice value of a process
+    fn set_process_priority(&self, _pid: u32, _nice: i32) -> Result<()> {
+        Err(MonitorError::Unsupported(
+            "Changing process priority is not supported".to_string(),
+        ))
+    }
+}
@@ -0,0 +1,61 @@
+//! SMART Collector
+//!
+//! Reads drive health through `smartctl` from smartmontools, which works the
+//! same on every platform, so it is shared by all system collectors.
+
+use std::io::{self, ErrorKind};
+use std::process::Command;
+use std::time::Duration;
+use tracing::warn;
+
+use super::helpers::{parse_smartctl_json, parse_smartctl_scan, run_command_with_timeout};
+use crate::error::{MonitorError, Result};
+use crate::types::SmartInfo;
+
+/// Gets the SMART health of every drive `smartctl --scan` finds
+///
+/// Each `smartctl` invocation is killed if it runs longer than `timeout`.
+/// Drives that do not support SMART, such as virtual disks, are skipped.
+///
+/// # Errors
+///
+/// Returns `MonitorError::CommandFailed` if `smartctl` is not installed, and
+/// `MonitorError::PermissionDenied` if it may not open a drive, which usually
+/// means the monitor is not running as root
+pub fn get_disk_smart(timeout: Duration) -> Result<Vec<SmartInfo>> {
+    let scan = smartctl(&["--scan", "-j"], timeout)?;
+    let mut drives = Vec::new();
+
+    for (device, device_type) in parse_smartctl_scan(&scan)? {
+        let output = smartctl(&["-H", "-A", "-j", "-d", &device_type, &device], timeout)?;
+        match parse_smartctl_json(&device, &output) {
+            Ok(info) => drives.push(info),
+            Err(e @ MonitorError::PermissionDenied(_)) => return Err(e),
+            Err(e) => warn!("Skipping SMART data of {}: {}", device, e),
+        }
+    }
+
+    Ok(drives)
+}
+
+/// Runs `smartctl` with `args`, returning its JSON output
+///
+/// The exit status is not checked: `smartctl` sets status bits for failing
+/// drives too, and reports errors in the JSON itself.
+fn smartctl(args: &[&str], timeout: Duration) -> Result<String> {
+    let mut command = Command::new("smartctl");
+    command.args(args);
+    match run_command_with_timeout(&mut command, timeout) {
+        Ok(output) => Ok(String::from_utf8_lossy(&output.stdout).into_owned()),
+        Err(MonitorError::CommandFailed { error, .. }) if error.kind() == ErrorKind::NotFound => {
+            Err(MonitorError::command(
+                "smartctl",
+                io::Error::new(
+                    ErrorKind::NotFound,
+                    "smartctl is not installed (install smartmontools)",
+                ),
+            ))
+        }
+        Err(e) => Err(e),
+    }
+}
@@ -0,0 +1,524 @@
+//! Portable System Information Collector
+//!
+//! Collector built on the `sysinfo` crate for platforms without procfs. It is
+//! the backend on non-Linux systems and can be selected on Linux with the
+//! `sysinfo-backend` feature.
+
+use chrono::{DateTime, Local, Utc};
+use std::collections::HashMap;
+use std::fs;
+use std::sync::{Mutex, MutexGuard};
+use sysinfo::{
+    Components, Disks, Networks, Pid, ProcessRefreshKind, ProcessStatus, System, UpdateKind, Users,
+};
+
+use super::helpers::*;
+use super::provider::SystemInfoProvider;
+use crate::error::{MonitorError, Result};
+use crate::types::*;
+
+/// Portable system information collector
+///
+/// Holds the `sysinfo` handles between calls, since CPU usage and network
+/// counters are computed from the difference to the previous refresh.
+#[derive(Debug)]
+pub struct SysinfoCollector {
+    /// CPU, memory and process state
+    system: Mutex<System>,
+    /// Network interface counters
+    networks: Mutex<Networks>,
+}
+
+impl SysinfoCollector {
+    /// Creates a new SysinfoCollector instance
+    pub fn new() -> Result<Self> {
+        let mut system = System::new();
+        // Prime the CPU counters so the first reading has a baseline
+        system.refresh_cpu();
+
+        Ok(Self {
+            system: Mutex::new(system),
+            networks: Mutex::new(Networks::new_with_refreshed_list()),
+        })
+    }
+}
+
+impl SystemInfoProvider for SysinfoCollector {
+    /// Gets the host and CPU details that do not change between calls
+    fn get_static_info(&self) -> Result<StaticSystemInfo> {
+        let system = self.system()?;
+        let cpus = system.cpus();
+        let first = cpus
+            .first()
+            .ok_or_else(|| MonitorError::Unsupported("No CPUs reported".to_string()))?;
+        let logical_cores = cpus.len() as u32;
+
+        Ok(StaticSystemInfo {
+            hostname: System::host_name()
+                .ok_or_else(|| MonitorError::Unsupported("Failed to get hostname".to_string()))?,
+            os_name: System::name().unwrap_or_else(|| "Unknown".to_string()),
+            os_version: System::os_version().unwrap_or_else(|| "Unknown".to_string()),
+            kernel_version: System::kernel_version().unwrap_or_else(|| "Unknown".to_string()),
+            cpu_name: first.brand().to_string(),
+            cpu_brand: first.brand().to_string(),
+            logical_cores,
+            physical_cores: system
+                .physical_core_count()
+                .map_or(logical_cores, |count| count as u32),
+            // sysinfo does not report the socket count
+            sockets: logical_cores,
+            core_types: Vec::new(),
+        })
+    }
+
+    /// Gets system information including hostname, OS details, and uptime
+    ///
+    /// The hostname, OS and kernel come from `static_info`.
+    fn get_system_info(&self, static_info: &StaticSystemInfo) -> Result<SystemInfo> {
+        let UptimeInfo {
+            uptime_secs: uptime,
+            uptime_human,
+            boot_time,
+        } = self.get_uptime()?;
+
+        Ok(SystemInfo {
+            hostname: static_info.hostname.clone(),
+            os_name: static_info.os_name.clone(),
+            os_version: static_info.os_version.clone(),
+            kernel_version: static_info.kernel_version.clone(),
+            uptime,
+            uptime_human,
+            boot_time,
+            timezone: get_timezone(),
+            local_time: Local::now().fixed_offset(),
+        })
+    }
+
+    /// Gets the uptime and boot time as sysinfo reports them
+    fn get_uptime(&self) -> Result<UptimeInfo> {
+        let uptime_secs = System::uptime();
+        let boot_time = DateTime::<Utc>::from_timestamp(System::boot_time() as i64, 0)
+            .unwrap_or_else(|| Utc::now() - chrono::Duration::seconds(uptime_secs as i64));
+
+        Ok(UptimeInfo {
+            uptime_secs,
+            uptime_human: format_uptime(uptime_secs),
+            boot_time,
+        })
+    }
+
+    /// Gets CPU information and usage statistics
+    ///
+    /// The model and core count come from `static_info`.
+    fn get_cpu_info(&self, static_info: &StaticSystemInfo) -> Result<CPUInfo> {
+        let mut system = self.system()?;
+        system.refresh_cpu();
+
+        let cpus = system.cpus();
+        let first = cpus
+            .first()
+            .ok_or_else(|| MonitorError::Unsupported("No CPUs reported".to_string()))?;
+        let per_core_freq_mhz: Vec<u64> = cpus.iter().map(|cpu| cpu.frequency()).collect();
+
+        Ok(CPUInfo {
+            name: static_info.cpu_name.clone(),
+            brand: static_info.cpu_brand.clone(),
+            frequency: first.frequency(),
+            // sysinfo does not report the maximum frequency
+            max_frequency: 0,
+            per_core_freq_mhz,
+            core_types: static_info.core_types.clone(),
+            cores: static_info.logical_cores,
+            logical_cores: static_info.logical_cores,
+            physical_cores: static_info.physical_cores,
+            sockets: static_info.sockets,
+            usage_percent: system.global_cpu_info().cpu_usage(),
+            temperature: get_cpu_temperature(),
+            quota_cores: None,
+            throttled: false,
+            throttle_count: 0,
+        })
+    }
+
+    /// Gets the 1/5/15-minute load averages
+    ///
+    /// Runnable and total task counts come from the process table.
+    fn get_load_average(&self) -> Result<LoadAverage> {
+        let load = System::load_average();
+
+        let mut system = self.system()?;
+        system.refresh_processes();
+        let processes = system.processes();
+        let runnable = processes
+            .values()
+            .filter(|process| process.status() == ProcessStatus::Run)
+            .count();
+
+        Ok(LoadAverage {
+            one: load.one,
+            five: load.five,
+            fifteen: load.fifteen,
+            runnable: runnable as u32,
+            total: processes.len() as u32,
+        })
+    }
+
+    /// Gets per-device swap usage
+    ///
+    /// `sysinfo` only reports the swap total, so this is always empty.
+    fn get_swaps(&self) -> Result<Vec<SwapDevice>> {
+        Ok(Vec::new())
+    }
+
+    /// Gets scheduler and process counters
+    ///
+    /// `sysinfo` does not report them, so this backend always fails.
+    fn get_kernel_stats(&self) -> Result<KernelStats> {
+        Err(MonitorError::Unsupported(
+            "Kernel statistics are not available with the sysinfo backend".to_string(),
+        ))
+    }
+
+    /// Gets the virtualization or container environment
+    ///
+    /// `sysinfo` does not detect it, so this backend always fails.
+    fn get_virtualization(&self) -> Result<VirtualizationInfo> {
+        Err(MonitorError::Unsupported(
+            "Virtualization detection is not available with the sysinfo backend".to_string(),
+        ))
+    }
+
+    /// Gets memory information including RAM and swap usage
+    fn get_memory_info(&self) -> Result<MemoryInfo> {
+        let mut system = self.system()?;
+        system.refresh_memory();
+
+        let total = system.total_memory();
+        let available = system.available_memory();
+        let used = total.saturating_sub(available);
+        let swap_total = system.total_swap();
+        let swap_used = system.used_swap();
+
+        Ok(MemoryInfo {
+            total,
+            used,
+            free: system.free_memory(),
+            available,
+            swap_total,
+            swap_used,
+            swap_free: system.free_swap(),
+            usage_percent: calculate_percentage(used, total),
+            swap_usage_percent: calculate_percentage(swap_used, swap_total),
+            // sysinfo does not break down kernel memory use
+            buffers: 0,
+            cached: 0,
+            slab: 0,
+            shmem: 0,
+            dirty: 0,
+            limit_bytes: None,
+            limit_usage_percent: None,
+        })
+    }
+
+    /// Gets disk information for all mounted filesystems
+    fn get_disk_info(&self) -> Result<Vec<DiskInfo>> {
+        let disks = Disks::new_with_refreshed_list();
+
+        Ok(disks
+            .list()
+            .iter()
+            .map(|disk| {
+                let total_space = disk.total_space();
+                let free_space = disk.available_space();
+                let used_space = total_space.saturating_sub(free_space);
+                let (inodes_total, inodes_free) = inode_counts(disk.mount_point());
+                let inodes_used = inodes_total.saturating_sub(inodes_free);
+
+                DiskInfo {
+                    name: disk.name().to_string_lossy().into_owned(),
+                    mount_point: disk.mount_point().to_string_lossy().into_owned(),
+                    file_system: disk.file_system().to_string_lossy().into_owned(),
+                    total_space,
+                    used_space,
+                    free_space,
+                    usage_percent: calculate_percentage(used_space, total_space),
+                    inodes_total,
+                    inodes_used,
+                    inodes_free,
+                    inodes_usage_percent: calculate_percentage(inodes_used, inodes_total),
+                }
+            })
+            .collect())
+    }
+
+    /// Gets network interface information and statistics
+    ///
+    /// IP addresses are not exposed by `sysinfo`; the IPv4 address is reported
+    /// as "N/A" and the IPv6 list is empty. Link state, speed and MTU are
+    /// not exposed either and are None.
+    fn get_network_info(&self) -> Result<Vec<NetworkInfo>> {
+        let mut networks = self.networks()?;
+        networks.refresh_list();
+
+        Ok(networks
+            .iter()
+            .map(|(interface, data)| NetworkInfo {
+                interface: interface.clone(),
+                ip_address: "N/A".to_string(),
+                ipv6_addresses: Vec::new(),
+                mac_address: data.mac_address().to_string(),
+                bytes_received: data.total_received(),
+                bytes_transmitted: data.total_transmitted(),
+                packets_received: data.total_packets_received(),
+                packets_transmitted: data.total_packets_transmitted(),
+                errors_received: data.total_errors_on_received(),
+                errors_transmitted: data.total_errors_on_transmitted(),
+                is_up: None,
+                speed_mbps: None,
+                mtu: None,
+            })
+            .collect())
+    }
+
+    /// Gets cumulative byte counters per interface
+    fn get_network_counters(&self) -> Result<Vec<InterfaceCounters>> {
+        let mut networks = self.networks()?;
+        networks.refresh_list();
+
+        Ok(networks
+            .iter()
+            .filter(|(interface, _)| !is_loopback_interface(interface))
+            .map(|(interface, data)| InterfaceCounters {
+                interface: interface.clone(),
+                bytes_received: data.total_received(),
+                bytes_transmitted: data.total_transmitted(),
+            })
+            .collect())
+    }
+
+    /// Gets TCP and UDP sockets
+    ///
+    /// `sysinfo` does not list sockets, so this backend reports none.
+    fn get_network_connections(&self) -> Result<Vec<Connection>> {
+        Ok(Vec::new())
+    }
+
+    /// Gets disk I/O counters for all active block devices
+    ///
+    /// `sysinfo` only tracks I/O per process, so this backend reports none.
+    fn get_disk_io(&self) -> Result<Vec<DiskIOStats>> {
+        Ok(Vec::new())
+    }
+
+    /// Gets readings from every temperature sensor the platform exposes
+    ///
+    /// `sysinfo` reports a single label per sensor, so `chip` is left empty.
+    fn get_temperatures(&self) -> Vec<TemperatureSensor> {
+        Components::new_with_refreshed_list()
+            .iter()
+            .map(|component| TemperatureSensor {
+                chip: String::new(),
+                label: component.label().to_string(),
+                celsius: component.temperature(),
+            })
+            .collect()
+    }
+
+    /// Gets battery charge state
+    ///
+    /// `sysinfo` does not report batteries, so none is ever present.
+    fn get_battery_info(&self) -> Result<BatteryInfo> {
+        Ok(BatteryInfo::default())
+    }
+
+    /// Gets information about all running processes
+    ///
+    /// `open_fds` is always 0, since sysinfo does not count descriptors.
+    fn get_processes(&self, _count_fds: bool) -> Result<Vec<ProcessInfo>> {
+        let mut system = self.system()?;
+        system.refresh_memory();
+        system.refresh_processes();
+
+        let users = Users::new_with_refreshed_list();
+        let total_memory = system.total_memory();
+
+        Ok(system
+            .processes()
+            .values()
+            .map(|process| process_info(process, &users, total_memory))
+            .collect())
+    }
+
+    /// Gets information about a specific process by PID, with its full command line
+    fn get_process_by_pid(&self, pid: u32) -> Result<Option<ProcessInfo>> {
+        let mut system = self.system()?;
+        system.refresh_memory();
+
+        let pid = Pid::from_u32(pid);
+        // refresh_process leaves the command line unread and the threads
+        // unlisted; refreshing by PID does both, and drops exited processes
+        let refresh_kind = ProcessRefreshKind::new()
+            .with_memory()
+            .with_cpu()
+            .with_disk_usage()
+            .with_exe(UpdateKind::OnlyIfNotSet)
+            .with_cmd(UpdateKind::OnlyIfNotSet);
+        system.refresh_pids_specifics(&[pid], refresh_kind);
+
+        let users = Users::new_with_refreshed_list();
+        let total_memory = system.total_memory();
+        Ok(system
+            .process(pid)
+            .map(|process| process_info(process, &users, total_memory)))
+    }
+
+    /// Sends a signal to a process
+    ///
+    /// Errors are classified as in the Linux collector, with `NotFound` for
+    /// a process that does not exist.
+    #[cfg(unix)]
+    fn signal_process(&self, pid: u32, signal: i32) -> Result<bool> {
+        use nix::errno::Errno;
+        use nix::sys::signal::{kill, Signal};
+
+        // 0 and values that wrap negative would address process groups
+        let raw_pid = match i32::try_from(pid) {
+            Ok(raw_pid) if raw_pid > 0 => raw_pid,
+            _ => return Err(Errno::EINVAL.into()),
+        };
+        let signal = Signal::try_from(signal)?;
+
+        kill(nix::unistd::Pid::from_raw(raw_pid), signal)?;
+        Ok(true)
+    }
+
+    /// Sends a signal to a process
+    #[cfg(not(unix))]
+    fn signal_process(&self, _pid: u32, _signal: i32) -> Result<bool> {
+        Err(MonitorError::Unsupported(
+            "Signaling processes is not supported on this platform".to_string(),
+        ))
+    }
+
+    /// Sets the nice value of a process
+    #[cfg(unix)]
+    fn set_process_priority(&self, pid: u32, nice: i32) -> Result<()> {
+        use nix::errno::Errno;
+
+        if pid == 0 || !(-20..=19).contains(&nice) {
+            return Err(Errno::EINVAL.into());
+        }
+
+        // SAFETY: setpriority only reads its integer arguments
+        let ret = unsafe { libc::setpriority(libc::PRIO_PROCESS, pid as libc::id_t, nice) };
+        if ret == -1 {
+            return Err(Errno::last().into());
+        }
+
+        Ok(())
+    }
+
+    /// Sets the nice value of a process
+    #[cfg(not(unix))]
+    fn set_process_priority(&self, _pid: u32, _nice: i32) -> Result<()> {
+        Err(MonitorError::Unsupported(
+            "Changing process priority is not supported on this platform".to_string(),
+        ))
+    }
+}
+
+impl SysinfoCollector {
+    fn system(&self) -> Result<MutexGuard<'_, System>> {
+        self.system
+            .lock()
+            .map_err(|_| MonitorError::Panicked("system state lock poisoned".to_string()))
+    }
+
+    fn networks(&self) -> Result<MutexGuard<'_, Networks>> {
+        self.networks
+            .lock()
+            .map_err(|_| MonitorError::Panicked("network state lock poisoned".to_string()))
+    }
+}
+
+/// Converts a `sysinfo` process into the shared ProcessInfo shape
+fn process_info(process: &sysinfo::Process, users: &Users, total_memory: u64) -> ProcessInfo {
+    let user = process
+        .user_id()
+        .map(|uid| {
+            users
+                .get_user_by_id(uid)
+                .map(|user| user.name().to_string())
+                .unwrap_or_else(|| uid.to_string())
+        })
+        .unwrap_or_default();
+
+    ProcessInfo {
+        pid: process.pid().as_u32(),
+        ppid: process.parent().map(|pid| pid.as_u32()).unwrap_or(0),
+        name: process.name().to_string(),
+        command: process.cmd().join(" "),
+        cpu_usage: process.cpu_usage(),
+        memory_usage: process.memory(),
+        memory_usage_percent: calculate_percentage(process.memory(), total_memory),
+        status: process.status().to_string(),
+        start_time: DateTime::<Utc>::from_timestamp(process.start_time() as i64, 0)
+            .unwrap_or_else(Utc::now),
+        // sysinfo only reports the usage percentage
+        cpu_time_secs: None,
+        user,
+        // Scheduling priority and policy are not exposed by sysinfo
+        priority: 0,
+        sched_policy: None,
+        rt_priority: None,
+        systemd_unit: None,
+        // sysinfo does not count file descriptors
+        open_fds: 0,
+        // sysinfo only lists threads on Linux
+        threads: process.tasks().map_or(0, |tasks| tasks.len() as u32),
+        read_bytes: 0,
+        write_bytes: 0,
+        read_syscalls: 0,
+        write_syscalls: 0,
+        extra: HashMap::new(),
+    }
+}
+
+/// Returns the total and free inodes of a mounted filesystem
+///
+/// sysinfo does not report inodes; zeros where `statvfs` is unavailable or fails.
+// fsfilcnt_t is only 32 bits wide on some targets
+#[allow(clippy::useless_conversion)]
+fn inode_counts(mount_point: &std::path::Path) -> (u64, u64) {
+    #[cfg(unix)]
+    {
+        nix::sys::statvfs::statvfs(mount_point)
+            .map(|stat| (u64::from(stat.files()), u64::from(stat.files_free())))
+            .unwrap_or((0, 0))
+    }
+    #[cfg(not(unix))]
+    {
+        let _ = mount_point;
+        (0, 0)
+    }
+}
+
+/// Picks the CPU package sensor, if the platform exposes one
+fn get_cpu_temperature() -> Option<f32> {
+    let components = Components::new_with_refreshed_list();
+    components
+        .iter()
+        .find(|component| {
+            let label = component.label();
+            label.contains("Package") || label.contains("Tctl") || label.contains("CPU")
+        })
+        .map(|component| component.temperature())
+}
+
+/// Reads the configured timezone from the /etc/localtime link, if present
+fn get_timezone() -> String {
+    fs::read_link("/etc/localtime")
+        .ok()
+        .and_then(|target| parse_zoneinfo_path(&target.to_string_lossy()))
+        .unwrap_or_else(|| "UTC".to_string())
+}
@@ -2,43 +2,148 @@
 //!
 //! Constant values used in MCP protocol including method names and error codes.
 
-/// MCP Method Names
-///
-/// Available methods that can be called via MCP protocol.
+// MCP Method Names
+//
+// Available methods that can be called via MCP protocol.
 
 /// Get system information (hostname, OS, kernel, uptime)
 pub const METHOD_GET_SYSTEM_INFO: &str = "getSystemInfo";
 
+/// Get the uptime and boot time alone
+pub const METHOD_GET_UPTIME: &str = "getUptime";
+
+/// Get headline numbers (uptime, CPU, load, memory, root disk, process count)
+pub const METHOD_GET_SUMMARY: &str = "getSummary";
+
 /// Get CPU information (usage, frequency, cores, temperature)
 pub const METHOD_GET_CPU_INFO: &str = "getCPUInfo";
 
+/// Get load average (1/5/15 minute) and runnable process counts
+pub const METHOD_GET_LOAD_AVERAGE: &str = "getLoadAverage";
+
+/// Get context switch, interrupt and fork counters and running/blocked process counts
+pub const METHOD_GET_KERNEL_STATS: &str = "getKernelStats";
+
+/// Get system-wide file handle usage against the kernel limit
+pub const METHOD_GET_FD_USAGE: &str = "getFdUsage";
+
+/// Get the bits of entropy available in the kernel pool
+pub const METHOD_GET_ENTROPY: &str = "getEntropy";
+
+/// Get pressure stall information (PSI) for CPU, memory and I/O
+pub const METHOD_GET_PRESSURE: &str = "getPressure";
+
+/// Get the virtualization or container environment (e.g., "kvm", "docker")
+pub const METHOD_GET_VIRTUALIZATION: &str = "getVirtualization";
+
 /// Get memory information (RAM and swap usage)
 pub const METHOD_GET_MEMORY_INFO: &str = "getMemoryInfo";
 
+/// Get per-device swap usage (partitions, files, zram)
+pub const METHOD_GET_SWAPS: &str = "getSwaps";
+
+/// Get disks and their partitions from /sys/block
+pub const METHOD_GET_BLOCK_DEVICES: &str = "getBlockDevices";
+
+/// Get the size of a directory tree and its largest subdirectories
+pub const METHOD_GET_DIRECTORY_USAGE: &str = "getDirectoryUsage";
+
+/// Get NUMA nodes with their CPUs and memory
+pub const METHOD_GET_NUMA_TOPOLOGY: &str = "getNUMATopology";
+
 /// Get disk information (storage usage, filesystems)
 pub const METHOD_GET_DISK_INFO: &str = "getDiskInfo";
 
 /// Get network information (interfaces, traffic statistics)
 pub const METHOD_GET_NETWORK_INFO: &str = "getNetworkInfo";
 
+/// Get network throughput (bytes/sec) since the previous call
+pub const METHOD_GET_NETWORK_RATES: &str = "getNetworkRates";
+
+/// Get TCP and UDP sockets with their addresses and states
+pub const METHOD_GET_NETWORK_CONNECTIONS: &str = "getNetworkConnections";
+
+/// Get the number of TCP sockets in each state
+pub const METHOD_GET_SOCKET_SUMMARY: &str = "getSocketSummary";
+
+/// Get disk I/O counters (reads, writes, bytes) per block device
+pub const METHOD_GET_DISK_IO: &str = "getDiskIO";
+
+/// Get readings from all temperature sensors
+pub const METHOD_GET_TEMPERATURES: &str = "getTemperatures";
+
+/// Get battery charge state
+pub const METHOD_GET_BATTERY_INFO: &str = "getBatteryInfo";
+
+/// Get NVIDIA GPU utilization, memory and temperature
+pub const METHOD_GET_GPU_INFO: &str = "getGPUInfo";
+
+/// Get SMART health, temperature and wear counters of each drive
+pub const METHOD_GET_DISK_SMART: &str = "getDiskSMART";
+
+/// Get systemd service units and their states
+pub const METHOD_GET_SERVICES: &str = "getServices";
+
+/// Get the most recent journal or syslog entries
+pub const METHOD_GET_RECENT_LOGS: &str = "getRecentLogs";
+
+/// Get recent CPU, memory and load samples from the history buffer
+pub const METHOD_GET_HISTORY: &str = "getHistory";
+
+/// Get min, max, mean, percentiles and deviation of a metric over the history
+pub const METHOD_GET_HISTORY_STATS: &str = "getHistoryStats";
+
+/// Get alerts whose threshold rules are currently firing
+pub const METHOD_GET_ALERTS: &str = "getAlerts";
+
 /// Get all running processes
 pub const METHOD_GET_PROCESSES: &str = "getProcesses";
 
+/// Get the processes using the most CPU, memory or I/O
+pub const METHOD_GET_TOP_PROCESSES: &str = "getTopProcesses";
+
 /// Get specific process by PID
 pub const METHOD_GET_PROCESS_BY_PID: &str = "getProcessByPID";
 
+/// Get a process and all of its descendants as a tree
+pub const METHOD_GET_PROCESS_TREE: &str = "getProcessTree";
+
+/// Get the memory breakdown (VmRSS, RssAnon, VmSwap, ...) of one process
+pub const METHOD_GET_PROCESS_MEMORY: &str = "getProcessMemory";
+
+/// Get the resource limits (open files, processes, ...) of one process
+pub const METHOD_GET_PROCESS_LIMITS: &str = "getProcessLimits";
+
+/// Get the monitor's own CPU time, memory, open files and request counts
+pub const METHOD_GET_SELF: &str = "getSelf";
+
+/// Send a signal to a process
+pub const METHOD_KILL_PROCESS: &str = "killProcess";
+
+/// Change the nice value of a process
+pub const METHOD_RENICE: &str = "renice";
+
 /// Get complete system metrics snapshot
 pub const METHOD_GET_SYSTEM_METRICS: &str = "getSystemMetrics";
 
+/// Compare two system metrics snapshots
+pub const METHOD_DIFF_METRICS: &str = "diffMetrics";
+
 /// Start continuous monitoring
 pub const METHOD_START_MONITORING: &str = "startMonitoring";
 
 /// Stop continuous monitoring
 pub const METHOD_STOP_MONITORING: &str = "stopMonitoring";
 
-/// Error Codes
-///
-/// Error codes used in MCP error responses following JSON-RPC 2.0 specification.
+/// Set the continuous monitoring interval (clamped to the configured floor)
+pub const METHOD_SET_MONITORING_INTERVAL: &str = "setMonitoringInterval";
+
+/// Signal sent by killProcess when none is given (SIGTERM)
+pub const DEFAULT_KILL_SIGNAL: i32 = 15;
+
+// Error Codes
+//
+// Error codes used in MCP error responses following JSON-RPC 2.0 specification.
 
 /// Invalid Request - The JSON sent is not a valid Request object
 pub const ERROR_INVALID_REQUEST: i32 = -32600;
@@ -70,4 +175,5 @@ pub const ERROR_SYSTEM_COMMAND_FAILED: i32 = -32004;
 /// Permission denied - Insufficient permissions to access system information
 pub const ERROR_PERMISSION_DENIED: i32 = -32005;
 
-
+/// Unsupported - The metric is not available on this platform or collector
+pub const ERROR_UNSUPPORTED: i32 = -32006;
@@ -4,8 +4,12 @@
 
 pub mod constants;
 pub mod protocol;
+pub mod query;
 pub mod system;
+pub mod version;
 
 pub use constants::*;
 pub use protocol::*;
+pub use query::*;
 pub use system::*;
+pub use version::*;
@@ -3,7 +3,6 @@
 //! Core JSON-RPC protocol structures for MCP communication.
 
 use serde::{Deserialize, Serialize};
-use serde_json::json;
 
 /// MCP Request structure following JSON-RPC 2.0 specification
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -27,8 +26,6 @@ fn deserialize_id_opt<'de, D>(deserializer: D) -> Result<Option<String>, D::Erro
 where
     D: serde::Deserializer<'de>,
 {
-    use serde::de::Deserializer as _;
-    
     // Use visit_some to get the value or default to None
     #[derive(Deserialize)]
     #[serde(untagged)]
@@ -37,7 +34,7 @@ where
         Number(i64),
         Null,
     }
-    
+
     let opt: Option<IdValue> = Option::deserialize(deserializer)?;
     Ok(opt.map(|v| match v {
         IdValue::String(s) => s,
@@ -53,7 +50,7 @@ where
 {
     match id {
         Some(id_str) => {
-            // Try to parse as number first  
+            // Try to parse as number first
             if let Ok(n) = id_str.parse::<i64>() {
                 n.serialize(serializer)
             } else if id_str == "null" {
@@ -61,8 +58,8 @@ where
             } else {
                 id_str.serialize(serializer)
             }
-        },
-        None => serializer.serialize_none()
+        }
+        None => serializer.serialize_none(),
     }
 }
 
@@ -0,0 +1,515 @@
+//! Query Types
+//!
+//! Parameters that shape collector results (sorting, pagination, history,
+//! filters).
+
+use serde::{Deserialize, Serialize};
+use std::str::FromStr;
+
+use super::system::SystemMetrics;
+
+/// Field to sort processes by
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ProcessSortKey {
+    /// CPU usage percentage
+    #[default]
+    Cpu,
+    /// Resident memory usage
+    Memory,
+    /// Process ID
+    Pid,
+    /// Process name
+    Name,
+    /// Bytes read plus bytes written since the process started
+    Io,
+}
+
+/// Sort direction
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum SortOrder {
+    /// Smallest first
+    Asc,
+    /// Largest first
+    #[default]
+    Desc,
+}
+
+/// Filtering, sorting and pagination options for process listings
+///
+/// Every field is optional; the default sorts by CPU usage, highest first,
+/// and returns all processes. Filters are combined with AND and applied
+/// before sorting and pagination.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ProcessQuery {
+    /// Field to sort by
+    pub sort_by: ProcessSortKey,
+    /// Sort direction
+    pub order: SortOrder,
+    /// Only return processes owned by this user (exact match)
+    pub user: Option<String>,
+    /// Only return processes whose name contains this, case-insensitive
+    pub name_contains: Option<String>,
+    /// Maximum number of processes to return (None for all)
+    pub limit: Option<usize>,
+    /// Number of sorted processes to skip
+    pub offset: usize,
+    /// Read the I/O counters of each returned process from `/proc/<pid>/io`
+    pub include_io: bool,
+    /// Supplemental fields to read into `ProcessInfo::extra` for each
+    /// returned process (none by default)
+    pub fields: ProcessFields,
+}
+
+/// Supplemental process attribute, read from `/proc` on request
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ProcessField {
+    /// Kernel function the process sleeps in, "-" if running
+    Wchan,
+    /// Session ID
+    Session,
+    /// Controlling terminal (e.g., "pts/0"), "?" if none
+    Tty,
+    /// Path of the process in the cgroup hierarchy
+    Cgroup,
+}
+
+impl ProcessField {
+    /// Every supplemental field
+    pub const ALL: [ProcessField; 4] = [
+        ProcessField::Wchan,
+        ProcessField::Session,
+        ProcessField::Tty,
+        ProcessField::Cgroup,
+    ];
+
+    /// Returns the key of the field in `ProcessInfo::extra`
+    pub fn name(self) -> &'static str {
+        match self {
+            ProcessField::Wchan => "wchan",
+            ProcessField::Session => "session",
+            ProcessField::Tty => "tty",
+            ProcessField::Cgroup => "cgroup",
+        }
+    }
+}
+
+impl FromStr for ProcessField {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let name = s.trim().to_lowercase();
+        ProcessField::ALL
+            .into_iter()
+            .find(|field| field.name() == name)
+            .ok_or_else(|| format!("Unknown process field: {}", name))
+    }
+}
+
+/// Supplemental process fields to collect
+///
+/// Deserializes from a comma-separated string (e.g., "wchan,tty") or a list
+/// of field names. The default selects none.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(try_from = "FieldList", into = "Vec<ProcessField>")]
+pub struct ProcessFields(Vec<ProcessField>);
+
+impl ProcessFields {
+    /// Returns whether no field is selected
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    /// Returns whether `field` is selected
+    pub fn contains(&self, field: ProcessField) -> bool {
+        self.0.contains(&field)
+    }
+
+    /// Iterates over the selected fields
+    pub fn iter(&self) -> impl Iterator<Item = ProcessField> + '_ {
+        self.0.iter().copied()
+    }
+}
+
+impl FromIterator<ProcessField> for ProcessFields {
+    fn from_iter<I: IntoIterator<Item = ProcessField>>(fields: I) -> Self {
+        let mut fields: Vec<ProcessField> = fields.into_iter().collect();
+        fields.sort();
+        fields.dedup();
+        ProcessFields(fields)
+    }
+}
+
+impl FromStr for ProcessFields {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        s.split(',')
+            .filter(|name| !name.trim().is_empty())
+            .map(str::parse)
+            .collect()
+    }
+}
+
+impl From<ProcessFields> for Vec<ProcessField> {
+    fn from(fields: ProcessFields) -> Self {
+        fields.0
+    }
+}
+
+/// Wire forms a `ProcessFields` is accepted in
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum FieldList {
+    /// Comma-separated names, as in a query string
+    Names(String),
+    /// JSON list of names
+    List(Vec<ProcessField>),
+}
+
+impl TryFrom<FieldList> for ProcessFields {
+    type Error = String;
+
+    fn try_from(list: FieldList) -> Result<Self, Self::Error> {
+        match list {
+            FieldList::Names(names) => names.parse(),
+            FieldList::List(fields) => Ok(fields.into_iter().collect()),
+        }
+    }
+}
+
+/// Resource a top consumers listing ranks processes by
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum TopMetric {
+    /// CPU usage percentage
+    #[default]
+    Cpu,
+    /// Resident memory usage
+    Memory,
+    /// Bytes read plus bytes written since the process started
+    Io,
+}
+
+impl TopMetric {
+    /// Returns the process sort key ranking by this metric
+    pub fn sort_key(self) -> ProcessSortKey {
+        match self {
+            TopMetric::Cpu => ProcessSortKey::Cpu,
+            TopMetric::Memory => ProcessSortKey::Memory,
+            TopMetric::Io => ProcessSortKey::Io,
+        }
+    }
+}
+
+/// Selection of the processes using the most of a resource
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct TopQuery {
+    /// Resource to rank by
+    pub by: TopMetric,
+    /// Number of processes to return (None for the default)
+    pub n: Option<usize>,
+}
+
+/// Metric tracked in the short-term history
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum HistoryMetric {
+    /// CPU usage percentage
+    #[default]
+    Cpu,
+    /// Memory usage percentage
+    Memory,
+    /// 1-minute load average
+    Load,
+}
+
+/// Selection from the short-term metrics history
+///
+/// Without a metric every field of each sample is returned.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct HistoryQuery {
+    /// Single metric to return as timestamp/value points
+    pub metric: Option<HistoryMetric>,
+    /// Number of most recent samples to return (None for all)
+    pub points: Option<usize>,
+}
+
+/// Selection of history samples to summarize
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct StatsQuery {
+    /// Metric to summarize
+    pub metric: HistoryMetric,
+    /// Number of most recent samples to summarize (None for all)
+    pub window: Option<usize>,
+}
+
+/// Options for network interface listings
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct NetworkQuery {
+    /// Keep the loopback interface
+    pub include_loopback: bool,
+    /// Keep link-local IPv6 addresses (fe80::/10)
+    pub include_link_local: bool,
+}
+
+/// Filter for network connection listings
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ConnectionQuery {
+    /// Only return sockets in this state, case-insensitive (e.g., "LISTEN")
+    pub state: Option<String>,
+}
+
+/// Filter for service listings
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ServiceQuery {
+    /// Only return units in this active or sub state, case-insensitive
+    /// (e.g., "failed", "running")
+    pub state: Option<String>,
+}
+
+/// Options for recent log listings
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct LogQuery {
+    /// Number of most recent entries to return (50 by default, at most 1000)
+    pub lines: Option<usize>,
+    /// Only return entries of this syslog priority or more severe (0-7)
+    pub priority: Option<u8>,
+}
+
+/// Directory to measure and how much of its tree to report
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct DirUsageQuery {
+    /// Directory to measure (required)
+    pub path: Option<String>,
+    /// Levels of subdirectories to report (1 by default)
+    pub depth: Option<u32>,
+    /// Number of largest subdirectories to return (10 by default)
+    pub limit: Option<usize>,
+    /// Descend into directories on other filesystems
+    pub cross_devices: bool,
+}
+
+/// A section of `SystemMetrics` that can be collected on its own
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum MetricSection {
+    /// System information
+    System,
+    /// CPU information
+    Cpu,
+    /// Memory information
+    Memory,
+    /// Load average
+    Load,
+    /// Mounted filesystems
+    Disks,
+    /// Network interfaces
+    Networks,
+    /// Running processes
+    Processes,
+}
+
+impl MetricSection {
+    /// Every section, matching a full `SystemMetrics` snapshot
+    pub const ALL: [MetricSection; 7] = [
+        MetricSection::System,
+        MetricSection::Cpu,
+        MetricSection::Memory,
+        MetricSection::Load,
+        MetricSection::Disks,
+        MetricSection::Networks,
+        MetricSection::Processes,
+    ];
+}
+
+impl FromStr for MetricSection {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_lowercase().as_str() {
+            "system" => Ok(MetricSection::System),
+            "cpu" => Ok(MetricSection::Cpu),
+            "memory" => Ok(MetricSection::Memory),
+            "load" => Ok(MetricSection::Load),
+            "disks" => Ok(MetricSection::Disks),
+            "networks" => Ok(MetricSection::Networks),
+            "processes" => Ok(MetricSection::Processes),
+            other => Err(format!("Unknown metrics component: {}", other)),
+        }
+    }
+}
+
+/// Sections collected by a system metrics snapshot
+///
+/// Deserializes from a comma-separated string (e.g., "cpu,memory,load") or a
+/// list of section names. The default selects every section.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(try_from = "SectionList", into = "Vec<MetricSection>")]
+pub struct MetricsSelector {
+    /// System information
+    pub system: bool,
+    /// CPU information
+    pub cpu: bool,
+    /// Memory information
+    pub memory: bool,
+    /// Load average
+    pub load: bool,
+    /// Mounted filesystems
+    pub disks: bool,
+    /// Network interfaces
+    pub networks: bool,
+    /// Running processes
+    pub processes: bool,
+}
+
+impl MetricsSelector {
+    /// Selects every section
+    pub fn all() -> Self {
+        MetricSection::ALL.into_iter().collect()
+    }
+
+    /// Returns whether `section` is selected
+    pub fn contains(&self, section: MetricSection) -> bool {
+        match section {
+            MetricSection::System => self.system,
+            MetricSection::Cpu => self.cpu,
+            MetricSection::Memory => self.memory,
+            MetricSection::Load => self.load,
+            MetricSection::Disks => self.disks,
+            MetricSection::Networks => self.networks,
+            MetricSection::Processes => self.processes,
+        }
+    }
+}
+
+impl Default for MetricsSelector {
+    fn default() -> Self {
+        Self::all()
+    }
+}
+
+impl FromIterator<MetricSection> for MetricsSelector {
+    fn from_iter<I: IntoIterator<Item = MetricSection>>(sections: I) -> Self {
+        let mut selector = MetricsSelector {
+            system: false,
+            cpu: false,
+            memory: false,
+            load: false,
+            disks: false,
+            networks: false,
+            processes: false,
+        };
+        for section in sections {
+            match section {
+                MetricSection::System => selector.system = true,
+                MetricSection::Cpu => selector.cpu = true,
+                MetricSection::Memory => selector.memory = true,
+                MetricSection::Load => selector.load = true,
+                MetricSection::Disks => selector.disks = true,
+                MetricSection::Networks => selector.networks = true,
+                MetricSection::Processes => selector.processes = true,
+            }
+        }
+        selector
+    }
+}
+
+impl FromStr for MetricsSelector {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        s.split(',')
+            .filter(|name| !name.trim().is_empty())
+            .map(str::parse)
+            .collect()
+    }
+}
+
+impl From<MetricsSelector> for Vec<MetricSection> {
+    fn from(selector: MetricsSelector) -> Self {
+        MetricSection::ALL
+            .into_iter()
+            .filter(|section| selector.contains(*section))
+            .collect()
+    }
+}
+
+/// Wire forms a `MetricsSelector` is accepted in
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum SectionList {
+    /// Comma-separated names, as in a query string
+    Names(String),
+    /// JSON list of names
+    List(Vec<MetricSection>),
+}
+
+impl TryFrom<SectionList> for MetricsSelector {
+    type Error = String;
+
+    fn try_from(list: SectionList) -> Result<Self, Self::Error> {
+        match list {
+            SectionList::Names(names) => names.parse(),
+            SectionList::List(sections) => Ok(sections.into_iter().collect()),
+        }
+    }
+}
+
+/// Selection for system metrics snapshots
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct MetricsQuery {
+    /// Sections to collect (all by default)
+    pub components: MetricsSelector,
+    /// Most processes to include, overriding the configured `max_processes`
+    pub max_processes: Option<usize>,
+}
+
+/// The two snapshots to compare with `diffMetrics`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct DiffQuery {
+    /// The earlier snapshot
+    pub before: SystemMetrics,
+    /// The later snapshot
+    pub after: SystemMetrics,
+}
+
+/// Presentation options for REST responses
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct HumanQuery {
+    /// Add a `<field>_human` string (e.g., "1.5 GiB") next to each byte count
+    pub human: bool,
+}
+
+/// Text format of the `/metrics` endpoint
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ExpositionFormat {
+    /// Prometheus text exposition format 0.0.4
+    #[default]
+    Prometheus,
+    /// OpenMetrics 1.0 text format
+    OpenMetrics,
+}
+
+/// Format selection for the `/metrics` endpoint
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ExpositionQuery {
+    /// Format to render the metrics in
+    pub format: ExpositionFormat,
+}
@@ -2,8 +2,10 @@
 //!
 //! Data structures for system information, metrics, and monitoring data.
 
-use chrono::{DateTime, Utc};
+use chrono::{DateTime, FixedOffset, Utc};
 use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::net::IpAddr;
 
 /// System information including hostname, OS details, and uptime
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -18,8 +20,61 @@ pub struct SystemInfo {
     pub kernel_version: String,
     /// System uptime in seconds
     pub uptime: u64,
+    /// System uptime for display (e.g., "3d 4h 12m")
+    pub uptime_human: String,
     /// System boot time
     pub boot_time: DateTime<Utc>,
+    /// Configured IANA timezone (e.g., "Europe/Berlin"), "UTC" if none is configured
+    pub timezone: String,
+    /// Current local time with the host's UTC offset
+    pub local_time: DateTime<FixedOffset>,
+}
+
+/// Time since boot, without the rest of `SystemInfo`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct UptimeInfo {
+    /// System uptime in seconds
+    pub uptime_secs: u64,
+    /// System uptime for display (e.g., "3d 4h 12m")
+    pub uptime_human: String,
+    /// System boot time
+    pub boot_time: DateTime<Utc>,
+}
+
+/// Headline numbers for status pages
+///
+/// Unlike `SystemMetrics` it has no per-process, per-interface or
+/// per-filesystem lists, so it is cheap to collect and to transfer.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SystemSummary {
+    /// Timestamp of the summary
+    pub timestamp: DateTime<Utc>,
+    /// System hostname
+    pub hostname: String,
+    /// System uptime in seconds
+    pub uptime: u64,
+    /// System uptime for display (e.g., "3d 4h 12m")
+    pub uptime_human: String,
+    /// CPU usage percentage (0.0 - 100.0)
+    pub cpu_usage_percent: f32,
+    /// Load average
+    pub load_average: LoadAverage,
+    /// Used physical memory in bytes
+    pub memory_used: u64,
+    /// Total physical memory in bytes
+    pub memory_total: u64,
+    /// Memory usage percentage (0.0 - 100.0)
+    pub memory_usage_percent: f32,
+    /// Swap usage percentage (0.0 - 100.0)
+    pub swap_usage_percent: f32,
+    /// Usage percentage of the filesystem mounted at `/`, None if the disk
+    /// collector is disabled or nothing is mounted there
+    pub root_disk_usage_percent: Option<f32>,
+    /// Number of processes, 0 if the process collector is disabled
+    pub process_count: usize,
+    /// Share of the system-wide file handle limit in use, None where the
+    /// collector cannot read it
+    pub fd_usage_percent: Option<f32>,
 }
 
 /// CPU information including usage, frequency, and specifications
@@ -31,12 +86,162 @@ pub struct CPUInfo {
     pub brand: String,
     /// CPU frequency in MHz
     pub frequency: u64,
-    /// Number of CPU cores
+    /// Highest frequency the CPUs may run at in MHz, from cpufreq (0 if
+    /// unavailable, and always 0 with the sysinfo collector)
+    pub max_frequency: u64,
+    /// Current frequency of each logical CPU in MHz (a single entry if per-core data is unavailable)
+    pub per_core_freq_mhz: Vec<u64>,
+    /// Core type of each logical CPU ("performance" or "efficiency") on hybrid CPUs, empty otherwise
+    pub core_types: Vec<String>,
+    /// Number of logical CPUs; same as `logical_cores`, kept for compatibility
     pub cores: u32,
+    /// Number of logical CPUs, counting each hyperthread
+    pub logical_cores: u32,
+    /// Number of physical cores across all sockets (`logical_cores` if unknown)
+    pub physical_cores: u32,
+    /// Number of CPU sockets (`logical_cores` if unknown)
+    pub sockets: u32,
     /// CPU usage percentage (0.0 - 100.0)
     pub usage_percent: f32,
     /// CPU temperature in Celsius (if available)
     pub temperature: Option<f32>,
+    /// CPU time the cgroup may use, in cores (e.g., 1.5), None if unlimited
+    pub quota_cores: Option<f64>,
+    /// Whether the CPU was throttled since the previous reading
+    pub throttled: bool,
+    /// Thermal throttle events since boot, summed over logical CPUs (0 if unavailable)
+    pub throttle_count: u64,
+}
+
+/// Host and CPU details that do not change while the monitor runs
+///
+/// Collected once and reused by every system and CPU info request.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct StaticSystemInfo {
+    /// System hostname
+    pub hostname: String,
+    /// Operating system name
+    pub os_name: String,
+    /// Operating system version
+    pub os_version: String,
+    /// Kernel version
+    pub kernel_version: String,
+    /// CPU model name
+    pub cpu_name: String,
+    /// CPU brand information
+    pub cpu_brand: String,
+    /// Number of logical CPUs
+    pub logical_cores: u32,
+    /// Number of physical cores (`logical_cores` if unknown)
+    pub physical_cores: u32,
+    /// Number of CPU sockets (`logical_cores` if unknown)
+    pub sockets: u32,
+    /// Core type of each logical CPU on hybrid CPUs, empty otherwise
+    pub core_types: Vec<String>,
+}
+
+/// Whether the host is bare metal, a virtual machine or a container
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct VirtualizationInfo {
+    /// Hypervisor or container technology (e.g., "kvm", "docker"), "none" on bare metal
+    pub virt_type: String,
+    /// Whether the monitor runs inside a container
+    pub is_container: bool,
+}
+
+/// System load averages and scheduler entity counts from /proc/loadavg
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LoadAverage {
+    /// 1-minute load average
+    pub one: f64,
+    /// 5-minute load average
+    pub five: f64,
+    /// 15-minute load average
+    pub fifteen: f64,
+    /// Number of currently runnable scheduling entities (processes/threads)
+    pub runnable: u32,
+    /// Total number of scheduling entities on the system
+    pub total: u32,
+}
+
+/// Scheduler and process counters from /proc/stat
+///
+/// `context_switches`, `interrupts` and `forks` are cumulative since boot;
+/// diff two samples to get rates. The `procs_*` fields are instantaneous.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct KernelStats {
+    /// Context switches since boot
+    pub context_switches: u64,
+    /// Interrupts serviced since boot
+    pub interrupts: u64,
+    /// Processes and threads created since boot
+    pub forks: u64,
+    /// Processes currently runnable
+    pub procs_running: u64,
+    /// Processes currently blocked waiting for I/O
+    pub procs_blocked: u64,
+}
+
+/// System-wide file handle usage from /proc/sys/fs/file-nr
+///
+/// Once `allocated` reaches `max`, opening any file fails with ENFILE in
+/// every process.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct FdUsage {
+    /// File handles in use
+    pub allocated: u64,
+    /// Most file handles the kernel will allocate (fs.file-max)
+    pub max: u64,
+    /// Share of `max` in use (0.0 - 100.0)
+    pub usage_percent: f32,
+}
+
+/// Kernel entropy pool from /proc/sys/kernel/random
+///
+/// On kernels before 5.18 reads from `/dev/random` block while the pool is
+/// low; since then the pool always reports 256 bits.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct EntropyInfo {
+    /// Bits of entropy available (entropy_avail)
+    pub available: u64,
+    /// Size of the pool in bits (poolsize)
+    pub pool_size: u64,
+}
+
+/// Share of time tasks were stalled on a resource, from a `/proc/pressure` line
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct PressureStall {
+    /// Percentage of the last 10 seconds
+    pub avg10: f64,
+    /// Percentage of the last 60 seconds
+    pub avg60: f64,
+    /// Percentage of the last 300 seconds
+    pub avg300: f64,
+    /// Total stall time since boot in microseconds
+    pub total: u64,
+}
+
+/// Pressure stall information for one resource
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct ResourcePressure {
+    /// Time at least one task was stalled
+    pub some: PressureStall,
+    /// Time all non-idle tasks were stalled at once (None for CPU before Linux 5.13)
+    pub full: Option<PressureStall>,
+}
+
+/// Pressure stall information (PSI) for CPU, memory and I/O
+///
+/// Each resource is None when the kernel does not report it (built without
+/// `CONFIG_PSI`, or booted with `psi=0`).
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+pub struct PressureInfo {
+    /// CPU pressure from `/proc/pressure/cpu`
+    pub cpu: Option<ResourcePressure>,
+    /// Memory pressure from `/proc/pressure/memory`
+    pub memory: Option<ResourcePressure>,
+    /// I/O pressure from `/proc/pressure/io`
+    pub io: Option<ResourcePressure>,
 }
 
 /// Memory information including RAM and swap usage
@@ -60,6 +265,35 @@ pub struct MemoryInfo {
     pub usage_percent: f32,
     /// Swap usage percentage (0.0 - 100.0)
     pub swap_usage_percent: f32,
+    /// Memory used by block device buffers in bytes
+    pub buffers: u64,
+    /// Page cache in bytes, reclaimable except for `shmem`
+    pub cached: u64,
+    /// Kernel slab allocations in bytes
+    pub slab: u64,
+    /// Shared memory and tmpfs in bytes, counted in `cached`
+    pub shmem: u64,
+    /// Memory waiting to be written back to disk in bytes
+    pub dirty: u64,
+    /// Memory limit of the cgroup in bytes, None if unlimited
+    pub limit_bytes: Option<u64>,
+    /// Cgroup memory usage as a percentage of `limit_bytes`
+    pub limit_usage_percent: Option<f32>,
+}
+
+/// A swap partition, file or zram device from `/proc/swaps`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SwapDevice {
+    /// Device or file path (e.g., "/dev/zram0", "/swapfile")
+    pub name: String,
+    /// "partition" or "file"
+    pub kind: String,
+    /// Size in bytes
+    pub size_bytes: u64,
+    /// Used space in bytes
+    pub used_bytes: u64,
+    /// Priority; higher-priority devices are used first
+    pub priority: i32,
 }
 
 /// Disk information including storage usage and file system details
@@ -79,6 +313,77 @@ pub struct DiskInfo {
     pub free_space: u64,
     /// Disk usage percentage (0.0 - 100.0)
     pub usage_percent: f32,
+    /// Total inodes; 0 for filesystems without a fixed inode table
+    pub inodes_total: u64,
+    /// Used inodes
+    pub inodes_used: u64,
+    /// Free inodes
+    pub inodes_free: u64,
+    /// Inode usage percentage (0.0 - 100.0)
+    pub inodes_usage_percent: f32,
+}
+
+/// A disk or partition from `/sys/block`
+///
+/// Disks are listed at the top level with their partitions as `children`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BlockDevice {
+    /// Kernel device name (e.g., "nvme0n1", "sda1")
+    pub name: String,
+    /// Size in bytes
+    pub size_bytes: u64,
+    /// Whether the device is a spinning disk; false for SSDs and NVMe drives
+    pub rotational: bool,
+    /// Whether the medium is removable (e.g., card readers, optical drives)
+    pub removable: bool,
+    /// Whether the device is read-only
+    pub read_only: bool,
+    /// Model reported by the drive; None for partitions and virtual devices
+    pub model: Option<String>,
+    /// Partitions of a disk, ordered by partition number; empty for partitions
+    pub children: Vec<BlockDevice>,
+}
+
+/// Space used below a directory, with its largest subdirectories
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DirectoryUsage {
+    /// Directory that was measured
+    pub path: String,
+    /// Total size of the files below the directory in bytes
+    pub size_bytes: u64,
+    /// Number of files and directories visited
+    pub files_scanned: u64,
+    /// Whether the walk stopped at the file limit, leaving sizes too small
+    pub truncated: bool,
+    /// Largest subdirectories, largest first
+    pub entries: Vec<DirectoryEntry>,
+}
+
+/// A subdirectory in a directory usage report
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DirectoryEntry {
+    /// Path of the subdirectory
+    pub path: String,
+    /// Levels below the measured directory (1 for its children)
+    pub depth: u32,
+    /// Total size of the files below the subdirectory in bytes
+    pub size_bytes: u64,
+    /// Whether the subdirectory or part of it could not be read, so the size
+    /// is a lower bound
+    pub inaccessible: bool,
+}
+
+/// A NUMA node: a set of CPUs and the memory attached to them
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct NumaNode {
+    /// Node number (e.g., 0 for "node0")
+    pub id: u32,
+    /// Logical CPU ids belonging to the node
+    pub cpus: Vec<u32>,
+    /// Memory attached to the node in bytes
+    pub mem_total: u64,
+    /// Unused memory on the node in bytes
+    pub mem_free: u64,
 }
 
 /// Network interface information and statistics
@@ -86,8 +391,11 @@ pub struct DiskInfo {
 pub struct NetworkInfo {
     /// Network interface name
     pub interface: String,
-    /// IP address
+    /// Primary IPv4 address, or "N/A"
     pub ip_address: String,
+    /// IPv6 addresses; link-local ones only if requested, see
+    /// `NetworkQuery::include_link_local`
+    pub ipv6_addresses: Vec<String>,
     /// MAC address
     pub mac_address: String,
     /// Total bytes received
@@ -102,6 +410,53 @@ pub struct NetworkInfo {
     pub errors_received: u64,
     /// Total transmit errors
     pub errors_transmitted: u64,
+    /// Whether the link is operationally up (None if unknown)
+    pub is_up: Option<bool>,
+    /// Negotiated link speed in Mbit/s (None for virtual or disconnected links)
+    pub speed_mbps: Option<u32>,
+    /// Maximum transmission unit in bytes (None if unknown)
+    pub mtu: Option<u32>,
+}
+
+/// Raw cumulative byte counters for a network interface from /proc/net/dev
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct InterfaceCounters {
+    /// Network interface name
+    pub interface: String,
+    /// Total bytes received
+    pub bytes_received: u64,
+    /// Total bytes transmitted
+    pub bytes_transmitted: u64,
+}
+
+/// Network interface throughput computed against the previous sample
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct NetworkRate {
+    /// Network interface name
+    pub interface: String,
+    /// Bytes received per second since the previous sample (None on the first sample)
+    pub rx_bytes_per_sec: Option<f64>,
+    /// Bytes transmitted per second since the previous sample (None on the first sample)
+    pub tx_bytes_per_sec: Option<f64>,
+}
+
+/// A TCP or UDP socket from `/proc/net/{tcp,tcp6,udp,udp6}`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Connection {
+    /// Protocol: "tcp", "tcp6", "udp" or "udp6"
+    pub proto: String,
+    /// Local address
+    pub local_addr: IpAddr,
+    /// Local port
+    pub local_port: u16,
+    /// Remote address (unspecified for listening sockets)
+    pub remote_addr: IpAddr,
+    /// Remote port (0 for listening sockets)
+    pub remote_port: u16,
+    /// Socket state (e.g., "LISTEN", "ESTABLISHED"; "UNCONN" for unconnected UDP)
+    pub state: String,
+    /// Socket inode, matching a `socket:[inode]` link in `/proc/<pid>/fd`
+    pub inode: u64,
 }
 
 /// Process information including resource usage and details
@@ -109,6 +464,8 @@ pub struct NetworkInfo {
 pub struct ProcessInfo {
     /// Process ID
     pub pid: u32,
+    /// Parent process ID (0 for init and kthreadd)
+    pub ppid: u32,
     /// Process name
     pub name: String,
     /// Full command line
@@ -123,27 +480,478 @@ pub struct ProcessInfo {
     pub status: String,
     /// Process start time
     pub start_time: DateTime<Utc>,
+    /// User plus system CPU time consumed, in seconds (None if unreadable)
+    pub cpu_time_secs: Option<f64>,
     /// Process owner
     pub user: String,
     /// Process priority
     pub priority: i32,
+    /// Scheduling policy, None if unreadable; real-time policies ignore the
+    /// nice value
+    pub sched_policy: Option<SchedPolicy>,
+    /// Real-time priority (1-99 under SCHED_FIFO and SCHED_RR, otherwise 0),
+    /// None if unreadable
+    pub rt_priority: Option<u32>,
+    /// Owning systemd unit (e.g., "nginx.service"), None on non-systemd hosts
+    pub systemd_unit: Option<String>,
+    /// Open file descriptors; 0 if unreadable or not counted, see
+    /// `CollectorConfig::process_fds`
+    pub open_fds: u32,
+    /// Number of threads; 0 if unreadable
+    pub threads: u32,
+    /// Bytes read from storage; 0 if unreadable or not collected, see
+    /// `ProcessQuery::include_io`
+    pub read_bytes: u64,
+    /// Bytes written to storage; 0 if unreadable or not collected
+    pub write_bytes: u64,
+    /// Read system calls (`read`, `pread`, ...); 0 if unreadable or not collected
+    pub read_syscalls: u64,
+    /// Write system calls (`write`, `pwrite`, ...); 0 if unreadable or not collected
+    pub write_syscalls: u64,
+    /// Supplemental fields asked for with `ProcessQuery::fields`, by name;
+    /// unreadable ones are left out
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    pub extra: HashMap<String, String>,
 }
 
-/// Complete system metrics snapshot
+/// Scheduling policy of a process, as `sched_setscheduler(2)` names it
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum SchedPolicy {
+    /// Default time-sharing policy
+    #[serde(rename = "SCHED_OTHER")]
+    Other,
+    /// Real-time first in, first out
+    #[serde(rename = "SCHED_FIFO")]
+    Fifo,
+    /// Real-time round robin
+    #[serde(rename = "SCHED_RR")]
+    RoundRobin,
+    /// Time-sharing for CPU-bound batch work
+    #[serde(rename = "SCHED_BATCH")]
+    Batch,
+    /// Runs only when nothing else wants the CPU
+    #[serde(rename = "SCHED_IDLE")]
+    Idle,
+    /// Earliest deadline first
+    #[serde(rename = "SCHED_DEADLINE")]
+    Deadline,
+}
+
+impl SchedPolicy {
+    /// Returns the policy with the kernel's number, None if unknown
+    pub fn from_number(policy: u32) -> Option<Self> {
+        match policy {
+            0 => Some(SchedPolicy::Other),
+            1 => Some(SchedPolicy::Fifo),
+            2 => Some(SchedPolicy::RoundRobin),
+            3 => Some(SchedPolicy::Batch),
+            5 => Some(SchedPolicy::Idle),
+            6 => Some(SchedPolicy::Deadline),
+            _ => None,
+        }
+    }
+
+    /// Returns whether the policy is real-time, so the nice value is ignored
+    pub fn is_realtime(self) -> bool {
+        matches!(self, SchedPolicy::Fifo | SchedPolicy::RoundRobin)
+    }
+}
+
+/// Cumulative I/O counters of one process, from `/proc/<pid>/io`
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ProcessIO {
+    /// Bytes the process caused to be fetched from storage
+    pub read_bytes: u64,
+    /// Bytes the process caused to be sent to storage
+    pub write_bytes: u64,
+    /// Read system calls
+    pub read_syscalls: u64,
+    /// Write system calls
+    pub write_syscalls: u64,
+}
+
+impl ProcessInfo {
+    /// Copies the I/O counters into the process
+    pub fn set_io(&mut self, io: ProcessIO) {
+        self.read_bytes = io.read_bytes;
+        self.write_bytes = io.write_bytes;
+        self.read_syscalls = io.read_syscalls;
+        self.write_syscalls = io.write_syscalls;
+    }
+}
+
+/// The processes using the most of a resource, with machine-wide totals
+///
+/// The totals cover every process, so `top_cpu_usage / total_cpu_usage` is
+/// the share of CPU time the listed processes account for.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TopProcesses {
+    /// Top processes, highest first
+    pub processes: Vec<ProcessInfo>,
+    /// Number of processes ranked
+    pub process_count: usize,
+    /// Summed CPU usage of all processes (percent)
+    pub total_cpu_usage: f32,
+    /// Summed resident memory of all processes in bytes
+    pub total_memory: u64,
+    /// Summed bytes read and written by all processes, only when ranking by I/O
+    pub total_io_bytes: Option<u64>,
+    /// Summed CPU usage of the top processes (percent)
+    pub top_cpu_usage: f32,
+    /// Summed resident memory of the top processes in bytes
+    pub top_memory: u64,
+    /// Summed bytes read and written by the top processes, only when ranking by I/O
+    pub top_io_bytes: Option<u64>,
+}
+
+/// Memory breakdown of one process, from `/proc/<pid>/status`
+///
+/// Every size is in bytes. Fields the kernel does not report, such as the
+/// `Rss*` lines before Linux 4.5 or every `Vm*` line of a kernel thread, are
+/// None.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ProcessMemory {
+    /// Process ID
+    pub pid: u32,
+    /// Virtual address space (VmSize)
+    pub vm_size: Option<u64>,
+    /// Resident set size (VmRSS)
+    pub vm_rss: Option<u64>,
+    /// Heap and other private data segments (VmData)
+    pub vm_data: Option<u64>,
+    /// Main thread stack (VmStk)
+    pub vm_stk: Option<u64>,
+    /// Executable code (VmExe)
+    pub vm_exe: Option<u64>,
+    /// Shared library code (VmLib)
+    pub vm_lib: Option<u64>,
+    /// Private memory swapped out (VmSwap)
+    pub vm_swap: Option<u64>,
+    /// Resident anonymous memory, where leaks grow (RssAnon)
+    pub rss_anon: Option<u64>,
+    /// Resident file mappings (RssFile)
+    pub rss_file: Option<u64>,
+    /// Resident shared memory (RssShmem)
+    pub rss_shmem: Option<u64>,
+}
+
+/// Resource limits of one process, from `/proc/<pid>/limits`
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ProcessLimits {
+    /// Process ID
+    pub pid: u32,
+    /// Limits in the order the kernel lists them
+    pub limits: Vec<ProcessLimit>,
+}
+
+/// One resource limit (rlimit) of a process
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ProcessLimit {
+    /// Limit as the kernel names it, such as "Max open files"
+    pub name: String,
+    /// Soft limit the process is held to, None if unlimited
+    pub soft: Option<u64>,
+    /// Hard limit the soft limit may be raised to, None if unlimited
+    pub hard: Option<u64>,
+    /// Unit of both limits, such as "files" or "bytes", None if unitless
+    pub units: Option<String>,
+}
+
+/// A process and its descendants
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ProcessNode {
+    /// The process itself
+    pub info: ProcessInfo,
+    /// Direct child processes, each with their own descendants
+    pub children: Vec<ProcessNode>,
+}
+
+/// Resource use of the monitor's own process, to spot leaks over long runs
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SelfInfo {
+    /// The monitor process, as `getProcessByPID` reports it
+    pub process: ProcessInfo,
+    /// Seconds since the monitor started
+    pub uptime_secs: u64,
+    /// MCP requests handled over all transports, including REST calls
+    pub requests_total: u64,
+    /// Requests answered with an error
+    pub request_errors_total: u64,
+}
+
+/// System metrics snapshot
+///
+/// Sections left out by the `MetricsSelector` are `None` or empty.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct SystemMetrics {
     /// Timestamp of the metrics collection
     pub timestamp: DateTime<Utc>,
     /// System information
-    pub system_info: SystemInfo,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub system_info: Option<SystemInfo>,
     /// CPU information
-    pub cpu_info: CPUInfo,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub cpu_info: Option<CPUInfo>,
     /// Memory information
-    pub memory_info: MemoryInfo,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub memory_info: Option<MemoryInfo>,
+    /// Load average
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub load_average: Option<LoadAverage>,
     /// Disk information for all mounted filesystems
     pub disks: Vec<DiskInfo>,
     /// Network information for all interfaces
     pub networks: Vec<NetworkInfo>,
     /// Process information for all running processes
     pub processes: Vec<ProcessInfo>,
+    /// Number of running processes, present only when `processes` was cut
+    /// down to `max_processes`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub total_count: Option<usize>,
+    /// NVIDIA GPUs, present only when the `gpus` collector is enabled
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub gpus: Option<Vec<GpuInfo>>,
+}
+
+/// A process identified by PID and name, as listed in a `MetricsDiff`
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ProcessRef {
+    /// Process ID
+    pub pid: u32,
+    /// Process name
+    pub name: String,
+}
+
+/// Traffic of one network interface between two snapshots
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct NetworkDelta {
+    /// Network interface name
+    pub interface: String,
+    /// Bytes received between the snapshots (0 if the counter was reset)
+    pub bytes_received: u64,
+    /// Bytes transmitted between the snapshots (0 if the counter was reset)
+    pub bytes_transmitted: u64,
+    /// Bytes received per second (None if the snapshots are not in order)
+    pub rx_bytes_per_sec: Option<f64>,
+    /// Bytes transmitted per second (None if the snapshots are not in order)
+    pub tx_bytes_per_sec: Option<f64>,
+}
+
+/// Changes between two `SystemMetrics` snapshots
+///
+/// Deltas are `after - before`; a delta is None when either snapshot lacks
+/// the section.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct MetricsDiff {
+    /// Timestamp of the earlier snapshot
+    pub from: DateTime<Utc>,
+    /// Timestamp of the later snapshot
+    pub to: DateTime<Utc>,
+    /// Seconds between the snapshots (negative if they are not in order)
+    pub elapsed_secs: f64,
+    /// Change in CPU usage, in percentage points
+    pub cpu_usage_delta: Option<f32>,
+    /// Change in used memory, in bytes
+    pub memory_used_delta: Option<i64>,
+    /// Change in memory usage, in percentage points
+    pub memory_usage_delta: Option<f32>,
+    /// Change in used swap, in bytes
+    pub swap_used_delta: Option<i64>,
+    /// Change in the 1-minute load average
+    pub load_one_delta: Option<f64>,
+    /// Processes only in the later snapshot, by PID
+    pub processes_started: Vec<ProcessRef>,
+    /// Processes only in the earlier snapshot, by PID
+    pub processes_exited: Vec<ProcessRef>,
+    /// Traffic of each interface present in both snapshots
+    pub networks: Vec<NetworkDelta>,
+}
+
+/// Lightweight metrics sample pushed to streaming subscribers
+///
+/// Omits the process, disk and network lists of `SystemMetrics`, which are
+/// too large to resend every few seconds.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LiveMetrics {
+    /// Timestamp of the sample
+    pub timestamp: DateTime<Utc>,
+    /// CPU information
+    pub cpu_info: CPUInfo,
+    /// Memory information
+    pub memory_info: MemoryInfo,
+    /// Load average
+    pub load_average: LoadAverage,
+}
+
+/// One entry of the short-term metrics history
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct HistorySample {
+    /// Timestamp of the sample
+    pub timestamp: DateTime<Utc>,
+    /// CPU usage percentage (0.0 - 100.0)
+    pub cpu_percent: f32,
+    /// Memory usage percentage (0.0 - 100.0)
+    pub memory_percent: f32,
+    /// 1-minute load average
+    pub load_one: f64,
+}
+
+/// A single metric value taken from the history
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct HistoryPoint {
+    /// Timestamp of the sample
+    pub timestamp: DateTime<Utc>,
+    /// Value of the requested metric
+    pub value: f64,
+}
+
+/// Summary statistics of one metric over a window of the history
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct MetricStats {
+    /// Number of samples summarized, fewer than requested if the history is shorter
+    pub samples: usize,
+    /// Smallest value
+    pub min: f64,
+    /// Largest value
+    pub max: f64,
+    /// Arithmetic mean
+    pub mean: f64,
+    /// Median, linearly interpolated
+    pub p50: f64,
+    /// 95th percentile, linearly interpolated
+    pub p95: f64,
+    /// 99th percentile, linearly interpolated
+    pub p99: f64,
+    /// Population standard deviation
+    pub stddev: f64,
+}
+
+/// A temperature reading from a hardware monitoring sensor
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TemperatureSensor {
+    /// Sensor chip name (e.g., "coretemp", "k10temp", "nvme")
+    pub chip: String,
+    /// Sensor label (e.g., "Package id 0", "Tctl"), or "tempN" if unlabeled
+    pub label: String,
+    /// Temperature in degrees Celsius
+    pub celsius: f32,
+}
+
+/// Battery charge state, aggregated over all batteries
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct BatteryInfo {
+    /// Whether any battery is installed
+    pub present: bool,
+    /// Charge level in percent
+    pub percent: Option<f32>,
+    /// Charge status (e.g., "Charging", "Discharging", "Full")
+    pub status: Option<String>,
+    /// Estimated seconds until empty (discharging) or full (charging)
+    pub time_remaining_secs: Option<u64>,
+}
+
+/// Utilization and memory of an NVIDIA GPU, as reported by `nvidia-smi`
+///
+/// Fields the driver reports as unsupported are None.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct GpuInfo {
+    /// GPU index as numbered by the driver
+    pub index: u32,
+    /// Product name (e.g., "NVIDIA A100-SXM4-40GB")
+    pub name: String,
+    /// GPU utilization percentage
+    pub utilization_percent: Option<f32>,
+    /// Used framebuffer memory in bytes
+    pub memory_used: Option<u64>,
+    /// Total framebuffer memory in bytes
+    pub memory_total: Option<u64>,
+    /// Core temperature in degrees Celsius
+    pub temperature: Option<f32>,
+}
+
+/// SMART health of a drive, as reported by `smartctl`
+///
+/// Values the drive does not report are None.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SmartInfo {
+    /// Device path (e.g., "/dev/sda", "/dev/nvme0")
+    pub device: String,
+    /// Whether the drive passed its overall health self-assessment
+    pub health_passed: Option<bool>,
+    /// Drive temperature in degrees Celsius
+    pub temperature: Option<f32>,
+    /// Hours the drive has been powered on
+    pub power_on_hours: Option<u64>,
+    /// Sectors remapped to the spare area (ATA attribute 5); None on NVMe drives
+    pub reallocated_sectors: Option<u64>,
+}
+
+/// A systemd service unit, as listed by `systemctl list-units`
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ServiceInfo {
+    /// Unit name (e.g., "nginx.service")
+    pub name: String,
+    /// Whether the unit file was loaded (e.g., "loaded", "not-found", "masked")
+    pub load_state: String,
+    /// High-level state (e.g., "active", "inactive", "failed")
+    pub active_state: String,
+    /// Unit-type specific state (e.g., "running", "exited", "dead")
+    pub sub_state: String,
+    /// Description from the unit file
+    pub description: String,
+}
+
+/// Service units of the host's init system
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ServiceList {
+    /// Whether the host runs systemd; `services` is empty if not
+    pub systemd: bool,
+    /// Service units, in the order systemctl lists them
+    pub services: Vec<ServiceInfo>,
+}
+
+/// A log entry from the systemd journal or the syslog file
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct LogEntry {
+    /// When the entry was logged, if known
+    pub timestamp: Option<DateTime<Utc>>,
+    /// systemd unit, or the syslog identifier outside units (e.g., "kernel")
+    pub unit: Option<String>,
+    /// Syslog priority from 0 (emerg) to 7 (debug); None for syslog file lines
+    pub priority: Option<u8>,
+    /// Log message
+    pub message: String,
+}
+
+/// Disk I/O counters for a block device, parsed from `/proc/diskstats`
+///
+/// All fields are monotonic counters since boot. To get throughput, take two
+/// samples and diff them (see `helpers::calculate_disk_io_rates`).
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DiskIOStats {
+    /// Block device name (e.g., "sda", "nvme0n1")
+    pub device: String,
+    /// Total reads completed successfully
+    pub reads_completed: u64,
+    /// Total writes completed successfully
+    pub writes_completed: u64,
+    /// Total sectors read
+    pub sectors_read: u64,
+    /// Total sectors written
+    pub sectors_written: u64,
+    /// Total bytes read (sectors_read × 512)
+    pub read_bytes: u64,
+    /// Total bytes written (sectors_written × 512)
+    pub write_bytes: u64,
+}
+
+/// Disk I/O throughput for a block device computed from two samples
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DiskIORate {
+    /// Block device name
+    pub device: String,
+    /// Bytes read per second over the sample interval
+    pub read_bytes_per_sec: f64,
+    /// Bytes written per second over the sample interval
+    pub write_bytes_per_sec: f64,
 }
@@ -0,0 +1,36 @@
+//! Build Information
+//!
+//! Which build of the server is running, as recorded by the build script.
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+/// Name, version and provenance of the running build
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct BuildInfo {
+    /// Crate name
+    pub name: String,
+    /// Crate version
+    pub version: String,
+    /// Commit the binary was built from (None outside a git checkout)
+    pub git_sha: Option<String>,
+    /// When the build script ran
+    pub build_timestamp: Option<DateTime<Utc>>,
+    /// Version of the compiler that built the binary
+    pub rustc_version: Option<String>,
+}
+
+impl BuildInfo {
+    /// Returns the information recorded for this build
+    pub fn current() -> Self {
+        Self {
+            name: env!("CARGO_PKG_NAME").to_string(),
+            version: env!("CARGO_PKG_VERSION").to_string(),
+            git_sha: option_env!("MCP_BUILD_GIT_SHA").map(str::to_string),
+            build_timestamp: option_env!("MCP_BUILD_TIMESTAMP")
+                .and_then(|epoch| epoch.parse().ok())
+                .and_then(|epoch| DateTime::from_timestamp(epoch, 0)),
+            rustc_version: option_env!("MCP_BUILD_RUSTC_VERSION").map(str::to_string),
+        }
+    }
+}
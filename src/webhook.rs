@@ -0,0 +1,257 @@
+//! Alert Webhooks
+//!
+//! Posts alert transitions to HTTP endpoints. Each delivery runs in its own
+//! task, so a slow or unreachable receiver never delays the sampler.
+
+use serde_json::{json, Value};
+use std::collections::HashMap;
+use std::time::{Duration, Instant};
+use tracing::{debug, warn};
+
+use crate::alerts::{AlertState, AlertTransition};
+use crate::config::{WebhookConfig, WebhookFormat};
+
+/// Upper bound on a single delivery attempt
+const DELIVERY_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Delay before the first retry; doubled for each further retry
+const RETRY_BACKOFF: Duration = Duration::from_secs(1);
+
+/// Sends alert transitions to the configured webhooks
+#[derive(Debug)]
+pub struct WebhookNotifier {
+    /// Shared HTTP client
+    client: reqwest::Client,
+    /// Global URL, payload format, cooldown and retries
+    config: WebhookConfig,
+    /// Parsed payload template, if configured
+    template: Option<Value>,
+    /// Hostname reported in every payload
+    hostname: String,
+    /// Per rule: when a firing notification was last sent, and whether its
+    /// resolution is still owed
+    sent: HashMap<String, (Instant, bool)>,
+}
+
+impl WebhookNotifier {
+    /// Creates a notifier reporting `hostname` in its payloads
+    ///
+    /// A template that is not valid JSON is logged and ignored, falling back
+    /// to `format`; configuration validation rejects it before this point.
+    pub fn new(config: WebhookConfig, hostname: impl Into<String>) -> Self {
+        let client = reqwest::Client::builder()
+            .timeout(DELIVERY_TIMEOUT)
+            .build()
+            .unwrap_or_default();
+        let template =
+            config
+                .template
+                .as_deref()
+                .and_then(|template| match serde_json::from_str(template) {
+                    Ok(template) => Some(template),
+                    Err(e) => {
+                        warn!(
+                            "Invalid webhook template; using the {:?} format: {}",
+                            config.format, e
+                        );
+                        None
+                    }
+                });
+
+        Self {
+            client,
+            config,
+            template,
+            hostname: hostname.into(),
+            sent: HashMap::new(),
+        }
+    }
+
+    /// Delivers the transitions that have a webhook and pass the cooldown
+    ///
+    /// A rule that fires again within `cooldown_secs` of its last firing
+    /// notification is not reported, and neither is its resolution, so a
+    /// flapping metric sends one notification per cooldown. Deliveries are
+    /// spawned on the current Tokio runtime; without one they are skipped.
+    ///
+    /// # Returns
+    ///
+    /// Returns the number of deliveries started
+    pub fn notify(&mut self, transitions: &[AlertTransition]) -> usize {
+        let Ok(runtime) = tokio::runtime::Handle::try_current() else {
+            if !transitions.is_empty() {
+                warn!("No async runtime; skipping alert webhooks");
+            }
+            return 0;
+        };
+
+        let mut started = 0;
+        for transition in transitions {
+            let Some(url) = transition
+                .rule
+                .webhook_url
+                .as_ref()
+                .or(self.config.url.as_ref())
+                .cloned()
+            else {
+                continue;
+            };
+            if !self.passes_cooldown(transition) {
+                debug!(
+                    "Alert {} {} within cooldown; not notifying",
+                    transition.rule.name, transition.state
+                );
+                continue;
+            }
+
+            let body = match &self.template {
+                Some(template) => render_template(template, transition, &self.hostname),
+                None => payload(transition, &self.hostname, self.config.format),
+            };
+            runtime.spawn(deliver(self.client.clone(), url, body, self.config.retries));
+            started += 1;
+        }
+        started
+    }
+
+    /// Records a transition against the cooldown, returning whether to send it
+    fn passes_cooldown(&mut self, transition: &AlertTransition) -> bool {
+        let cooldown = Duration::from_secs(self.config.cooldown_secs);
+        let now = Instant::now();
+
+        match (transition.state, self.sent.get_mut(&transition.rule.name)) {
+            (AlertState::Firing, Some((at, owed))) if now.duration_since(*at) < cooldown => {
+                *owed = false;
+                false
+            }
+            (AlertState::Firing, _) => {
+                self.sent.insert(transition.rule.name.clone(), (now, true));
+                true
+            }
+            (AlertState::Resolved, Some((_, owed))) => std::mem::take(owed),
+            (AlertState::Resolved, None) => false,
+        }
+    }
+}
+
+/// Builds the webhook body for a transition
+///
+/// # Arguments
+///
+/// * `transition` - The rule that fired or resolved
+/// * `hostname` - Host the alert is about
+/// * `format` - Payload shape
+///
+/// # Returns
+///
+/// Returns `{rule, metric, value, threshold, state, hostname, timestamp}`,
+/// or a Slack message `{"text": ...}` summarizing the same fields
+pub fn payload(transition: &AlertTransition, hostname: &str, format: WebhookFormat) -> Value {
+    let rule = &transition.rule;
+    match format {
+        WebhookFormat::Json => json!({
+            "rule": rule.name,
+            "metric": rule.metric,
+            "value": transition.value,
+            "threshold": rule.threshold,
+            "state": transition.state,
+            "hostname": hostname,
+            "timestamp": transition.timestamp,
+        }),
+        WebhookFormat::Slack => json!({
+            "text": format!(
+                "[{}] {} on {}: {} is {:.1} ({} {})",
+                transition.state.to_string().to_uppercase(),
+                rule.name,
+                hostname,
+                rule.metric,
+                transition.value,
+                rule.comparator,
+                rule.threshold
+            ),
+        }),
+    }
+}
+
+/// Builds the webhook body for a transition from a template
+///
+/// # Arguments
+///
+/// * `template` - JSON body whose strings may contain placeholders
+/// * `transition` - The rule that fired or resolved
+/// * `hostname` - Host the alert is about
+///
+/// # Returns
+///
+/// Returns `template` with `{rule}`, `{metric}`, `{value}`, `{threshold}`,
+/// `{state}`, `{hostname}` and `{timestamp}` replaced in every string,
+/// including object keys. Other JSON values are kept as they are.
+pub fn render_template(template: &Value, transition: &AlertTransition, hostname: &str) -> Value {
+    let rule = &transition.rule;
+    let fields = [
+        ("{rule}", rule.name.clone()),
+        ("{metric}", rule.metric.to_string()),
+        ("{value}", format!("{:.1}", transition.value)),
+        ("{threshold}", rule.threshold.to_string()),
+        ("{state}", transition.state.to_string()),
+        ("{hostname}", hostname.to_string()),
+        (
+            "{timestamp}",
+            transition
+                .timestamp
+                .to_rfc3339_opts(chrono::SecondsFormat::AutoSi, true),
+        ),
+    ];
+    let fill = |text: &str| {
+        fields
+            .iter()
+            .fold(text.to_string(), |text, (placeholder, value)| {
+                text.replace(placeholder, value)
+            })
+    };
+
+    match template {
+        Value::String(text) => Value::String(fill(text)),
+        Value::Array(items) => Value::Array(
+            items
+                .iter()
+                .map(|item| render_template(item, transition, hostname))
+                .collect(),
+        ),
+        Value::Object(entries) => Value::Object(
+            entries
+                .iter()
+                .map(|(key, value)| (fill(key), render_template(value, transition, hostname)))
+                .collect(),
+        ),
+        other => other.clone(),
+    }
+}
+
+/// Posts a payload, retrying with exponential backoff on failure
+async fn deliver(client: reqwest::Client, url: String, body: Value, retries: u32) {
+    let mut backoff = RETRY_BACKOFF;
+    for attempt in 0..=retries {
+        if attempt > 0 {
+            tokio::time::sleep(backoff).await;
+            backoff *= 2;
+        }
+
+        let result = client
+            .post(&url)
+            .json(&body)
+            .send()
+            .await
+            .and_then(|response| response.error_for_status());
+        match result {
+            Ok(_) => return,
+            Err(e) => warn!(
+                "Alert webhook delivery failed (attempt {} of {}): {}",
+                attempt + 1,
+                retries + 1,
+                // The URL may embed a secret token
+                e.without_url()
+            ),
+        }
+    }
+}
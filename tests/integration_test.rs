@@ -4,11 +4,48 @@
 
 use serde_json::json;
 use std::sync::Arc;
+use std::time::Duration;
 use tokio::sync::RwLock;
 
 use mcp_system_monitor::{
-    types::{MCPRequest, METHOD_GET_CPU_INFO, METHOD_GET_SYSTEM_INFO},
-    HTTPServer, MCPServer, SystemMonitor,
+    patch::{apply_merge_patch, merge_patch_diff, PatchTracker, StreamUpdate},
+    system_monitor::{
+        core::{DEFAULT_MIN_MONITORING_INTERVAL, MIN_INTERVAL_FLOOR},
+        helpers::{
+            add_human_sizes, apply_network_query, apply_process_query, build_process_tree,
+            calculate_disk_io_rates, classify_core_capacities, counter_rate, detect_virtualization,
+            diff_metrics, estimate_battery_time, format_bytes, format_uptime, history_points,
+            is_link_local_ipv6, is_pid, metric_stats, package_temperature, parse_cgroup_container,
+            parse_cgroup_memory_limit, parse_cgroup_path, parse_cmdline, parse_cpu_list,
+            parse_cpu_max, parse_cpu_topology, parse_diskstats, parse_file_nr,
+            parse_interface_addresses, parse_journal_entry, parse_kernel_stats, parse_link_speed,
+            parse_link_state, parse_loadavg, parse_meminfo, parse_millidegrees, parse_net_dev_line,
+            parse_node_meminfo, parse_nvidia_smi_csv, parse_os_release, parse_pressure,
+            parse_proc_net_sockets, parse_process_io, parse_process_limits, parse_process_memory,
+            parse_process_sched, parse_process_session_tty, parse_process_stat, parse_size,
+            parse_size_decimal, parse_smartctl_json, parse_smartctl_scan, parse_stat_value,
+            parse_status_value, parse_swaps, parse_syslog_line, parse_systemctl_units,
+            parse_systemd_unit, parse_uptime, parse_vcgencmd_throttled, parse_zoneinfo_path,
+            run_command_with_timeout, tally_tcp_states, top_processes, tty_name,
+        },
+    },
+    types::{
+        BuildInfo, CPUInfo, DiskInfo, HistoryMetric, LoadAverage, LogEntry, MCPRequest, MemoryInfo,
+        MetricSection, MetricsDiff, MetricsQuery, MetricsSelector, NetworkInfo, NetworkQuery,
+        ProcessField, ProcessFields, ProcessInfo, ProcessMemory, ProcessQuery, ProcessRef,
+        ProcessSortKey, SelfInfo, ServiceInfo, ServiceList, SortOrder, StaticSystemInfo,
+        SystemInfo, SystemMetrics, SystemSummary, TemperatureSensor, TopMetric, TopProcesses,
+        ERROR_INTERNAL_ERROR, ERROR_INVALID_PARAMS, ERROR_PERMISSION_DENIED,
+        ERROR_PROCESS_NOT_FOUND, ERROR_SYSTEM_COMMAND_FAILED, ERROR_UNSUPPORTED, METHOD_GET_ALERTS,
+        METHOD_GET_BATTERY_INFO, METHOD_GET_CPU_INFO, METHOD_GET_DISK_IO, METHOD_GET_DISK_SMART,
+        METHOD_GET_GPU_INFO, METHOD_GET_HISTORY, METHOD_GET_HISTORY_STATS, METHOD_GET_KERNEL_STATS,
+        METHOD_GET_PROCESSES, METHOD_GET_PROCESS_BY_PID, METHOD_GET_PROCESS_TREE,
+        METHOD_GET_RECENT_LOGS, METHOD_GET_SERVICES, METHOD_GET_SUMMARY, METHOD_GET_SYSTEM_INFO,
+        METHOD_GET_SYSTEM_METRICS, METHOD_GET_TEMPERATURES, METHOD_GET_TOP_PROCESSES,
+        METHOD_KILL_PROCESS, METHOD_RENICE, METHOD_SET_MONITORING_INTERVAL,
+        METHOD_START_MONITORING,
+    },
+    HTTPServer, MCPServer, MonitorError, SystemInfoProvider, SystemMonitor,
 };
 
 /// Test helper to create test system monitor
@@ -30,6 +67,178 @@ async fn create_test_http_server() -> HTTPServer {
     HTTPServer::new(system_monitor)
 }
 
+/// Provider returning fixed data, for asserting exact response shapes
+#[derive(Debug)]
+struct MockProvider;
+
+impl SystemInfoProvider for MockProvider {
+    fn get_static_info(&self) -> mcp_system_monitor::error::Result<StaticSystemInfo> {
+        Ok(StaticSystemInfo {
+            hostname: "mock-host".to_string(),
+            os_name: "MockOS".to_string(),
+            os_version: "1.0".to_string(),
+            kernel_version: "6.0.0-mock".to_string(),
+            cpu_name: "Mock CPU".to_string(),
+            cpu_brand: "Mock CPU".to_string(),
+            logical_cores: 8,
+            physical_cores: 4,
+            sockets: 1,
+            core_types: Vec::new(),
+        })
+    }
+
+    fn get_system_info(
+        &self,
+        static_info: &StaticSystemInfo,
+    ) -> mcp_system_monitor::error::Result<SystemInfo> {
+        let boot_time = chrono::DateTime::from_timestamp(1_700_000_000, 0).unwrap();
+        Ok(SystemInfo {
+            hostname: static_info.hostname.clone(),
+            os_name: static_info.os_name.clone(),
+            os_version: static_info.os_version.clone(),
+            kernel_version: static_info.kernel_version.clone(),
+            uptime: 3600,
+            uptime_human: "1h".to_string(),
+            boot_time,
+            timezone: "UTC".to_string(),
+            local_time: boot_time.fixed_offset(),
+        })
+    }
+
+    fn get_cpu_info(
+        &self,
+        static_info: &StaticSystemInfo,
+    ) -> mcp_system_monitor::error::Result<CPUInfo> {
+        Ok(CPUInfo {
+            name: static_info.cpu_name.clone(),
+            brand: static_info.cpu_brand.clone(),
+            frequency: 3000,
+            max_frequency: 4200,
+            per_core_freq_mhz: Vec::new(),
+            core_types: Vec::new(),
+            cores: static_info.logical_cores,
+            logical_cores: static_info.logical_cores,
+            physical_cores: static_info.physical_cores,
+            sockets: static_info.sockets,
+            usage_percent: 12.5,
+            temperature: None,
+            quota_cores: None,
+            throttled: false,
+            throttle_count: 0,
+        })
+    }
+
+    fn get_load_average(&self) -> mcp_system_monitor::error::Result<LoadAverage> {
+        Ok(LoadAverage {
+            one: 0.5,
+            five: 0.25,
+            fifteen: 0.125,
+            runnable: 1,
+            total: 100,
+        })
+    }
+
+    fn get_memory_info(&self) -> mcp_system_monitor::error::Result<MemoryInfo> {
+        Ok(MemoryInfo {
+            total: 1024,
+            used: 256,
+            free: 512,
+            available: 768,
+            swap_total: 0,
+            swap_used: 0,
+            swap_free: 0,
+            usage_percent: 25.0,
+            swap_usage_percent: 0.0,
+            buffers: 64,
+            cached: 128,
+            slab: 32,
+            shmem: 16,
+            dirty: 0,
+            limit_bytes: None,
+            limit_usage_percent: None,
+        })
+    }
+
+    fn get_disk_info(&self) -> mcp_system_monitor::error::Result<Vec<DiskInfo>> {
+        Ok(vec![DiskInfo {
+            name: "/dev/mock0".to_string(),
+            mount_point: "/".to_string(),
+            file_system: "ext4".to_string(),
+            total_space: 1000,
+            used_space: 950,
+            free_space: 50,
+            usage_percent: 95.0,
+            inodes_total: 100,
+            inodes_used: 10,
+            inodes_free: 90,
+            inodes_usage_percent: 10.0,
+        }])
+    }
+
+    fn get_network_info(&self) -> mcp_system_monitor::error::Result<Vec<NetworkInfo>> {
+        Ok(Vec::new())
+    }
+
+    fn get_processes(
+        &self,
+        _count_fds: bool,
+    ) -> mcp_system_monitor::error::Result<Vec<ProcessInfo>> {
+        Ok(vec![test_process(1, 0), test_process(42, 1)])
+    }
+
+    fn get_process_by_pid(
+        &self,
+        pid: u32,
+    ) -> mcp_system_monitor::error::Result<Option<ProcessInfo>> {
+        Ok(self
+            .get_processes(false)?
+            .into_iter()
+            .find(|p| p.pid == pid))
+    }
+
+    fn get_services(&self) -> mcp_system_monitor::error::Result<ServiceList> {
+        let service = |name: &str, active_state: &str, sub_state: &str| ServiceInfo {
+            name: name.to_string(),
+            load_state: "loaded".to_string(),
+            active_state: active_state.to_string(),
+            sub_state: sub_state.to_string(),
+            description: format!("Mock {}", name),
+        };
+        Ok(ServiceList {
+            systemd: true,
+            services: vec![
+                service("backup.service", "failed", "failed"),
+                service("nginx.service", "active", "running"),
+            ],
+        })
+    }
+
+    fn get_recent_logs(
+        &self,
+        lines: usize,
+        priority: Option<u8>,
+    ) -> mcp_system_monitor::error::Result<Vec<LogEntry>> {
+        Ok((0..lines)
+            .map(|line| LogEntry {
+                timestamp: None,
+                unit: Some("mock.service".to_string()),
+                priority: Some(priority.unwrap_or(6)),
+                message: format!("line {}", line),
+            })
+            .collect())
+    }
+
+    fn signal_process(&self, pid: u32, _signal: i32) -> mcp_system_monitor::error::Result<bool> {
+        Err(MonitorError::PermissionDenied(format!("kill {}", pid)))
+    }
+}
+
+/// Test helper to create an MCP server backed by `MockProvider`
+fn create_mock_mcp_server() -> MCPServer {
+    let monitor = SystemMonitor::with_provider(MockProvider).expect("mock provider never fails");
+    MCPServer::new(Arc::new(RwLock::new(monitor)))
+}
+
 #[tokio::test]
 async fn test_mcp_server_creation() {
     let server = create_test_mcp_server().await;
@@ -48,7 +257,7 @@ async fn test_mcp_get_system_info() {
 
     let request = MCPRequest {
         jsonrpc: "2.0".to_string(),
-        id: "test-1".to_string(),
+        id: Some("test-1".to_string()),
         method: METHOD_GET_SYSTEM_INFO.to_string(),
         params: json!({}),
     };
@@ -56,12 +265,11 @@ async fn test_mcp_get_system_info() {
     let response = server.handle_request(request).await;
 
     assert_eq!(response.jsonrpc, "2.0");
-    assert_eq!(response.id, "test-1");
+    assert_eq!(response.id.as_deref(), Some("test-1"));
 
     // On non-Linux systems, this might fail, so we check for either success or appropriate error
-    if response.error.is_some() {
+    if let Some(error) = response.error {
         // If there's an error, it should be a system-related error, not a protocol error
-        let error = response.error.unwrap();
         assert!(error.code != -32601); // Should not be METHOD_NOT_FOUND
     } else {
         // If successful, verify the result structure
@@ -75,13 +283,80 @@ async fn test_mcp_get_system_info() {
     }
 }
 
+#[tokio::test]
+#[cfg(target_os = "linux")]
+async fn test_mcp_get_uptime() {
+    use mcp_system_monitor::types::{UptimeInfo, METHOD_GET_UPTIME};
+    let server = create_test_mcp_server().await;
+
+    let request = MCPRequest {
+        jsonrpc: "2.0".to_string(),
+        id: Some("test-uptime".to_string()),
+        method: METHOD_GET_UPTIME.to_string(),
+        params: json!({}),
+    };
+    let response = server.handle_request(request).await;
+    assert!(response.error.is_none());
+    let result = response.result.unwrap();
+    assert_eq!(result.as_object().unwrap().len(), 3);
+    let uptime: UptimeInfo = serde_json::from_value(result).unwrap();
+    assert!(uptime.uptime_secs > 0);
+    assert!(!uptime.uptime_human.is_empty());
+    let since_boot = (chrono::Utc::now() - uptime.boot_time).num_seconds();
+    assert!(
+        since_boot.abs_diff(uptime.uptime_secs as i64) < 60,
+        "boot time {} does not match uptime {}",
+        uptime.boot_time,
+        uptime.uptime_secs
+    );
+}
+
+#[tokio::test]
+#[cfg(target_os = "linux")]
+async fn test_mcp_get_summary() {
+    let server = create_test_mcp_server().await;
+
+    let request = MCPRequest {
+        jsonrpc: "2.0".to_string(),
+        id: Some("test-summary".to_string()),
+        method: METHOD_GET_SUMMARY.to_string(),
+        params: json!({}),
+    };
+
+    let response = server.handle_request(request).await;
+    let result = response.result.expect("summary should succeed");
+    let summary: SystemSummary = serde_json::from_value(result.clone()).unwrap();
+    assert!(!summary.hostname.is_empty());
+    assert!(!summary.uptime_human.is_empty());
+    assert!(summary.memory_total > 0);
+    assert!(summary.memory_used <= summary.memory_total);
+    assert!(summary.process_count > 0);
+    let root_usage = summary
+        .root_disk_usage_percent
+        .expect("/ should be mounted");
+    assert!((0.0..=100.0).contains(&root_usage));
+    #[cfg(not(feature = "sysinfo-backend"))]
+    {
+        let fd_usage = summary
+            .fd_usage_percent
+            .expect("file-nr should be readable");
+        assert!((0.0..=100.0).contains(&fd_usage));
+    }
+    // Only headline numbers, no lists
+    assert!(result
+        .as_object()
+        .unwrap()
+        .values()
+        .all(|value| !value.is_array()));
+}
+
 #[tokio::test]
 async fn test_mcp_get_cpu_info() {
     let server = create_test_mcp_server().await;
 
     let request = MCPRequest {
         jsonrpc: "2.0".to_string(),
-        id: "test-2".to_string(),
+        id: Some("test-2".to_string()),
         method: METHOD_GET_CPU_INFO.to_string(),
         params: json!({}),
     };
@@ -89,12 +364,11 @@ async fn test_mcp_get_cpu_info() {
     let response = server.handle_request(request).await;
 
     assert_eq!(response.jsonrpc, "2.0");
-    assert_eq!(response.id, "test-2");
+    assert_eq!(response.id.as_deref(), Some("test-2"));
 
     // On non-Linux systems, this might fail, so we check for either success or appropriate error
-    if response.error.is_some() {
+    if let Some(error) = response.error {
         // If there's an error, it should be a system-related error, not a protocol error
-        let error = response.error.unwrap();
         assert!(error.code != -32601); // Should not be METHOD_NOT_FOUND
     } else {
         // If successful, verify the result structure
@@ -113,7 +387,7 @@ async fn test_mcp_invalid_method() {
 
     let request = MCPRequest {
         jsonrpc: "2.0".to_string(),
-        id: "test-3".to_string(),
+        id: Some("test-3".to_string()),
         method: "invalidMethod".to_string(),
         params: json!({}),
     };
@@ -121,7 +395,7 @@ async fn test_mcp_invalid_method() {
     let response = server.handle_request(request).await;
 
     assert_eq!(response.jsonrpc, "2.0");
-    assert_eq!(response.id, "test-3");
+    assert_eq!(response.id.as_deref(), Some("test-3"));
     assert!(response.result.is_none());
     assert!(response.error.is_some());
 
@@ -144,35 +418,40 @@ async fn test_system_monitor_basic_functionality() {
 
 #[tokio::test]
 async fn test_system_monitor_start_stop() {
-    let mut system_monitor = SystemMonitor::new().expect("Failed to create system monitor");
+    let system_monitor = create_test_system_monitor().await;
+    let timeout = Duration::from_secs(10);
 
     // Test starting monitoring
-    let started = system_monitor
-        .start_monitoring()
+    let started = SystemMonitor::start_monitoring(&system_monitor, timeout)
+        .await
         .expect("Failed to start monitoring");
     assert!(started);
-    assert!(system_monitor.is_monitoring_active());
+    assert!(system_monitor.read().await.is_monitoring_active());
 
     // Test starting again (should return false)
-    let started_again = system_monitor
-        .start_monitoring()
+    let started_again = SystemMonitor::start_monitoring(&system_monitor, timeout)
+        .await
         .expect("Failed to start monitoring again");
     assert!(!started_again);
-    assert!(system_monitor.is_monitoring_active());
+    assert!(system_monitor.read().await.is_monitoring_active());
 
     // Test stopping monitoring
     let stopped = system_monitor
+        .write()
+        .await
         .stop_monitoring()
         .expect("Failed to stop monitoring");
     assert!(stopped);
-    assert!(!system_monitor.is_monitoring_active());
+    assert!(!system_monitor.read().await.is_monitoring_active());
 
     // Test stopping again (should return false)
     let stopped_again = system_monitor
+        .write()
+        .await
         .stop_monitoring()
         .expect("Failed to stop monitoring again");
     assert!(!stopped_again);
-    assert!(!system_monitor.is_monitoring_active());
+    assert!(!system_monitor.read().await.is_monitoring_active());
 }
 
 #[tokio::test]
@@ -187,6 +466,7 @@ async fn test_system_info_collection() {
             assert!(!system_info.os_name.is_empty());
             assert!(!system_info.kernel_version.is_empty());
             assert!(system_info.uptime > 0);
+            assert!(!system_info.timezone.is_empty());
         }
         Err(e) => {
             // On non-Linux systems, this is expected to fail
@@ -198,6 +478,25 @@ async fn test_system_info_collection() {
     }
 }
 
+#[test]
+fn test_boot_time_matches_uptime() {
+    assert_eq!(
+        parse_stat_value("cpu  1 2 3 4\nctxt 987654\nbtime 1700000000\n", "btime"),
+        Some(1700000000)
+    );
+    assert_eq!(parse_stat_value("cpu  1 2 3 4\n", "btime"), None);
+
+    let mut system_monitor = SystemMonitor::new().expect("Failed to create system monitor");
+    let system_info = system_monitor.get_system_info().unwrap();
+    let booted_for = chrono::Utc::now() - system_info.boot_time;
+    let drift = booted_for.num_seconds() - system_info.uptime as i64;
+    assert!(
+        drift.abs() <= 2,
+        "boot_time and uptime disagree by {}s",
+        drift
+    );
+}
+
 #[tokio::test]
 async fn test_cpu_info_collection() {
     let mut system_monitor = SystemMonitor::new().expect("Failed to create system monitor");
@@ -208,6 +507,14 @@ async fn test_cpu_info_collection() {
             // Verify basic fields are present
             assert!(!cpu_info.name.is_empty());
             assert!(cpu_info.cores > 0);
+            assert_eq!(cpu_info.cores, cpu_info.logical_cores);
+            assert!(cpu_info.physical_cores <= cpu_info.logical_cores);
+            assert!(cpu_info.sockets <= cpu_info.physical_cores);
+            assert!(!cpu_info.per_core_freq_mhz.is_empty());
+            assert!(
+                cpu_info.core_types.is_empty()
+                    || cpu_info.core_types.len() == cpu_info.per_core_freq_mhz.len()
+            );
             assert!(cpu_info.usage_percent >= 0.0);
             assert!(cpu_info.usage_percent <= 100.0);
         }
@@ -232,6 +539,7 @@ async fn test_memory_info_collection() {
             assert!(memory_info.available <= memory_info.total);
             assert!(memory_info.usage_percent >= 0.0);
             assert!(memory_info.usage_percent <= 100.0);
+            assert!(memory_info.shmem <= memory_info.total);
         }
         Err(e) => {
             // On non-Linux systems, this is expected to fail
@@ -241,6 +549,30 @@ async fn test_memory_info_collection() {
             );
         }
     }
+
+    let meminfo = "MemTotal:       16384000 kB
+MemFree:         2048000 kB
+MemAvailable:    8192000 kB
+Buffers:          512000 kB
+Cached:          6144000 kB
+SwapCached:        10000 kB
+Shmem:            256000 kB
+Slab:             768000 kB
+SwapTotal:       4096000 kB
+SwapFree:        4096000 kB
+Dirty:              1024 kB
+";
+    let memory_info = parse_meminfo(meminfo);
+    assert_eq!(memory_info.total, 16384000 * 1024);
+    assert_eq!(memory_info.used, 8192000 * 1024);
+    assert_eq!(memory_info.usage_percent, 50.0);
+    assert_eq!(memory_info.buffers, 512000 * 1024);
+    // SwapCached must not be mistaken for Cached
+    assert_eq!(memory_info.cached, 6144000 * 1024);
+    assert_eq!(memory_info.slab, 768000 * 1024);
+    assert_eq!(memory_info.shmem, 256000 * 1024);
+    assert_eq!(memory_info.dirty, 1024 * 1024);
+    assert_eq!(memory_info.swap_used, 0);
 }
 
 #[tokio::test]
@@ -261,6 +593,9 @@ async fn test_disk_info_collection() {
                 assert!(disk.free_space <= disk.total_space);
                 assert!(disk.usage_percent >= 0.0);
                 assert!(disk.usage_percent <= 100.0);
+                assert_eq!(disk.inodes_used + disk.inodes_free, disk.inodes_total);
+                assert!(disk.inodes_usage_percent >= 0.0);
+                assert!(disk.inodes_usage_percent <= 100.0);
             }
         }
         Err(e) => {
@@ -270,20 +605,161 @@ async fn test_disk_info_collection() {
     }
 }
 
+#[tokio::test]
+async fn test_block_device_collection() {
+    let mut system_monitor = SystemMonitor::new().expect("Failed to create system monitor");
+
+    match system_monitor.get_block_devices() {
+        Ok(devices) => {
+            assert!(devices.windows(2).all(|pair| pair[0].name < pair[1].name));
+            for device in devices {
+                assert!(!device.name.is_empty());
+                // Partitions fit in their disk and share its medium
+                let partitioned: u64 = device.children.iter().map(|part| part.size_bytes).sum();
+                assert!(partitioned <= device.size_bytes);
+                for partition in &device.children {
+                    assert!(partition.name.starts_with(&device.name));
+                    assert_eq!(partition.rotational, device.rotational);
+                    assert!(partition.model.is_none());
+                    assert!(partition.children.is_empty());
+                }
+            }
+        }
+        Err(e) => {
+            println!(
+                "Block device collection failed (expected on non-Linux): {}",
+                e
+            );
+        }
+    }
+}
+
+#[tokio::test]
+async fn test_numa_topology_collection() {
+    let meminfo = "Node 1 MemTotal:       16318680 kB\n\
+                   Node 1 MemFree:         1024000 kB\n\
+                   Node 1 MemUsed:        15294680 kB\n";
+    assert_eq!(
+        parse_node_meminfo(meminfo),
+        (16318680 * 1024, 1024000 * 1024)
+    );
+
+    let mut system_monitor = SystemMonitor::new().expect("Failed to create system monitor");
+
+    match system_monitor.get_numa_topology() {
+        Ok(nodes) => {
+            // Even single-node hosts report one node
+            assert!(!nodes.is_empty());
+            assert!(nodes.windows(2).all(|pair| pair[0].id < pair[1].id));
+            for node in &nodes {
+                assert!(node.mem_free <= node.mem_total);
+            }
+            let mut cpus: Vec<u32> = nodes.iter().flat_map(|node| node.cpus.clone()).collect();
+            let count = cpus.len();
+            cpus.sort_unstable();
+            cpus.dedup();
+            assert_eq!(cpus.len(), count, "a CPU belongs to a single node");
+        }
+        Err(e) => {
+            println!(
+                "NUMA topology collection failed (expected on non-Linux): {}",
+                e
+            );
+        }
+    }
+}
+
+#[tokio::test]
+#[cfg(unix)]
+async fn test_directory_usage() {
+    use mcp_system_monitor::system_monitor::dir_usage::get_directory_usage;
+    use mcp_system_monitor::types::METHOD_GET_DIRECTORY_USAGE;
+
+    let root = std::env::temp_dir().join(format!("mcp-system-monitor-du-{}", std::process::id()));
+    std::fs::create_dir_all(root.join("a/deep")).unwrap();
+    std::fs::create_dir_all(root.join("b")).unwrap();
+    std::fs::write(root.join("a/one"), [0u8; 100]).unwrap();
+    std::fs::write(root.join("a/deep/two"), [0u8; 50]).unwrap();
+    std::fs::write(root.join("b/three"), [0u8; 10]).unwrap();
+    std::fs::write(root.join("four"), [0u8; 5]).unwrap();
+    // A link back to the root must not be followed
+    std::os::unix::fs::symlink(&root, root.join("b/loop")).unwrap();
+
+    let usage = get_directory_usage(&root, 1, 10, false, u64::MAX).unwrap();
+    assert_eq!(usage.size_bytes, 165);
+    assert!(!usage.truncated);
+    let sizes: Vec<(u32, u64)> = usage
+        .entries
+        .iter()
+        .map(|entry| (entry.depth, entry.size_bytes))
+        .collect();
+    assert_eq!(sizes, [(1, 150), (1, 10)]);
+    assert!(usage.entries[0].path.ends_with("/a"));
+    assert!(usage.entries.iter().all(|entry| !entry.inaccessible));
+
+    let usage = get_directory_usage(&root, 2, 2, false, u64::MAX).unwrap();
+    assert_eq!(usage.entries.len(), 2);
+    assert_eq!(usage.entries[1].size_bytes, 50);
+    assert!(usage.entries[1].path.ends_with("/a/deep"));
+
+    let usage = get_directory_usage(&root, 1, 10, false, 2).unwrap();
+    assert!(usage.truncated);
+    assert_eq!(usage.files_scanned, 2);
+
+    // The walk does not wait for the monitor lock held by a collection
+    let system_monitor = create_test_system_monitor().await;
+    let server =
+        MCPServer::new(system_monitor.clone()).with_collection_timeout(Duration::from_millis(500));
+    let guard = system_monitor.write().await;
+    let request = MCPRequest {
+        jsonrpc: "2.0".to_string(),
+        id: Some("test-dir-usage".to_string()),
+        method: METHOD_GET_DIRECTORY_USAGE.to_string(),
+        params: json!({ "path": root, "depth": 0 }),
+    };
+    let response = server.handle_request(request).await;
+    let result = response.result.expect("directory usage should be returned");
+    assert_eq!(result["size_bytes"], 165);
+    assert!(result["entries"].as_array().unwrap().is_empty());
+    drop(guard);
+
+    std::fs::remove_dir_all(&root).unwrap();
+
+    // The path is required and must exist
+    for (params, code) in [
+        (json!({}), ERROR_INVALID_PARAMS),
+        (json!({ "path": root }), ERROR_PROCESS_NOT_FOUND),
+    ] {
+        let request = MCPRequest {
+            jsonrpc: "2.0".to_string(),
+            id: Some("test-dir-usage-invalid".to_string()),
+            method: METHOD_GET_DIRECTORY_USAGE.to_string(),
+            params,
+        };
+        let response = server.handle_request(request).await;
+        assert_eq!(response.error.unwrap().code, code);
+    }
+}
+
 #[tokio::test]
 async fn test_network_info_collection() {
     let mut system_monitor = SystemMonitor::new().expect("Failed to create system monitor");
 
     // Test getting network info - this might fail on non-Linux systems
-    match system_monitor.get_network_info() {
+    match system_monitor.get_network_info(&NetworkQuery::default()) {
         Ok(networks) => {
             // Verify we have at least one network interface
             assert!(!networks.is_empty());
 
             for network in networks {
                 assert!(!network.interface.is_empty());
+                assert_ne!(network.interface, "lo");
                 // Other fields might be "N/A" on some systems, so we just check they exist
                 assert!(!network.ip_address.is_empty());
+                assert!(network
+                    .ipv6_addresses
+                    .iter()
+                    .all(|address| !is_link_local_ipv6(address)));
                 assert!(!network.mac_address.is_empty());
             }
         }
@@ -330,12 +806,16 @@ async fn test_system_metrics_collection() {
     let mut system_monitor = SystemMonitor::new().expect("Failed to create system monitor");
 
     // Test getting complete system metrics - this might fail on non-Linux systems
-    match system_monitor.get_system_metrics() {
+    match system_monitor
+        .get_system_metrics(MetricsQuery::default())
+        .await
+    {
         Ok(metrics) => {
             // Verify all components are present
-            assert!(!metrics.system_info.hostname.is_empty());
-            assert!(!metrics.cpu_info.name.is_empty());
-            assert!(metrics.memory_info.total > 0);
+            assert!(!metrics.system_info.unwrap().hostname.is_empty());
+            assert!(!metrics.cpu_info.unwrap().name.is_empty());
+            assert!(metrics.memory_info.unwrap().total > 0);
+            assert!(metrics.load_average.unwrap().total > 0);
             assert!(!metrics.disks.is_empty());
             assert!(!metrics.networks.is_empty());
             assert!(!metrics.processes.is_empty());
@@ -360,7 +840,14 @@ async fn test_process_by_pid() {
             // Process 1 should exist on Linux systems
             if let Some(process) = process {
                 assert_eq!(process.pid, 1);
+                assert_eq!(process.ppid, 0);
                 assert!(!process.name.is_empty());
+                #[cfg(not(feature = "sysinfo-backend"))]
+                {
+                    use mcp_system_monitor::types::SchedPolicy;
+                    assert_eq!(process.sched_policy, Some(SchedPolicy::Other));
+                    assert_eq!(process.rt_priority, Some(0));
+                }
             }
         }
         Err(e) => {
@@ -394,7 +881,7 @@ async fn test_mcp_error_handling() {
     // Test with invalid parameters
     let request = MCPRequest {
         jsonrpc: "2.0".to_string(),
-        id: "test-error".to_string(),
+        id: Some("test-error".to_string()),
         method: METHOD_GET_SYSTEM_INFO.to_string(),
         params: json!({"invalid": "params"}), // Extra params should be ignored
     };
@@ -412,7 +899,7 @@ async fn test_mcp_response_format() {
 
     let request = MCPRequest {
         jsonrpc: "2.0".to_string(),
-        id: "test-format".to_string(),
+        id: Some("test-format".to_string()),
         method: METHOD_GET_SYSTEM_INFO.to_string(),
         params: json!({}),
     };
@@ -421,7 +908,4234 @@ async fn test_mcp_response_format() {
 
     // Verify JSON-RPC 2.0 compliance
     assert_eq!(response.jsonrpc, "2.0");
-    assert_eq!(response.id, "test-format");
+    assert_eq!(response.id.as_deref(), Some("test-format"));
     assert!(response.error.is_none() || response.result.is_none()); // XOR
     assert!(!(response.error.is_some() && response.result.is_some())); // Not both
 }
+
+#[test]
+fn test_parse_diskstats() {
+    let content = "\
+   7       0 loop0 0 0 0 0 0 0 0 0 0 0 0 0 0 0 0 0 0
+   8       0 sda 12000 300 960000 4500 8000 200 640000 9000 0 7000 13500 0 0 0 0 0 0
+ 259       0 nvme0n1 500 0 4096 100 250 0 2048 50 0 120 150 0 0 0 0
+";
+
+    let stats = parse_diskstats(content);
+
+    // Idle loop device is skipped
+    assert_eq!(stats.len(), 2);
+
+    let sda = &stats[0];
+    assert_eq!(sda.device, "sda");
+    assert_eq!(sda.reads_completed, 12000);
+    assert_eq!(sda.writes_completed, 8000);
+    assert_eq!(sda.sectors_read, 960000);
+    assert_eq!(sda.sectors_written, 640000);
+    assert_eq!(sda.read_bytes, 960000 * 512);
+    assert_eq!(sda.write_bytes, 640000 * 512);
+
+    assert_eq!(stats[1].device, "nvme0n1");
+    assert_eq!(stats[1].read_bytes, 4096 * 512);
+}
+
+#[test]
+fn test_calculate_disk_io_rates() {
+    let before = parse_diskstats("8 0 sda 10 0 100 0 10 0 200 0 0 0 0\n");
+    let after = parse_diskstats("8 0 sda 20 0 300 0 20 0 200 0 0 0 0\n");
+
+    let rates = calculate_disk_io_rates(&before, &after, std::time::Duration::from_secs(2));
+
+    assert_eq!(rates.len(), 1);
+    assert_eq!(rates[0].device, "sda");
+    assert_eq!(rates[0].read_bytes_per_sec, (200.0 * 512.0) / 2.0);
+    assert_eq!(rates[0].write_bytes_per_sec, 0.0);
+
+    // A counter reset must not produce a bogus huge rate
+    let reset = calculate_disk_io_rates(&after, &before, std::time::Duration::from_secs(2));
+    assert_eq!(reset[0].read_bytes_per_sec, 0.0);
+}
+
+#[test]
+fn test_temperature_helpers() {
+    assert_eq!(parse_millidegrees("45000\n"), Some(45.0));
+    assert_eq!(parse_millidegrees("-5500"), Some(-5.5));
+    assert_eq!(parse_millidegrees("N/A"), None);
+
+    let sensor = |chip: &str, label: &str, celsius: f32| TemperatureSensor {
+        chip: chip.to_string(),
+        label: label.to_string(),
+        celsius,
+    };
+    let sensors = vec![
+        sensor("nvme", "Composite", 38.0),
+        sensor("coretemp", "Core 0", 50.0),
+        sensor("coretemp", "Package id 0", 55.0),
+    ];
+    assert_eq!(package_temperature(&sensors), Some(55.0));
+    assert_eq!(
+        package_temperature(&[sensor("k10temp", "Tctl", 61.5)]),
+        Some(61.5)
+    );
+    assert_eq!(package_temperature(&sensors[..2]), None);
+}
+
+#[test]
+fn test_parse_vcgencmd_throttled() {
+    assert_eq!(
+        parse_vcgencmd_throttled("throttled=0x50005\n"),
+        Some(0x50005)
+    );
+    assert_eq!(parse_vcgencmd_throttled("throttled=0x0"), Some(0));
+    assert_eq!(
+        parse_vcgencmd_throttled("error=1 error_msg=\"Command not registered\""),
+        None
+    );
+    assert_eq!(parse_vcgencmd_throttled("throttled=0xzz"), None);
+}
+
+#[tokio::test]
+async fn test_mcp_get_temperatures() {
+    let server = create_test_mcp_server().await;
+
+    let request = MCPRequest {
+        jsonrpc: "2.0".to_string(),
+        id: Some("test-temperatures".to_string()),
+        method: METHOD_GET_TEMPERATURES.to_string(),
+        params: json!({}),
+    };
+
+    let response = server.handle_request(request).await;
+
+    assert!(response.error.is_none());
+    // Virtual machines and containers often expose no sensors at all
+    let result = response.result.expect("Result should be present");
+    for sensor in result.as_array().expect("Result should be an array") {
+        assert!(sensor.get("chip").is_some());
+        assert!(sensor.get("label").is_some());
+        assert!(sensor["celsius"].is_number());
+    }
+}
+
+#[test]
+fn test_estimate_battery_time() {
+    // 30 Wh left at 15 W lasts two hours
+    assert_eq!(
+        estimate_battery_time("Discharging", 30_000_000, 50_000_000, 15_000_000),
+        Some(7200)
+    );
+    // 20 Wh to go at 40 W takes half an hour
+    assert_eq!(
+        estimate_battery_time("Charging", 30_000_000, 50_000_000, 40_000_000),
+        Some(1800)
+    );
+    assert_eq!(
+        estimate_battery_time("Discharging", 30_000_000, 50_000_000, 0),
+        None
+    );
+    assert_eq!(
+        estimate_battery_time("Full", 50_000_000, 50_000_000, 1_000_000),
+        None
+    );
+}
+
+#[tokio::test]
+async fn test_mcp_get_battery_info() {
+    let server = create_test_mcp_server().await;
+
+    let request = MCPRequest {
+        jsonrpc: "2.0".to_string(),
+        id: Some("test-battery".to_string()),
+        method: METHOD_GET_BATTERY_INFO.to_string(),
+        params: json!({}),
+    };
+
+    let response = server.handle_request(request).await;
+
+    assert!(response.error.is_none());
+    let result = response.result.expect("Result should be present");
+    let present = result["present"]
+        .as_bool()
+        .expect("present should be a bool");
+    if !present {
+        assert!(result["percent"].is_null());
+        assert!(result["time_remaining_secs"].is_null());
+    }
+}
+
+#[test]
+fn test_parse_nvidia_smi_csv() {
+    let output = "0, NVIDIA A100-SXM4-40GB, 87, 30512, 40960, 64\n\
+                  1, NVIDIA GeForce GTX 1080, [N/A], 1024, 8192, [Not Supported]\n\
+                  garbage line\n";
+
+    let gpus = parse_nvidia_smi_csv(output);
+    assert_eq!(gpus.len(), 2);
+
+    assert_eq!(gpus[0].index, 0);
+    assert_eq!(gpus[0].name, "NVIDIA A100-SXM4-40GB");
+    assert_eq!(gpus[0].utilization_percent, Some(87.0));
+    assert_eq!(gpus[0].memory_used, Some(30512 * 1024 * 1024));
+    assert_eq!(gpus[0].memory_total, Some(40960 * 1024 * 1024));
+    assert_eq!(gpus[0].temperature, Some(64.0));
+
+    assert_eq!(gpus[1].utilization_percent, None);
+    assert_eq!(gpus[1].temperature, None);
+    assert_eq!(gpus[1].memory_total, Some(8192 * 1024 * 1024));
+}
+
+#[tokio::test]
+async fn test_mcp_get_gpu_info() {
+    let server = create_test_mcp_server().await;
+
+    let request = MCPRequest {
+        jsonrpc: "2.0".to_string(),
+        id: Some("test-gpu".to_string()),
+        method: METHOD_GET_GPU_INFO.to_string(),
+        params: json!({}),
+    };
+
+    let response = server.handle_request(request).await;
+
+    // Hosts without nvidia-smi get an empty list, not an error
+    assert!(response.error.is_none());
+    let result = response.result.expect("Result should be present");
+    assert!(result.is_array());
+}
+
+#[test]
+fn test_parse_smartctl_json() {
+    let scan = r#"{"devices": [
+        {"name": "/dev/sda", "info_name": "/dev/sda [SAT]", "type": "sat", "protocol": "ATA"},
+        {"name": "/dev/nvme0", "info_name": "/dev/nvme0", "type": "nvme", "protocol": "NVMe"}
+    ]}"#;
+    assert_eq!(
+        parse_smartctl_scan(scan).unwrap(),
+        vec![
+            ("/dev/sda".to_string(), "sat".to_string()),
+            ("/dev/nvme0".to_string(), "nvme".to_string()),
+        ]
+    );
+    assert!(parse_smartctl_scan("{}").unwrap().is_empty());
+
+    let ata = r#"{
+        "device": {"name": "/dev/sda", "type": "sat", "protocol": "ATA"},
+        "smart_status": {"passed": false},
+        "ata_smart_attributes": {"table": [
+            {"id": 1, "name": "Raw_Read_Error_Rate", "raw": {"value": 0}},
+            {"id": 5, "name": "Reallocated_Sector_Ct", "raw": {"value": 24}}
+        ]},
+        "power_on_time": {"hours": 31337},
+        "temperature": {"current": 41}
+    }"#;
+    let info = parse_smartctl_json("/dev/sda", ata).unwrap();
+    assert_eq!(info.device, "/dev/sda");
+    assert_eq!(info.health_passed, Some(false));
+    assert_eq!(info.temperature, Some(41.0));
+    assert_eq!(info.power_on_hours, Some(31337));
+    assert_eq!(info.reallocated_sectors, Some(24));
+
+    let nvme = r#"{"smart_status": {"passed": true}, "temperature": {"current": 35}}"#;
+    let info = parse_smartctl_json("/dev/nvme0", nvme).unwrap();
+    assert_eq!(info.device, "/dev/nvme0");
+    assert_eq!(info.health_passed, Some(true));
+    assert_eq!(info.reallocated_sectors, None);
+
+    let denied = r#"{"smartctl": {"messages": [{
+        "string": "Smartctl open device: /dev/sda failed: Permission denied",
+        "severity": "error"
+    }]}}"#;
+    assert!(matches!(
+        parse_smartctl_json("/dev/sda", denied),
+        Err(MonitorError::PermissionDenied(_))
+    ));
+
+    let virtual_disk = r#"{"smartctl": {"messages": [{
+        "string": "/dev/vda: Unable to detect device type",
+        "severity": "error"
+    }]}}"#;
+    assert!(matches!(
+        parse_smartctl_json("/dev/vda", virtual_disk),
+        Err(MonitorError::Unsupported(_))
+    ));
+    assert!(matches!(
+        parse_smartctl_json("/dev/sda", "not json"),
+        Err(MonitorError::ParseError(_))
+    ));
+}
+
+#[tokio::test]
+async fn test_mcp_get_disk_smart() {
+    let server = create_test_mcp_server().await;
+
+    let request = MCPRequest {
+        jsonrpc: "2.0".to_string(),
+        id: Some("test-smart".to_string()),
+        method: METHOD_GET_DISK_SMART.to_string(),
+        params: json!({}),
+    };
+
+    let response = server.handle_request(request).await;
+
+    // Without smartctl or root the caller learns which one is missing
+    match response.error {
+        Some(error) => assert!(
+            error.code == ERROR_SYSTEM_COMMAND_FAILED || error.code == ERROR_PERMISSION_DENIED,
+            "{:?}",
+            error
+        ),
+        None => assert!(response
+            .result
+            .expect("Result should be present")
+            .is_array()),
+    }
+}
+
+#[tokio::test]
+async fn test_mcp_get_disk_io() {
+    let server = create_test_mcp_server().await;
+
+    let request = MCPRequest {
+        jsonrpc: "2.0".to_string(),
+        id: Some("test-disk-io".to_string()),
+        method: METHOD_GET_DISK_IO.to_string(),
+        params: json!({}),
+    };
+
+    let response = server.handle_request(request).await;
+
+    assert_eq!(response.id.as_deref(), Some("test-disk-io"));
+    if let Some(error) = response.error {
+        // /proc/diskstats may be unavailable (non-Linux, restricted containers)
+        assert!(error.code != -32601);
+    } else {
+        let result = response.result.expect("Result should be present");
+        for device in result.as_array().expect("Result should be an array") {
+            assert!(device.get("device").is_some());
+            assert!(device.get("read_bytes").is_some());
+            assert!(device.get("write_bytes").is_some());
+        }
+    }
+}
+
+#[test]
+fn test_parse_systemd_unit() {
+    // cgroup v2 unified hierarchy
+    assert_eq!(
+        parse_systemd_unit("0::/system.slice/nginx.service\n").as_deref(),
+        Some("nginx.service")
+    );
+
+    // User session scope
+    assert_eq!(
+        parse_systemd_unit("0::/user.slice/user-1000.slice/session-2.scope\n").as_deref(),
+        Some("session-2.scope")
+    );
+
+    // Nested user manager unit reports the innermost unit
+    assert_eq!(
+        parse_systemd_unit(
+            "0::/user.slice/user-1000.slice/user@1000.service/app.slice/app-foo.service\n"
+        )
+        .as_deref(),
+        Some("app-foo.service")
+    );
+
+    // cgroup v1 picks the name=systemd hierarchy
+    let v1 = "12:cpu,cpuacct:/\n1:name=systemd:/system.slice/sshd.service\n";
+    assert_eq!(parse_systemd_unit(v1).as_deref(), Some("sshd.service"));
+
+    // Root cgroup or non-systemd container paths have no unit
+    assert_eq!(parse_systemd_unit("0::/\n"), None);
+    assert_eq!(parse_systemd_unit("0::/docker/3f1c2a\n"), None);
+
+    // The whole path is available on its own
+    assert_eq!(parse_cgroup_path(v1), Some("/system.slice/sshd.service"));
+    assert_eq!(
+        parse_cgroup_path("0::/docker/3f1c2a\n"),
+        Some("/docker/3f1c2a")
+    );
+    assert_eq!(parse_cgroup_path("4:memory:/user.slice\n"), None);
+}
+
+#[test]
+fn test_parse_process_session_tty() {
+    let stat =
+        "1234 (tmux: server) S 1 1234 1234 34816 1234 4194560 500 0 0 0 10 5 0 0 20 0 1 0 100 0";
+    let (session, tty_nr) = parse_process_session_tty(stat).unwrap();
+    assert_eq!(session, 1234);
+    assert_eq!(tty_name(tty_nr), "pts/0");
+    assert!(parse_process_session_tty("1234 (sh) S 1").is_none());
+    // The command name may itself contain ") "
+    assert_eq!(
+        parse_process_session_tty("1234 (a) b) S 1 1234 77 0 1234"),
+        Some((77, 0))
+    );
+
+    assert_eq!(tty_name(0), "?");
+    assert_eq!(tty_name((136 << 8) | 7), "pts/7");
+    assert_eq!(tty_name((137 << 8) | 2), "pts/258");
+    // Minors above 255 are split around the major
+    assert_eq!(tty_name((136 << 8) | (1 << 20) | 4), "pts/260");
+    assert_eq!(tty_name((4 << 8) | 1), "tty1");
+    assert_eq!(tty_name((4 << 8) | 64), "ttyS0");
+    assert_eq!(tty_name((188 << 8) | 1), "188:1");
+}
+
+#[test]
+fn test_parse_process_sched() {
+    use mcp_system_monitor::types::SchedPolicy;
+
+    // Fields 39-41 are the processor, rt_priority and policy
+    let stat = |processor_rt_policy: &str| {
+        format!(
+            "12 (migration/0) S 2 0 0 0 -1 69238848 0 0 0 0 0 31 0 0 -100 0 1 0 3 0 0 \
+             18446744073709551615 0 0 0 0 0 0 0 2147483647 0 0 0 0 17 {} 0 0 0 0 0 0 0 0 0 0 0",
+            processor_rt_policy
+        )
+    };
+    assert_eq!(
+        parse_process_sched(&stat("0 99 1")),
+        Some((SchedPolicy::Fifo, 99))
+    );
+    assert_eq!(
+        parse_process_sched(&stat("3 0 0")),
+        Some((SchedPolicy::Other, 0))
+    );
+    assert_eq!(
+        parse_process_sched(&stat("1 0 5")),
+        Some((SchedPolicy::Idle, 0))
+    );
+    assert!(SchedPolicy::RoundRobin.is_realtime());
+    assert!(!SchedPolicy::Batch.is_realtime());
+
+    // Unknown policies and truncated lines
+    assert_eq!(parse_process_sched(&stat("0 0 4")), None);
+    assert_eq!(parse_process_sched("1234 (sh) S 1 1234"), None);
+
+    assert_eq!(
+        serde_json::to_value(SchedPolicy::RoundRobin).unwrap(),
+        json!("SCHED_RR")
+    );
+}
+
+#[test]
+fn test_parse_cmdline() {
+    assert_eq!(
+        parse_cmdline(b"java\0-Xmx2g\0-jar\0app.jar\0").as_deref(),
+        Some("java -Xmx2g -jar app.jar")
+    );
+    // Without a trailing NUL, and with an empty argument
+    assert_eq!(parse_cmdline(b"node\0\0x").as_deref(), Some("node  x"));
+    // Kernel threads and zombies have no command line
+    assert_eq!(parse_cmdline(b""), None);
+}
+
+#[test]
+#[cfg(target_os = "linux")]
+fn test_process_by_pid_full_command_line() {
+    let long_arg = format!("--classpath={}", "lib/dependency.jar:".repeat(40));
+    let mut child = std::process::Command::new("sh")
+        .args(["-c", "sleep 30; true", "sh", &long_arg])
+        .spawn()
+        .expect("Failed to spawn child");
+
+    let mut monitor = SystemMonitor::new().expect("Failed to create system monitor");
+    let process = monitor.get_process_by_pid(child.id());
+    child.kill().unwrap();
+    child.wait().unwrap();
+
+    let process = process.unwrap().expect("child should be running");
+    assert!(
+        process.command.ends_with(&long_arg),
+        "truncated: {}",
+        process.command
+    );
+}
+
+#[test]
+fn test_parse_status_value() {
+    let status = "Name:\tjava\nThreads:\t42\nVmRSS:\t  123456 kB\nSigQ:\t0/31\n";
+    assert_eq!(parse_status_value(status, "Threads"), Some(42));
+    assert_eq!(parse_status_value(status, "VmRSS"), Some(123456));
+    assert_eq!(parse_status_value(status, "Name"), None);
+    assert_eq!(parse_status_value(status, "VmSwap"), None);
+}
+
+#[test]
+fn test_parse_process_stat() {
+    let boot = chrono::DateTime::from_timestamp(1_700_000_000, 0).unwrap();
+    // The command name contains a space and a closing parenthesis
+    let stat = "4242 (tmux: server) R) S 1 4242 4242 0 -1 4194560 1503 0 0 0 250 125 0 0 20 0 1 0 1234567 12345678 1024 18446744073709551615\n";
+
+    let (cpu_time, start_time) = parse_process_stat(stat, boot, 100).unwrap();
+    assert_eq!(cpu_time, 3.75);
+    assert_eq!(
+        start_time,
+        boot + chrono::Duration::milliseconds(12_345_670)
+    );
+
+    assert!(parse_process_stat("4242 (short) S 1 2 3", boot, 100).is_none());
+    assert!(parse_process_stat(stat, boot, 0).is_none());
+}
+
+#[test]
+fn test_parse_process_io() {
+    let io = parse_process_io(
+        "rchar: 323934931
+wchar: 323929600
+syscr: 632687
+syscw: 632675
+read_bytes: 4096
+write_bytes: 323932160
+cancelled_write_bytes: 0
+",
+    );
+    assert_eq!(io.read_bytes, 4096);
+    assert_eq!(io.write_bytes, 323932160);
+    assert_eq!(io.read_syscalls, 632687);
+    assert_eq!(io.write_syscalls, 632675);
+
+    // Kernels without task I/O accounting only report the character counts
+    let io = parse_process_io("rchar: 100\nwchar: 50\nsyscr: 3\nsyscw: 2\n");
+    assert_eq!((io.read_bytes, io.write_bytes, io.read_syscalls), (0, 0, 3));
+}
+
+#[test]
+fn test_parse_process_memory() {
+    let memory = parse_process_memory(
+        42,
+        "Name:\tpostgres
+VmPeak:\t  230512 kB
+VmSize:\t  228400 kB
+VmRSS:\t   51200 kB
+RssAnon:\t   20480 kB
+RssFile:\t   28672 kB
+RssShmem:\t    2048 kB
+VmData:\t   40960 kB
+VmStk:\t     132 kB
+VmExe:\t    6144 kB
+VmLib:\t   12288 kB
+VmSwap:\t       0 kB
+Threads:\t1
+",
+    );
+    assert_eq!(memory.pid, 42);
+    assert_eq!(memory.vm_size, Some(228400 * 1024));
+    assert_eq!(memory.vm_rss, Some(51200 * 1024));
+    assert_eq!(memory.rss_anon, Some(20480 * 1024));
+    assert_eq!(memory.rss_file, Some(28672 * 1024));
+    assert_eq!(memory.rss_shmem, Some(2048 * 1024));
+    assert_eq!(memory.vm_stk, Some(132 * 1024));
+    assert_eq!(memory.vm_swap, Some(0));
+
+    // Kernel threads have no Vm* lines, and kernels before 4.5 no Rss* lines
+    let memory = parse_process_memory(2, "Name:\tkthreadd\nState:\tS (sleeping)\n");
+    assert_eq!(
+        memory,
+        ProcessMemory {
+            pid: 2,
+            ..Default::default()
+        }
+    );
+    let memory = parse_process_memory(7, "VmSize:\t 1000 kB\nVmRSS:\t 500 kB\n");
+    assert_eq!(memory.vm_rss, Some(500 * 1024));
+    assert_eq!((memory.rss_anon, memory.vm_swap), (None, None));
+}
+
+#[test]
+fn test_parse_process_limits() {
+    let limits = parse_process_limits(
+        42,
+        "Limit                     Soft Limit           Hard Limit           Units     
+Max cpu time              unlimited            unlimited            seconds   
+Max stack size            8388608              unlimited            bytes     
+Max processes             23960                23960                processes 
+Max open files            1024                 524288               files     
+Max nice priority         0                    0                    
+Max realtime timeout      unlimited            unlimited            us        
+",
+    );
+    assert_eq!(limits.pid, 42);
+    assert_eq!(limits.limits.len(), 6);
+
+    let limit = |name: &str| {
+        limits
+            .limits
+            .iter()
+            .find(|limit| limit.name == name)
+            .unwrap()
+    };
+    let open_files = limit("Max open files");
+    assert_eq!(
+        (open_files.soft, open_files.hard),
+        (Some(1024), Some(524288))
+    );
+    assert_eq!(open_files.units.as_deref(), Some("files"));
+    let stack = limit("Max stack size");
+    assert_eq!((stack.soft, stack.hard), (Some(8388608), None));
+    let cpu = limit("Max cpu time");
+    assert_eq!((cpu.soft, cpu.hard), (None, None));
+    assert_eq!(cpu.units.as_deref(), Some("seconds"));
+    let nice = limit("Max nice priority");
+    assert_eq!(
+        (nice.soft, nice.hard, nice.units.as_deref()),
+        (Some(0), Some(0), None)
+    );
+
+    // Column widths are not relied on, and malformed lines are skipped
+    let limits = parse_process_limits(
+        7,
+        "Max open files 64 unlimited files\nMax processes\nMax locked memory 65536\n",
+    );
+    assert_eq!(limits.limits.len(), 1);
+    assert_eq!(limits.limits[0].name, "Max open files");
+    assert_eq!(limits.limits[0].hard, None);
+}
+
+#[test]
+#[cfg(all(target_os = "linux", not(feature = "sysinfo-backend")))]
+fn test_process_fds_and_threads() {
+    use mcp_system_monitor::config::CollectorConfig;
+
+    let pid = std::process::id();
+    let mut monitor = SystemMonitor::new().expect("Failed to create system monitor");
+    let own = monitor.get_process_by_pid(pid).unwrap().unwrap();
+    assert!(own.open_fds >= 3, "stdio should be open: {}", own.open_fds);
+    assert!(own.threads >= 1);
+    // The start time comes from /proc/<pid>/stat, so it is not rounded to seconds
+    assert!(own.cpu_time_secs.is_some());
+    assert!(own.start_time <= chrono::Utc::now());
+    assert!(own.start_time > chrono::Utc::now() - chrono::Duration::hours(1));
+
+    // Listings skip the fd scan unless enabled
+    let listed = |monitor: &mut SystemMonitor| {
+        let processes = monitor.get_processes().unwrap();
+        processes
+            .into_iter()
+            .find(|process| process.pid == pid)
+            .unwrap()
+    };
+    assert_eq!(listed(&mut monitor).open_fds, 0);
+    assert!(listed(&mut monitor).threads >= 1);
+    monitor.set_collectors(CollectorConfig {
+        process_fds: true,
+        ..CollectorConfig::default()
+    });
+    assert!(listed(&mut monitor).open_fds >= 3);
+
+    // The own /proc/<pid>/io is always readable and has seen some syscalls
+    assert!(own.read_syscalls > 0 && own.write_syscalls > 0);
+    let io_of = |monitor: &mut SystemMonitor, include_io| {
+        let query = ProcessQuery {
+            include_io,
+            ..ProcessQuery::default()
+        };
+        let processes = monitor.query_processes(&query).unwrap();
+        let own = processes.iter().find(|process| process.pid == pid).unwrap();
+        own.read_syscalls
+    };
+    assert_eq!(io_of(&mut monitor, false), 0);
+    assert!(io_of(&mut monitor, true) > 0);
+
+    // Supplemental fields come from /proc only when asked for
+    let query = ProcessQuery {
+        fields: ProcessField::ALL.into_iter().collect(),
+        ..ProcessQuery::default()
+    };
+    let processes = monitor.query_processes(&query).unwrap();
+    let own = processes.iter().find(|process| process.pid == pid).unwrap();
+    assert!(own.extra["session"].parse::<u32>().is_ok());
+    assert!(!own.extra["wchan"].is_empty());
+    assert!(!own.extra["tty"].is_empty());
+    assert!(own.extra["cgroup"].starts_with('/'));
+    let processes = monitor.query_processes(&ProcessQuery::default()).unwrap();
+    assert!(processes.iter().all(|process| process.extra.is_empty()));
+}
+
+#[test]
+#[cfg(target_endian = "little")]
+fn test_parse_proc_net_sockets() {
+    let tcp = "  sl  local_address rem_address   st tx_queue rx_queue tr tm->when retrnsmt   uid  timeout inode
+   0: 0100007F:0035 00000000:0000 0A 00000000:00000000 00:00000000 00000000   101        0 21456 1 0000000000000000 100 0 0 10 0
+   1: 0F02000A:0016 0102000A:C350 01 00000000:00000000 02:000A7D2B 00000000     0        0 33712 4 0000000000000000 20 4 30 10 -1
+";
+    let connections = parse_proc_net_sockets(tcp, "tcp");
+    assert_eq!(connections.len(), 2);
+    assert_eq!(connections[0].local_addr.to_string(), "127.0.0.1");
+    assert_eq!(connections[0].local_port, 53);
+    assert_eq!(connections[0].remote_addr.to_string(), "0.0.0.0");
+    assert_eq!(connections[0].state, "LISTEN");
+    assert_eq!(connections[0].inode, 21456);
+    assert_eq!(connections[1].local_addr.to_string(), "10.0.2.15");
+    assert_eq!(connections[1].remote_addr.to_string(), "10.0.2.1");
+    assert_eq!(connections[1].remote_port, 50000);
+    assert_eq!(connections[1].state, "ESTABLISHED");
+
+    let tcp6 = "  sl  local_address                         remote_address                        st tx_queue rx_queue tr tm->when retrnsmt   uid  timeout inode
+   0: 00000000000000000000000001000000:1F90 00000000000000000000000000000000:0000 0A 00000000:00000000 00:00000000 00000000  1000        0 40001 1 0000000000000000 100 0 0 10 0
+   1: B80D01200000000000000000EFBEADDE:01BB 00000000000000000000000000000000:0000 0A 00000000:00000000 00:00000000 00000000     0        0 40002 1 0000000000000000 100 0 0 10 0
+";
+    let connections = parse_proc_net_sockets(tcp6, "tcp6");
+    assert_eq!(connections[0].local_addr.to_string(), "::1");
+    assert_eq!(connections[0].local_port, 8080);
+    assert_eq!(connections[1].local_addr.to_string(), "2001:db8::dead:beef");
+    assert_eq!(connections[1].proto, "tcp6");
+
+    let mut counts = std::collections::HashMap::new();
+    tally_tcp_states(tcp, &mut counts);
+    tally_tcp_states(tcp6, &mut counts);
+    assert_eq!(counts.len(), 2);
+    assert_eq!(counts["LISTEN"], 3);
+    assert_eq!(counts["ESTABLISHED"], 1);
+
+    // Unbound UDP sockets are reported in the CLOSE state
+    let udp = "   sl  local_address rem_address   st tx_queue rx_queue tr tm->when retrnsmt   uid  timeout inode ref pointer drops
+  123: 00000000:0044 00000000:0000 07 00000000:00000000 00:00000000 00000000     0        0 18000 2 0000000000000000 0
+";
+    assert_eq!(parse_proc_net_sockets(udp, "udp")[0].state, "UNCONN");
+
+    // Malformed lines are skipped
+    assert!(parse_proc_net_sockets("header\n   0: zz:0035 00000000:0000 0A\n", "tcp").is_empty());
+}
+
+#[tokio::test]
+#[cfg(all(target_os = "linux", not(feature = "sysinfo-backend")))]
+async fn test_mcp_get_network_connections() {
+    use mcp_system_monitor::types::{METHOD_GET_NETWORK_CONNECTIONS, METHOD_GET_SOCKET_SUMMARY};
+
+    let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+    let port = listener.local_addr().unwrap().port();
+    let server = create_test_mcp_server().await;
+
+    let request = MCPRequest {
+        jsonrpc: "2.0".to_string(),
+        id: Some("test-connections".to_string()),
+        method: METHOD_GET_NETWORK_CONNECTIONS.to_string(),
+        params: json!({ "state": "listen" }),
+    };
+    let response = server.handle_request(request).await;
+    let result = response.result.expect("connections should be returned");
+    let connections = result.as_array().unwrap();
+    assert!(connections.iter().all(|c| c["state"] == "LISTEN"));
+    assert!(
+        connections
+            .iter()
+            .any(|c| c["local_port"] == port && c["local_addr"] == "127.0.0.1"),
+        "listener on port {} not found",
+        port
+    );
+
+    let request = MCPRequest {
+        jsonrpc: "2.0".to_string(),
+        id: Some("test-socket-summary".to_string()),
+        method: METHOD_GET_SOCKET_SUMMARY.to_string(),
+        params: json!({}),
+    };
+    let response = server.handle_request(request).await;
+    let summary = response.result.expect("socket summary should be returned");
+    assert!(summary["LISTEN"].as_u64().unwrap() >= 1);
+}
+
+#[test]
+fn test_counter_rate() {
+    let elapsed = std::time::Duration::from_secs(4);
+
+    assert_eq!(counter_rate(1000, 5000, elapsed), 1000.0);
+    // Wraparound / reset is reported as 0 rather than a huge value
+    assert_eq!(counter_rate(u32::MAX as u64 - 10, 20, elapsed), 0.0);
+    // No elapsed time cannot produce a rate
+    assert_eq!(counter_rate(0, 100, std::time::Duration::ZERO), 0.0);
+}
+
+#[tokio::test]
+async fn test_network_rates_first_call_has_no_rates() {
+    let mut system_monitor = SystemMonitor::new().expect("Failed to create system monitor");
+
+    match system_monitor.get_network_rates() {
+        Ok(first) => {
+            for rate in &first {
+                assert!(rate.rx_bytes_per_sec.is_none());
+                assert!(rate.tx_bytes_per_sec.is_none());
+            }
+
+            let second = system_monitor
+                .get_network_rates()
+                .expect("Second sample should succeed");
+            for rate in &second {
+                assert!(rate.rx_bytes_per_sec.is_some_and(|r| r >= 0.0));
+                assert!(rate.tx_bytes_per_sec.is_some_and(|r| r >= 0.0));
+            }
+        }
+        Err(e) => {
+            // On non-Linux systems, this is expected to fail
+            println!("Network rates failed (expected on non-Linux): {}", e);
+        }
+    }
+}
+
+#[test]
+fn test_is_pid() {
+    assert!(is_pid("1"));
+    assert!(is_pid("4194304"));
+    assert!(!is_pid(""));
+    assert!(!is_pid("self"));
+    assert!(!is_pid("12a"));
+    assert!(!is_pid("-1"));
+}
+
+#[test]
+fn test_parse_loadavg() {
+    let load = parse_loadavg("0.50 0.75 1.00 2/345 6789\n").expect("Valid loadavg line");
+
+    assert_eq!(load.one, 0.50);
+    assert_eq!(load.five, 0.75);
+    assert_eq!(load.fifteen, 1.00);
+    assert_eq!(load.runnable, 2);
+    assert_eq!(load.total, 345);
+
+    assert!(parse_loadavg("").is_err());
+    assert!(parse_loadavg("0.50 0.75 1.00").is_err());
+    assert!(parse_loadavg("abc 0.75 1.00 2/345 6789").is_err());
+}
+
+#[test]
+fn test_detect_virtualization() {
+    assert_eq!(
+        parse_cgroup_container("12:pids:/docker/4f1c2b9e8a7d\n0::/docker/4f1c2b9e8a7d\n"),
+        Some("docker")
+    );
+    assert_eq!(
+        parse_cgroup_container(
+            "0::/kubepods.slice/kubepods-burstable.slice/cri-containerd-1.scope\n"
+        ),
+        Some("kubernetes")
+    );
+    assert_eq!(parse_cgroup_container("0::/lxc.payload.web\n"), Some("lxc"));
+    assert_eq!(parse_cgroup_container("0::/init.scope\n"), None);
+
+    // Bare metal: systemd-detect-virt prints "none"
+    let bare = detect_virtualization(Some("0::/init.scope\n"), false, Some("none\n"));
+    assert_eq!(
+        (bare.virt_type.as_str(), bare.is_container),
+        ("none", false)
+    );
+    // A VM, and no systemd-detect-virt at all
+    let vm = detect_virtualization(Some("0::/init.scope\n"), false, Some("kvm\n"));
+    assert_eq!((vm.virt_type.as_str(), vm.is_container), ("kvm", false));
+    assert_eq!(detect_virtualization(None, false, None).virt_type, "none");
+    // cgroup namespaces hide the path, but /.dockerenv gives Docker away
+    let docker = detect_virtualization(Some("0::/\n"), true, None);
+    assert_eq!(
+        (docker.virt_type.as_str(), docker.is_container),
+        ("docker", true)
+    );
+    let nspawn = detect_virtualization(None, false, Some("systemd-nspawn\n"));
+    assert!(nspawn.is_container);
+}
+
+#[test]
+fn test_parse_cgroup_limits() {
+    assert_eq!(parse_cgroup_memory_limit("536870912\n"), Some(536870912));
+    assert_eq!(parse_cgroup_memory_limit("max\n"), None);
+    // cgroup v1 reports "unlimited" as the largest page-aligned value
+    assert_eq!(parse_cgroup_memory_limit("9223372036854771712\n"), None);
+
+    assert_eq!(parse_cpu_max("150000 100000\n"), Some(1.5));
+    assert_eq!(parse_cpu_max("max 100000\n"), None);
+    // cgroup v1 quota and period joined into the v2 format
+    assert_eq!(parse_cpu_max("50000 100000"), Some(0.5));
+    assert_eq!(parse_cpu_max("-1 100000"), None);
+    assert_eq!(parse_cpu_max(""), None);
+}
+
+#[test]
+fn test_parse_uptime() {
+    assert_eq!(parse_uptime("354.87 1360.52\n").unwrap(), 354.87);
+    assert!(parse_uptime("").is_err());
+    assert!(parse_uptime("up 1360.52").is_err());
+}
+
+#[test]
+fn test_run_command_with_timeout() {
+    let output = run_command_with_timeout(
+        std::process::Command::new("echo").arg("hello"),
+        Duration::from_secs(5),
+    )
+    .unwrap();
+    assert!(output.status.success());
+    assert_eq!(String::from_utf8_lossy(&output.stdout), "hello\n");
+
+    let failure = |error: MonitorError| match error {
+        MonitorError::CommandFailed { command, error } => (command, error.kind()),
+        other => panic!("unexpected error: {}", other),
+    };
+
+    // A hung command is killed at the deadline instead of blocking
+    let started = std::time::Instant::now();
+    let error = run_command_with_timeout(
+        std::process::Command::new("sleep").arg("10"),
+        Duration::from_millis(100),
+    )
+    .unwrap_err();
+    assert!(started.elapsed() < Duration::from_secs(5));
+    assert_eq!(
+        failure(error),
+        ("sleep".to_string(), std::io::ErrorKind::TimedOut)
+    );
+
+    let error = run_command_with_timeout(
+        &mut std::process::Command::new("no-such-command-mcp"),
+        Duration::from_secs(1),
+    )
+    .unwrap_err();
+    assert_eq!(failure(error).1, std::io::ErrorKind::NotFound);
+}
+
+#[test]
+fn test_parse_pressure() {
+    let memory = parse_pressure(
+        "some avg10=12.34 avg60=5.00 avg300=1.00 total=987654
+full avg10=6.17 avg60=2.50 avg300=0.50 total=456789
+",
+    )
+    .unwrap();
+    assert_eq!(memory.some.avg10, 12.34);
+    assert_eq!(memory.some.avg300, 1.0);
+    assert_eq!(memory.some.total, 987654);
+    assert_eq!(memory.full.unwrap().avg60, 2.5);
+
+    // CPU has no "full" line before Linux 5.13
+    let cpu = parse_pressure("some avg10=0.00 avg60=0.00 avg300=0.00 total=0\n").unwrap();
+    assert_eq!(cpu.full, None);
+
+    assert!(parse_pressure("").is_err());
+    assert!(parse_pressure("some avg10=x avg60=0.00 avg300=0.00 total=0\n").is_err());
+}
+
+#[test]
+fn test_parse_swaps() {
+    let swaps = "Filename\t\t\t\tType\t\tSize\t\tUsed\t\tPriority
+/dev/zram0                              partition\t8388604\t\t1024\t\t100
+/var/swap\\040file                        file\t\t2097148\t\t0\t\t-2
+";
+    let devices = parse_swaps(swaps);
+    assert_eq!(devices.len(), 2);
+    assert_eq!(devices[0].name, "/dev/zram0");
+    assert_eq!(devices[0].kind, "partition");
+    assert_eq!(devices[0].size_bytes, 8388604 * 1024);
+    assert_eq!(devices[0].used_bytes, 1024 * 1024);
+    assert_eq!(devices[0].priority, 100);
+    assert_eq!(devices[1].name, "/var/swap file");
+    assert_eq!(devices[1].kind, "file");
+    assert_eq!(devices[1].priority, -2);
+
+    // Only the header is present without swap
+    assert!(parse_swaps("Filename\tType\tSize\tUsed\tPriority\n").is_empty());
+}
+
+#[test]
+fn test_parse_size() {
+    const KIB: u64 = 1024;
+    const GIB: u64 = 1024 * 1024 * 1024;
+    const PIB: u64 = 1024 * 1024 * 1024 * 1024 * 1024;
+
+    assert_eq!(parse_size("0").unwrap(), 0);
+    assert_eq!(parse_size("").unwrap(), 0);
+    assert_eq!(parse_size("1024").unwrap(), 1024);
+    assert_eq!(parse_size("512B").unwrap(), 512);
+    assert_eq!(parse_size("1K").unwrap(), KIB);
+    assert_eq!(parse_size("2.5M").unwrap(), 2621440);
+    assert_eq!(parse_size("4,6G").unwrap(), (4.6 * GIB as f64) as u64);
+    assert_eq!(parse_size("1.5P").unwrap(), PIB + PIB / 2);
+    assert_eq!(parse_size("2E").unwrap(), 2 * 1024 * PIB);
+    assert_eq!(parse_size("3GiB").unwrap(), 3 * GIB);
+    assert_eq!(parse_size("3GB").unwrap(), 3 * GIB);
+    assert_eq!(parse_size(" 0K ").unwrap(), 0);
+
+    assert!(parse_size("16E").is_err());
+    assert!(parse_size("12X").is_err());
+    assert!(parse_size("G").is_err());
+    assert!(parse_size("-1K").is_err());
+    assert!(parse_size("1KX").is_err());
+    assert!(parse_size("abc").is_err());
+
+    assert_eq!(parse_size_decimal("1024").unwrap(), 1024);
+    assert_eq!(parse_size_decimal("1K").unwrap(), 1000);
+    assert_eq!(parse_size_decimal("1.5P").unwrap(), 1_500_000_000_000_000);
+    assert_eq!(parse_size_decimal("4,6G").unwrap(), 4_600_000_000);
+    assert_eq!(parse_size_decimal("1KiB").unwrap(), KIB);
+    assert_eq!(parse_size_decimal("0").unwrap(), 0);
+}
+
+#[test]
+fn test_format_bytes() {
+    assert_eq!(format_bytes(0), "0 B");
+    assert_eq!(format_bytes(1), "1 B");
+    assert_eq!(format_bytes(1023), "1023 B");
+    assert_eq!(format_bytes(1024), "1.0 KiB");
+    assert_eq!(format_bytes(1536), "1.5 KiB");
+    assert_eq!(format_bytes(1024 * 1024 - 1), "1.0 MiB");
+    assert_eq!(format_bytes(1024 * 1024), "1.0 MiB");
+    assert_eq!(format_bytes(3 * 1024 * 1024 * 1024 / 2), "1.5 GiB");
+    assert_eq!(format_bytes(4 * 1024u64.pow(4)), "4.0 TiB");
+    assert_eq!(format_bytes(12_500_000_000_000), "11.4 TiB");
+    assert_eq!(format_bytes(1024u64.pow(5)), "1.0 PiB");
+    assert_eq!(format_bytes(u64::MAX), "16.0 EiB");
+}
+
+#[test]
+fn test_format_uptime() {
+    assert_eq!(format_uptime(0), "0s");
+    assert_eq!(format_uptime(59), "59s");
+    assert_eq!(format_uptime(60), "1m");
+    assert_eq!(format_uptime(3600), "1h");
+    assert_eq!(
+        format_uptime(3 * 86400 + 4 * 3600 + 12 * 60 + 30),
+        "3d 4h 12m"
+    );
+    assert_eq!(format_uptime(86400 + 5 * 60), "1d 5m");
+    // Only the three most significant units, years counted as 365 days
+    assert_eq!(
+        format_uptime(2 * 365 * 86400 + 15 * 86400 + 3 * 3600 + 7 * 60),
+        "2y 15d 3h"
+    );
+    assert_eq!(format_uptime(365 * 86400 + 3 * 3600 + 5 * 60), "1y 3h");
+    assert_eq!(format_uptime(u64::MAX), "584942417355y 26d 7h");
+}
+
+#[test]
+fn test_add_human_sizes() {
+    let mut disks = json!([
+        {"mount_point": "/", "total_space": 1024, "used_space": 0},
+        {"mount_point": "/boot", "total_space": 2048, "used_space": null}
+    ]);
+    add_human_sizes(&mut disks, &["total_space", "used_space", "free_space"]);
+
+    // Raw counts stay untouched next to the formatted strings
+    assert_eq!(disks[0]["total_space"], 1024);
+    assert_eq!(disks[0]["total_space_human"], "1.0 KiB");
+    assert_eq!(disks[0]["used_space_human"], "0 B");
+    assert_eq!(disks[1]["total_space_human"], "2.0 KiB");
+    // Null and missing fields get no sibling
+    assert!(disks[1].get("used_space_human").is_none());
+    assert!(disks[0].get("free_space_human").is_none());
+}
+
+#[test]
+fn test_parse_net_dev_line() {
+    let header = "Inter-|   Receive                                                |  Transmit";
+    assert_eq!(parse_net_dev_line(header), None);
+    assert_eq!(
+        parse_net_dev_line(
+            " face |bytes    packets errs drop fifo frame compressed multicast|bytes"
+        ),
+        None
+    );
+
+    let (name, stats) =
+        parse_net_dev_line("  eth0: 1000 10 1 0 0 0 0 0 2000 20 2 0 0 0 0 0").unwrap();
+    assert_eq!(name, "eth0");
+    assert_eq!((stats[0], stats[1], stats[2]), (1000, 10, 1));
+    assert_eq!((stats[8], stats[9], stats[10]), (2000, 20, 2));
+
+    // Receive bytes wide enough to reach the colon
+    let (name, stats) =
+        parse_net_dev_line("  eth0:123456789012345 10 0 0 0 0 0 0 98765432109876 20 0 0 0 0 0 0")
+            .unwrap();
+    assert_eq!(name, "eth0");
+    assert_eq!(stats[0], 123456789012345);
+    assert_eq!(stats[8], 98765432109876);
+
+    let (name, _) =
+        parse_net_dev_line("enp0s20f0u1u4i5: 1 2 3 4 5 6 7 8 9 10 11 12 13 14 15 16").unwrap();
+    assert_eq!(name, "enp0s20f0u1u4i5");
+
+    assert_eq!(parse_net_dev_line("eth0: 1 2 3"), None);
+}
+
+#[test]
+fn test_parse_interface_addresses() {
+    let output = "\
+2: eth0: <BROADCAST,MULTICAST,UP,LOWER_UP> mtu 1500 qdisc fq_codel state UP group default qlen 1000
+    link/ether 52:54:00:12:34:56 brd ff:ff:ff:ff:ff:ff
+    inet 192.168.1.10/24 brd 192.168.1.255 scope global dynamic eth0
+       valid_lft 86000sec preferred_lft 86000sec
+    inet 10.0.0.5/8 scope global secondary eth0
+    inet6 2001:db8::10/64 scope global dynamic mngtmpaddr
+       valid_lft 86000sec preferred_lft 14000sec
+    inet6 fd00::1234/64 scope global
+    inet6 fe80::5054:ff:fe12:3456/64 scope link
+       valid_lft forever preferred_lft forever";
+
+    let (ipv4, ipv6) = parse_interface_addresses(output);
+    assert_eq!(ipv4.as_deref(), Some("192.168.1.10"));
+    assert_eq!(
+        ipv6,
+        vec!["2001:db8::10", "fd00::1234", "fe80::5054:ff:fe12:3456"]
+    );
+
+    // IPv6-only interface
+    let (ipv4, ipv6) = parse_interface_addresses(
+        "3: wg0: <POINTOPOINT,NOARP,UP,LOWER_UP> mtu 1420\n    inet6 2001:db8:1::2/128 scope global",
+    );
+    assert_eq!(ipv4, None);
+    assert_eq!(ipv6, vec!["2001:db8:1::2"]);
+
+    assert_eq!(parse_interface_addresses(""), (None, Vec::new()));
+
+    assert!(is_link_local_ipv6("fe80::1"));
+    assert!(is_link_local_ipv6("febf::1"));
+    assert!(!is_link_local_ipv6("fec0::1"));
+    assert!(!is_link_local_ipv6("2001:db8::1"));
+    assert!(!is_link_local_ipv6("not an address"));
+}
+
+#[test]
+fn test_parse_link_state() {
+    assert_eq!(parse_link_state("up\n", None), Some(true));
+    assert_eq!(parse_link_state("down\n", Some("0x1003")), Some(false));
+    assert_eq!(parse_link_state("lowerlayerdown", None), Some(false));
+    // Loopback does not track its operational state
+    assert_eq!(parse_link_state("unknown\n", Some("0x9\n")), Some(true));
+    assert_eq!(parse_link_state("unknown", Some("0x1002")), Some(false));
+    assert_eq!(parse_link_state("unknown", None), None);
+    assert_eq!(parse_link_state("", None), None);
+
+    assert_eq!(parse_link_speed("1000\n"), Some(1000));
+    assert_eq!(parse_link_speed("-1\n"), None);
+    assert_eq!(parse_link_speed("0"), None);
+    assert_eq!(parse_link_speed(""), None);
+}
+
+#[test]
+fn test_apply_network_query() {
+    let interface = |name: &str, ipv6: &[&str]| NetworkInfo {
+        ipv6_addresses: ipv6.iter().map(|address| address.to_string()).collect(),
+        ..test_interface(name)
+    };
+    let networks = vec![
+        interface("lo", &["::1"]),
+        interface("eth0", &["fe80::1", "2001:db8::1"]),
+    ];
+
+    let filtered = apply_network_query(networks.clone(), &NetworkQuery::default());
+    assert_eq!(filtered.len(), 1);
+    assert_eq!(filtered[0].interface, "eth0");
+    assert_eq!(filtered[0].ipv6_addresses, vec!["2001:db8::1"]);
+
+    let query = NetworkQuery {
+        include_loopback: true,
+        include_link_local: true,
+    };
+    let all = apply_network_query(networks, &query);
+    assert_eq!(all.len(), 2);
+    assert_eq!(all[0].interface, "lo");
+    assert_eq!(all[1].ipv6_addresses, vec!["fe80::1", "2001:db8::1"]);
+}
+
+#[test]
+fn test_parse_kernel_stats() {
+    let stat = "cpu  10132153 290696 3084719 46828483 16683 0 25195 0 0 0
+cpu0 1393280 32966 572056 13343292 6130 0 17875 0 0 0
+intr 1462898 0 9 0 0 0 0 3 0 1 0 0 0 0
+ctxt 115315133
+btime 1700000000
+processes 86031
+procs_running 3
+procs_blocked 1
+softirq 12121874 0 1 2 3 4 5 6 7 8 9
+";
+    let stats = parse_kernel_stats(stat).unwrap();
+    assert_eq!(stats.context_switches, 115315133);
+    assert_eq!(stats.interrupts, 1462898);
+    assert_eq!(stats.forks, 86031);
+    assert_eq!(stats.procs_running, 3);
+    assert_eq!(stats.procs_blocked, 1);
+
+    let error = parse_kernel_stats("cpu  1 2 3 4\n").unwrap_err();
+    assert!(matches!(error, MonitorError::ParseError(_)), "{}", error);
+}
+
+#[test]
+fn test_parse_file_nr() {
+    let usage = parse_file_nr("3072\t0\t4096\n").unwrap();
+    assert_eq!((usage.allocated, usage.max), (3072, 4096));
+    assert_eq!(usage.usage_percent, 75.0);
+
+    // Kernels before 2.6 kept freed handles allocated but unused
+    let usage = parse_file_nr("1000\t200\t8000\n").unwrap();
+    assert_eq!(usage.allocated, 800);
+    assert_eq!(usage.usage_percent, 10.0);
+
+    // fs.file-max defaults to LONG_MAX on recent kernels
+    let usage = parse_file_nr("2048\t0\t9223372036854775807\n").unwrap();
+    assert!(usage.usage_percent < 0.001);
+
+    for content in ["", "3072\t0\n", "3072\tx\t4096\n"] {
+        let error = parse_file_nr(content).unwrap_err();
+        assert!(matches!(error, MonitorError::ParseError(_)), "{}", error);
+    }
+}
+
+#[tokio::test]
+async fn test_mcp_tools_call_process_by_pid() {
+    let server = create_test_mcp_server().await;
+    let request = MCPRequest {
+        jsonrpc: "2.0".to_string(),
+        id: Some("test-tools-call-pid".to_string()),
+        method: "tools/call".to_string(),
+        params: json!({
+            "name": "get_process_by_pid",
+            "arguments": { "pid": std::process::id() }
+        }),
+    };
+
+    let response = server.handle_request(request).await;
+    assert!(response.error.is_none(), "{:?}", response.error);
+    let result = response.result.expect("Result should be present");
+    let text = result["content"][0]["text"].as_str().unwrap();
+    let process: serde_json::Value = serde_json::from_str(text).unwrap();
+    assert_eq!(process["pid"], json!(std::process::id()));
+
+    let request = MCPRequest {
+        jsonrpc: "2.0".to_string(),
+        id: Some("test-tools-call-pid-missing".to_string()),
+        method: "tools/call".to_string(),
+        params: json!({ "name": "get_process_by_pid" }),
+    };
+    let response = server.handle_request(request).await;
+    assert_eq!(response.error.map(|e| e.code), Some(-32602));
+}
+
+#[cfg(target_os = "linux")]
+#[tokio::test]
+async fn test_mcp_tools_call_reads_arguments() {
+    let server = create_test_mcp_server().await;
+    let request = MCPRequest {
+        jsonrpc: "2.0".to_string(),
+        id: Some("test-tools-call-arguments".to_string()),
+        method: "tools/call".to_string(),
+        params: json!({"name": "get_process_by_pid", "arguments": {"pid": 1}}),
+    };
+
+    let response = server.handle_request(request).await;
+    assert!(response.error.is_none(), "{:?}", response.error);
+    let result = response.result.expect("Result should be present");
+    let text = result["content"][0]["text"].as_str().unwrap();
+    let process: serde_json::Value = serde_json::from_str(text).unwrap();
+    assert_eq!(process["pid"], 1);
+
+    // A pid at the top level of params is not a tool argument
+    let request = MCPRequest {
+        jsonrpc: "2.0".to_string(),
+        id: Some("test-tools-call-top-level".to_string()),
+        method: "tools/call".to_string(),
+        params: json!({"name": "get_process_by_pid", "pid": 1}),
+    };
+    let response = server.handle_request(request).await;
+    assert_eq!(response.error.map(|e| e.code), Some(-32602));
+
+    let request = MCPRequest {
+        jsonrpc: "2.0".to_string(),
+        id: Some("test-tools-call-processes".to_string()),
+        method: "tools/call".to_string(),
+        params: json!({"name": "get_processes", "arguments": {"sort_by": "pid", "order": "asc", "limit": 1}}),
+    };
+    let response = server.handle_request(request).await;
+    let result = response.result.expect("Result should be present");
+    let text = result["content"][0]["text"].as_str().unwrap();
+    let processes: serde_json::Value = serde_json::from_str(text).unwrap();
+    assert_eq!(processes.as_array().unwrap().len(), 1);
+}
+
+#[tokio::test]
+async fn test_mcp_tools_list_monitoring_tools() {
+    let server = create_test_mcp_server().await;
+    let request = MCPRequest {
+        jsonrpc: "2.0".to_string(),
+        id: Some("test-tools-list".to_string()),
+        method: "tools/list".to_string(),
+        params: json!({}),
+    };
+
+    let response = server.handle_request(request).await;
+    let result = response.result.expect("Result should be present");
+    let tools = result["tools"].as_array().unwrap();
+    for name in ["get_process_by_pid", "start_monitoring", "stop_monitoring"] {
+        assert!(tools.iter().any(|tool| tool["name"] == name), "{}", name);
+    }
+    let pid_tool = tools
+        .iter()
+        .find(|tool| tool["name"] == "get_process_by_pid")
+        .unwrap();
+    assert_eq!(pid_tool["inputSchema"]["required"], json!(["pid"]));
+}
+
+#[test]
+fn test_metrics_selector_parsing() {
+    let selector: MetricsSelector = "cpu, memory,load".parse().unwrap();
+    assert!(selector.cpu && selector.memory && selector.load);
+    assert!(!selector.system && !selector.disks && !selector.processes);
+    assert_eq!(
+        Vec::<MetricSection>::from(selector),
+        vec![
+            MetricSection::Cpu,
+            MetricSection::Memory,
+            MetricSection::Load
+        ]
+    );
+
+    // A query string and a JSON list select the same sections
+    let from_list: MetricsSelector =
+        serde_json::from_value(json!(["load", "cpu", "memory"])).unwrap();
+    assert_eq!(from_list, selector);
+    assert_eq!(MetricsSelector::default(), MetricsSelector::all());
+    assert!("cpu,gpu".parse::<MetricsSelector>().is_err());
+}
+
+#[test]
+fn test_process_fields_parsing() {
+    let fields: ProcessFields = "tty, wchan,tty".parse().unwrap();
+    assert_eq!(
+        Vec::<ProcessField>::from(fields.clone()),
+        vec![ProcessField::Wchan, ProcessField::Tty]
+    );
+    assert!(fields.contains(ProcessField::Tty) && !fields.contains(ProcessField::Cgroup));
+
+    let from_list: ProcessFields = serde_json::from_value(json!(["wchan", "tty"])).unwrap();
+    assert_eq!(from_list, fields);
+    assert!(ProcessFields::default().is_empty());
+    assert!("tty,rss".parse::<ProcessFields>().is_err());
+
+    // Unknown fields are rejected as invalid query params
+    let query =
+        serde_json::from_value::<ProcessQuery>(json!({"fields": "session,cgroup"})).unwrap();
+    assert_eq!(query.fields.iter().count(), 2);
+    assert!(serde_json::from_value::<ProcessQuery>(json!({"fields": "nope"})).is_err());
+}
+
+#[tokio::test]
+async fn test_mcp_system_metrics_components() {
+    let server = create_mock_mcp_server();
+
+    for params in [
+        json!({"components": "cpu,memory"}),
+        json!({"components": ["cpu", "memory"]}),
+    ] {
+        let request = MCPRequest {
+            jsonrpc: "2.0".to_string(),
+            id: Some("test-components".to_string()),
+            method: METHOD_GET_SYSTEM_METRICS.to_string(),
+            params,
+        };
+
+        let response = server.handle_request(request).await;
+        let metrics = response.result.expect("Result should be present");
+        assert!(metrics["cpu_info"].is_object());
+        assert_eq!(metrics["memory_info"]["total"], 1024);
+        // Omitted sections are left out or empty
+        assert!(metrics.get("system_info").is_none());
+        assert!(metrics.get("load_average").is_none());
+        assert_eq!(metrics["disks"], json!([]));
+        assert_eq!(metrics["processes"], json!([]));
+    }
+
+    let request = MCPRequest {
+        jsonrpc: "2.0".to_string(),
+        id: Some("test-components-invalid".to_string()),
+        method: METHOD_GET_SYSTEM_METRICS.to_string(),
+        params: json!({"components": "cpu,bogus"}),
+    };
+    let response = server.handle_request(request).await;
+    assert_eq!(response.error.unwrap().code, ERROR_INVALID_PARAMS);
+}
+
+#[tokio::test]
+async fn test_mcp_initialize() {
+    let server = create_test_mcp_server().await;
+    let request = MCPRequest {
+        jsonrpc: "2.0".to_string(),
+        id: Some("test-initialize".to_string()),
+        method: "initialize".to_string(),
+        params: json!({"protocolVersion": "2025-06-18"}),
+    };
+
+    let response = server.handle_request(request).await;
+    let result = response.result.expect("Result should be present");
+    assert_eq!(result["protocolVersion"], "2025-06-18");
+    assert!(result["capabilities"]["tools"].is_object());
+    assert!(result["capabilities"]["resources"].is_object());
+    assert!(result["capabilities"]["logging"].is_object());
+    assert_eq!(result["serverInfo"]["version"], env!("CARGO_PKG_VERSION"));
+}
+
+#[tokio::test]
+async fn test_mcp_logging_set_level() {
+    use tracing_subscriber::filter::LevelFilter;
+    use tracing_subscriber::reload;
+
+    let set_level = |params: serde_json::Value| MCPRequest {
+        jsonrpc: "2.0".to_string(),
+        id: Some("test-set-level".to_string()),
+        method: "logging/setLevel".to_string(),
+        params,
+    };
+
+    // Without a handle the server cannot change its embedder's logging
+    let server = create_test_mcp_server().await;
+    let response = server
+        .handle_request(set_level(json!({"level": "debug"})))
+        .await;
+    assert_eq!(response.error.unwrap().code, ERROR_INTERNAL_ERROR);
+
+    let (_level_filter, handle) =
+        reload::Layer::<_, tracing_subscriber::Registry>::new(LevelFilter::INFO);
+    let server = create_test_mcp_server()
+        .await
+        .with_log_level_handle(handle.clone());
+
+    let response = server
+        .handle_request(set_level(json!({"level": "debug"})))
+        .await;
+    assert_eq!(response.result, Some(json!({})));
+    assert_eq!(handle.clone_current(), Some(LevelFilter::DEBUG));
+
+    let response = server
+        .handle_request(set_level(json!({"level": "critical"})))
+        .await;
+    assert!(response.error.is_none());
+    assert_eq!(handle.clone_current(), Some(LevelFilter::ERROR));
+
+    for params in [json!({"level": "verbose"}), json!({"level": 3}), json!({})] {
+        let response = server.handle_request(set_level(params)).await;
+        assert_eq!(response.error.unwrap().code, ERROR_INVALID_PARAMS);
+    }
+    assert_eq!(handle.clone_current(), Some(LevelFilter::ERROR));
+}
+
+#[tokio::test]
+async fn test_mcp_resources() {
+    let server = create_test_mcp_server().await;
+
+    let request = MCPRequest {
+        jsonrpc: "2.0".to_string(),
+        id: Some("test-resources-list".to_string()),
+        method: "resources/list".to_string(),
+        params: json!({}),
+    };
+    let response = server.handle_request(request).await;
+    let result = response.result.expect("Result should be present");
+    let resources = result["resources"].as_array().unwrap();
+    for uri in ["system://cpu", "system://memory", "system://disks"] {
+        assert!(resources.iter().any(|r| r["uri"] == uri), "{}", uri);
+    }
+
+    let request = MCPRequest {
+        jsonrpc: "2.0".to_string(),
+        id: Some("test-resources-read".to_string()),
+        method: "resources/read".to_string(),
+        params: json!({"uri": "system://memory"}),
+    };
+    let response = server.handle_request(request).await;
+    assert!(response.error.is_none(), "{:?}", response.error);
+    let result = response.result.expect("Result should be present");
+    let contents = &result["contents"][0];
+    assert_eq!(contents["uri"], "system://memory");
+    assert_eq!(contents["mimeType"], "application/json");
+    let memory: serde_json::Value =
+        serde_json::from_str(contents["text"].as_str().unwrap()).unwrap();
+    assert!(memory["total"].as_u64().unwrap() > 0);
+
+    let request = MCPRequest {
+        jsonrpc: "2.0".to_string(),
+        id: Some("test-resources-unknown".to_string()),
+        method: "resources/read".to_string(),
+        params: json!({"uri": "system://nope"}),
+    };
+    let response = server.handle_request(request).await;
+    assert_eq!(response.error.map(|e| e.code), Some(-32602));
+}
+
+#[tokio::test]
+async fn test_mcp_mock_provider_responses() {
+    let server = create_mock_mcp_server();
+    let call = |method: &str, params: serde_json::Value| MCPRequest {
+        jsonrpc: "2.0".to_string(),
+        id: Some("test-mock".to_string()),
+        method: method.to_string(),
+        params,
+    };
+
+    let response = server
+        .handle_request(call("getMemoryInfo", json!({})))
+        .await;
+    assert_eq!(
+        response.result,
+        Some(json!({
+            "total": 1024,
+            "used": 256,
+            "free": 512,
+            "available": 768,
+            "swap_total": 0,
+            "swap_used": 0,
+            "swap_free": 0,
+            "usage_percent": 25.0,
+            "swap_usage_percent": 0.0,
+            "buffers": 64,
+            "cached": 128,
+            "slab": 32,
+            "shmem": 16,
+            "dirty": 0,
+            "limit_bytes": null,
+            "limit_usage_percent": null
+        }))
+    );
+
+    let response = server
+        .handle_request(call(METHOD_GET_CPU_INFO, json!({})))
+        .await;
+    let cpu = response.result.unwrap();
+    assert_eq!(cpu["name"], "Mock CPU");
+    assert_eq!(cpu["logical_cores"], 8);
+    assert_eq!(cpu["physical_cores"], 4);
+    assert_eq!(cpu["usage_percent"], 12.5);
+
+    let response = server
+        .handle_request(call(METHOD_GET_SYSTEM_INFO, json!({})))
+        .await;
+    let info = response.result.unwrap();
+    assert_eq!(info["hostname"], "mock-host");
+    assert_eq!(info["boot_time"], "2023-11-14T22:13:20Z");
+
+    let response = server
+        .handle_request(call(METHOD_GET_PROCESS_BY_PID, json!({"pid": 42})))
+        .await;
+    assert_eq!(response.result.unwrap()["name"], "proc-42");
+    let response = server
+        .handle_request(call(METHOD_GET_PROCESS_BY_PID, json!({"pid": 7})))
+        .await;
+    assert_eq!(response.error.unwrap().code, ERROR_PROCESS_NOT_FOUND);
+
+    // Collectors the mock leaves out fall back to the trait defaults
+    let response = server
+        .handle_request(call(METHOD_GET_TEMPERATURES, json!({})))
+        .await;
+    assert_eq!(response.result, Some(json!([])));
+    let response = server
+        .handle_request(call(METHOD_GET_KERNEL_STATS, json!({})))
+        .await;
+    let error = response.error.unwrap();
+    assert_eq!(error.code, ERROR_UNSUPPORTED);
+    assert_eq!(error.data.unwrap()["kind"], "unsupported");
+}
+
+#[tokio::test]
+async fn test_mcp_get_services() {
+    let server = create_mock_mcp_server();
+    let call = |params: serde_json::Value| MCPRequest {
+        jsonrpc: "2.0".to_string(),
+        id: Some("test-services".to_string()),
+        method: METHOD_GET_SERVICES.to_string(),
+        params,
+    };
+
+    let response = server.handle_request(call(json!({}))).await;
+    let result = response.result.unwrap();
+    assert_eq!(result["systemd"], true);
+    assert_eq!(result["services"].as_array().unwrap().len(), 2);
+
+    let response = server
+        .handle_request(call(json!({"state": "FAILED"})))
+        .await;
+    let result = response.result.unwrap();
+    assert_eq!(
+        result["services"],
+        json!([{
+            "name": "backup.service",
+            "load_state": "loaded",
+            "active_state": "failed",
+            "sub_state": "failed",
+            "description": "Mock backup.service"
+        }])
+    );
+
+    // Sub states filter too
+    let response = server
+        .handle_request(call(json!({"state": "running"})))
+        .await;
+    assert_eq!(
+        response.result.unwrap()["services"][0]["name"],
+        "nginx.service"
+    );
+
+    let response = server.handle_request(call(json!({"state": 1}))).await;
+    assert_eq!(response.error.unwrap().code, ERROR_INVALID_PARAMS);
+}
+
+#[tokio::test]
+async fn test_mcp_get_recent_logs() {
+    let server = create_mock_mcp_server();
+    let call = |params: serde_json::Value| MCPRequest {
+        jsonrpc: "2.0".to_string(),
+        id: Some("test-logs".to_string()),
+        method: METHOD_GET_RECENT_LOGS.to_string(),
+        params,
+    };
+
+    let response = server.handle_request(call(json!({}))).await;
+    assert_eq!(response.result.unwrap().as_array().unwrap().len(), 50);
+
+    let response = server
+        .handle_request(call(json!({"lines": 3, "priority": 3})))
+        .await;
+    let logs = response.result.unwrap();
+    assert_eq!(logs.as_array().unwrap().len(), 3);
+    assert_eq!(logs[0]["priority"], 3);
+
+    // Large requests are capped rather than rejected
+    let response = server
+        .handle_request(call(json!({"lines": 1_000_000})))
+        .await;
+    assert_eq!(response.result.unwrap().as_array().unwrap().len(), 1000);
+
+    let response = server.handle_request(call(json!({"priority": 8}))).await;
+    assert_eq!(response.error.unwrap().code, ERROR_INVALID_PARAMS);
+}
+
+#[test]
+fn test_parse_log_lines() {
+    let entry = parse_journal_entry(
+        r#"{"__REALTIME_TIMESTAMP":"1760610896123456","PRIORITY":"3","_SYSTEMD_UNIT":"nginx.service","SYSLOG_IDENTIFIER":"nginx","MESSAGE":"bind() failed"}"#,
+    )
+    .unwrap();
+    assert_eq!(
+        entry.timestamp,
+        chrono::DateTime::from_timestamp_micros(1_760_610_896_123_456)
+    );
+    assert_eq!(entry.unit.as_deref(), Some("nginx.service"));
+    assert_eq!(entry.priority, Some(3));
+    assert_eq!(entry.message, "bind() failed");
+
+    // Kernel messages have no unit, and binary messages come as byte arrays
+    let entry = parse_journal_entry(
+        r#"{"PRIORITY":"4","SYSLOG_IDENTIFIER":"kernel","MESSAGE":[104,105,255]}"#,
+    )
+    .unwrap();
+    assert_eq!(entry.unit.as_deref(), Some("kernel"));
+    assert_eq!(entry.message, "hi\u{fffd}");
+    assert!(parse_journal_entry("not json").is_none());
+
+    let now = chrono::Local::now();
+    let entry = parse_syslog_line(
+        "2026-01-02T03:04:05.123456+00:00 web1 sshd[812]: Accepted publickey  for root",
+        now,
+    );
+    assert_eq!(
+        entry.timestamp.unwrap().to_rfc3339(),
+        "2026-01-02T03:04:05.123456+00:00"
+    );
+    assert_eq!(entry.unit.as_deref(), Some("sshd"));
+    assert_eq!(entry.priority, None);
+    assert_eq!(entry.message, "Accepted publickey  for root");
+
+    let entry = parse_syslog_line("Jan  2 03:04:05 web1 CRON: session opened", now);
+    let timestamp = entry.timestamp.unwrap().with_timezone(&chrono::Local);
+    assert_eq!(
+        timestamp.format("%m-%d %H:%M:%S").to_string(),
+        "01-02 03:04:05"
+    );
+    assert!(timestamp <= now + chrono::Duration::days(1));
+    assert_eq!(entry.unit.as_deref(), Some("CRON"));
+    assert_eq!(entry.message, "session opened");
+
+    let entry = parse_syslog_line("-- MARK --", now);
+    assert_eq!(entry.timestamp, None);
+    assert_eq!(entry.message, "-- MARK --");
+}
+
+#[test]
+fn test_parse_systemctl_units() {
+    let output = r#"[
+        {"unit": "cron.service", "load": "loaded", "active": "active", "sub": "running", "description": "Regular background program processing daemon"},
+        {"unit": "nfs-server.service", "load": "not-found", "active": "inactive", "sub": "dead", "description": "nfs-server.service"}
+    ]"#;
+
+    let services = parse_systemctl_units(output).unwrap();
+    assert_eq!(services.len(), 2);
+    assert_eq!(services[0].name, "cron.service");
+    assert_eq!(services[0].sub_state, "running");
+    assert_eq!(
+        services[0].description,
+        "Regular background program processing daemon"
+    );
+    assert_eq!(services[1].load_state, "not-found");
+    assert_eq!(services[1].active_state, "inactive");
+
+    // Older systemd prints its table regardless of --output=json
+    assert!(matches!(
+        parse_systemctl_units("  UNIT  LOAD  ACTIVE SUB DESCRIPTION"),
+        Err(MonitorError::ParseError(_))
+    ));
+}
+
+#[tokio::test]
+async fn test_mock_provider_fires_disk_alert() {
+    let monitor = SystemMonitor::with_provider(MockProvider).unwrap();
+    let monitor = Arc::new(RwLock::new(monitor));
+    let server = MCPServer::new(monitor.clone());
+
+    // The default disk_almost_full rule fires at once above 90%
+    {
+        let mut monitor = monitor.write().await;
+        let sample = monitor.record_history_sample().unwrap();
+        monitor.evaluate_alerts(&sample).unwrap();
+    }
+    let request = MCPRequest {
+        jsonrpc: "2.0".to_string(),
+        id: Some("test-mock-alerts".to_string()),
+        method: METHOD_GET_ALERTS.to_string(),
+        params: json!({}),
+    };
+    let alerts = server.handle_request(request).await.result.unwrap();
+    let alerts = alerts.as_array().unwrap();
+    assert_eq!(alerts.len(), 1);
+    assert_eq!(alerts[0]["rule"], "disk_almost_full");
+    assert_eq!(alerts[0]["value"], 95.0);
+}
+
+#[tokio::test]
+async fn test_mcp_ping() {
+    let server = create_test_mcp_server().await;
+    // Hold the monitor so a ping that touched it would block
+    let monitor = server.system_monitor();
+    let _guard = monitor.write().await;
+
+    let request = MCPRequest {
+        jsonrpc: "2.0".to_string(),
+        id: Some("test-ping".to_string()),
+        method: "ping".to_string(),
+        params: json!({}),
+    };
+
+    let response = tokio::time::timeout(
+        std::time::Duration::from_secs(1),
+        server.handle_request(request),
+    )
+    .await
+    .expect("ping should not wait for the system monitor");
+    assert!(response.error.is_none());
+    assert_eq!(response.id, Some("test-ping".to_string()));
+    assert_eq!(response.result, Some(json!({})));
+}
+
+#[tokio::test]
+async fn test_mcp_get_kernel_stats() {
+    let server = create_test_mcp_server().await;
+    let request = MCPRequest {
+        jsonrpc: "2.0".to_string(),
+        id: Some("test-kernel-stats".to_string()),
+        method: METHOD_GET_KERNEL_STATS.to_string(),
+        params: json!({}),
+    };
+
+    let response = server.handle_request(request).await;
+    if let Some(error) = response.error {
+        // The sysinfo backend has no /proc/stat counters
+        assert!(error.code != -32601);
+    } else {
+        let result = response.result.expect("Result should be present");
+        assert!(result["context_switches"].as_u64().unwrap() > 0);
+        assert!(result["procs_running"].as_u64().unwrap() >= 1);
+    }
+}
+
+#[tokio::test]
+async fn test_mcp_get_entropy() {
+    use mcp_system_monitor::types::METHOD_GET_ENTROPY;
+    let server = create_test_mcp_server().await;
+    let request = MCPRequest {
+        jsonrpc: "2.0".to_string(),
+        id: Some("test-entropy".to_string()),
+        method: METHOD_GET_ENTROPY.to_string(),
+        params: json!({}),
+    };
+
+    let response = server.handle_request(request).await;
+    if let Some(error) = response.error {
+        // The sysinfo backend has no /proc/sys/kernel/random
+        assert!(error.code != -32601);
+    } else {
+        let result = response.result.expect("Result should be present");
+        let pool_size = result["pool_size"].as_u64().unwrap();
+        assert!(pool_size > 0);
+        assert!(result["available"].as_u64().unwrap() <= pool_size);
+    }
+}
+
+#[test]
+fn test_parse_os_release() {
+    let (name, version) = parse_os_release(
+        "NAME=\"Ubuntu\"
+VERSION=\"22.04.3 LTS (Jammy Jellyfish)\"
+ID=ubuntu
+PRETTY_NAME=\"Ubuntu 22.04.3 LTS\"
+VERSION_ID=\"22.04\"
+",
+    );
+    assert_eq!(name, "Ubuntu 22.04.3 LTS");
+    assert_eq!(version, "22.04");
+
+    let (name, version) = parse_os_release("NAME='Alpine Linux'\nVERSION=3.19.1\n");
+    assert_eq!(name, "Alpine Linux");
+    assert_eq!(version, "3.19.1");
+
+    // Minimal images may only set ID, or leave even that empty
+    let (name, version) = parse_os_release("ID=distroless\n");
+    assert_eq!(name, "distroless");
+    assert_eq!(version, "Unknown");
+    let (name, version) = parse_os_release("ID=\n");
+    assert_eq!(name, std::env::consts::OS);
+    assert_eq!(version, "Unknown");
+
+    // A missing os-release file is read as empty
+    let (name, version) = parse_os_release("");
+    assert_eq!(name, std::env::consts::OS);
+    assert_eq!(version, "Unknown");
+}
+
+#[test]
+fn test_parse_cpu_list() {
+    assert_eq!(parse_cpu_list("0-3\n"), vec![0, 1, 2, 3]);
+    assert_eq!(parse_cpu_list("0,2,4-5"), vec![0, 2, 4, 5]);
+    assert_eq!(parse_cpu_list("16-23"), (16..=23).collect::<Vec<u32>>());
+    assert!(parse_cpu_list("").is_empty());
+}
+
+#[test]
+fn test_parse_cpu_topology() {
+    // 2 sockets × 2 cores × 2 hyperthreads
+    let cpuinfo: String = (0..8)
+        .map(|cpu| {
+            format!(
+                "processor\t: {}\nmodel name\t: Xeon\nphysical id\t: {}\ncore id\t\t: {}\n\n",
+                cpu,
+                cpu / 4,
+                cpu % 2
+            )
+        })
+        .collect();
+    assert_eq!(parse_cpu_topology(&cpuinfo), Some((4, 2)));
+
+    // Many VMs (and ARM boards) omit the topology fields
+    let vm = "processor\t: 0\nmodel name\t: vCPU\n\nprocessor\t: 1\nmodel name\t: vCPU\n";
+    assert_eq!(parse_cpu_topology(vm), None);
+}
+
+#[test]
+fn test_classify_core_capacities() {
+    // big.LITTLE: 4 little cores followed by 4 big cores
+    let types = classify_core_capacities(&[446, 446, 446, 446, 1024, 1024, 1024, 1024]);
+    assert_eq!(types[0], "efficiency");
+    assert_eq!(types[7], "performance");
+    assert_eq!(types.len(), 8);
+
+    // Homogeneous CPUs carry no core type hint
+    assert!(classify_core_capacities(&[1024, 1024]).is_empty());
+    assert!(classify_core_capacities(&[]).is_empty());
+}
+
+#[test]
+fn test_merge_patch_diff_roundtrip() {
+    let previous = json!({
+        "cpu_info": {"usage_percent": 10.0, "cores": 8, "temperature": 50.0},
+        "memory_info": {"used": 100, "total": 1000},
+        "networks": [{"interface": "eth0", "bytes_received": 1}]
+    });
+    let current = json!({
+        "cpu_info": {"usage_percent": 12.5, "cores": 8},
+        "memory_info": {"used": 100, "total": 1000},
+        "networks": [{"interface": "eth0", "bytes_received": 2}],
+        "load_average": {"one": 0.5}
+    });
+
+    let patch = merge_patch_diff(&previous, &current).expect("Documents differ");
+
+    // Only changed members are present; removed members become null
+    assert_eq!(
+        patch,
+        json!({
+            "cpu_info": {"usage_percent": 12.5, "temperature": null},
+            "networks": [{"interface": "eth0", "bytes_received": 2}],
+            "load_average": {"one": 0.5}
+        })
+    );
+
+    let mut patched = previous.clone();
+    apply_merge_patch(&mut patched, &patch);
+    assert_eq!(patched, current);
+
+    assert!(merge_patch_diff(&current, &current).is_none());
+}
+
+#[test]
+fn test_patch_tracker_resyncs_periodically() {
+    let mut tracker = PatchTracker::new(3);
+
+    let first = tracker.next_update(json!({"cpu": 1}));
+    assert_eq!(first, Some(StreamUpdate::Full(json!({"cpu": 1}))));
+
+    // Unchanged samples are not sent
+    assert_eq!(tracker.next_update(json!({"cpu": 1})), None);
+
+    assert_eq!(
+        tracker.next_update(json!({"cpu": 2})),
+        Some(StreamUpdate::Patch(json!({"cpu": 2})))
+    );
+    assert_eq!(
+        tracker.next_update(json!({"cpu": 3})),
+        Some(StreamUpdate::Patch(json!({"cpu": 3})))
+    );
+
+    // Every third update is a full snapshot
+    assert_eq!(
+        tracker.next_update(json!({"cpu": 4})),
+        Some(StreamUpdate::Full(json!({"cpu": 4})))
+    );
+}
+
+fn test_process(pid: u32, ppid: u32) -> ProcessInfo {
+    ProcessInfo {
+        pid,
+        ppid,
+        name: format!("proc-{}", pid),
+        command: String::new(),
+        cpu_usage: 0.0,
+        memory_usage: 0,
+        memory_usage_percent: 0.0,
+        status: "S".to_string(),
+        start_time: chrono::Utc::now(),
+        cpu_time_secs: None,
+        user: "root".to_string(),
+        priority: 0,
+        sched_policy: None,
+        rt_priority: None,
+        systemd_unit: None,
+        open_fds: 0,
+        threads: 1,
+        read_bytes: 0,
+        write_bytes: 0,
+        read_syscalls: 0,
+        write_syscalls: 0,
+        extra: std::collections::HashMap::new(),
+    }
+}
+
+fn test_interface(name: &str) -> NetworkInfo {
+    NetworkInfo {
+        interface: name.to_string(),
+        ip_address: "N/A".to_string(),
+        ipv6_addresses: Vec::new(),
+        mac_address: "N/A".to_string(),
+        bytes_received: 0,
+        bytes_transmitted: 0,
+        packets_received: 0,
+        packets_transmitted: 0,
+        errors_received: 0,
+        errors_transmitted: 0,
+        is_up: Some(true),
+        speed_mbps: None,
+        mtu: Some(1500),
+    }
+}
+
+/// Two snapshots of the mock provider 10 seconds apart, with processes,
+/// memory, CPU and traffic changed in between
+fn test_metrics_pair() -> (SystemMetrics, SystemMetrics) {
+    let static_info = MockProvider.get_static_info().unwrap();
+    let before = SystemMetrics {
+        timestamp: chrono::Utc::now(),
+        system_info: None,
+        cpu_info: Some(MockProvider.get_cpu_info(&static_info).unwrap()),
+        memory_info: Some(MockProvider.get_memory_info().unwrap()),
+        load_average: Some(MockProvider.get_load_average().unwrap()),
+        disks: Vec::new(),
+        networks: vec![
+            NetworkInfo {
+                bytes_received: 1000,
+                bytes_transmitted: 5000,
+                ..test_interface("eth0")
+            },
+            test_interface("wg0"),
+        ],
+        processes: vec![test_process(1, 0), test_process(42, 1), test_process(50, 1)],
+        total_count: None,
+        gpus: None,
+    };
+
+    let mut after = before.clone();
+    after.timestamp = before.timestamp + chrono::Duration::seconds(10);
+    after.cpu_info.as_mut().unwrap().usage_percent += 40.0;
+    let memory = after.memory_info.as_mut().unwrap();
+    memory.used -= 128;
+    memory.usage_percent -= 12.5;
+    after.load_average.as_mut().unwrap().one += 1.0;
+    after.networks = vec![
+        NetworkInfo {
+            bytes_received: 21000,
+            // Counter reset
+            bytes_transmitted: 100,
+            ..test_interface("eth0")
+        },
+        test_interface("docker0"),
+    ];
+    // PID 50 was reused by another program
+    after.processes = vec![
+        test_process(1, 0),
+        test_process(7, 1),
+        ProcessInfo {
+            name: "other".to_string(),
+            ..test_process(50, 1)
+        },
+    ];
+
+    (before, after)
+}
+
+#[test]
+fn test_diff_metrics() {
+    let (before, after) = test_metrics_pair();
+    let diff = diff_metrics(&before, &after);
+
+    assert_eq!(diff.from, before.timestamp);
+    assert_eq!(diff.to, after.timestamp);
+    assert_eq!(diff.elapsed_secs, 10.0);
+    assert_eq!(diff.cpu_usage_delta, Some(40.0));
+    assert_eq!(diff.memory_used_delta, Some(-128));
+    assert_eq!(diff.memory_usage_delta, Some(-12.5));
+    assert_eq!(diff.swap_used_delta, Some(0));
+    assert_eq!(diff.load_one_delta, Some(1.0));
+
+    let pids = |processes: &[ProcessRef]| -> Vec<(u32, String)> {
+        processes
+            .iter()
+            .map(|process| (process.pid, process.name.clone()))
+            .collect()
+    };
+    assert_eq!(
+        pids(&diff.processes_started),
+        vec![(7, "proc-7".to_string()), (50, "other".to_string())]
+    );
+    assert_eq!(
+        pids(&diff.processes_exited),
+        vec![(42, "proc-42".to_string()), (50, "proc-50".to_string())]
+    );
+
+    // Only interfaces in both snapshots
+    assert_eq!(diff.networks.len(), 1);
+    let eth0 = &diff.networks[0];
+    assert_eq!(eth0.interface, "eth0");
+    assert_eq!(eth0.bytes_received, 20000);
+    assert_eq!(eth0.rx_bytes_per_sec, Some(2000.0));
+    assert_eq!(eth0.bytes_transmitted, 0);
+    assert_eq!(eth0.tx_bytes_per_sec, Some(0.0));
+
+    // Snapshots out of order have no rates, and missing sections no deltas
+    let mut partial = before.clone();
+    partial.cpu_info = None;
+    let reversed = diff_metrics(&after, &partial);
+    assert_eq!(reversed.elapsed_secs, -10.0);
+    assert_eq!(reversed.cpu_usage_delta, None);
+    assert_eq!(reversed.networks[0].rx_bytes_per_sec, None);
+
+    // Deltas beyond the i64 range saturate instead of overflowing
+    let mut huge = before.clone();
+    let memory = huge.memory_info.as_mut().unwrap();
+    memory.used = u64::MAX;
+    memory.swap_used = u64::MAX;
+    let mut zero = before.clone();
+    let memory = zero.memory_info.as_mut().unwrap();
+    memory.used = 0;
+    memory.swap_used = 0;
+    let up = diff_metrics(&zero, &huge);
+    assert_eq!(up.memory_used_delta, Some(i64::MAX));
+    assert_eq!(up.swap_used_delta, Some(i64::MAX));
+    let down = diff_metrics(&huge, &zero);
+    assert_eq!(down.memory_used_delta, Some(i64::MIN));
+    assert_eq!(down.swap_used_delta, Some(i64::MIN));
+}
+
+#[test]
+fn test_build_process_tree() {
+    let processes = vec![
+        test_process(1, 0),
+        test_process(10, 1),
+        test_process(11, 10),
+        test_process(12, 10),
+        test_process(20, 1),
+        test_process(30, 99),
+    ];
+
+    let tree = build_process_tree(processes.clone(), 10).unwrap();
+    assert_eq!(tree.info.pid, 10);
+    let mut child_pids: Vec<u32> = tree.children.iter().map(|c| c.info.pid).collect();
+    child_pids.sort();
+    assert_eq!(child_pids, vec![11, 12]);
+
+    let full = build_process_tree(processes.clone(), 1).unwrap();
+    assert_eq!(full.children.len(), 2);
+
+    assert!(build_process_tree(processes, 42).is_none());
+
+    // A PPID cycle below the root must not recurse forever
+    let cyclic = vec![
+        test_process(1, 0),
+        test_process(2, 3),
+        test_process(3, 2),
+        test_process(4, 1),
+    ];
+    let tree = build_process_tree(cyclic, 2).unwrap();
+    assert_eq!(tree.children.len(), 1);
+    assert_eq!(tree.children[0].info.pid, 3);
+    assert!(tree.children[0].children.is_empty());
+}
+
+#[tokio::test]
+async fn test_mcp_get_process_tree_not_found() {
+    let server = create_test_mcp_server().await;
+
+    let request = MCPRequest {
+        jsonrpc: "2.0".to_string(),
+        id: Some("test-tree".to_string()),
+        method: METHOD_GET_PROCESS_TREE.to_string(),
+        params: json!({"pid": 999999}),
+    };
+
+    let response = server.handle_request(request).await;
+    assert!(response.result.is_none());
+    assert_eq!(response.error.unwrap().code, ERROR_PROCESS_NOT_FOUND);
+}
+
+#[tokio::test]
+#[cfg(all(target_os = "linux", not(feature = "sysinfo-backend")))]
+async fn test_mcp_get_process_memory() {
+    use mcp_system_monitor::types::METHOD_GET_PROCESS_MEMORY;
+    let server = create_test_mcp_server().await;
+    let pid = std::process::id();
+
+    let request = MCPRequest {
+        jsonrpc: "2.0".to_string(),
+        id: Some("test-memory".to_string()),
+        method: METHOD_GET_PROCESS_MEMORY.to_string(),
+        params: json!({"pid": pid}),
+    };
+    let response = server.handle_request(request).await;
+    assert!(response.error.is_none());
+    let memory: ProcessMemory = serde_json::from_value(response.result.unwrap()).unwrap();
+    assert_eq!(memory.pid, pid);
+    assert!(memory.vm_rss.unwrap() > 0);
+    assert!(memory.vm_size.unwrap() >= memory.vm_rss.unwrap());
+
+    let request = MCPRequest {
+        jsonrpc: "2.0".to_string(),
+        id: Some("test-memory".to_string()),
+        method: METHOD_GET_PROCESS_MEMORY.to_string(),
+        params: json!({"pid": 999999}),
+    };
+    let response = server.handle_request(request).await;
+    assert!(response.result.is_none());
+    assert_eq!(response.error.unwrap().code, ERROR_PROCESS_NOT_FOUND);
+}
+
+#[tokio::test]
+#[cfg(all(target_os = "linux", not(feature = "sysinfo-backend")))]
+async fn test_mcp_get_process_limits() {
+    use mcp_system_monitor::types::METHOD_GET_PROCESS_LIMITS;
+    let server = create_test_mcp_server().await;
+    let pid = std::process::id();
+
+    let request = MCPRequest {
+        jsonrpc: "2.0".to_string(),
+        id: Some("test-limits".to_string()),
+        method: METHOD_GET_PROCESS_LIMITS.to_string(),
+        params: json!({"pid": pid}),
+    };
+    let response = server.handle_request(request).await;
+    assert!(response.error.is_none());
+    let result = response.result.unwrap();
+    assert_eq!(result["pid"], pid);
+    let open_files = result["limits"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .find(|limit| limit["name"] == "Max open files")
+        .expect("no open files limit");
+    assert!(open_files["soft"].as_u64().unwrap() > 0);
+    assert_eq!(open_files["units"], "files");
+
+    let request = MCPRequest {
+        jsonrpc: "2.0".to_string(),
+        id: Some("test-limits".to_string()),
+        method: METHOD_GET_PROCESS_LIMITS.to_string(),
+        params: json!({"pid": 999999}),
+    };
+    let response = server.handle_request(request).await;
+    assert!(response.result.is_none());
+    assert_eq!(response.error.unwrap().code, ERROR_PROCESS_NOT_FOUND);
+}
+
+#[test]
+fn test_parse_zoneinfo_path() {
+    assert_eq!(
+        parse_zoneinfo_path("/usr/share/zoneinfo/Europe/Berlin").as_deref(),
+        Some("Europe/Berlin")
+    );
+    assert_eq!(
+        parse_zoneinfo_path("../usr/share/zoneinfo/Etc/UTC").as_deref(),
+        Some("Etc/UTC")
+    );
+    assert_eq!(
+        parse_zoneinfo_path("/usr/share/zoneinfo/posix/America/New_York").as_deref(),
+        Some("America/New_York")
+    );
+    assert_eq!(parse_zoneinfo_path("/etc/localtime.bak"), None);
+    assert_eq!(parse_zoneinfo_path("/usr/share/zoneinfo/"), None);
+}
+
+#[test]
+fn test_monitoring_interval_floor() {
+    let mut system_monitor = SystemMonitor::new().expect("Failed to create system monitor");
+
+    let effective = system_monitor.set_monitoring_interval(Duration::from_millis(100));
+    assert_eq!(effective, DEFAULT_MIN_MONITORING_INTERVAL);
+    assert_eq!(
+        system_monitor.monitoring_interval(),
+        DEFAULT_MIN_MONITORING_INTERVAL
+    );
+
+    let effective = system_monitor.set_monitoring_interval(Duration::from_secs(10));
+    assert_eq!(effective, Duration::from_secs(10));
+
+    // Raising the floor drags the current interval up with it
+    system_monitor.set_min_monitoring_interval(Duration::from_secs(30));
+    assert_eq!(
+        system_monitor.monitoring_interval(),
+        Duration::from_secs(30)
+    );
+
+    // A zero floor still keeps intervals above zero
+    system_monitor.set_min_monitoring_interval(Duration::ZERO);
+    assert_eq!(system_monitor.min_monitoring_interval(), MIN_INTERVAL_FLOOR);
+    assert_eq!(
+        system_monitor.clamp_interval(Duration::ZERO),
+        MIN_INTERVAL_FLOOR
+    );
+}
+
+#[tokio::test]
+async fn test_mcp_set_monitoring_interval_clamps() {
+    let server = create_test_mcp_server().await;
+
+    let request = MCPRequest {
+        jsonrpc: "2.0".to_string(),
+        id: Some("test-interval".to_string()),
+        method: METHOD_SET_MONITORING_INTERVAL.to_string(),
+        params: json!({"interval_ms": 100}),
+    };
+
+    let response = server.handle_request(request).await;
+    let result = response.result.expect("interval should be accepted");
+    assert_eq!(result["interval_ms"], 1000);
+    assert_eq!(result["clamped"], true);
+
+    let request = MCPRequest {
+        jsonrpc: "2.0".to_string(),
+        id: Some("test-interval-invalid".to_string()),
+        method: METHOD_SET_MONITORING_INTERVAL.to_string(),
+        params: json!({"interval_ms": "fast"}),
+    };
+
+    let response = server.handle_request(request).await;
+    assert_eq!(response.error.unwrap().code, ERROR_INVALID_PARAMS);
+}
+
+#[test]
+fn test_apply_process_query() {
+    let mut processes = vec![test_process(3, 1), test_process(1, 0), test_process(2, 1)];
+    processes[0].cpu_usage = 5.0;
+    processes[1].cpu_usage = 1.0;
+    processes[2].cpu_usage = 9.0;
+    processes[0].memory_usage = 300;
+    processes[1].memory_usage = 100;
+    processes[2].memory_usage = 200;
+
+    let pids = |processes: Vec<ProcessInfo>| processes.iter().map(|p| p.pid).collect::<Vec<_>>();
+
+    // Defaults to CPU usage, highest first
+    let sorted = apply_process_query(processes.clone(), &ProcessQuery::default());
+    assert_eq!(pids(sorted), vec![2, 3, 1]);
+
+    let query = ProcessQuery {
+        sort_by: ProcessSortKey::Memory,
+        order: SortOrder::Asc,
+        ..Default::default()
+    };
+    assert_eq!(
+        pids(apply_process_query(processes.clone(), &query)),
+        vec![1, 2, 3]
+    );
+
+    let query = ProcessQuery {
+        sort_by: ProcessSortKey::Pid,
+        order: SortOrder::Asc,
+        limit: Some(1),
+        offset: 1,
+        ..Default::default()
+    };
+    assert_eq!(
+        pids(apply_process_query(processes.clone(), &query)),
+        vec![2]
+    );
+
+    let query = ProcessQuery {
+        offset: 10,
+        ..Default::default()
+    };
+    assert!(apply_process_query(processes.clone(), &query).is_empty());
+
+    // Filters combine with AND and run before sorting and pagination
+    processes[0].name = "nginx: worker".to_string();
+    processes[1].name = "NGINX".to_string();
+    processes[1].user = "www-data".to_string();
+    let query = ProcessQuery {
+        name_contains: Some("Nginx".to_string()),
+        ..Default::default()
+    };
+    assert_eq!(
+        pids(apply_process_query(processes.clone(), &query)),
+        vec![3, 1]
+    );
+    let query = ProcessQuery {
+        user: Some("www-data".to_string()),
+        name_contains: Some("nginx".to_string()),
+        limit: Some(1),
+        ..Default::default()
+    };
+    assert_eq!(
+        pids(apply_process_query(processes.clone(), &query)),
+        vec![1]
+    );
+    let query = ProcessQuery {
+        user: Some("www".to_string()),
+        ..Default::default()
+    };
+    assert!(apply_process_query(processes, &query).is_empty());
+}
+
+#[test]
+fn test_top_processes() {
+    let mut processes = vec![test_process(1, 0), test_process(2, 1), test_process(3, 1)];
+    processes[0].cpu_usage = 10.0;
+    processes[1].cpu_usage = 30.0;
+    processes[2].cpu_usage = 60.0;
+    processes[0].memory_usage = 500;
+    processes[1].memory_usage = 100;
+    processes[2].memory_usage = 200;
+    processes[0].read_bytes = 10;
+    processes[1].write_bytes = 1000;
+    processes[2].read_bytes = 40;
+    processes[2].write_bytes = 60;
+
+    let pids = |top: &TopProcesses| top.processes.iter().map(|p| p.pid).collect::<Vec<_>>();
+
+    let top = top_processes(processes.clone(), TopMetric::Cpu, 2);
+    assert_eq!(pids(&top), vec![3, 2]);
+    assert_eq!(top.process_count, 3);
+    assert_eq!(top.total_cpu_usage, 100.0);
+    assert_eq!(top.top_cpu_usage, 90.0);
+    assert_eq!((top.total_memory, top.top_memory), (800, 300));
+    assert_eq!((top.total_io_bytes, top.top_io_bytes), (None, None));
+
+    let top = top_processes(processes.clone(), TopMetric::Memory, 1);
+    assert_eq!(pids(&top), vec![1]);
+    assert_eq!(top.top_memory, 500);
+
+    let top = top_processes(processes, TopMetric::Io, 10);
+    assert_eq!(pids(&top), vec![2, 3, 1]);
+    assert_eq!(top.total_io_bytes, Some(1110));
+    assert_eq!(top.top_io_bytes, Some(1110));
+}
+
+#[tokio::test]
+async fn test_mcp_get_processes_query() {
+    let server = create_test_mcp_server().await;
+
+    let request = MCPRequest {
+        jsonrpc: "2.0".to_string(),
+        id: Some("test-processes-limit".to_string()),
+        method: METHOD_GET_PROCESSES.to_string(),
+        params: json!({"sort_by": "pid", "order": "asc", "limit": 2}),
+    };
+
+    let response = server.handle_request(request).await;
+    let result = response.result.expect("query should succeed");
+    let processes = result.as_array().unwrap();
+    assert!(processes.len() <= 2);
+    if processes.len() == 2 {
+        assert!(processes[0]["pid"].as_u64() < processes[1]["pid"].as_u64());
+    }
+
+    let request = MCPRequest {
+        jsonrpc: "2.0".to_string(),
+        id: Some("test-processes-invalid".to_string()),
+        method: METHOD_GET_PROCESSES.to_string(),
+        params: json!({"sort_by": "vibes"}),
+    };
+
+    let response = server.handle_request(request).await;
+    assert_eq!(response.error.unwrap().code, ERROR_INVALID_PARAMS);
+
+    let request = MCPRequest {
+        jsonrpc: "2.0".to_string(),
+        id: Some("test-processes-filter".to_string()),
+        method: METHOD_GET_PROCESSES.to_string(),
+        params: json!({"user": "no-such-user-mcp"}),
+    };
+
+    let response = server.handle_request(request).await;
+    assert_eq!(response.result, Some(json!([])));
+}
+
+#[tokio::test]
+async fn test_mcp_get_top_processes() {
+    let server = create_test_mcp_server().await;
+
+    let request = MCPRequest {
+        jsonrpc: "2.0".to_string(),
+        id: Some("test-top".to_string()),
+        method: METHOD_GET_TOP_PROCESSES.to_string(),
+        params: json!({"by": "memory", "n": 5000}),
+    };
+
+    let response = server.handle_request(request).await;
+    let result = response.result.expect("top processes should succeed");
+    let top: TopProcesses = serde_json::from_value(result).unwrap();
+    assert!(top.processes.len() <= 100);
+    assert!(top.processes.len() <= top.process_count);
+    assert!(top
+        .processes
+        .windows(2)
+        .all(|pair| pair[0].memory_usage >= pair[1].memory_usage));
+    assert!(top.top_memory <= top.total_memory);
+    assert!(top.total_io_bytes.is_none());
+
+    let request = MCPRequest {
+        jsonrpc: "2.0".to_string(),
+        id: Some("test-top-invalid".to_string()),
+        method: METHOD_GET_TOP_PROCESSES.to_string(),
+        params: json!({"by": "pid"}),
+    };
+
+    let response = server.handle_request(request).await;
+    assert_eq!(response.error.unwrap().code, ERROR_INVALID_PARAMS);
+}
+
+#[tokio::test]
+async fn test_mcp_kill_process() {
+    let server = create_test_mcp_server().await;
+    let mut child = std::process::Command::new("sleep")
+        .arg("30")
+        .spawn()
+        .expect("Failed to spawn sleep");
+
+    let request = MCPRequest {
+        jsonrpc: "2.0".to_string(),
+        id: Some("test-kill".to_string()),
+        method: METHOD_KILL_PROCESS.to_string(),
+        params: json!({"pid": child.id(), "signal": 9}),
+    };
+
+    let response = server.handle_request(request).await;
+    let result = response.result.expect("signal should be delivered");
+    assert_eq!(result["signaled"], true);
+
+    let status = child.wait().expect("Failed to reap sleep");
+    assert!(!status.success());
+}
+
+#[tokio::test]
+async fn test_mcp_kill_process_errors() {
+    let server = create_test_mcp_server().await;
+
+    let cases = [
+        (json!({"pid": 999999}), ERROR_PROCESS_NOT_FOUND),
+        (json!({"pid": 0}), ERROR_INVALID_PARAMS),
+        (json!({"pid": 1, "signal": 999}), ERROR_INVALID_PARAMS),
+        (json!({"signal": 15}), ERROR_INVALID_PARAMS),
+    ];
+
+    for (params, expected) in cases {
+        let request = MCPRequest {
+            jsonrpc: "2.0".to_string(),
+            id: Some("test-kill-error".to_string()),
+            method: METHOD_KILL_PROCESS.to_string(),
+            params: params.clone(),
+        };
+
+        let response = server.handle_request(request).await;
+        let error = response.error.expect("signal should be rejected");
+        assert_eq!(error.code, expected, "params: {}", params);
+    }
+}
+
+#[tokio::test]
+async fn test_mcp_kill_process_out_of_range_pid() {
+    let server = create_test_mcp_server().await;
+    let mut child = std::process::Command::new("sleep")
+        .arg("30")
+        .spawn()
+        .expect("Failed to spawn sleep");
+
+    // Truncated to 32 bits, this PID would be the child's
+    let pid = (1u64 << 32) + u64::from(child.id());
+    let request = MCPRequest {
+        jsonrpc: "2.0".to_string(),
+        id: Some("test-kill-out-of-range".to_string()),
+        method: METHOD_KILL_PROCESS.to_string(),
+        params: json!({"pid": pid, "signal": 9}),
+    };
+
+    let response = server.handle_request(request).await;
+    assert_eq!(response.error.unwrap().code, ERROR_INVALID_PARAMS);
+    assert!(child.try_wait().unwrap().is_none(), "child was signaled");
+
+    child.kill().ok();
+    child.wait().ok();
+}
+
+#[tokio::test]
+async fn test_mcp_renice() {
+    let server = create_test_mcp_server().await;
+    let mut child = std::process::Command::new("sleep")
+        .arg("30")
+        .spawn()
+        .expect("Failed to spawn sleep");
+
+    // Raising the nice value is always allowed for our own children
+    let request = MCPRequest {
+        jsonrpc: "2.0".to_string(),
+        id: Some("test-renice".to_string()),
+        method: METHOD_RENICE.to_string(),
+        params: json!({"pid": child.id(), "nice": 19}),
+    };
+
+    let response = server.handle_request(request).await;
+    let result = response.result.expect("renice should succeed");
+    assert_eq!(result["nice"], 19);
+
+    let stat = std::fs::read_to_string(format!("/proc/{}/stat", child.id())).unwrap();
+    let fields: Vec<&str> = stat
+        .rsplit_once(')')
+        .unwrap()
+        .1
+        .split_whitespace()
+        .collect();
+    assert_eq!(fields[16], "19");
+
+    child.kill().ok();
+    child.wait().ok();
+
+    for (params, expected) in [
+        (json!({"pid": child.id(), "nice": 20}), ERROR_INVALID_PARAMS),
+        (json!({"pid": 999999, "nice": 5}), ERROR_PROCESS_NOT_FOUND),
+    ] {
+        let request = MCPRequest {
+            jsonrpc: "2.0".to_string(),
+            id: Some("test-renice-error".to_string()),
+            method: METHOD_RENICE.to_string(),
+            params,
+        };
+
+        let response = server.handle_request(request).await;
+        assert_eq!(response.error.unwrap().code, expected);
+    }
+}
+
+#[tokio::test]
+async fn test_mcp_renice_out_of_range_pid() {
+    let server = create_test_mcp_server().await;
+    let mut child = std::process::Command::new("sleep")
+        .arg("30")
+        .spawn()
+        .expect("Failed to spawn sleep");
+
+    // Truncated to 32 bits, this PID would be the child's
+    let pid = (1u64 << 32) + u64::from(child.id());
+    let request = MCPRequest {
+        jsonrpc: "2.0".to_string(),
+        id: Some("test-renice-out-of-range".to_string()),
+        method: METHOD_RENICE.to_string(),
+        params: json!({"pid": pid, "nice": 19}),
+    };
+
+    let response = server.handle_request(request).await;
+    assert_eq!(response.error.unwrap().code, ERROR_INVALID_PARAMS);
+
+    // The child keeps the nice value it inherited from the test process
+    let nice = |pid: &str| {
+        let stat = std::fs::read_to_string(format!("/proc/{}/stat", pid)).unwrap();
+        stat.rsplit_once(')')
+            .unwrap()
+            .1
+            .split_whitespace()
+            .nth(16)
+            .unwrap()
+            .to_string()
+    };
+    assert_eq!(
+        nice(&child.id().to_string()),
+        nice("self"),
+        "child was reniced"
+    );
+
+    child.kill().ok();
+    child.wait().ok();
+}
+
+#[tokio::test]
+async fn test_sse_streams_live_metrics() {
+    use axum::body::Body;
+    use axum::http::{Request, StatusCode};
+    use futures::StreamExt;
+    use tower::ServiceExt;
+
+    let app = create_test_http_server().await.router();
+    let request = Request::get("/?interval_secs=1&patch=true")
+        .body(Body::empty())
+        .unwrap();
+
+    let response = app.oneshot(request).await.unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+
+    let mut body = response.into_body().into_data_stream();
+    let mut received = String::new();
+    let sample = tokio::time::timeout(Duration::from_secs(10), async {
+        while let Some(Ok(chunk)) = body.next().await {
+            received.push_str(&String::from_utf8_lossy(&chunk));
+            if let Some((_, rest)) = received.split_once("event: metrics\ndata: ") {
+                if let Some((data, _)) = rest.split_once('\n') {
+                    return Some(data.to_string());
+                }
+            }
+        }
+        None
+    })
+    .await
+    .expect("timed out waiting for a metrics event")
+    .expect("stream ended without a metrics event");
+
+    // The first patch-mode sample is always a full snapshot
+    let update: serde_json::Value = serde_json::from_str(&sample).unwrap();
+    assert_eq!(update["type"], "full");
+    assert!(update["data"]["cpu_info"].is_object());
+    assert!(update["data"]["load_average"].is_object());
+}
+
+#[tokio::test]
+async fn test_sse_zero_interval() {
+    use axum::body::Body;
+    use axum::http::{Request, StatusCode};
+    use futures::StreamExt;
+    use tower::ServiceExt;
+
+    let mut monitor =
+        SystemMonitor::with_provider(MockProvider).expect("mock provider never fails");
+    monitor.set_min_monitoring_interval(Duration::ZERO);
+    let app = HTTPServer::new(Arc::new(RwLock::new(monitor))).router();
+    let request = Request::get("/?interval_secs=0")
+        .body(Body::empty())
+        .unwrap();
+
+    let response = app.oneshot(request).await.unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+
+    // The stream runs at the shortest interval instead of panicking
+    let mut body = response.into_body().into_data_stream();
+    let mut received = String::new();
+    tokio::time::timeout(Duration::from_secs(10), async {
+        while !received.contains("event: metrics") {
+            let chunk = body.next().await.expect("stream ended").unwrap();
+            received.push_str(&String::from_utf8_lossy(&chunk));
+        }
+    })
+    .await
+    .expect("timed out waiting for a metrics event");
+}
+
+#[tokio::test]
+async fn test_http_human_sizes() {
+    use axum::body::Body;
+    use axum::http::{Request, StatusCode};
+    use tower::ServiceExt;
+
+    let monitor = SystemMonitor::with_provider(MockProvider).expect("mock provider never fails");
+    let app = HTTPServer::new(Arc::new(RwLock::new(monitor))).router();
+
+    for (uri, human) in [
+        ("/api/system/memory", false),
+        ("/api/system/memory?human=true", true),
+    ] {
+        let request = Request::get(uri).body(Body::empty()).unwrap();
+        let response = app.clone().oneshot(request).await.unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        let memory: serde_json::Value = serde_json::from_slice(&body).unwrap();
+        assert_eq!(memory["total"], 1024);
+        if human {
+            assert_eq!(memory["total_human"], "1.0 KiB");
+            assert_eq!(memory["used_human"], "256 B");
+        } else {
+            assert!(memory.get("total_human").is_none());
+        }
+    }
+}
+
+#[tokio::test]
+async fn test_http_self_endpoint() {
+    use axum::body::Body;
+    use axum::http::{Request, StatusCode};
+    use tower::ServiceExt;
+
+    let app = create_test_http_server().await.router();
+
+    // A lookup of a PID that cannot exist counts as a failed request
+    let request = Request::get("/api/system/processes/4294967295")
+        .body(Body::empty())
+        .unwrap();
+    let response = app.clone().oneshot(request).await.unwrap();
+    assert_eq!(response.status(), StatusCode::NOT_FOUND);
+
+    let request = Request::get("/api/self").body(Body::empty()).unwrap();
+    let response = app.oneshot(request).await.unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+        .await
+        .unwrap();
+    let info: SelfInfo = serde_json::from_slice(&body).unwrap();
+
+    assert_eq!(info.process.pid, std::process::id());
+    assert!(info.process.memory_usage > 0);
+    assert!(info.process.threads > 0);
+    assert_eq!(info.requests_total, 1);
+    assert_eq!(info.request_errors_total, 1);
+}
+
+#[tokio::test]
+async fn test_http_version_endpoint() {
+    use axum::body::Body;
+    use axum::http::{Request, StatusCode};
+    use tower::ServiceExt;
+
+    // Open like /health, even when a token is required
+    let app = create_test_http_server()
+        .await
+        .with_auth_token("secret")
+        .router();
+    let request = Request::get("/version").body(Body::empty()).unwrap();
+
+    let response = app.oneshot(request).await.unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+        .await
+        .unwrap();
+    let info: BuildInfo = serde_json::from_slice(&body).unwrap();
+
+    assert_eq!(info.name, "mcp-system-monitor");
+    assert_eq!(info.version, env!("CARGO_PKG_VERSION"));
+    assert!(info
+        .rustc_version
+        .as_deref()
+        .is_some_and(|version| version.starts_with("rustc ")));
+    assert!(info.build_timestamp.is_some());
+    if let Some(sha) = &info.git_sha {
+        assert!(!sha.is_empty());
+    }
+}
+
+#[tokio::test]
+async fn test_http_diff_metrics() {
+    use axum::body::Body;
+    use axum::http::{header, Request, StatusCode};
+    use tower::ServiceExt;
+
+    let app = create_test_http_server().await.router();
+    let (before, after) = test_metrics_pair();
+    let post = |body: serde_json::Value| {
+        Request::post("/api/system/diff")
+            .header(header::CONTENT_TYPE, "application/json")
+            .body(Body::from(body.to_string()))
+            .unwrap()
+    };
+
+    let request = post(json!({"before": before, "after": after}));
+    let response = app.clone().oneshot(request).await.unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+        .await
+        .unwrap();
+    let diff: MetricsDiff = serde_json::from_slice(&body).unwrap();
+    assert_eq!(diff.cpu_usage_delta, Some(40.0));
+    assert_eq!(diff.processes_started.len(), 2);
+    assert_eq!(diff.networks[0].rx_bytes_per_sec, Some(2000.0));
+
+    let request = post(json!({"before": before}));
+    let response = app.oneshot(request).await.unwrap();
+    assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+}
+
+#[test]
+fn test_rate_limiter_token_bucket() {
+    use mcp_system_monitor::rate_limit::RateLimiter;
+    use std::net::{IpAddr, Ipv4Addr};
+    use std::time::Instant;
+
+    let limiter = RateLimiter::new(10, 3);
+    let client = Some(IpAddr::V4(Ipv4Addr::new(192, 0, 2, 1)));
+    let other = Some(IpAddr::V4(Ipv4Addr::new(192, 0, 2, 2)));
+    let start = Instant::now();
+
+    for _ in 0..3 {
+        assert!(limiter.check_at(client, start).is_ok());
+    }
+    let wait = limiter.check_at(client, start).unwrap_err();
+    assert!(wait > Duration::ZERO && wait <= Duration::from_millis(100));
+
+    // Buckets are per client
+    assert!(limiter.check_at(other, start).is_ok());
+
+    // One token every 100ms
+    let later = start + Duration::from_millis(100);
+    assert!(limiter.check_at(client, later).is_ok());
+    assert!(limiter.check_at(client, later).is_err());
+
+    // Refills are capped at the burst size
+    let much_later = start + Duration::from_secs(60);
+    for _ in 0..3 {
+        assert!(limiter.check_at(client, much_later).is_ok());
+    }
+    assert!(limiter.check_at(client, much_later).is_err());
+}
+
+#[test]
+fn test_rate_limiter_bounds_tracked_clients() {
+    use mcp_system_monitor::rate_limit::{RateLimiter, MAX_TRACKED_CLIENTS};
+    use std::net::{IpAddr, Ipv6Addr};
+    use std::time::Instant;
+
+    // One token per second, so no bucket refills during the test
+    let limiter = RateLimiter::new(1, 1);
+    let client = |i: usize| {
+        Some(IpAddr::V6(Ipv6Addr::new(
+            0x2001, 0xdb8, 0, 0, 0, 0, 0, i as u16,
+        )))
+    };
+    let start = Instant::now();
+    let at = |i: usize| start + Duration::from_micros(i as u64);
+
+    for i in 0..MAX_TRACKED_CLIENTS {
+        assert!(limiter.check_at(client(i), at(i)).is_ok());
+    }
+    assert!(limiter
+        .check_at(client(1), at(MAX_TRACKED_CLIENTS))
+        .is_err());
+
+    // A new client evicts the one idle longest, while the others stay limited
+    let now = at(MAX_TRACKED_CLIENTS + 1);
+    assert!(limiter.check_at(client(MAX_TRACKED_CLIENTS), now).is_ok());
+    assert!(limiter.check_at(client(0), now).is_ok());
+    assert!(limiter.check_at(client(1), now).is_err());
+}
+
+#[tokio::test]
+async fn test_http_rate_limit() {
+    use axum::body::Body;
+    use axum::extract::ConnectInfo;
+    use axum::http::{header, Request, StatusCode};
+    use std::net::SocketAddr;
+    use tower::ServiceExt;
+
+    let app = create_test_http_server()
+        .await
+        .with_rate_limit(1, 2)
+        .router();
+    let request_from = |path: &str, addr: &str| {
+        let mut request = Request::get(path).body(Body::empty()).unwrap();
+        request
+            .extensions_mut()
+            .insert(ConnectInfo(addr.parse::<SocketAddr>().unwrap()));
+        request
+    };
+
+    for _ in 0..2 {
+        let request = request_from("/api/monitoring/status", "192.0.2.1:40000");
+        let response = app.clone().oneshot(request).await.unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+    }
+
+    // The port does not matter, only the address
+    let request = request_from("/api/monitoring/status", "192.0.2.1:40001");
+    let response = app.clone().oneshot(request).await.unwrap();
+    assert_eq!(response.status(), StatusCode::TOO_MANY_REQUESTS);
+    assert_eq!(response.headers()[header::RETRY_AFTER], "1");
+
+    let request = request_from("/api/monitoring/status", "192.0.2.2:40000");
+    let response = app.clone().oneshot(request).await.unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+
+    let request = request_from("/health", "192.0.2.1:40000");
+    let response = app.oneshot(request).await.unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+}
+
+#[tokio::test]
+async fn test_http_error_status_codes() {
+    use axum::body::Body;
+    use axum::http::{header, Request, StatusCode};
+    use tower::ServiceExt;
+
+    let monitor = SystemMonitor::with_provider(MockProvider).expect("mock provider never fails");
+    let app = HTTPServer::new(Arc::new(RwLock::new(monitor))).router();
+
+    let json_post = |uri: &str, body: &str| {
+        Request::post(uri)
+            .header(header::CONTENT_TYPE, "application/json")
+            .body(Body::from(body.to_string()))
+            .unwrap()
+    };
+    let cases = [
+        (
+            Request::get("/api/system/processes/999999")
+                .body(Body::empty())
+                .unwrap(),
+            StatusCode::NOT_FOUND,
+            ERROR_PROCESS_NOT_FOUND,
+        ),
+        (
+            json_post("/api/system/processes/42/signal", "{}"),
+            StatusCode::FORBIDDEN,
+            ERROR_PERMISSION_DENIED,
+        ),
+        (
+            json_post("/api/monitoring/interval", r#"{"interval_ms": "fast"}"#),
+            StatusCode::BAD_REQUEST,
+            ERROR_INVALID_PARAMS,
+        ),
+        (
+            Request::get("/api/system/battery")
+                .body(Body::empty())
+                .unwrap(),
+            StatusCode::NOT_IMPLEMENTED,
+            ERROR_UNSUPPORTED,
+        ),
+    ];
+
+    for (request, status, code) in cases {
+        let uri = request.uri().to_string();
+        let response = app.clone().oneshot(request).await.unwrap();
+        assert_eq!(response.status(), status, "{}", uri);
+
+        // The MCP error is returned as the body
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        let body: serde_json::Value = serde_json::from_slice(&body).unwrap();
+        assert_eq!(body["error"]["code"], code, "{}", uri);
+        assert!(body["error"]["message"].is_string(), "{}", uri);
+    }
+}
+
+#[tokio::test]
+async fn test_prometheus_metrics_endpoint() {
+    use axum::body::Body;
+    use axum::http::{header, Request, StatusCode};
+    use tower::ServiceExt;
+
+    let app = create_test_http_server().await.router();
+    let request = Request::get("/metrics").body(Body::empty()).unwrap();
+
+    let response = app.oneshot(request).await.unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    assert!(response.headers()[header::CONTENT_TYPE]
+        .to_str()
+        .unwrap()
+        .starts_with("text/plain; version=0.0.4"));
+
+    let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+        .await
+        .unwrap();
+    let text = String::from_utf8(body.to_vec()).unwrap();
+
+    assert!(text.contains("# TYPE node_cpu_usage_percent gauge"));
+    assert!(text.contains("# TYPE node_memory_total_bytes gauge"));
+    assert!(text.contains("# TYPE node_network_receive_bytes_total counter"));
+    assert!(text.contains("# TYPE mcp_requests_total counter"));
+
+    for line in text.lines().filter(|line| !line.starts_with('#')) {
+        let (_, value) = line.rsplit_once(' ').expect("sample without value");
+        assert!(value.parse::<f64>().is_ok(), "bad sample: {}", line);
+    }
+}
+
+#[tokio::test]
+async fn test_openmetrics_endpoint() {
+    use axum::body::Body;
+    use axum::http::{header, Request, StatusCode};
+    use tower::ServiceExt;
+
+    let app = create_test_http_server().await.router();
+    let request = Request::get("/metrics?format=openmetrics")
+        .body(Body::empty())
+        .unwrap();
+
+    let response = app.clone().oneshot(request).await.unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    assert!(response.headers()[header::CONTENT_TYPE]
+        .to_str()
+        .unwrap()
+        .starts_with("application/openmetrics-text; version=1.0.0"));
+
+    let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+        .await
+        .unwrap();
+    let text = String::from_utf8(body.to_vec()).unwrap();
+
+    assert!(text.ends_with("\n# EOF\n"));
+    assert_eq!(text.matches("# EOF").count(), 1);
+    assert!(text
+        .contains("# TYPE node_memory_total_bytes gauge\n# UNIT node_memory_total_bytes bytes\n"));
+    assert!(text.contains("# TYPE node_network_receive_bytes counter\n"));
+    assert!(text.contains("# UNIT node_network_receive_bytes bytes\n"));
+    assert!(!text.contains("# TYPE node_network_receive_bytes_total"));
+    assert!(!text.contains("# UNIT node_load1 "));
+
+    for line in text.lines().filter(|line| !line.starts_with('#')) {
+        let (_, value) = line.rsplit_once(' ').expect("sample without value");
+        assert!(value.parse::<f64>().is_ok(), "bad sample: {}", line);
+    }
+
+    let request = Request::get("/metrics?format=xml")
+        .body(Body::empty())
+        .unwrap();
+    let response = app.oneshot(request).await.unwrap();
+    assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+}
+
+#[tokio::test]
+async fn test_influx_metrics_endpoint() {
+    use axum::body::Body;
+    use axum::http::{header, Request, StatusCode};
+    use tower::ServiceExt;
+
+    let app = create_test_http_server().await.router();
+    let request = Request::get("/metrics/influx").body(Body::empty()).unwrap();
+
+    let response = app.oneshot(request).await.unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    assert!(response.headers()[header::CONTENT_TYPE]
+        .to_str()
+        .unwrap()
+        .starts_with("text/plain"));
+
+    let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+        .await
+        .unwrap();
+    let text = String::from_utf8(body.to_vec()).unwrap();
+
+    assert!(text.contains("cpu,host="));
+    assert!(text.contains(" usage_percent="));
+    assert!(text.contains("memory,host="));
+    assert!(text.contains("system,host="));
+    assert!(text.contains(" load1="));
+
+    let mut timestamps = std::collections::HashSet::new();
+    for line in text.lines() {
+        let (series, rest) = line.split_once(' ').expect("line without fields");
+        let (fields, timestamp) = rest.rsplit_once(' ').expect("line without timestamp");
+        assert!(series.contains(",host="), "untagged line: {}", line);
+        for field in fields.split(',') {
+            let (_, value) = field.split_once('=').expect("field without value");
+            assert!(value.parse::<f64>().is_ok(), "bad field: {}", line);
+        }
+        timestamps.insert(timestamp.parse::<i64>().expect("bad timestamp"));
+        if series.starts_with("disk,") {
+            assert!(series.contains(",mount="), "disk without mount: {}", line);
+        }
+    }
+
+    // Every line carries the collection time, in nanoseconds
+    assert_eq!(timestamps.len(), 1);
+    let timestamp = timestamps.into_iter().next().unwrap();
+    assert!(timestamp > 1_000_000_000_000_000_000);
+}
+
+/// Waits for the next text frame on a WebSocket and parses it as JSON
+async fn next_ws_frame<S>(socket: &mut S) -> serde_json::Value
+where
+    S: futures::Stream<
+            Item = Result<
+                tokio_tungstenite::tungstenite::Message,
+                tokio_tungstenite::tungstenite::Error,
+            >,
+        > + Unpin,
+{
+    use futures::StreamExt;
+    use tokio_tungstenite::tungstenite::Message;
+
+    loop {
+        let message = tokio::time::timeout(Duration::from_secs(10), socket.next())
+            .await
+            .expect("timed out waiting for a frame")
+            .expect("connection closed")
+            .expect("receive failed");
+        if let Message::Text(text) = message {
+            return serde_json::from_str(&text).unwrap();
+        }
+    }
+}
+
+#[tokio::test]
+async fn test_websocket_stream_and_control() {
+    use futures::SinkExt;
+    use tokio_tungstenite::tungstenite::Message;
+
+    let app = create_test_http_server().await.router();
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap();
+    tokio::spawn(async move { axum::serve(listener, app).await });
+
+    let url = format!("ws://{}/ws?interval_secs=1", addr);
+    let (mut socket, _) = tokio_tungstenite::connect_async(url)
+        .await
+        .expect("WebSocket handshake failed");
+
+    // The first tick fires immediately with a full snapshot
+    let frame = next_ws_frame(&mut socket).await;
+    assert_eq!(frame["type"], "metrics");
+    assert!(frame["data"]["cpu_info"].is_object());
+    assert!(frame["data"]["processes"].is_array());
+
+    let subscribe = json!({"action": "subscribe", "metrics": ["cpu", "memory"]});
+    socket
+        .send(Message::Text(subscribe.to_string()))
+        .await
+        .unwrap();
+    socket
+        .send(Message::Text("not json".to_string()))
+        .await
+        .unwrap();
+
+    let mut saw_ack = false;
+    let mut saw_error = false;
+    let mut saw_filtered = false;
+    while !(saw_ack && saw_error && saw_filtered) {
+        let frame = next_ws_frame(&mut socket).await;
+        match frame["type"].as_str() {
+            Some("ack") => saw_ack = true,
+            // The connection must survive a bad control frame
+            Some("error") => saw_error = true,
+            Some("metrics") if saw_ack => {
+                let data = frame["data"].as_object().unwrap();
+                assert!(data.contains_key("cpu_info"));
+                assert!(data.contains_key("memory_info"));
+                assert!(!data.contains_key("processes"));
+                saw_filtered = true;
+            }
+            _ => {}
+        }
+    }
+
+    socket.close(None).await.unwrap();
+}
+
+#[tokio::test]
+async fn test_websocket_patch_stream() {
+    use futures::SinkExt;
+    use tokio_tungstenite::tungstenite::Message;
+
+    let app = create_test_http_server().await.router();
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap();
+    tokio::spawn(async move { axum::serve(listener, app).await });
+
+    let url = format!("ws://{}/ws?interval_secs=1&patch=true", addr);
+    let (mut socket, _) = tokio_tungstenite::connect_async(url)
+        .await
+        .expect("WebSocket handshake failed");
+
+    // The first frame carries the full snapshot
+    let frame = next_ws_frame(&mut socket).await;
+    assert_eq!(frame["type"], "metrics");
+    assert_eq!(frame["data"]["type"], "full");
+    assert!(frame["data"]["data"]["cpu_info"].is_object());
+    assert!(frame["data"]["data"]["processes"].is_array());
+
+    // Unsubscribed sections are removed by the next patch
+    let subscribe = json!({"action": "subscribe", "metrics": ["cpu"]});
+    socket
+        .send(Message::Text(subscribe.to_string()))
+        .await
+        .unwrap();
+    let mut saw_ack = false;
+    loop {
+        let frame = next_ws_frame(&mut socket).await;
+        match frame["type"].as_str() {
+            Some("ack") => saw_ack = true,
+            Some("metrics") if saw_ack => {
+                assert_eq!(frame["data"]["type"], "patch");
+                assert!(frame["data"]["data"]["processes"].is_null());
+                assert!(frame["data"]["data"]
+                    .as_object()
+                    .unwrap()
+                    .contains_key("processes"));
+                break;
+            }
+            _ => {}
+        }
+    }
+
+    socket.close(None).await.unwrap();
+}
+
+#[tokio::test]
+async fn test_websocket_zero_interval() {
+    use futures::SinkExt;
+    use tokio_tungstenite::tungstenite::Message;
+
+    let mut monitor =
+        SystemMonitor::with_provider(MockProvider).expect("mock provider never fails");
+    monitor.set_min_monitoring_interval(Duration::ZERO);
+    let app = HTTPServer::new(Arc::new(RwLock::new(monitor))).router();
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap();
+    tokio::spawn(async move { axum::serve(listener, app).await });
+
+    // A zero query interval runs at the shortest period instead of panicking
+    let url = format!("ws://{}/ws?interval_secs=0", addr);
+    let (mut socket, _) = tokio_tungstenite::connect_async(url)
+        .await
+        .expect("WebSocket handshake failed");
+    assert_eq!(next_ws_frame(&mut socket).await["type"], "metrics");
+
+    // A zero control interval is rejected and the stream carries on
+    let set_interval = json!({"action": "set_interval", "secs": 0});
+    socket
+        .send(Message::Text(set_interval.to_string()))
+        .await
+        .unwrap();
+    loop {
+        let frame = next_ws_frame(&mut socket).await;
+        if frame["type"] == "error" {
+            assert!(frame["message"].as_str().unwrap().contains("secs"));
+            break;
+        }
+        assert_eq!(frame["type"], "metrics");
+    }
+    assert_eq!(next_ws_frame(&mut socket).await["type"], "metrics");
+
+    socket.close(None).await.unwrap();
+}
+
+#[tokio::test]
+async fn test_bearer_token_authentication() {
+    use axum::body::Body;
+    use axum::http::{header, Request, StatusCode};
+    use tower::ServiceExt;
+
+    let app = create_test_http_server()
+        .await
+        .with_auth_token("s3cret")
+        .router();
+
+    let status = |uri: &str, token: Option<&str>| {
+        let app = app.clone();
+        let mut request = Request::get(uri);
+        if let Some(token) = token {
+            request = request.header(header::AUTHORIZATION, format!("Bearer {}", token));
+        }
+        let request = request.body(Body::empty()).unwrap();
+        async move { app.oneshot(request).await.unwrap().status() }
+    };
+
+    assert_eq!(
+        status("/api/system/memory", None).await,
+        StatusCode::UNAUTHORIZED
+    );
+    assert_eq!(
+        status("/api/system/memory", Some("wrong")).await,
+        StatusCode::UNAUTHORIZED
+    );
+    assert_eq!(status("/metrics", None).await, StatusCode::UNAUTHORIZED);
+    assert_eq!(
+        status("/api/system/memory", Some("s3cret")).await,
+        StatusCode::OK
+    );
+    assert_eq!(status("/health", None).await, StatusCode::OK);
+}
+
+#[cfg(target_os = "linux")]
+#[test]
+fn test_linux_collector_reads_proc_root() {
+    use mcp_system_monitor::system_monitor::linux::LinuxSystemInfo;
+    use std::path::Path;
+
+    let root = Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures/proc");
+    let collector = LinuxSystemInfo::with_proc_root(&root);
+    assert_eq!(collector.proc_root(), root);
+
+    let load = collector.get_load_average().unwrap();
+    assert_eq!((load.one, load.runnable, load.total), (0.52, 3, 467));
+
+    let stats = collector.get_kernel_stats().unwrap();
+    assert_eq!(stats.context_switches, 115315133);
+    assert_eq!(stats.forks, 86031);
+
+    let uptime = collector.get_uptime().unwrap();
+    assert_eq!(
+        (uptime.uptime_secs, uptime.uptime_human.as_str()),
+        (354, "5m")
+    );
+    assert_eq!(uptime.boot_time.timestamp(), 1700000000);
+
+    let fd_usage = collector.get_fd_usage().unwrap();
+    assert_eq!((fd_usage.allocated, fd_usage.max), (3072, 4096));
+
+    let entropy = collector.get_entropy().unwrap();
+    assert_eq!((entropy.available, entropy.pool_size), (180, 4096));
+
+    let memory = collector.get_memory_info().unwrap();
+    assert_eq!(memory.total, 16384000 * 1024);
+    assert_eq!(memory.available, 8192000 * 1024);
+    assert_eq!(memory.swap_used, 1024000 * 1024);
+
+    let disk_io = collector.get_disk_io().unwrap();
+    assert_eq!(disk_io.len(), 1);
+    assert_eq!(disk_io[0].device, "sda");
+    assert_eq!(disk_io[0].reads_completed, 1000);
+
+    // The fixture has no /proc/pressure/io
+    let pressure = collector.get_pressure().unwrap();
+    assert_eq!(pressure.cpu.unwrap().some.avg10, 1.5);
+    assert_eq!(pressure.memory.unwrap().full.unwrap().total, 456789);
+    assert!(pressure.io.is_none());
+
+    let swaps = collector.get_swaps().unwrap();
+    assert_eq!(swaps.len(), 2);
+    assert_eq!(swaps[1].name, "/swap file");
+
+    let counters = collector.get_network_counters().unwrap();
+    assert_eq!(counters.len(), 1);
+    assert_eq!(counters[0].interface, "eth0");
+    assert_eq!(counters[0].bytes_received, 98765432101);
+    assert_eq!(counters[0].bytes_transmitted, 12345678);
+
+    // tcp6 and the udp tables are missing from the fixture and skipped
+    let connections = collector.get_network_connections().unwrap();
+    assert_eq!(connections.len(), 1);
+    assert_eq!(connections[0].local_port, 3306);
+    assert_eq!(connections[0].state, "LISTEN");
+
+    // Unlike the counters, the interface list includes loopback
+    let networks = collector.get_network_info().unwrap();
+    assert_eq!(networks.len(), 2);
+    assert_eq!(networks[1].interface, "eth0");
+    assert_eq!(networks[1].bytes_received, 98765432101);
+    assert_eq!(
+        (networks[1].packets_received, networks[1].errors_received),
+        (80000, 2)
+    );
+
+    let missing = LinuxSystemInfo::with_proc_root(root.join("missing"));
+    let error = missing.get_load_average().unwrap_err();
+    assert!(error.to_string().contains("missing/loadavg"), "{}", error);
+    // Files are read directly, so errors name the file rather than a command
+    let error = missing.get_network_info().unwrap_err();
+    assert!(error.to_string().contains("missing/net/dev"), "{}", error);
+    let error = missing.get_memory_info().unwrap_err();
+    assert!(error.to_string().contains("missing/meminfo"), "{}", error);
+
+    assert!(collector.probe().is_ok());
+    assert!(missing.probe().is_err());
+
+    // Outside a container the root cgroup has no limits
+    let memory = LinuxSystemInfo::with_proc_root(&root)
+        .with_cgroup_root(root.join("missing"))
+        .get_memory_info()
+        .unwrap();
+    assert_eq!(
+        (memory.limit_bytes, memory.limit_usage_percent),
+        (None, None)
+    );
+
+    let limited = LinuxSystemInfo::with_proc_root(&root)
+        .with_cgroup_root(root.join("../cgroup"))
+        .get_memory_info()
+        .unwrap();
+    assert_eq!(limited.limit_bytes, Some(512 * 1024 * 1024));
+    assert_eq!(limited.limit_usage_percent, Some(25.0));
+
+    let virtualization = collector.get_virtualization().unwrap();
+    assert_eq!(virtualization.virt_type, "docker");
+    assert!(virtualization.is_container);
+}
+
+#[cfg(target_os = "linux")]
+#[tokio::test]
+async fn test_health_check_reports_degraded() {
+    use axum::body::Body;
+    use axum::http::{Request, StatusCode};
+    use mcp_system_monitor::system_monitor::linux::LinuxSystemInfo;
+    use std::path::Path;
+    use tower::ServiceExt;
+
+    let root = Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures/proc");
+    for (proc_root, expected, status) in [
+        (root.clone(), StatusCode::OK, "healthy"),
+        (
+            root.join("missing"),
+            StatusCode::SERVICE_UNAVAILABLE,
+            "degraded",
+        ),
+    ] {
+        let monitor = SystemMonitor::with_provider(LinuxSystemInfo::with_proc_root(proc_root))
+            .expect("static info does not need procfs");
+        let app = HTTPServer::new(Arc::new(RwLock::new(monitor))).router();
+
+        let request = Request::get("/health").body(Body::empty()).unwrap();
+        let response = app.oneshot(request).await.unwrap();
+        assert_eq!(response.status(), expected);
+
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        let health: serde_json::Value = serde_json::from_slice(&body).unwrap();
+        assert_eq!(health["status"], status);
+        if expected == StatusCode::OK {
+            assert!(health.get("reason").is_none());
+        } else {
+            assert!(health["reason"].as_str().unwrap().contains("uptime"));
+        }
+    }
+
+    // A collection holding the monitor lock does not delay the probe
+    let monitor = SystemMonitor::with_provider(LinuxSystemInfo::with_proc_root(root))
+        .expect("static info does not need procfs");
+    let monitor = Arc::new(RwLock::new(monitor));
+    let app = HTTPServer::new(monitor.clone())
+        .with_collection_timeout(Duration::from_millis(500))
+        .router();
+    let _guard = monitor.write().await;
+    let request = Request::get("/health").body(Body::empty()).unwrap();
+    let response = app.oneshot(request).await.unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+}
+
+#[test]
+fn test_load_tls_config() {
+    use mcp_system_monitor::http_server::load_tls_config;
+    use std::path::Path;
+
+    let fixtures = Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures/tls");
+    let cert = fixtures.join("cert.pem");
+    let key = fixtures.join("key.pem");
+
+    let config = load_tls_config(&cert, &key).expect("fixture certificate should load");
+    assert!(config.alpn_protocols.contains(&b"http/1.1".to_vec()));
+
+    let missing = fixtures.join("missing.pem");
+    let error = load_tls_config(&missing, &key).unwrap_err().to_string();
+    assert!(error.contains("missing.pem"), "{}", error);
+
+    // Swapped files: neither contains what it is supposed to
+    let error = load_tls_config(&key, &key).unwrap_err().to_string();
+    assert!(error.contains("No PEM certificates"), "{}", error);
+    let error = load_tls_config(&cert, &cert).unwrap_err().to_string();
+    assert!(error.contains("No PEM private key"), "{}", error);
+}
+
+#[tokio::test]
+async fn test_http_server_port_in_use() {
+    use std::net::{IpAddr, Ipv4Addr};
+
+    let occupied = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+    let port = occupied.local_addr().unwrap().port();
+
+    let monitor = Arc::new(RwLock::new(SystemMonitor::new().unwrap()));
+    let server = HTTPServer::new(monitor).with_bind_addr(IpAddr::V4(Ipv4Addr::LOCALHOST));
+
+    let error = server.run(port).await.unwrap_err().to_string();
+    assert!(error.contains("already in use"), "{}", error);
+}
+
+#[tokio::test]
+#[cfg(unix)]
+async fn test_http_server_unix_socket() {
+    use std::os::unix::fs::PermissionsExt;
+    use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+    let path = std::env::temp_dir().join(format!("mcp-system-monitor-{}.sock", std::process::id()));
+    // Dropping a listener leaves its socket file behind, like a crashed server
+    drop(std::os::unix::net::UnixListener::bind(&path).unwrap());
+
+    let monitor = Arc::new(RwLock::new(SystemMonitor::new().unwrap()));
+    let (stop, stopped) = tokio::sync::oneshot::channel::<()>();
+    let server = HTTPServer::new(monitor.clone()).run_uds_with_shutdown(&path, async {
+        let _ = stopped.await;
+    });
+
+    let client = async {
+        let mode = std::fs::metadata(&path).unwrap().permissions().mode();
+        assert_eq!(mode & 0o777, 0o600);
+
+        let mut stream = tokio::net::UnixStream::connect(&path).await.unwrap();
+        stream
+            .write_all(b"GET /health HTTP/1.1\r\nHost: localhost\r\nConnection: close\r\n\r\n")
+            .await
+            .unwrap();
+        let mut response = String::new();
+        stream.read_to_string(&mut response).await.unwrap();
+        assert!(response.starts_with("HTTP/1.1 200"), "{}", response);
+
+        // A live socket is not taken over by a second server
+        let error = HTTPServer::new(monitor.clone())
+            .run_uds_with_shutdown(&path, std::future::pending())
+            .await
+            .unwrap_err()
+            .to_string();
+        assert!(error.contains("already in use"), "{}", error);
+
+        stop.send(()).unwrap();
+    };
+
+    let (result, ()) = tokio::join!(server, client);
+    result.unwrap();
+    assert!(!path.exists(), "socket file should be removed on shutdown");
+
+    // Regular files are never replaced
+    std::fs::write(&path, "not a socket").unwrap();
+    let error = HTTPServer::new(monitor)
+        .run_uds_with_shutdown(&path, std::future::pending())
+        .await
+        .unwrap_err()
+        .to_string();
+    std::fs::remove_file(&path).unwrap();
+    assert!(error.contains("not a socket"), "{}", error);
+}
+
+/// Writes `contents` to a uniquely named TOML file in the temp directory
+fn write_temp_config(name: &str, contents: &str) -> std::path::PathBuf {
+    let path = std::env::temp_dir().join(format!(
+        "mcp-system-monitor-{}-{}.toml",
+        name,
+        std::process::id()
+    ));
+    std::fs::write(&path, contents).unwrap();
+    path
+}
+
+#[test]
+fn test_config_resolve_precedence() {
+    use mcp_system_monitor::config::{Config, ConfigSource, LogFormat};
+
+    let path = write_temp_config(
+        "precedence",
+        "bind_addr = \"127.0.0.1\"\nport = 9000\nmonitoring_interval_ms = 2000\n\n[collectors]\nprocesses = false\n",
+    );
+    let args: Vec<String> = vec![
+        "mcp-system-monitor".into(),
+        format!("--config={}", path.display()),
+        "--port".into(),
+        "9100".into(),
+    ];
+    let env = |name: &str| match name {
+        "MCP_PORT" => Some("9050".to_string()),
+        "MCP_AUTH_TOKEN" => Some("secret".to_string()),
+        "MCP_LOG_FORMAT" => Some("JSON".to_string()),
+        _ => None,
+    };
+
+    let (config, sources) = Config::resolve(&args, env).unwrap();
+    std::fs::remove_file(&path).unwrap();
+
+    assert_eq!(config.bind_addr.to_string(), "127.0.0.1");
+    assert_eq!(config.port, 9100);
+    assert_eq!(config.auth_token.as_deref(), Some("secret"));
+    assert_eq!(config.monitoring_interval_ms, 2000);
+    assert!(!config.collectors.processes);
+    assert!(config.collectors.disks);
+    assert_eq!(config.log_format, LogFormat::Json);
+
+    assert_eq!(sources["bind_addr"], ConfigSource::File);
+    assert_eq!(sources["port"], ConfigSource::Cli);
+    assert_eq!(sources["auth_token"], ConfigSource::Env);
+    assert_eq!(sources["tls_cert"], ConfigSource::Default);
+    assert_eq!(sources["collectors"], ConfigSource::File);
+}
+
+#[test]
+fn test_config_defaults_without_file() {
+    use mcp_system_monitor::config::Config;
+
+    let args = vec!["mcp-system-monitor".to_string()];
+    let (config, _) = Config::resolve(&args, |_| None).unwrap();
+    assert_eq!(config, Config::default());
+    assert_eq!(config.port, 57996);
+    assert_eq!(config.bind_addr.to_string(), "0.0.0.0");
+}
+
+#[test]
+fn test_config_validation_errors() {
+    use mcp_system_monitor::config::Config;
+
+    let path = write_temp_config("unknown", "prot = 9000\n");
+    let error = format!("{:#}", Config::load(&path).unwrap_err());
+    std::fs::remove_file(&path).unwrap();
+    assert!(error.contains("prot"), "{}", error);
+
+    let path = write_temp_config("webhook-template", "[webhook]\ntemplate = \"{\"\n");
+    let error = Config::load(&path).unwrap_err().to_string();
+    std::fs::remove_file(&path).unwrap();
+    assert!(error.contains("webhook template"), "{}", error);
+
+    let path = write_temp_config("tls", "tls_cert = \"/tmp/cert.pem\"\n");
+    let error = Config::load(&path).unwrap_err().to_string();
+    std::fs::remove_file(&path).unwrap();
+    assert!(error.contains("tls_key"), "{}", error);
+
+    let args = vec![
+        "mcp-system-monitor".to_string(),
+        "--socket=/run/mcp.sock".to_string(),
+        "--tls-cert=/tmp/cert.pem".to_string(),
+        "--tls-key=/tmp/key.pem".to_string(),
+    ];
+    let error = Config::resolve(&args, |_| None).unwrap_err().to_string();
+    assert!(error.contains("socket_path"), "{}", error);
+
+    let args = vec![
+        "mcp-system-monitor".to_string(),
+        "--rate-limit-burst=0".to_string(),
+    ];
+    let error = Config::resolve(&args, |_| None).unwrap_err().to_string();
+    assert!(error.contains("rate_limit_burst"), "{}", error);
+
+    let args = vec!["mcp-system-monitor".to_string()];
+    let error = Config::resolve(&args, |name| {
+        (name == "MCP_MIN_INTERVAL_MS").then(|| "0".to_string())
+    })
+    .unwrap_err()
+    .to_string();
+    assert!(error.contains("min_interval_ms"), "{}", error);
+
+    let args = vec!["mcp-system-monitor".to_string(), "--port=http".to_string()];
+    let error = Config::resolve(&args, |_| None).unwrap_err().to_string();
+    assert!(error.contains("Invalid port"), "{}", error);
+
+    let args = vec![
+        "mcp-system-monitor".to_string(),
+        "--log-format=xml".to_string(),
+    ];
+    let error = Config::resolve(&args, |_| None).unwrap_err().to_string();
+    assert!(error.contains("Invalid log_format"), "{}", error);
+
+    let path = write_temp_config("max-processes", "[collectors]\nmax_processes = 0\n");
+    let error = Config::load(&path).unwrap_err().to_string();
+    std::fs::remove_file(&path).unwrap();
+    assert!(error.contains("max_processes"), "{}", error);
+}
+
+#[test]
+fn test_disabled_collectors_report_empty() {
+    use mcp_system_monitor::config::CollectorConfig;
+
+    let mut monitor = SystemMonitor::new().unwrap();
+    monitor.set_collectors(CollectorConfig {
+        disks: false,
+        networks: true,
+        processes: false,
+        gpus: false,
+        process_fds: false,
+        max_processes: None,
+        process_sort_by: ProcessSortKey::Cpu,
+    });
+
+    assert!(monitor.get_processes().unwrap().is_empty());
+    assert!(monitor.get_disk_info().unwrap().is_empty());
+    assert!(monitor.get_disk_io().unwrap().is_empty());
+    assert!(monitor.get_process_tree(1).unwrap().is_none());
+}
+
+#[tokio::test]
+async fn test_max_processes() {
+    use mcp_system_monitor::config::CollectorConfig;
+
+    let mut monitor =
+        SystemMonitor::with_provider(MockProvider).expect("mock provider never fails");
+    monitor.set_collectors(CollectorConfig {
+        max_processes: Some(1),
+        process_sort_by: ProcessSortKey::Pid,
+        ..CollectorConfig::default()
+    });
+
+    // The snapshot keeps the highest PID and reports how many there were
+    let metrics = monitor
+        .get_system_metrics(MetricsQuery::default())
+        .await
+        .unwrap();
+    assert_eq!(metrics.processes.len(), 1);
+    assert_eq!(metrics.processes[0].pid, 42);
+    assert_eq!(metrics.total_count, Some(2));
+    let json = serde_json::to_value(&metrics).unwrap();
+    assert_eq!(json["total_count"], 2);
+
+    // A per-request maximum overrides the configured one
+    let metrics = monitor
+        .get_system_metrics(MetricsQuery {
+            max_processes: Some(5),
+            ..MetricsQuery::default()
+        })
+        .await
+        .unwrap();
+    assert_eq!(metrics.processes.len(), 2);
+    assert_eq!(metrics.total_count, None);
+    let json = serde_json::to_value(&metrics).unwrap();
+    assert!(json.get("total_count").is_none());
+
+    // Process listings are not capped; they page with their own limit
+    let processes = monitor.query_processes(&ProcessQuery::default()).unwrap();
+    assert_eq!(processes.len(), 2);
+
+    let server = MCPServer::new(Arc::new(RwLock::new(monitor)));
+    let request = MCPRequest {
+        jsonrpc: "2.0".to_string(),
+        id: Some("test-max-processes".to_string()),
+        method: METHOD_GET_SYSTEM_METRICS.to_string(),
+        params: json!({"components": "processes", "max_processes": 0}),
+    };
+    let response = server.handle_request(request).await;
+    assert_eq!(response.error.unwrap().code, ERROR_INVALID_PARAMS);
+}
+
+#[tokio::test]
+async fn test_run_blocking_times_out_on_held_lock() {
+    let system_monitor = create_test_system_monitor().await;
+
+    let memory = SystemMonitor::run_blocking(&system_monitor, Duration::from_secs(10), |monitor| {
+        monitor.get_memory_info()
+    })
+    .await
+    .unwrap();
+    assert!(memory.total > 0);
+
+    let _guard = system_monitor.write().await;
+    let error =
+        SystemMonitor::run_blocking(&system_monitor, Duration::from_millis(50), |monitor| {
+            monitor.get_memory_info()
+        })
+        .await
+        .unwrap_err();
+    assert!(matches!(error, MonitorError::Timeout(_)), "{}", error);
+}
+
+#[tokio::test]
+async fn test_mcp_collection_timeout() {
+    let system_monitor = create_test_system_monitor().await;
+    let server =
+        MCPServer::new(system_monitor.clone()).with_collection_timeout(Duration::from_millis(50));
+    let _guard = system_monitor.write().await;
+
+    for method in [METHOD_GET_CPU_INFO, METHOD_GET_SYSTEM_METRICS] {
+        let request = MCPRequest {
+            jsonrpc: "2.0".to_string(),
+            id: Some("test-timeout".to_string()),
+            method: method.to_string(),
+            params: json!({}),
+        };
+
+        let response = server.handle_request(request).await;
+        let error = response.error.expect("collection should time out");
+        assert_eq!(
+            error.code, ERROR_SYSTEM_COMMAND_FAILED,
+            "method: {}",
+            method
+        );
+        assert!(error.message.contains("timed out"), "{}", error.message);
+        let data = error.data.expect("collector errors carry data");
+        assert_eq!(data["method"], method);
+        assert_eq!(data["kind"], "timeout");
+        assert_eq!(data["timeout_ms"], 50);
+    }
+
+    // Request validation errors have no collector context
+    let request = MCPRequest {
+        jsonrpc: "2.0".to_string(),
+        id: Some("test-invalid-params".to_string()),
+        method: METHOD_GET_PROCESS_BY_PID.to_string(),
+        params: json!({}),
+    };
+    let response = server.handle_request(request).await;
+    assert!(response.error.unwrap().data.is_none());
+}
+
+#[test]
+fn test_static_info_is_cached() {
+    let mut monitor = SystemMonitor::new().unwrap();
+    let static_info = monitor.static_info().clone();
+
+    let system_info = monitor.get_system_info().unwrap();
+    assert_eq!(system_info.hostname, static_info.hostname);
+    assert_eq!(system_info.kernel_version, static_info.kernel_version);
+
+    let cpu_info = monitor.get_cpu_info().unwrap();
+    assert_eq!(cpu_info.name, static_info.cpu_name);
+    assert_eq!(cpu_info.cores, static_info.logical_cores);
+    assert!(cpu_info.cores > 0);
+
+    monitor.refresh_static().unwrap();
+    assert_eq!(monitor.static_info().hostname, static_info.hostname);
+}
+
+#[test]
+fn test_monitor_error_kinds() {
+    let error = parse_loadavg("garbage").unwrap_err();
+    assert!(matches!(error, MonitorError::ParseError(_)), "{}", error);
+
+    let denied = std::io::Error::from(std::io::ErrorKind::PermissionDenied);
+    let error = MonitorError::io("Failed to read /proc/1/io", denied);
+    assert!(
+        matches!(error, MonitorError::PermissionDenied(_)),
+        "{}",
+        error
+    );
+
+    let missing = std::io::Error::from(std::io::ErrorKind::NotFound);
+    let error = MonitorError::Collector {
+        name: "disk",
+        error: Box::new(MonitorError::command("df", missing)),
+    };
+    assert!(error
+        .to_string()
+        .starts_with("disk collector failed: Failed to execute df"));
+    assert!(matches!(error.root(), MonitorError::CommandFailed { .. }));
+}
+
+#[test]
+fn test_history_ring_buffer() {
+    let mut monitor = SystemMonitor::new().unwrap();
+    monitor.set_history_capacity(3);
+    assert!(monitor.history(None).is_empty());
+
+    let mut recorded = Vec::new();
+    for _ in 0..5 {
+        recorded.push(monitor.record_history_sample().unwrap());
+    }
+
+    assert_eq!(monitor.history(None), recorded[2..]);
+    assert_eq!(monitor.history(Some(2)), recorded[3..]);
+    assert_eq!(monitor.history(Some(100)).len(), 3);
+    assert!(monitor.history(Some(0)).is_empty());
+
+    let points = history_points(&recorded, HistoryMetric::Load);
+    assert_eq!(points.len(), 5);
+    assert_eq!(points[4].timestamp, recorded[4].timestamp);
+    assert_eq!(points[4].value, recorded[4].load_one);
+}
+
+#[test]
+fn test_metric_stats() {
+    assert_eq!(metric_stats(&[]), None);
+
+    let stats = metric_stats(&[4.0, 1.0, 3.0, 2.0, f64::NAN]).unwrap();
+    assert_eq!(stats.samples, 4);
+    assert_eq!(stats.min, 1.0);
+    assert_eq!(stats.max, 4.0);
+    assert_eq!(stats.mean, 2.5);
+    // Interpolated between ranks rather than rounded to the nearest one
+    assert_eq!(stats.p50, 2.5);
+    assert!((stats.p95 - 3.85).abs() < 1e-9);
+    assert!((stats.p99 - 3.97).abs() < 1e-9);
+    assert!((stats.stddev - 1.25f64.sqrt()).abs() < 1e-9);
+
+    let single = metric_stats(&[7.0]).unwrap();
+    assert_eq!((single.p50, single.p99, single.stddev), (7.0, 7.0, 0.0));
+}
+
+#[tokio::test]
+async fn test_history_sampler_and_mcp_get_history() {
+    let system_monitor = create_test_system_monitor().await;
+    {
+        let mut monitor = system_monitor.write().await;
+        monitor.set_min_monitoring_interval(Duration::from_millis(20));
+        monitor.set_monitoring_interval(Duration::from_millis(20));
+    }
+    let server = MCPServer::new(system_monitor.clone());
+    let before = system_monitor.read().await.last_update();
+
+    // Nothing is recorded until monitoring starts
+    tokio::time::sleep(Duration::from_millis(100)).await;
+    assert!(system_monitor.read().await.history(None).is_empty());
+
+    let request = MCPRequest {
+        jsonrpc: "2.0".to_string(),
+        id: Some("test-start".to_string()),
+        method: METHOD_START_MONITORING.to_string(),
+        params: json!({}),
+    };
+    let response = server.handle_request(request).await;
+    assert_eq!(response.result.unwrap()["started"], true);
+
+    let deadline = tokio::time::Instant::now() + Duration::from_secs(10);
+    while system_monitor.read().await.history(None).len() < 2 {
+        assert!(
+            tokio::time::Instant::now() < deadline,
+            "sampler recorded nothing"
+        );
+        tokio::time::sleep(Duration::from_millis(20)).await;
+    }
+    assert!(system_monitor.read().await.last_update() > before);
+
+    // Stopping cancels the sampler, so the history stops growing
+    assert!(system_monitor.write().await.stop_monitoring().unwrap());
+    let recorded = system_monitor.read().await.history(None).len();
+    tokio::time::sleep(Duration::from_millis(100)).await;
+    assert_eq!(system_monitor.read().await.history(None).len(), recorded);
+
+    let server = MCPServer::new(system_monitor);
+    let request = MCPRequest {
+        jsonrpc: "2.0".to_string(),
+        id: Some("test-history".to_string()),
+        method: METHOD_GET_HISTORY.to_string(),
+        params: json!({"metric": "cpu", "points": 1}),
+    };
+    let response = server.handle_request(request).await;
+    let points = response.result.expect("history should be returned");
+    assert_eq!(points.as_array().unwrap().len(), 1);
+    assert!(points[0]["value"].is_number());
+
+    // A window longer than the history uses every recorded sample
+    let request = MCPRequest {
+        jsonrpc: "2.0".to_string(),
+        id: Some("test-history-stats".to_string()),
+        method: METHOD_GET_HISTORY_STATS.to_string(),
+        params: json!({"metric": "memory", "window": 10_000}),
+    };
+    let response = server.handle_request(request).await;
+    let stats = response.result.expect("stats should be returned");
+    assert_eq!(stats["samples"], recorded);
+    assert!(stats["min"].as_f64().unwrap() <= stats["p95"].as_f64().unwrap());
+    assert!(stats["p95"].as_f64().unwrap() <= stats["max"].as_f64().unwrap());
+
+    let request = MCPRequest {
+        jsonrpc: "2.0".to_string(),
+        id: Some("test-history-invalid".to_string()),
+        method: METHOD_GET_HISTORY.to_string(),
+        params: json!({"metric": "swap"}),
+    };
+    let response = server.handle_request(request).await;
+    assert_eq!(response.error.unwrap().code, ERROR_INVALID_PARAMS);
+}
+
+#[test]
+fn test_alert_evaluator_duration() {
+    use mcp_system_monitor::alerts::{
+        AlertEvaluator, AlertMetric, AlertRule, AlertState, Comparator,
+    };
+
+    let rule = AlertRule {
+        name: "busy".to_string(),
+        metric: AlertMetric::Cpu,
+        comparator: Comparator::Above,
+        threshold: 80.0,
+        duration_secs: 30,
+        webhook_url: None,
+    };
+    let mut evaluator = AlertEvaluator::new(vec![rule]);
+    let start = chrono::Utc::now();
+    let at = |secs| start + chrono::Duration::seconds(secs);
+
+    assert!(evaluator.evaluate(at(0), |_| Some(90.0)).is_empty());
+    assert!(evaluator.firing().is_empty(), "fired before the duration");
+
+    let transitions = evaluator.evaluate(at(30), |_| Some(95.0));
+    assert_eq!(transitions.len(), 1);
+    assert_eq!(transitions[0].state, AlertState::Firing);
+    assert!(evaluator.evaluate(at(35), |_| Some(95.0)).is_empty());
+    let firing = evaluator.firing();
+    assert_eq!(firing.len(), 1);
+    assert_eq!(firing[0].rule, "busy");
+    assert_eq!(firing[0].value, 95.0);
+    assert_eq!(firing[0].since, at(0));
+
+    // Dropping below the threshold, or losing the metric, clears the alert
+    let transitions = evaluator.evaluate(at(40), |_| Some(50.0));
+    assert_eq!(transitions[0].state, AlertState::Resolved);
+    assert_eq!(transitions[0].value, 50.0);
+    assert!(evaluator.firing().is_empty());
+    evaluator.evaluate(at(50), |_| Some(90.0));
+    evaluator.evaluate(at(90), |_| None);
+    assert!(evaluator.firing().is_empty());
+}
+
+#[test]
+fn test_config_alert_rules() {
+    use mcp_system_monitor::alerts::{AlertMetric, Comparator};
+    use mcp_system_monitor::config::Config;
+
+    let defaults = Config::default().alerts;
+    assert!(defaults
+        .iter()
+        .any(|rule| rule.metric == AlertMetric::Disk && rule.threshold == 90.0));
+    assert!(defaults
+        .iter()
+        .any(|rule| rule.metric == AlertMetric::Memory && rule.threshold == 95.0));
+
+    let path = write_temp_config(
+        "alerts",
+        "[[alerts]]\nname = \"load\"\nmetric = \"load\"\ncomparator = \">=\"\nthreshold = 8.0\nduration_secs = 120\n",
+    );
+    let config = Config::load(&path).unwrap();
+    std::fs::remove_file(&path).unwrap();
+    assert_eq!(config.alerts.len(), 1);
+    assert_eq!(config.alerts[0].comparator, Comparator::AtLeast);
+    assert_eq!(config.alerts[0].duration_secs, 120);
+
+    let path = write_temp_config(
+        "alerts-dup",
+        "[[alerts]]\nname = \"a\"\nmetric = \"cpu\"\ncomparator = \">\"\nthreshold = 1.0\n\n[[alerts]]\nname = \"a\"\nmetric = \"cpu\"\ncomparator = \"<\"\nthreshold = 1.0\n",
+    );
+    let error = Config::load(&path).unwrap_err().to_string();
+    std::fs::remove_file(&path).unwrap();
+    assert!(error.contains("Duplicate alert rule"), "{}", error);
+}
+
+#[cfg(target_os = "linux")]
+#[test]
+fn test_pressure_alert_rule() {
+    use mcp_system_monitor::alerts::{AlertMetric, AlertState};
+    use mcp_system_monitor::config::Config;
+    use mcp_system_monitor::system_monitor::linux::LinuxSystemInfo;
+    use std::path::Path;
+
+    let path = write_temp_config(
+        "alerts-pressure",
+        "[[alerts]]\nname = \"memory_stall\"\nmetric = \"memory_pressure\"\ncomparator = \">\"\nthreshold = 10.0\n\n[[alerts]]\nname = \"io_stall\"\nmetric = \"io_pressure\"\ncomparator = \">=\"\nthreshold = 0.0\n",
+    );
+    let config = Config::load(&path).unwrap();
+    std::fs::remove_file(&path).unwrap();
+    assert_eq!(config.alerts[0].metric, AlertMetric::MemoryPressure);
+
+    let root = Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures/proc");
+    let mut monitor = SystemMonitor::with_provider(LinuxSystemInfo::with_proc_root(root)).unwrap();
+    monitor.set_alert_rules(config.alerts);
+    let sample = monitor.record_history_sample().unwrap();
+
+    // The fixture reports 12.34% memory stall and no I/O pressure at all
+    let transitions = monitor.evaluate_alerts(&sample).unwrap();
+    assert_eq!(transitions.len(), 1);
+    assert_eq!(transitions[0].rule.name, "memory_stall");
+    assert_eq!(transitions[0].state, AlertState::Firing);
+    assert_eq!(transitions[0].value, 12.34);
+}
+
+#[cfg(target_os = "linux")]
+#[test]
+fn test_fd_alert_rule() {
+    use mcp_system_monitor::alerts::{AlertMetric, AlertState};
+    use mcp_system_monitor::config::Config;
+    use mcp_system_monitor::system_monitor::linux::LinuxSystemInfo;
+    use std::path::Path;
+
+    let path = write_temp_config(
+        "alerts-fd",
+        "[[alerts]]\nname = \"fd_exhaustion\"\nmetric = \"fd\"\ncomparator = \">\"\nthreshold = 70.0\n",
+    );
+    let config = Config::load(&path).unwrap();
+    std::fs::remove_file(&path).unwrap();
+    assert_eq!(config.alerts[0].metric, AlertMetric::Fd);
+
+    let root = Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures/proc");
+    let mut monitor = SystemMonitor::with_provider(LinuxSystemInfo::with_proc_root(root)).unwrap();
+    monitor.set_alert_rules(config.alerts);
+    let sample = monitor.record_history_sample().unwrap();
+
+    // The fixture has 3072 of 4096 file handles in use
+    let transitions = monitor.evaluate_alerts(&sample).unwrap();
+    assert_eq!(transitions.len(), 1);
+    assert_eq!(transitions[0].rule.name, "fd_exhaustion");
+    assert_eq!(transitions[0].state, AlertState::Firing);
+    assert_eq!(transitions[0].value, 75.0);
+}
+
+#[cfg(target_os = "linux")]
+#[test]
+fn test_entropy_alert_rule() {
+    use mcp_system_monitor::alerts::{AlertMetric, AlertState};
+    use mcp_system_monitor::config::Config;
+    use mcp_system_monitor::system_monitor::linux::LinuxSystemInfo;
+    use std::path::Path;
+
+    let path = write_temp_config(
+        "alerts-entropy",
+        "[[alerts]]\nname = \"low_entropy\"\nmetric = \"entropy\"\ncomparator = \"<\"\nthreshold = 200.0\n",
+    );
+    let config = Config::load(&path).unwrap();
+    std::fs::remove_file(&path).unwrap();
+    assert_eq!(config.alerts[0].metric, AlertMetric::Entropy);
+
+    let root = Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures/proc");
+    let mut monitor = SystemMonitor::with_provider(LinuxSystemInfo::with_proc_root(root)).unwrap();
+    monitor.set_alert_rules(config.alerts);
+    let sample = monitor.record_history_sample().unwrap();
+
+    // The fixture has 180 bits of entropy available
+    let transitions = monitor.evaluate_alerts(&sample).unwrap();
+    assert_eq!(transitions.len(), 1);
+    assert_eq!(transitions[0].rule.name, "low_entropy");
+    assert_eq!(transitions[0].state, AlertState::Firing);
+    assert_eq!(transitions[0].value, 180.0);
+}
+
+#[tokio::test]
+async fn test_mcp_get_alerts() {
+    use mcp_system_monitor::alerts::{AlertMetric, AlertRule, Comparator};
+
+    let system_monitor = create_test_system_monitor().await;
+    {
+        let mut monitor = system_monitor.write().await;
+        monitor.set_alert_rules(vec![AlertRule {
+            name: "always".to_string(),
+            metric: AlertMetric::Memory,
+            comparator: Comparator::AtLeast,
+            threshold: 0.0,
+            duration_secs: 0,
+            webhook_url: None,
+        }]);
+        let sample = monitor.record_history_sample().unwrap();
+        monitor.evaluate_alerts(&sample).unwrap();
+    }
+
+    let server = MCPServer::new(system_monitor);
+    let request = MCPRequest {
+        jsonrpc: "2.0".to_string(),
+        id: Some("test-alerts".to_string()),
+        method: METHOD_GET_ALERTS.to_string(),
+        params: json!({}),
+    };
+    let response = server.handle_request(request).await;
+    let alerts = response.result.expect("alerts should be returned");
+    assert_eq!(alerts[0]["rule"], "always");
+    assert_eq!(alerts[0]["comparator"], ">=");
+    assert!(alerts[0]["value"].is_number());
+    assert!(alerts[0]["since"].is_string());
+}
+
+#[tokio::test]
+async fn test_alert_webhooks() {
+    use axum::{routing::post, Json, Router};
+    use mcp_system_monitor::alerts::{
+        AlertMetric, AlertRule, AlertState, AlertTransition, Comparator,
+    };
+    use mcp_system_monitor::config::{WebhookConfig, WebhookFormat};
+    use mcp_system_monitor::webhook::{payload, render_template, WebhookNotifier};
+
+    let (tx, mut rx) = tokio::sync::mpsc::unbounded_channel();
+    let app = Router::new().route(
+        "/hook",
+        post(move |Json(body): Json<serde_json::Value>| async move {
+            tx.send(body).unwrap();
+        }),
+    );
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap();
+    tokio::spawn(async move { axum::serve(listener, app).await });
+
+    let transition = |state| AlertTransition {
+        rule: AlertRule {
+            name: "disk".to_string(),
+            metric: AlertMetric::Disk,
+            comparator: Comparator::Above,
+            threshold: 90.0,
+            duration_secs: 0,
+            webhook_url: None,
+        },
+        state,
+        value: 93.5,
+        timestamp: chrono::Utc::now(),
+    };
+    let mut notifier = WebhookNotifier::new(
+        WebhookConfig {
+            url: Some(format!("http://{}/hook", addr)),
+            cooldown_secs: 3600,
+            ..WebhookConfig::default()
+        },
+        "web-1",
+    );
+
+    assert_eq!(notifier.notify(&[transition(AlertState::Firing)]), 1);
+    let body = tokio::time::timeout(Duration::from_secs(5), rx.recv())
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(body["rule"], "disk");
+    assert_eq!(body["metric"], "disk");
+    assert_eq!(body["state"], "firing");
+    assert_eq!(body["hostname"], "web-1");
+    assert_eq!(body["value"], 93.5);
+    assert!(body["timestamp"].is_string());
+
+    // The resolution is owed, but a re-fire within the cooldown is not sent
+    assert_eq!(notifier.notify(&[transition(AlertState::Resolved)]), 1);
+    assert_eq!(notifier.notify(&[transition(AlertState::Firing)]), 0);
+    assert_eq!(notifier.notify(&[transition(AlertState::Resolved)]), 0);
+
+    let slack = payload(
+        &transition(AlertState::Firing),
+        "web-1",
+        WebhookFormat::Slack,
+    );
+    assert_eq!(slack["text"], "[FIRING] disk on web-1: disk is 93.5 (> 90)");
+
+    let body = tokio::time::timeout(Duration::from_secs(5), rx.recv())
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(body["state"], "resolved");
+
+    // A template replaces the format, with the fields filled into its strings
+    let mut notifier = WebhookNotifier::new(
+        WebhookConfig {
+            url: Some(format!("http://{}/hook", addr)),
+            format: WebhookFormat::Slack,
+            template: Some(
+                r#"{"summary": "{rule} {state} on {hostname}", "labels": {"{metric}": "{value}/{threshold}"}, "severity": 2}"#
+                    .to_string(),
+            ),
+            ..WebhookConfig::default()
+        },
+        "web-1",
+    );
+    assert_eq!(notifier.notify(&[transition(AlertState::Firing)]), 1);
+    let body = tokio::time::timeout(Duration::from_secs(5), rx.recv())
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(
+        body,
+        json!({"summary": "disk firing on web-1", "labels": {"disk": "93.5/90"}, "severity": 2})
+    );
+
+    let firing = transition(AlertState::Firing);
+    let rendered = render_template(&json!(["{timestamp}"]), &firing, "web-1");
+    assert_eq!(rendered[0], json!(firing.timestamp));
+}